@@ -0,0 +1,84 @@
+// Measures the code size of log macro call sites, which matters for programs
+// with thousands of them.
+//
+// Call sites are placed in a dedicated linker section, its size is read from
+// the `__start_`/`__stop_` symbols the linker defines for it. Run with
+//
+//     cargo bench --bench call_site_size
+//
+// The benchmark fails if a call site exceeds `MAX_BYTES_PER_CALL_SITE`.
+
+use std::hint::black_box;
+
+use spdlog::{info, LevelFilter, Logger};
+
+const CALL_SITES: usize = 256;
+
+// The disabled path is a load, a bit test and a branch, and the enabled path
+// sets up the arguments of the outlined `__log`.
+const MAX_BYTES_PER_CALL_SITE: usize = 384;
+
+// Expands to 256 call sites with distinct messages, so that they are not
+// merged.
+macro_rules! call_sites {
+    ($logger:ident; $($a:tt)*) => {
+        $(call_sites!(@inner $logger; $a; 0 1 2 3 4 5 6 7 8 9 a b c d e f);)*
+    };
+    (@inner $logger:ident; $a:tt; $($b:tt)*) => {
+        $(info!(logger: $logger, user = "alice", id = 42u64;
+            concat!("message ", stringify!($a), stringify!($b), " {}"), black_box(1));)*
+    };
+}
+
+#[inline(never)]
+#[cfg_attr(target_os = "linux", link_section = "spdlog_call_sites")]
+fn call_sites(logger: &Logger) {
+    call_sites!(logger; 0 1 2 3 4 5 6 7 8 9 a b c d e f);
+}
+
+#[cfg(target_os = "linux")]
+fn call_sites_size() -> Option<usize> {
+    extern "C" {
+        static __start_spdlog_call_sites: u8;
+        static __stop_spdlog_call_sites: u8;
+    }
+
+    // SAFETY: Only the addresses of the symbols are taken.
+    let (start, stop) = unsafe {
+        (
+            &__start_spdlog_call_sites as *const u8 as usize,
+            &__stop_spdlog_call_sites as *const u8 as usize,
+        )
+    };
+    Some(stop - start)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn call_sites_size() -> Option<usize> {
+    None
+}
+
+fn main() {
+    let logger = Logger::builder().level_filter(LevelFilter::Off).build();
+    call_sites(black_box(&logger));
+
+    let Some(size) = call_sites_size() else {
+        println!("call_site_size: unsupported on this platform");
+        return;
+    };
+    let per_call_site = size / CALL_SITES;
+    println!(
+        "call_site_size: {} call sites, {} bytes, {} bytes per call site",
+        CALL_SITES, size, per_call_site
+    );
+
+    if cfg!(debug_assertions) {
+        return;
+    }
+    assert!(
+        per_call_site <= MAX_BYTES_PER_CALL_SITE,
+        "call sites take {} bytes each, more than {}",
+        per_call_site,
+        MAX_BYTES_PER_CALL_SITE
+    );
+}
@@ -58,3 +58,22 @@ fn bench_level_off(bencher: &mut Bencher) {
 
     bencher.iter(|| info!(logger, bench_log_message!()))
 }
+
+#[bench]
+fn bench_file_kv(bencher: &mut Bencher) {
+    let path = LOGS_PATH.join("file_kv.log");
+
+    let drain = Fuse(
+        FileLoggerBuilder::new(path)
+            .truncate()
+            .source_location(SourceLocation::None)
+            .build()
+            .unwrap(),
+    );
+    let logger = Logger::root(drain, o!());
+
+    bencher.iter(|| {
+        info!(logger, bench_log_message!();
+            "user" => "alice", "id" => 42u64, "latency" => 0.25, "cached" => true)
+    })
+}
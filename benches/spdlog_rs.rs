@@ -9,7 +9,7 @@ use test::Bencher;
 
 use once_cell::sync::Lazy;
 
-use spdlog::{prelude::*, sink::*, LevelFilter, Logger};
+use spdlog::{formatter::Formatter, prelude::*, sink::*, LevelFilter, Logger, Record};
 
 static LOGS_PATH: Lazy<PathBuf> = Lazy::new(|| {
     let path = common::BENCH_LOGS_PATH.join("spdlog_rs");
@@ -69,3 +69,81 @@ fn bench_level_off(bencher: &mut Bencher) {
 
     bencher.iter(|| info!(logger: logger, bench_log_message!()))
 }
+
+#[bench]
+fn bench_level_filtered_fields(bencher: &mut Bencher) {
+    let logger = Logger::builder()
+        .level_filter(LevelFilter::MoreSevereEqual(Level::Warn))
+        .build();
+
+    bencher.iter(|| {
+        info!(logger: logger, user = "alice", id = 42u64;
+            "{} {}", test::black_box(1), bench_log_message!())
+    })
+}
+
+#[bench]
+fn bench_file_fields(bencher: &mut Bencher) {
+    let path = LOGS_PATH.join("file_fields.log");
+
+    let sink = Arc::new(FileSink::new(path, true).unwrap());
+    let logger = Logger::builder().sink(sink).build();
+
+    bencher.iter(|| {
+        info!(logger: logger, user = "alice", id = 42u64, latency = 0.25, cached = true;
+            bench_log_message!())
+    })
+}
+
+// Discards records, for measuring the cost of building and dispatching them.
+struct NullSink;
+
+impl Sink for NullSink {
+    fn log(&self, record: &Record) -> spdlog::Result<()> {
+        test::black_box(record);
+        Ok(())
+    }
+
+    fn flush(&self) -> spdlog::Result<()> {
+        Ok(())
+    }
+
+    fn level_filter(&self) -> LevelFilter {
+        LevelFilter::All
+    }
+
+    fn set_level_filter(&self, _level_filter: LevelFilter) {}
+
+    fn swap_formatter(&self, formatter: Box<dyn Formatter>) -> Box<dyn Formatter> {
+        formatter
+    }
+}
+
+#[bench]
+fn bench_null_sink(bencher: &mut Bencher) {
+    let logger = Logger::builder().sink(Arc::new(NullSink)).build();
+
+    bencher.iter(|| info!(logger: logger, bench_log_message!()))
+}
+
+#[bench]
+fn bench_null_sink_fields(bencher: &mut Bencher) {
+    let logger = Logger::builder().sink(Arc::new(NullSink)).build();
+
+    bencher.iter(|| {
+        info!(logger: logger, user = "alice", id = 42u64, latency = 0.25, cached = true;
+            bench_log_message!())
+    })
+}
+
+#[bench]
+fn bench_record_to_owned_fields(bencher: &mut Bencher) {
+    let record = Record::builder(Level::Info, bench_log_message!())
+        .field("user", "alice")
+        .field("id", 42u64)
+        .field("latency", 0.25)
+        .field("cached", true)
+        .build();
+
+    bencher.iter(|| test::black_box(&record).to_owned())
+}
@@ -0,0 +1,62 @@
+#![feature(test)]
+
+extern crate test;
+
+mod common;
+
+use std::{
+    fs::{self, File},
+    path::PathBuf,
+    sync::Mutex,
+};
+use test::Bencher;
+
+use once_cell::sync::Lazy;
+
+use tracing::{info, level_filters::LevelFilter, subscriber};
+
+static LOGS_PATH: Lazy<PathBuf> = Lazy::new(|| {
+    let path = common::BENCH_LOGS_PATH.join("tracing");
+    fs::create_dir_all(&path).unwrap();
+    path
+});
+
+fn bench_with_file<F>(bencher: &mut Bencher, file_name: &str, f: F)
+where
+    F: FnMut(),
+{
+    let file = File::create(LOGS_PATH.join(file_name)).unwrap();
+    let subscriber = tracing_subscriber::fmt()
+        .with_writer(Mutex::new(file))
+        .with_ansi(false)
+        .finish();
+
+    subscriber::with_default(subscriber, || bencher.iter(f))
+}
+
+#[bench]
+fn bench_file(bencher: &mut Bencher) {
+    bench_with_file(bencher, "file.log", || info!(bench_log_message!()))
+}
+
+#[bench]
+fn bench_level_off(bencher: &mut Bencher) {
+    let subscriber = tracing_subscriber::fmt()
+        .with_max_level(LevelFilter::OFF)
+        .finish();
+
+    subscriber::with_default(subscriber, || bencher.iter(|| info!(bench_log_message!())))
+}
+
+#[bench]
+fn bench_file_fields(bencher: &mut Bencher) {
+    bench_with_file(bencher, "file_fields.log", || {
+        info!(
+            user = "alice",
+            id = 42u64,
+            latency = 0.25,
+            cached = true,
+            bench_log_message!()
+        )
+    })
+}
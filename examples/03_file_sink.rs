@@ -27,7 +27,9 @@ fn main() {
     logger.set_flush_level_filter(LevelFilter::MoreSevereEqual(Level::Warn));
 
     // Flush every 10 seconds.
-    logger.set_flush_period(Some(Duration::from_secs(10)));
+    logger
+        .set_flush_period(Some(Duration::from_secs(10)))
+        .unwrap();
 
     info!(logger: logger, "hello");
     warn!(logger: logger, "world");
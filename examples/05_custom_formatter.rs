@@ -1,7 +1,7 @@
 use std::{fmt::Write, sync::Arc};
 
 use spdlog::{
-    formatter::{FmtExtraInfo, Formatter},
+    formatter::{FmtExtraInfo, Formatter, FormatterContext},
     prelude::*,
     sink::Sink,
     Record, StringBuf,
@@ -17,7 +17,12 @@ impl CustomFormatter {
 }
 
 impl Formatter for CustomFormatter {
-    fn format(&self, record: &Record, dest: &mut StringBuf) -> spdlog::Result<FmtExtraInfo> {
+    fn format(
+        &self,
+        record: &Record,
+        dest: &mut StringBuf,
+        _ctx: &FormatterContext,
+    ) -> spdlog::Result<FmtExtraInfo> {
         let style_range_begin: usize = dest.len();
 
         dest.write_str(&record.level().as_str().to_ascii_uppercase())
@@ -25,7 +30,7 @@ impl Formatter for CustomFormatter {
 
         let style_range_end: usize = dest.len();
 
-        write!(dest, " {}\n", record.payload()).map_err(spdlog::Error::FormatRecord)?;
+        write!(dest, " {}", record.payload()).map_err(spdlog::Error::FormatRecord)?;
 
         Ok(FmtExtraInfo::builder()
             .style_range(style_range_begin..style_range_end)
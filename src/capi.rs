@@ -0,0 +1,686 @@
+//! Provides a C ABI to log to `spdlog-rs` from C and C++ code.
+//!
+//! This module is available with the crate feature `capi`. It exports
+//! functions with unmangled names, so that in codebases mixing C++ and Rust,
+//! logs of the C++ side (e.g. of C++ spdlog) can be funneled into the default
+//! logger of `spdlog-rs`, sharing its sinks, files and rotation with the Rust
+//! side. The functions are available to C and C++ code linked with a static or
+//! dynamic library built from a Rust crate depending on `spdlog-rs` with this
+//! feature enabled.
+//!
+//! Levels are the values of `spdlog::level::level_enum` of C++ spdlog, from
+//! `0` (trace) to `5` (critical), so the levels of C++ spdlog can be passed
+//! directly.
+//!
+//! # Handles
+//!
+//! Besides logging to the default logger, loggers and sinks can be created and
+//! used through opaque handles, e.g. by bindings of other languages embedding
+//! Rust. A handle is created by a `*_new` function (or
+//! [`spdlog_rs_default_logger`]) and must be freed exactly once by the
+//! corresponding `*_free` function. Adding a sink to a logger or setting the
+//! default logger shares the underlying object, so the handle can be freed
+//! right after.
+//!
+//! Source locations of records require strings living for the rest of the
+//! process, so the distinct logger names and file paths passed to the logging
+//! functions are kept in a process-wide table and never freed. They are
+//! expected to be a small, fixed set (e.g. the source files of a C++ library).
+//! The table is capped at 4096 strings, once it is full, a record with a new
+//! file path is logged without a source location, and a new logger name is not
+//! used as the module path.
+//!
+//! # Thread Safety
+//!
+//! All functions are thread-safe, and a handle can be used from multiple
+//! threads at the same time, since loggers and sinks are [`Sync`]. A handle
+//! must not be used after it is freed, so freeing it must happen after all
+//! other uses of it on all threads. No function calls back into foreign code,
+//! so they can be called while holding locks of foreign runtimes, e.g. the GIL
+//! of Python.
+//!
+//! # Panics
+//!
+//! No function unwinds into foreign code. A panic inside a function (e.g. of
+//! a sink) is caught, and reported as `-1` by functions returning a status, as
+//! null by functions returning a handle, and as `0` by functions returning a
+//! boolean.
+//!
+//! # Examples
+//!
+//! The declarations for C and C++:
+//!
+//! ```c
+//! #include <stdint.h>
+//!
+//! #ifdef __cplusplus
+//! extern "C" {
+//! #endif
+//!
+//! typedef struct spdlog_rs_logger spdlog_rs_logger;
+//! typedef struct spdlog_rs_sink spdlog_rs_sink;
+//!
+//! int spdlog_rs_log(int level, const char *name, const char *msg, const char *file, uint32_t line);
+//! int spdlog_rs_should_log(int level);
+//! void spdlog_rs_flush(void);
+//!
+//! spdlog_rs_logger *spdlog_rs_logger_new(const char *name);
+//! spdlog_rs_logger *spdlog_rs_default_logger(void);
+//! int spdlog_rs_set_default_logger(const spdlog_rs_logger *logger);
+//! int spdlog_rs_logger_add_sink(const spdlog_rs_logger *logger, const spdlog_rs_sink *sink);
+//! int spdlog_rs_logger_set_level(const spdlog_rs_logger *logger, int level);
+//! int spdlog_rs_logger_should_log(const spdlog_rs_logger *logger, int level);
+//! int spdlog_rs_logger_log(const spdlog_rs_logger *logger, int level, const char *msg, const char *file, uint32_t line);
+//! void spdlog_rs_logger_flush(const spdlog_rs_logger *logger);
+//! void spdlog_rs_logger_free(spdlog_rs_logger *logger);
+//!
+//! spdlog_rs_sink *spdlog_rs_sink_std_stream_new(int stream);
+//! spdlog_rs_sink *spdlog_rs_sink_file_new(const char *path, int truncate);
+//! int spdlog_rs_sink_set_level(const spdlog_rs_sink *sink, int level);
+//! void spdlog_rs_sink_free(spdlog_rs_sink *sink);
+//!
+//! #ifdef __cplusplus
+//! }
+//! #endif
+//! ```
+//!
+//! A C++ spdlog sink forwarding records to `spdlog-rs`:
+//!
+//! ```cpp
+//! class rust_sink : public spdlog::sinks::base_sink<spdlog::details::null_mutex> {
+//! protected:
+//!     void sink_it_(const spdlog::details::log_msg &msg) override {
+//!         std::string name(msg.logger_name.data(), msg.logger_name.size());
+//!         std::string payload(msg.payload.data(), msg.payload.size());
+//!         spdlog_rs_log(msg.level, name.c_str(), payload.c_str(), msg.source.filename, msg.source.line);
+//!     }
+//!
+//!     void flush_() override { spdlog_rs_flush(); }
+//! };
+//! ```
+//!
+//! A Python [`logging`] handler forwarding records to a logger of `spdlog-rs`
+//! with [`ctypes`], for an application embedding Python, where the Rust crate
+//! is also built as a `cdylib`:
+//!
+//! ```python
+//! import ctypes
+//! import logging
+//!
+//! lib = ctypes.CDLL("libapp.so")
+//! lib.spdlog_rs_logger_new.restype = ctypes.c_void_p
+//! lib.spdlog_rs_sink_file_new.restype = ctypes.c_void_p
+//! lib.spdlog_rs_logger_add_sink.argtypes = [ctypes.c_void_p, ctypes.c_void_p]
+//! lib.spdlog_rs_sink_free.argtypes = [ctypes.c_void_p]
+//! lib.spdlog_rs_logger_log.argtypes = [
+//!     ctypes.c_void_p, ctypes.c_int, ctypes.c_char_p, ctypes.c_char_p, ctypes.c_uint32
+//! ]
+//!
+//! class SpdlogHandler(logging.Handler):
+//!     def __init__(self, name, path):
+//!         super().__init__()
+//!         self.logger = lib.spdlog_rs_logger_new(name.encode())
+//!         sink = lib.spdlog_rs_sink_file_new(path.encode(), 0)
+//!         lib.spdlog_rs_logger_add_sink(self.logger, sink)
+//!         lib.spdlog_rs_sink_free(sink)
+//!
+//!     def emit(self, record):
+//!         level = min(max(record.levelno // 10, 1), 5)
+//!         msg = self.format(record).encode(errors="replace")
+//!         lib.spdlog_rs_logger_log(
+//!             self.logger, level, msg, record.pathname.encode(), record.lineno
+//!         )
+//!
+//! logging.getLogger().addHandler(SpdlogHandler("python", "app.log"))
+//! ```
+//!
+//! [`logging`]: https://docs.python.org/3/library/logging.html
+//! [`ctypes`]: https://docs.python.org/3/library/ctypes.html
+
+use std::{
+    collections::HashSet,
+    ffi::CStr,
+    os::raw::{c_char, c_int},
+    panic::{self, AssertUnwindSafe},
+    sync::Arc,
+};
+
+use once_cell::sync::Lazy;
+
+use crate::{
+    default_logger,
+    sink::{FileSink, Sink, StdStream, StdStreamSink},
+    terminal_style::StyleMode,
+    Level, LevelFilter, Logger, Record, SourceLocation,
+};
+
+/// An opaque handle of a [`Logger`] for foreign code.
+///
+/// See the [module level documentation](self) for the usage.
+pub struct LoggerHandle {
+    logger: Arc<Logger>,
+}
+
+/// An opaque handle of a [`Sink`] for foreign code.
+///
+/// See the [module level documentation](self) for the usage.
+pub struct SinkHandle {
+    sink: Arc<dyn Sink>,
+}
+
+/// Logs a message to the default logger.
+///
+/// `name` is the name of the (C++) logger, and `file` and `line` are the
+/// source location of the message, they can be null or zero if unknown. The
+/// logger name is also used as the module path of the source location.
+/// Strings are converted from UTF-8 lossily.
+///
+/// Returns `0` on success, or `-1` if the level is invalid, `msg` is null or
+/// a sink panicked. Level `6` (off of C++ spdlog) is accepted and ignored.
+///
+/// # Safety
+///
+/// `name`, `msg` and `file` must be null or valid pointers to NUL-terminated
+/// strings.
+#[no_mangle]
+pub unsafe extern "C" fn spdlog_rs_log(
+    level: c_int,
+    name: *const c_char,
+    msg: *const c_char,
+    file: *const c_char,
+    line: u32,
+) -> c_int {
+    if msg.is_null() {
+        return -1;
+    }
+    let level = match to_level(level) {
+        Ok(Some(level)) => level,
+        Ok(None) => return 0,
+        Err(()) => return -1,
+    };
+
+    catch_unwind(-1, || {
+        // SAFETY: The caller guarantees that non-null pointers are valid strings.
+        let name = unsafe { to_str(name) };
+        // SAFETY: Same as above.
+        unsafe { log(&default_logger(), level, name.as_deref(), msg, file, line) };
+        0
+    })
+}
+
+/// Returns `1` if a message of the level would be logged by the default
+/// logger, otherwise `0`, so that callers can skip formatting messages.
+#[no_mangle]
+pub extern "C" fn spdlog_rs_should_log(level: c_int) -> c_int {
+    match to_level(level) {
+        Ok(Some(level)) => catch_unwind(0, || default_logger().should_log(level) as c_int),
+        _ => 0,
+    }
+}
+
+/// Flushes the default logger.
+#[no_mangle]
+pub extern "C" fn spdlog_rs_flush() {
+    catch_unwind((), || default_logger().flush());
+}
+
+/// Creates a logger without sinks, and returns its handle.
+///
+/// `name` can be null for an unnamed logger. Returns null if the name is not
+/// a valid logger name, see [`LoggerBuilder::name`].
+///
+/// # Safety
+///
+/// `name` must be null or a valid pointer to a NUL-terminated string.
+///
+/// [`LoggerBuilder::name`]: crate::LoggerBuilder::name
+#[no_mangle]
+pub unsafe extern "C" fn spdlog_rs_logger_new(name: *const c_char) -> *mut LoggerHandle {
+    // SAFETY: The caller guarantees that the pointer is null or a valid string.
+    let name = unsafe { to_str(name) };
+    // `LoggerBuilder::name` panics on invalid names.
+    catch_unwind(std::ptr::null_mut(), || {
+        let mut builder = Logger::builder();
+        if let Some(name) = name {
+            builder.name(name);
+        }
+        into_raw_logger(Arc::new(builder.build()))
+    })
+}
+
+/// Returns a handle of the current default logger.
+#[no_mangle]
+pub extern "C" fn spdlog_rs_default_logger() -> *mut LoggerHandle {
+    catch_unwind(std::ptr::null_mut(), || into_raw_logger(default_logger()))
+}
+
+/// Sets the logger as the default logger.
+///
+/// Returns `0` on success, or `-1` if the handle is null.
+#[no_mangle]
+pub extern "C" fn spdlog_rs_set_default_logger(logger: Option<&LoggerHandle>) -> c_int {
+    match logger {
+        Some(logger) => catch_unwind(-1, || {
+            crate::set_default_logger(logger.logger.clone());
+            0
+        }),
+        None => -1,
+    }
+}
+
+/// Adds a sink to the logger.
+///
+/// Returns `0` on success, or `-1` if a handle is null.
+#[no_mangle]
+pub extern "C" fn spdlog_rs_logger_add_sink(
+    logger: Option<&LoggerHandle>,
+    sink: Option<&SinkHandle>,
+) -> c_int {
+    match (logger, sink) {
+        (Some(logger), Some(sink)) => catch_unwind(-1, || {
+            logger.logger.push_sink(sink.sink.clone());
+            0
+        }),
+        _ => -1,
+    }
+}
+
+/// Sets the level filter of the logger, to log messages of the level and more
+/// severe levels. Level `6` turns the logger off.
+///
+/// Returns `0` on success, or `-1` if the handle is null or the level is
+/// invalid.
+#[no_mangle]
+pub extern "C" fn spdlog_rs_logger_set_level(logger: Option<&LoggerHandle>, level: c_int) -> c_int {
+    match (logger, to_level_filter(level)) {
+        (Some(logger), Some(level_filter)) => catch_unwind(-1, || {
+            logger.logger.set_level_filter(level_filter);
+            0
+        }),
+        _ => -1,
+    }
+}
+
+/// Returns `1` if a message of the level would be logged by the logger,
+/// otherwise `0`.
+#[no_mangle]
+pub extern "C" fn spdlog_rs_logger_should_log(
+    logger: Option<&LoggerHandle>,
+    level: c_int,
+) -> c_int {
+    match (logger, to_level(level)) {
+        (Some(logger), Ok(Some(level))) => {
+            catch_unwind(0, || logger.logger.should_log(level) as c_int)
+        }
+        _ => 0,
+    }
+}
+
+/// Logs a message to the logger.
+///
+/// Like [`spdlog_rs_log`], but the name of the logger is used.
+///
+/// Returns `0` on success, or `-1` if the handle is null, the level is
+/// invalid, `msg` is null or a sink panicked.
+///
+/// # Safety
+///
+/// `msg` and `file` must be null or valid pointers to NUL-terminated strings.
+#[no_mangle]
+pub unsafe extern "C" fn spdlog_rs_logger_log(
+    logger: Option<&LoggerHandle>,
+    level: c_int,
+    msg: *const c_char,
+    file: *const c_char,
+    line: u32,
+) -> c_int {
+    let logger = match logger {
+        Some(logger) if !msg.is_null() => &logger.logger,
+        _ => return -1,
+    };
+    match to_level(level) {
+        Ok(Some(level)) => catch_unwind(-1, || {
+            // SAFETY: The caller guarantees that non-null pointers are valid strings.
+            unsafe { log(logger, level, logger.name(), msg, file, line) };
+            0
+        }),
+        Ok(None) => 0,
+        Err(()) => -1,
+    }
+}
+
+/// Flushes the logger.
+#[no_mangle]
+pub extern "C" fn spdlog_rs_logger_flush(logger: Option<&LoggerHandle>) {
+    if let Some(logger) = logger {
+        catch_unwind((), || logger.logger.flush());
+    }
+}
+
+/// Frees a logger handle. Null is ignored.
+#[no_mangle]
+pub extern "C" fn spdlog_rs_logger_free(logger: Option<Box<LoggerHandle>>) {
+    catch_unwind((), || drop(logger));
+}
+
+/// Creates a sink writing to `stdout` (`0`) or `stderr` (`1`), and returns
+/// its handle.
+///
+/// Styles are enabled if the stream is a terminal. Returns null if the stream
+/// is invalid.
+#[no_mangle]
+pub extern "C" fn spdlog_rs_sink_std_stream_new(stream: c_int) -> *mut SinkHandle {
+    let std_stream = match stream {
+        0 => StdStream::Stdout,
+        1 => StdStream::Stderr,
+        _ => return std::ptr::null_mut(),
+    };
+    catch_unwind(std::ptr::null_mut(), || {
+        into_raw_sink(Arc::new(StdStreamSink::new(std_stream, StyleMode::Auto)))
+    })
+}
+
+/// Creates a sink writing to the file, and returns its handle.
+///
+/// The file is truncated if `truncate` is non-zero, otherwise it is appended.
+/// Returns null if `path` is null or the file cannot be opened.
+///
+/// # Safety
+///
+/// `path` must be null or a valid pointer to a NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn spdlog_rs_sink_file_new(
+    path: *const c_char,
+    truncate: c_int,
+) -> *mut SinkHandle {
+    // SAFETY: The caller guarantees that the pointer is null or a valid string.
+    let path = match unsafe { to_str(path) } {
+        Some(path) => path,
+        None => return std::ptr::null_mut(),
+    };
+    catch_unwind(std::ptr::null_mut(), || {
+        match FileSink::new(path, truncate != 0) {
+            Ok(sink) => into_raw_sink(Arc::new(sink)),
+            Err(_) => std::ptr::null_mut(),
+        }
+    })
+}
+
+/// Sets the level filter of the sink, like [`spdlog_rs_logger_set_level`].
+///
+/// Returns `0` on success, or `-1` if the handle is null or the level is
+/// invalid.
+#[no_mangle]
+pub extern "C" fn spdlog_rs_sink_set_level(sink: Option<&SinkHandle>, level: c_int) -> c_int {
+    match (sink, to_level_filter(level)) {
+        (Some(sink), Some(level_filter)) => catch_unwind(-1, || {
+            sink.sink.set_level_filter(level_filter);
+            0
+        }),
+        _ => -1,
+    }
+}
+
+/// Frees a sink handle. Null is ignored.
+#[no_mangle]
+pub extern "C" fn spdlog_rs_sink_free(sink: Option<Box<SinkHandle>>) {
+    catch_unwind((), || drop(sink));
+}
+
+// Unwinding into foreign code is undefined behavior, so panics (e.g. of sinks)
+// are caught at the boundary and reported by the return value.
+fn catch_unwind<R>(default: R, f: impl FnOnce() -> R) -> R {
+    panic::catch_unwind(AssertUnwindSafe(f)).unwrap_or(default)
+}
+
+fn into_raw_logger(logger: Arc<Logger>) -> *mut LoggerHandle {
+    Box::into_raw(Box::new(LoggerHandle { logger }))
+}
+
+fn into_raw_sink(sink: Arc<dyn Sink>) -> *mut SinkHandle {
+    Box::into_raw(Box::new(SinkHandle { sink }))
+}
+
+unsafe fn log(
+    logger: &Logger,
+    level: Level,
+    name: Option<&str>,
+    msg: *const c_char,
+    file: *const c_char,
+    line: u32,
+) {
+    if !logger.should_log(level) {
+        return;
+    }
+
+    // SAFETY: The caller guarantees that non-null pointers are valid strings.
+    let (msg, file) = unsafe { (to_str(msg), to_str(file)) };
+    let name = name.filter(|name| !name.is_empty());
+    let mut builder = Record::builder(level, msg.unwrap_or_default());
+    if let Some(name) = name {
+        builder = builder.logger_name(name);
+    }
+    if let Some(file) = file.filter(|file| !file.is_empty()) {
+        if let Some(file) = intern(&file) {
+            builder = builder.source_location(Some(SourceLocation::new(
+                name.and_then(intern).unwrap_or_default(),
+                file,
+                line,
+                0,
+            )));
+        }
+    }
+    logger.log(&builder.build());
+}
+
+// Converts a level of C++ spdlog, `Ok(None)` for off.
+fn to_level(level: c_int) -> Result<Option<Level>, ()> {
+    match level {
+        0 => Ok(Some(Level::Trace)),
+        1 => Ok(Some(Level::Debug)),
+        2 => Ok(Some(Level::Info)),
+        3 => Ok(Some(Level::Warn)),
+        4 => Ok(Some(Level::Error)),
+        5 => Ok(Some(Level::Critical)),
+        6 => Ok(None),
+        _ => Err(()),
+    }
+}
+
+fn to_level_filter(level: c_int) -> Option<LevelFilter> {
+    match to_level(level) {
+        Ok(Some(level)) => Some(LevelFilter::MoreSevereEqual(level)),
+        Ok(None) => Some(LevelFilter::Off),
+        Err(()) => None,
+    }
+}
+
+unsafe fn to_str(ptr: *const c_char) -> Option<String> {
+    if ptr.is_null() {
+        return None;
+    }
+    // SAFETY: The caller guarantees that the pointer is a valid string.
+    let str = unsafe { CStr::from_ptr(ptr) };
+    Some(str.to_string_lossy().into_owned())
+}
+
+const MAX_INTERNED: usize = 4096;
+
+// Source locations require static strings. File paths and logger names from
+// C and C++ code are expected to be a bounded set, so they are leaked once and
+// reused. Returns `None` if the string is new and the table is full, so that
+// callers passing unbounded strings cannot leak memory without limit.
+fn intern(str: &str) -> Option<&'static str> {
+    static INTERNED: Lazy<spin::Mutex<HashSet<&'static str>>> = Lazy::new(Default::default);
+
+    let mut interned = INTERNED.lock();
+    match interned.get(str) {
+        Some(str) => Some(str),
+        None if interned.len() >= MAX_INTERNED => None,
+        None => {
+            let str = Box::leak(str.to_owned().into_boxed_str());
+            interned.insert(str);
+            Some(str)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{ffi::CString, ptr, sync::Arc};
+
+    use super::*;
+    use crate::{test_utils::*, LevelFilter};
+
+    #[test]
+    fn log() {
+        let sink = Arc::new(CounterSink::new());
+        let logger = Arc::new(test_logger_builder().sink(sink.clone()).build());
+        logger.set_level_filter(LevelFilter::MoreSevereEqual(Level::Info));
+
+        crate::test::with_isolated_default_logger(|| {
+            crate::set_default_logger(logger);
+
+            let name = CString::new("cpp").unwrap();
+            let msg = CString::new("hello from c++").unwrap();
+            let file = CString::new("main.cpp").unwrap();
+            unsafe {
+                assert_eq!(
+                    spdlog_rs_log(3, name.as_ptr(), msg.as_ptr(), file.as_ptr(), 42),
+                    0
+                );
+                assert_eq!(
+                    spdlog_rs_log(1, ptr::null(), msg.as_ptr(), ptr::null(), 0),
+                    0
+                );
+                assert_eq!(
+                    spdlog_rs_log(6, ptr::null(), msg.as_ptr(), ptr::null(), 0),
+                    0
+                );
+                assert_eq!(
+                    spdlog_rs_log(7, ptr::null(), msg.as_ptr(), ptr::null(), 0),
+                    -1
+                );
+                assert_eq!(
+                    spdlog_rs_log(2, ptr::null(), ptr::null(), ptr::null(), 0),
+                    -1
+                );
+            }
+            assert_eq!(spdlog_rs_should_log(2), 1);
+            assert_eq!(spdlog_rs_should_log(1), 0);
+            spdlog_rs_flush();
+        });
+
+        let records = sink.records();
+        assert_eq!(records.len(), 1);
+        let record = &records[0];
+        assert_eq!(record.level(), Level::Warn);
+        assert_eq!(record.logger_name(), Some("cpp"));
+        assert_eq!(record.payload(), "hello from c++");
+        let srcloc = record.source_location().unwrap();
+        assert_eq!(
+            (srcloc.module_path(), srcloc.file(), srcloc.line()),
+            ("cpp", "main.cpp", 42)
+        );
+        assert_eq!(sink.flush_count(), 1);
+    }
+
+    #[test]
+    fn catch_panics() {
+        struct PanickingSink;
+
+        impl Sink for PanickingSink {
+            fn log(&self, _record: &Record) -> crate::Result<()> {
+                panic!("log");
+            }
+
+            fn flush(&self) -> crate::Result<()> {
+                panic!("flush");
+            }
+
+            fn level_filter(&self) -> LevelFilter {
+                LevelFilter::All
+            }
+
+            fn set_level_filter(&self, _level_filter: LevelFilter) {}
+
+            fn swap_formatter(
+                &self,
+                formatter: Box<dyn crate::formatter::Formatter>,
+            ) -> Box<dyn crate::formatter::Formatter> {
+                formatter
+            }
+        }
+
+        let logger = Arc::new(test_logger_builder().sink(Arc::new(PanickingSink)).build());
+        let msg = CString::new("hello").unwrap();
+
+        let handle = into_raw_logger(logger.clone());
+        unsafe {
+            assert_eq!(
+                spdlog_rs_logger_log(handle.as_ref(), 2, msg.as_ptr(), ptr::null(), 0),
+                -1
+            );
+            spdlog_rs_logger_flush(handle.as_ref());
+            spdlog_rs_logger_free(Some(Box::from_raw(handle)));
+        }
+
+        crate::test::with_isolated_default_logger(|| {
+            crate::set_default_logger(logger);
+
+            unsafe {
+                assert_eq!(
+                    spdlog_rs_log(2, ptr::null(), msg.as_ptr(), ptr::null(), 0),
+                    -1
+                );
+            }
+            assert_eq!(spdlog_rs_should_log(2), 1);
+            spdlog_rs_flush();
+        });
+    }
+
+    #[test]
+    fn handles() {
+        let path = CString::new(
+            TEST_LOGS_PATH
+                .join("capi_handles.log")
+                .to_str()
+                .unwrap()
+                .to_owned(),
+        )
+        .unwrap();
+        let name = CString::new("python").unwrap();
+        let msg = CString::new("hello from python").unwrap();
+        let file = CString::new("app.py").unwrap();
+        let invalid_name = CString::new("a,b").unwrap();
+
+        unsafe {
+            assert!(spdlog_rs_logger_new(invalid_name.as_ptr()).is_null());
+            assert!(spdlog_rs_sink_std_stream_new(2).is_null());
+
+            let logger = spdlog_rs_logger_new(name.as_ptr());
+            let sink = spdlog_rs_sink_file_new(path.as_ptr(), 1);
+            assert!(!logger.is_null() && !sink.is_null());
+            assert_eq!(spdlog_rs_logger_add_sink(logger.as_ref(), sink.as_ref()), 0);
+            spdlog_rs_sink_free(Box::from_raw(sink).into());
+
+            assert_eq!(spdlog_rs_logger_set_level(logger.as_ref(), 1), 0);
+            assert_eq!(spdlog_rs_logger_set_level(logger.as_ref(), 7), -1);
+            assert_eq!(spdlog_rs_logger_should_log(logger.as_ref(), 1), 1);
+            assert_eq!(spdlog_rs_logger_should_log(logger.as_ref(), 0), 0);
+            assert_eq!(
+                spdlog_rs_logger_log(logger.as_ref(), 2, msg.as_ptr(), file.as_ptr(), 7),
+                0
+            );
+            assert_eq!(
+                spdlog_rs_logger_log(None, 2, msg.as_ptr(), file.as_ptr(), 7),
+                -1
+            );
+            spdlog_rs_logger_flush(logger.as_ref());
+            spdlog_rs_logger_free(Some(Box::from_raw(logger)));
+        }
+
+        let content = std::fs::read_to_string(TEST_LOGS_PATH.join("capi_handles.log")).unwrap();
+        assert!(content.contains("[python, app.py:7] hello from python"));
+    }
+}
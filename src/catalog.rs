@@ -0,0 +1,172 @@
+//! Provides message catalogs for localizing log output.
+//!
+//! A record may carry a message key along with its payload (see [`log!`] for
+//! how to set it from log macros). The payload is the canonical message, which
+//! is written by formatters by default, e.g. to log files. Formatters
+//! configured with a [`MessageCatalog`] (see [`FullFormatterBuilder::catalog`])
+//! resolve the key through the catalog instead, with the fields of the record
+//! as arguments, so that user-facing output can be localized.
+//!
+//! # Examples
+//!
+//! ```
+//! use std::sync::Arc;
+//!
+//! use spdlog::{
+//!     catalog::MapCatalog,
+//!     formatter::FullFormatter,
+//!     prelude::*,
+//!     sink::{Sink, StdStream, StdStreamSink},
+//!     terminal_style::StyleMode,
+//! };
+//!
+//! let catalog = MapCatalog::new().message("disk.full", "Le disque {mount} est plein");
+//! let formatter = FullFormatter::builder().catalog(Arc::new(catalog)).build();
+//!
+//! let sink = StdStreamSink::new(StdStream::Stderr, StyleMode::Auto);
+//! sink.set_formatter(Box::new(formatter));
+//! let logger: Logger = Logger::builder().sink(Arc::new(sink)).build();
+//!
+//! # let path = "/var";
+//! // The terminal shows "Le disque /var est plein mount=/var", while sinks
+//! // without the catalog write "disk /var is full mount=/var".
+//! warn!(logger: logger, message_key: "disk.full", mount = path; "disk {} is full", path);
+//! ```
+//!
+//! [`log!`]: crate::log
+//! [`FullFormatterBuilder::catalog`]: crate::formatter::FullFormatterBuilder::catalog
+
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    fmt::{self, Write},
+};
+
+use crate::Field;
+
+/// A trait for catalogs resolving message keys into localized messages.
+///
+/// See the [module level documentation](self) for details.
+pub trait MessageCatalog: Send + Sync {
+    /// Resolves a message key with the fields of the record as arguments.
+    ///
+    /// Returns `None` if the key is unknown to the catalog, in which case the
+    /// canonical payload of the record is used.
+    fn resolve(&self, key: &str, args: &[Field]) -> Option<String>;
+}
+
+impl fmt::Debug for dyn MessageCatalog {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("MessageCatalog")
+    }
+}
+
+/// A [`MessageCatalog`] of message templates in memory.
+///
+/// Templates reference arguments by field keys in braces, e.g. `{mount}`.
+/// Literal braces are escaped by doubling them, i.e. `{{` and `}}`. References
+/// to absent fields are kept as they are.
+///
+/// # Examples
+///
+/// ```
+/// use spdlog::{catalog::{MapCatalog, MessageCatalog}, Field};
+///
+/// let catalog = MapCatalog::new()
+///     .message("disk.full", "Le disque {mount} est plein")
+///     .message("retry", "Nouvel essai {{{attempt}}}");
+///
+/// let args = [Field::new("mount", "/var"), Field::new("attempt", 2)];
+/// assert_eq!(
+///     catalog.resolve("disk.full", &args).as_deref(),
+///     Some("Le disque /var est plein")
+/// );
+/// assert_eq!(
+///     catalog.resolve("retry", &args).as_deref(),
+///     Some("Nouvel essai {2}")
+/// );
+/// assert_eq!(catalog.resolve("unknown", &args), None);
+/// ```
+#[derive(Clone, Default, Debug)]
+pub struct MapCatalog {
+    templates: HashMap<Cow<'static, str>, Cow<'static, str>>,
+}
+
+impl MapCatalog {
+    /// Constructs an empty `MapCatalog`.
+    pub fn new() -> MapCatalog {
+        MapCatalog::default()
+    }
+
+    /// Adds the template of a message key, replacing the existing one.
+    #[must_use]
+    pub fn message<K, T>(mut self, key: K, template: T) -> Self
+    where
+        K: Into<Cow<'static, str>>,
+        T: Into<Cow<'static, str>>,
+    {
+        self.templates.insert(key.into(), template.into());
+        self
+    }
+}
+
+impl MessageCatalog for MapCatalog {
+    fn resolve(&self, key: &str, args: &[Field]) -> Option<String> {
+        let template = self.templates.get(key)?;
+
+        let mut message = String::with_capacity(template.len());
+        let mut rest = template.as_ref();
+        while let Some(index) = rest.find(['{', '}']) {
+            message.push_str(&rest[..index]);
+            rest = &rest[index..];
+
+            if rest.starts_with("{{") || rest.starts_with("}}") {
+                message.push_str(&rest[..1]);
+                rest = &rest[2..];
+                continue;
+            }
+
+            let reference = rest
+                .strip_prefix('{')
+                .and_then(|after| after.find('}').map(|end| &after[..end]));
+            let arg =
+                reference.and_then(|name| args.iter().find(|field| field.key() == name.trim()));
+            match (reference, arg) {
+                (Some(reference), Some(arg)) => {
+                    write!(message, "{}", arg.value()).ok()?;
+                    rest = &rest[reference.len() + 2..];
+                }
+                _ => {
+                    message.push_str(&rest[..1]);
+                    rest = &rest[1..];
+                }
+            }
+        }
+        message.push_str(rest);
+
+        Some(message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn map_catalog() {
+        let catalog = MapCatalog::new()
+            .message("absent", "{missing} and { mount }")
+            .message("unclosed", "}{mount")
+            .message("typed", "{count} {ok} {elapsed:?}");
+
+        let args = [
+            Field::new("mount", "/var"),
+            Field::new("count", 2),
+            Field::new("ok", true),
+        ];
+        let resolve = |key| catalog.resolve(key, &args).unwrap();
+        assert_eq!(resolve("absent"), "{missing} and /var");
+        assert_eq!(resolve("unclosed"), "}{mount");
+        assert_eq!(resolve("typed"), "2 true {elapsed:?}");
+    }
+}
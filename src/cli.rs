@@ -0,0 +1,226 @@
+//! Provides standard logging flags for command line tools built with [clap].
+//!
+//! This module is available with the crate feature `clap`. [`LogArgs`] is a
+//! set of arguments to be flattened into the arguments of a tool, and
+//! [`init`] applies them to the default logger, so that tools share the same
+//! flags and behavior:
+//!
+//! ```text
+//!     --log-level <LEVEL>    Level filter of logs, e.g. `debug`, `all` or `off`
+//!     --log-file <PATH>      Also write logs to the file, appending to it
+//!     --log-json             Write logs as JSON objects, one per line
+//! -q, --quiet                Only write errors to the terminal
+//!     --color <WHEN>         When to style logs on the terminal [default: auto]
+//! ```
+//!
+//! # Examples
+//!
+//! ```
+//! # #[cfg(feature = "clap")]
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! use clap::Parser;
+//! use spdlog::{cli::LogArgs, prelude::*};
+//!
+//! #[derive(Parser)]
+//! struct Args {
+//!     #[clap(flatten)]
+//!     log: LogArgs,
+//! }
+//!
+//! let args = Args::parse_from(["app", "--log-level", "debug", "--color", "never"]);
+//! spdlog::cli::init(&args.log)?;
+//!
+//! assert_eq!(
+//!     spdlog::default_logger().level_filter(),
+//!     LevelFilter::MoreSevereEqual(Level::Debug)
+//! );
+//! # Ok(()) }
+//! # #[cfg(not(feature = "clap"))]
+//! # fn main() {}
+//! ```
+//!
+//! [clap]: https://docs.rs/clap
+
+use std::{path::PathBuf, sync::Arc};
+
+use crate::{
+    default_logger,
+    formatter::JsonFormatter,
+    sink::{FileSink, Sink, StdStream, StdStreamSink},
+    terminal_style::StyleMode,
+    Level, LevelFilter, Result,
+};
+
+/// Standard logging arguments of command line tools.
+///
+/// See the [module level documentation](self) for the flags and an example.
+#[derive(clap::Args, Clone, Debug)]
+pub struct LogArgs {
+    /// Level filter of logs, e.g. `debug`, `all` or `off`
+    #[clap(long, value_name = "LEVEL", value_parser = parse_level_filter)]
+    pub log_level: Option<LevelFilter>,
+
+    /// Also write logs to the file, appending to it
+    #[clap(long, value_name = "PATH")]
+    pub log_file: Option<PathBuf>,
+
+    /// Write logs as JSON objects, one per line
+    #[clap(long)]
+    pub log_json: bool,
+
+    /// Only write errors to the terminal
+    #[clap(short, long)]
+    pub quiet: bool,
+
+    /// When to style logs on the terminal
+    #[clap(
+        long,
+        value_name = "WHEN",
+        default_value = "auto",
+        value_parser = parse_style_mode
+    )]
+    pub color: StyleMode,
+}
+
+/// Applies the arguments to the default logger.
+///
+/// The sinks of the default logger are replaced:
+///
+///  - Logs are written to the terminal like the default logger, `info` and more
+///    verbose levels to `stdout`, `warn` and more severe levels to `stderr`.
+///    With `--quiet`, only `error` and more severe levels are written, to
+///    `stderr`.
+///  - With `--log-file`, all logs are also written to the file.
+///  - With `--log-json`, all sinks write logs with [`JsonFormatter`] without
+///    styles.
+///
+/// The level filter of the default logger is set if `--log-level` is given,
+/// otherwise it is kept, e.g. as configured by [`init_env_level`].
+///
+/// # Errors
+///
+/// If the log file cannot be opened, the error is returned and the default
+/// logger is not changed.
+///
+/// [`init_env_level`]: crate::init_env_level
+pub fn init(args: &LogArgs) -> Result<()> {
+    let style_mode = if args.log_json {
+        StyleMode::Never
+    } else {
+        args.color
+    };
+
+    let mut sinks: Vec<Arc<dyn Sink>> = vec![];
+    if args.quiet {
+        let stderr = StdStreamSink::new(StdStream::Stderr, style_mode);
+        stderr.set_level_filter(LevelFilter::MoreSevereEqual(Level::Error));
+        sinks.push(Arc::new(stderr));
+    } else {
+        let stdout = StdStreamSink::new(StdStream::Stdout, style_mode);
+        stdout.set_level_filter(LevelFilter::MoreVerbose(Level::Warn));
+        let stderr = StdStreamSink::new(StdStream::Stderr, style_mode);
+        stderr.set_level_filter(LevelFilter::MoreSevereEqual(Level::Warn));
+        sinks.push(Arc::new(stdout));
+        sinks.push(Arc::new(stderr));
+    }
+    if let Some(path) = &args.log_file {
+        sinks.push(Arc::new(FileSink::new(path, false)?));
+    }
+    if args.log_json {
+        sinks
+            .iter()
+            .for_each(|sink| sink.set_formatter(Box::new(JsonFormatter::new())));
+    }
+
+    let logger = default_logger();
+    if let Some(level_filter) = args.log_level {
+        logger.set_level_filter(level_filter);
+    }
+    logger.set_sinks(sinks);
+    Ok(())
+}
+
+fn parse_level_filter(text: &str) -> std::result::Result<LevelFilter, String> {
+    LevelFilter::from_str_for_env(text).ok_or_else(|| {
+        format!(
+            "unknown level '{}', expected one of off, critical, error, warn, info, debug, trace, all",
+            text
+        )
+    })
+}
+
+fn parse_style_mode(text: &str) -> std::result::Result<StyleMode, String> {
+    match text.to_ascii_lowercase().as_str() {
+        "always" => Ok(StyleMode::Always),
+        "auto" => Ok(StyleMode::Auto),
+        "never" => Ok(StyleMode::Never),
+        _ => Err(format!(
+            "unknown value '{}', expected one of always, auto, never",
+            text
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+
+    use super::*;
+    use crate::test_utils::*;
+
+    #[derive(Parser)]
+    struct Args {
+        #[clap(flatten)]
+        log: LogArgs,
+    }
+
+    #[test]
+    fn parse() {
+        let args = Args::try_parse_from(["app"]).unwrap().log;
+        assert_eq!(args.log_level, None);
+        assert_eq!(args.color, StyleMode::Auto);
+        assert!(!args.quiet && !args.log_json);
+
+        let args = Args::try_parse_from(["app", "--log-level", "OFF", "-q", "--color", "always"])
+            .unwrap()
+            .log;
+        assert_eq!(args.log_level, Some(LevelFilter::Off));
+        assert_eq!(args.color, StyleMode::Always);
+        assert!(args.quiet);
+
+        assert!(Args::try_parse_from(["app", "--log-level", "verbose"]).is_err());
+        assert!(Args::try_parse_from(["app", "--color", "sometimes"]).is_err());
+    }
+
+    #[test]
+    fn init_default_logger() {
+        let path = TEST_LOGS_PATH.join("cli_init.log");
+        let _ = std::fs::remove_file(&path);
+        let args = Args::try_parse_from([
+            "app",
+            "--log-level",
+            "trace",
+            "--log-file",
+            path.to_str().unwrap(),
+            "--log-json",
+            "--quiet",
+        ])
+        .unwrap()
+        .log;
+
+        crate::test::with_isolated_default_logger(|| {
+            init(&args).unwrap();
+            let logger = default_logger();
+            assert_eq!(
+                logger.level_filter(),
+                LevelFilter::MoreSevereEqual(Level::Trace)
+            );
+            assert_eq!(logger.sinks().len(), 2);
+
+            crate::trace!("from cli test");
+            logger.flush();
+        });
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert!(content.contains("\"message\":\"from cli test\""));
+    }
+}
@@ -1,22 +1,96 @@
 use std::{
     collections::{hash_map::Entry, HashMap},
     env::{self, VarError},
+    fmt,
+    str::FromStr,
     sync::RwLock,
 };
 
 use once_cell::sync::Lazy;
 use thiserror::Error;
 
-use crate::LevelFilter;
+use crate::{Level, LevelFilter};
 
-pub(crate) type EnvLevel = HashMap<EnvLevelLogger, LevelFilter>;
+pub(crate) type EnvLevel = HashMap<EnvLevelKey, EnvLevelFilter>;
 
 static ENV_LEVEL: Lazy<RwLock<Option<EnvLevel>>> = Lazy::new(|| RwLock::new(None));
 
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub(crate) struct EnvLevelKey {
+    // The sink name of a per-sink directive, e.g. `file` for `[file]gui=trace`.
+    sink: Option<String>,
+    logger: EnvLevelLogger,
+}
+
+impl EnvLevelKey {
+    #[cfg(test)]
+    pub(crate) fn logger(logger: EnvLevelLogger) -> Self {
+        Self { sink: None, logger }
+    }
+
+    #[cfg(test)]
+    pub(crate) fn sink(sink: &str, logger: EnvLevelLogger) -> Self {
+        Self {
+            sink: Some(sink.into()),
+            logger,
+        }
+    }
+}
+
+// The level filter of a directive. For a range such as `debug..warn`, the level
+// filter accepts the verbose end, and the most severe level accepted is the
+// severe end.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub(crate) struct EnvLevelFilter {
+    level_filter: LevelFilter,
+    most_severe: Option<Level>,
+}
+
+impl EnvLevelFilter {
+    pub(crate) fn level_filter(&self) -> LevelFilter {
+        self.level_filter
+    }
+
+    // The filter rejecting records more severe than the range, if it is a range.
+    pub(crate) fn severity_filter(&self) -> Option<LevelFilter> {
+        self.most_severe.map(LevelFilter::MoreVerboseEqual)
+    }
+
+    fn parse(text: &str) -> Option<Self> {
+        match text.split_once("..") {
+            None => LevelFilter::from_str_for_env(text).map(Self::from),
+            Some((start, end)) => {
+                let (start, end) = (Level::from_str(start.trim()), Level::from_str(end.trim()));
+                let (start, end) = (start.ok()?, end.ok()?);
+                let (verbose, severe) = if start as u16 >= end as u16 {
+                    (start, end)
+                } else {
+                    (end, start)
+                };
+                Some(Self {
+                    level_filter: LevelFilter::MoreSevereEqual(verbose),
+                    most_severe: Some(severe),
+                })
+            }
+        }
+    }
+}
+
+impl From<LevelFilter> for EnvLevelFilter {
+    fn from(level_filter: LevelFilter) -> Self {
+        Self {
+            level_filter,
+            most_severe: None,
+        }
+    }
+}
+
 #[derive(Clone, Eq, PartialEq, Hash, Debug)]
 pub(crate) enum EnvLevelLogger {
     Default,
     Named(String),
+    // Loggers with names starting with the prefix, e.g. `net.` for `net.*`.
+    Prefix(String),
     Unnamed,
     AllExceptDefault,
 }
@@ -29,21 +103,92 @@ pub enum EnvLevelError {
     FetchEnvVar(VarError),
 
     /// Parse environment variable error, usually caused by incorrect format.
-    #[error("parse environment variable error: {0}")]
+    #[error("parse environment variable error: {}", display_directive_errors(.0))]
     ParseEnvVar(
-        /// Parse error description
+        /// The invalid directives, in the order of their positions.
+        Vec<EnvLevelDirectiveError>,
+    ),
+}
+
+fn display_directive_errors(errors: &[EnvLevelDirectiveError]) -> String {
+    errors
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+/// The error of an invalid directive in the value of the environment variable,
+/// e.g. `network=verbose` in `info,network=verbose`.
+#[derive(Error, Clone, Eq, PartialEq, Debug)]
+#[error("{kind} in directive '{directive}' at position {position}")]
+pub struct EnvLevelDirectiveError {
+    directive: String,
+    position: usize,
+    kind: EnvLevelDirectiveErrorKind,
+}
+
+impl EnvLevelDirectiveError {
+    /// Gets the directive, with surrounding whitespaces trimmed.
+    pub fn directive(&self) -> &str {
+        &self.directive
+    }
+
+    /// Gets the byte offset of the directive in the value.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Gets the reason why the directive is invalid.
+    pub fn kind(&self) -> &EnvLevelDirectiveErrorKind {
+        &self.kind
+    }
+}
+
+/// The reason why a directive is invalid, see [`EnvLevelDirectiveError`].
+#[derive(Clone, Eq, PartialEq, Debug)]
+#[non_exhaustive]
+pub enum EnvLevelDirectiveErrorKind {
+    /// The directive is not in the format of `level`, `logger_name=level` or
+    /// `prefix*=level`, optionally prefixed by `[sink_name]`.
+    InvalidSyntax,
+    /// The level cannot be parsed.
+    InvalidLevel(
+        /// The level text
         String,
     ),
+    /// The same logger is specified by a previous directive.
+    Duplicated,
+}
+
+impl fmt::Display for EnvLevelDirectiveErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::InvalidSyntax => f.write_str("invalid syntax"),
+            Self::InvalidLevel(level) => write!(f, "unknown level '{}'", level),
+            Self::Duplicated => f.write_str("level specified multiple times"),
+        }
+    }
 }
 
 impl EnvLevelLogger {
-    fn from_key(logger_name: &str) -> Self {
-        if logger_name.is_empty() {
+    // Returns `None` if the key has a wildcard not at the end.
+    fn from_key(logger_name: &str) -> Option<Self> {
+        let logger = if logger_name.is_empty() {
             EnvLevelLogger::Unnamed
         } else if logger_name == "*" {
             EnvLevelLogger::AllExceptDefault
         } else {
-            EnvLevelLogger::Named(logger_name.into())
+            match logger_name.strip_suffix('*') {
+                Some(prefix) => EnvLevelLogger::Prefix(prefix.into()),
+                None => EnvLevelLogger::Named(logger_name.into()),
+            }
+        };
+        match &logger {
+            EnvLevelLogger::Named(name) | EnvLevelLogger::Prefix(name) if name.contains('*') => {
+                None
+            }
+            _ => Some(logger),
         }
     }
 
@@ -55,75 +200,127 @@ impl EnvLevelLogger {
     }
 }
 
-pub(crate) fn from_env(env_name: &str) -> Result<bool, EnvLevelError> {
+pub(crate) fn from_env(env_name: &str, lenient: bool) -> Result<bool, EnvLevelError> {
     let var = match env::var(env_name) {
         Err(VarError::NotPresent) => return Ok(false),
         Err(err) => return Err(EnvLevelError::FetchEnvVar(err)),
         Ok(var) => var,
     };
-    from_str(&var)?;
+    from_str(&var, lenient)?;
     Ok(true)
 }
 
-pub(crate) fn from_str(var: &str) -> Result<(), EnvLevelError> {
-    let env_level = from_str_inner(var)?;
-    *ENV_LEVEL.write().unwrap() = Some(env_level);
-    Ok(())
+// If `lenient` is `true`, the valid directives are applied even if there are
+// invalid ones.
+pub(crate) fn from_str(var: &str, lenient: bool) -> Result<(), EnvLevelError> {
+    let (env_level, errors) = parse(var);
+    if lenient || errors.is_empty() {
+        *ENV_LEVEL.write().unwrap() = Some(env_level);
+    }
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(EnvLevelError::ParseEnvVar(errors))
+    }
 }
 
+#[cfg(test)]
 pub(crate) fn from_str_inner(var: &str) -> Result<EnvLevel, EnvLevelError> {
-    (|| {
-        let mut env_level = EnvLevel::new();
+    match parse(var) {
+        (env_level, errors) if errors.is_empty() => Ok(env_level),
+        (_, errors) => Err(EnvLevelError::ParseEnvVar(errors)),
+    }
+}
 
-        for kv_str in var.split(',').map(str::trim) {
-            if kv_str.is_empty() {
-                continue;
-            }
+// Returns the valid directives and the errors of the invalid ones.
+fn parse(var: &str) -> (EnvLevel, Vec<EnvLevelDirectiveError>) {
+    let mut env_level = EnvLevel::new();
+    let mut errors = vec![];
 
-            let mut kv = kv_str.split('=');
-            let (left, right) = (kv.next().map(str::trim), kv.next().map(str::trim));
-
-            let (logger, level) = match (left, right, kv.next()) {
-                (Some(default_logger_level), None, None) => {
-                    if let Some(level) = LevelFilter::from_str_for_env(default_logger_level) {
-                        (EnvLevelLogger::Default, level)
-                    } else {
-                        return Err(format!(
-                            "cannot parse level for default logger: '{}'",
-                            kv_str
-                        ));
-                    }
-                }
-                (Some(logger_name), Some(level), None) => {
-                    if let Some(level) = LevelFilter::from_str_for_env(level) {
-                        (EnvLevelLogger::from_key(logger_name), level)
-                    } else {
-                        return Err(format!(
-                            "cannot parse level for logger '{}': '{}'",
-                            logger_name, kv_str
-                        ));
-                    }
-                }
-                _ => {
-                    return Err(format!("invalid kv: '{}'", kv_str));
-                }
-            };
+    let mut offset = 0;
+    for raw in var.split(',') {
+        let kv_str = raw.trim();
+        let position = offset + (raw.len() - raw.trim_start().len());
+        offset += raw.len() + 1;
+
+        if kv_str.is_empty() {
+            continue;
+        }
 
-            match env_level.entry(logger) {
-                Entry::Occupied(_) => {
-                    return Err(format!("specified level multiple times: '{}'", kv_str));
+        let mut error = |kind| {
+            errors.push(EnvLevelDirectiveError {
+                directive: kv_str.to_string(),
+                position,
+                kind,
+            })
+        };
+
+        let Some((sink, kv_str)) = split_sink(kv_str) else {
+            error(EnvLevelDirectiveErrorKind::InvalidSyntax);
+            continue;
+        };
+
+        let mut kv = kv_str.split('=');
+        let (left, right) = (kv.next().map(str::trim), kv.next().map(str::trim));
+
+        let (logger, level) = match (left, right, kv.next()) {
+            (Some(level), None, None) => (EnvLevelLogger::Default, level),
+            (Some(logger_name), Some(level), None) => match EnvLevelLogger::from_key(logger_name) {
+                Some(logger) => (logger, level),
+                None => {
+                    error(EnvLevelDirectiveErrorKind::InvalidSyntax);
+                    continue;
                 }
-                Entry::Vacant(entry) => entry.insert(level),
-            };
+            },
+            _ => {
+                error(EnvLevelDirectiveErrorKind::InvalidSyntax);
+                continue;
+            }
+        };
+
+        let level = match EnvLevelFilter::parse(level) {
+            Some(level) => level,
+            None => {
+                error(EnvLevelDirectiveErrorKind::InvalidLevel(level.to_string()));
+                continue;
+            }
+        };
+
+        let key = EnvLevelKey {
+            sink: sink.map(Into::into),
+            logger,
+        };
+        match env_level.entry(key) {
+            Entry::Occupied(_) => error(EnvLevelDirectiveErrorKind::Duplicated),
+            Entry::Vacant(entry) => {
+                entry.insert(level);
+            }
         }
+    }
 
-        Ok(env_level)
-    })()
-    .map_err(EnvLevelError::ParseEnvVar)
+    (env_level, errors)
 }
 
-pub(crate) fn logger_level(kind: LoggerKind) -> Option<LevelFilter> {
-    logger_level_inner(ENV_LEVEL.read().unwrap().as_ref()?, kind)
+// Splits the `[sink_name]` prefix of a per-sink directive. Returns `None` if
+// the prefix is malformed.
+fn split_sink(text: &str) -> Option<(Option<&str>, &str)> {
+    let Some(rest) = text.strip_prefix('[') else {
+        return Some((None, text));
+    };
+    let (sink, rest) = rest.split_once(']')?;
+    let sink = sink.trim();
+    if sink.is_empty() || sink.contains(['[', '*']) {
+        return None;
+    }
+    Some((Some(sink), rest.trim_start()))
+}
+
+pub(crate) fn with_env_level<R>(f: impl FnOnce(&EnvLevel) -> R) -> Option<R> {
+    ENV_LEVEL.read().unwrap().as_ref().map(f)
+}
+
+pub(crate) fn logger_level(kind: LoggerKind) -> Option<EnvLevelFilter> {
+    with_env_level(|env_level| logger_level_inner(env_level, kind)).flatten()
 }
 
 #[derive(Clone, Eq, PartialEq, Debug)]
@@ -132,45 +329,82 @@ pub(crate) enum LoggerKind<'a> {
     Other(Option<&'a str>),
 }
 
-pub(crate) fn logger_level_inner(env_level: &EnvLevel, kind: LoggerKind) -> Option<LevelFilter> {
-    let level = match kind {
-        LoggerKind::Default => env_level.get(&EnvLevelLogger::Default)?,
-        LoggerKind::Other(logger_name) => env_level
-            .get(&EnvLevelLogger::from_logger(logger_name))
-            .or_else(|| env_level.get(&EnvLevelLogger::AllExceptDefault))?,
+pub(crate) fn logger_level_inner(env_level: &EnvLevel, kind: LoggerKind) -> Option<EnvLevelFilter> {
+    resolve(env_level, None, kind)
+}
+
+pub(crate) fn sink_level_inner(
+    env_level: &EnvLevel,
+    sink_name: &str,
+    kind: LoggerKind,
+) -> Option<EnvLevelFilter> {
+    resolve(env_level, Some(sink_name), kind)
+}
+
+pub(crate) fn has_sink_directives(env_level: &EnvLevel) -> bool {
+    env_level.keys().any(|key| key.sink.is_some())
+}
+
+fn resolve(env_level: &EnvLevel, sink: Option<&str>, kind: LoggerKind) -> Option<EnvLevelFilter> {
+    let get = |logger| {
+        env_level
+            .get(&EnvLevelKey {
+                sink: sink.map(Into::into),
+                logger,
+            })
+            .copied()
     };
-    Some(*level)
+    match kind {
+        LoggerKind::Default => get(EnvLevelLogger::Default),
+        LoggerKind::Other(logger_name) => get(EnvLevelLogger::from_logger(logger_name))
+            .or_else(|| longest_prefix(env_level, sink, logger_name?))
+            .or_else(|| get(EnvLevelLogger::AllExceptDefault)),
+    }
+}
+
+// Returns the level of the longest prefix matching the logger name, so that
+// e.g. `net.http.*` takes precedence over `net.*`.
+fn longest_prefix(
+    env_level: &EnvLevel,
+    sink: Option<&str>,
+    logger_name: &str,
+) -> Option<EnvLevelFilter> {
+    env_level
+        .iter()
+        .filter(|(key, _)| key.sink.as_deref() == sink)
+        .filter_map(|(key, level)| match &key.logger {
+            EnvLevelLogger::Prefix(prefix) if logger_name.starts_with(prefix.as_str()) => {
+                Some((prefix.len(), level))
+            }
+            _ => None,
+        })
+        .max_by_key(|(len, _)| *len)
+        .map(|(_, level)| *level)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::Level;
+
+    fn level(env_level: &EnvLevel, kind: LoggerKind) -> Option<LevelFilter> {
+        logger_level_inner(env_level, kind).map(|level| level.level_filter())
+    }
 
     #[test]
     fn validation() {
         macro_rules! assert_levels {
             ($env_level:expr, DEFAULT => $default:expr, UNNAMED => $unnamed:expr, NAMED($name:literal) => $named:expr $(,)?) => {
-                assert_eq!(
-                    logger_level_inner(&$env_level, LoggerKind::Default),
-                    $default
-                );
-                assert_eq!(
-                    logger_level_inner(&$env_level, LoggerKind::Other(None)),
-                    $unnamed
-                );
-                assert_eq!(
-                    logger_level_inner(&$env_level, LoggerKind::Other(Some($name))),
-                    $named
-                );
+                assert_eq!(level(&$env_level, LoggerKind::Default), $default);
+                assert_eq!(level(&$env_level, LoggerKind::Other(None)), $unnamed);
+                assert_eq!(level(&$env_level, LoggerKind::Other(Some($name))), $named);
             };
         }
 
         {
             let mut env_level = HashMap::new();
             env_level.insert(
-                EnvLevelLogger::Default,
-                LevelFilter::MoreSevereEqual(Level::Debug),
+                EnvLevelKey::logger(EnvLevelLogger::Default),
+                LevelFilter::MoreSevereEqual(Level::Debug).into(),
             );
             assert_eq!(from_str_inner("dEBUg").unwrap(), env_level);
 
@@ -184,10 +418,13 @@ mod tests {
 
         {
             let mut env_level = HashMap::new();
-            env_level.insert(EnvLevelLogger::Default, LevelFilter::All);
             env_level.insert(
-                EnvLevelLogger::Unnamed,
-                LevelFilter::MoreSevereEqual(Level::Info),
+                EnvLevelKey::logger(EnvLevelLogger::Default),
+                LevelFilter::All.into(),
+            );
+            env_level.insert(
+                EnvLevelKey::logger(EnvLevelLogger::Unnamed),
+                LevelFilter::MoreSevereEqual(Level::Info).into(),
             );
             assert_eq!(from_str_inner("aLl,=inFo").unwrap(), env_level);
 
@@ -201,14 +438,17 @@ mod tests {
 
         {
             let mut env_level = HashMap::new();
-            env_level.insert(EnvLevelLogger::Default, LevelFilter::Off);
             env_level.insert(
-                EnvLevelLogger::Unnamed,
-                LevelFilter::MoreSevereEqual(Level::Info),
+                EnvLevelKey::logger(EnvLevelLogger::Default),
+                LevelFilter::Off.into(),
+            );
+            env_level.insert(
+                EnvLevelKey::logger(EnvLevelLogger::Unnamed),
+                LevelFilter::MoreSevereEqual(Level::Info).into(),
             );
             env_level.insert(
-                EnvLevelLogger::AllExceptDefault,
-                LevelFilter::MoreSevereEqual(Level::Error),
+                EnvLevelKey::logger(EnvLevelLogger::AllExceptDefault),
+                LevelFilter::MoreSevereEqual(Level::Error).into(),
             );
             assert_eq!(from_str_inner("oFf,=iNfo,*=erRor").unwrap(), env_level);
 
@@ -223,12 +463,12 @@ mod tests {
         {
             let mut env_level = HashMap::new();
             env_level.insert(
-                EnvLevelLogger::Unnamed,
-                LevelFilter::MoreSevereEqual(Level::Warn),
+                EnvLevelKey::logger(EnvLevelLogger::Unnamed),
+                LevelFilter::MoreSevereEqual(Level::Warn).into(),
             );
             env_level.insert(
-                EnvLevelLogger::Named("name".into()),
-                LevelFilter::MoreSevereEqual(Level::Trace),
+                EnvLevelKey::logger(EnvLevelLogger::Named("name".into())),
+                LevelFilter::MoreSevereEqual(Level::Trace).into(),
             );
             assert_eq!(from_str_inner("=wArn,name=trAce").unwrap(), env_level);
 
@@ -243,12 +483,12 @@ mod tests {
         {
             let mut env_level = HashMap::new();
             env_level.insert(
-                EnvLevelLogger::AllExceptDefault,
-                LevelFilter::MoreSevereEqual(Level::Warn),
+                EnvLevelKey::logger(EnvLevelLogger::AllExceptDefault),
+                LevelFilter::MoreSevereEqual(Level::Warn).into(),
             );
             env_level.insert(
-                EnvLevelLogger::Named("name".into()),
-                LevelFilter::MoreSevereEqual(Level::Trace),
+                EnvLevelKey::logger(EnvLevelLogger::Named("name".into())),
+                LevelFilter::MoreSevereEqual(Level::Trace).into(),
             );
             assert_eq!(from_str_inner("*=wArn,name=trAce").unwrap(), env_level);
 
@@ -262,8 +502,14 @@ mod tests {
 
         {
             let mut env_level = HashMap::new();
-            env_level.insert(EnvLevelLogger::Default, LevelFilter::All);
-            env_level.insert(EnvLevelLogger::AllExceptDefault, LevelFilter::All);
+            env_level.insert(
+                EnvLevelKey::logger(EnvLevelLogger::Default),
+                LevelFilter::All.into(),
+            );
+            env_level.insert(
+                EnvLevelKey::logger(EnvLevelLogger::AllExceptDefault),
+                LevelFilter::All.into(),
+            );
             assert_eq!(from_str_inner("all,*=all").unwrap(), env_level);
 
             assert_levels!(
@@ -276,8 +522,14 @@ mod tests {
 
         {
             let mut env_level = HashMap::new();
-            env_level.insert(EnvLevelLogger::Default, LevelFilter::Off);
-            env_level.insert(EnvLevelLogger::AllExceptDefault, LevelFilter::All);
+            env_level.insert(
+                EnvLevelKey::logger(EnvLevelLogger::Default),
+                LevelFilter::Off.into(),
+            );
+            env_level.insert(
+                EnvLevelKey::logger(EnvLevelLogger::AllExceptDefault),
+                LevelFilter::All.into(),
+            );
             assert_eq!(from_str_inner("off,*=all").unwrap(), env_level);
 
             assert_levels!(
@@ -288,4 +540,243 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn prefix() {
+        let env_level = from_str_inner("net.*=trace,net.http.*=warn,net.http=error,*=off").unwrap();
+        let level = |name| level(&env_level, LoggerKind::Other(Some(name)));
+
+        assert_eq!(
+            level("net.tcp"),
+            Some(LevelFilter::MoreSevereEqual(Level::Trace))
+        );
+        assert_eq!(
+            level("net.http.client"),
+            Some(LevelFilter::MoreSevereEqual(Level::Warn))
+        );
+        assert_eq!(
+            level("net.http"),
+            Some(LevelFilter::MoreSevereEqual(Level::Error))
+        );
+        assert_eq!(level("net"), Some(LevelFilter::Off));
+        assert_eq!(level("network"), Some(LevelFilter::Off));
+        assert_eq!(
+            self::level(&env_level, LoggerKind::Other(None)),
+            Some(LevelFilter::Off)
+        );
+
+        let (_, errors) = parse("n*t=info,net.**=info,net.*=info,net.*=warn");
+        assert_eq!(
+            errors
+                .iter()
+                .map(|err| (err.directive(), err.kind().clone()))
+                .collect::<Vec<_>>(),
+            vec![
+                ("n*t=info", EnvLevelDirectiveErrorKind::InvalidSyntax),
+                ("net.**=info", EnvLevelDirectiveErrorKind::InvalidSyntax),
+                ("net.*=warn", EnvLevelDirectiveErrorKind::Duplicated),
+            ]
+        );
+    }
+
+    #[test]
+    fn range() {
+        let env_level = from_str_inner("debug..warn,gui=error..info,net=info..info").unwrap();
+
+        let expected = EnvLevelFilter {
+            level_filter: LevelFilter::MoreSevereEqual(Level::Debug),
+            most_severe: Some(Level::Warn),
+        };
+        assert_eq!(
+            logger_level_inner(&env_level, LoggerKind::Default),
+            Some(expected)
+        );
+        assert_eq!(
+            expected.severity_filter(),
+            Some(LevelFilter::MoreVerboseEqual(Level::Warn))
+        );
+
+        let gui = logger_level_inner(&env_level, LoggerKind::Other(Some("gui"))).unwrap();
+        assert_eq!(
+            gui.level_filter(),
+            LevelFilter::MoreSevereEqual(Level::Info)
+        );
+        assert_eq!(
+            gui.severity_filter(),
+            Some(LevelFilter::MoreVerboseEqual(Level::Error))
+        );
+
+        let net = logger_level_inner(&env_level, LoggerKind::Other(Some("net"))).unwrap();
+        assert_eq!(
+            net.level_filter(),
+            LevelFilter::MoreSevereEqual(Level::Info)
+        );
+        assert_eq!(
+            net.severity_filter(),
+            Some(LevelFilter::MoreVerboseEqual(Level::Info))
+        );
+
+        let (_, errors) = parse("a=off..info,b=info..,c=..warn");
+        assert_eq!(
+            errors
+                .iter()
+                .map(|err| err.kind().clone())
+                .collect::<Vec<_>>(),
+            vec![
+                EnvLevelDirectiveErrorKind::InvalidLevel("off..info".into()),
+                EnvLevelDirectiveErrorKind::InvalidLevel("info..".into()),
+                EnvLevelDirectiveErrorKind::InvalidLevel("..warn".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn sink_directives() {
+        let env_level = from_str_inner(
+            "info,gui=warn,[file]gui=trace,[file]debug,[ file ] net.*=error..warn,app::db=debug,\
+             [file]app::db=trace",
+        )
+        .unwrap();
+        assert!(has_sink_directives(&env_level));
+        assert!(!has_sink_directives(
+            &from_str_inner("info,gui=warn").unwrap()
+        ));
+
+        let mut expected = HashMap::new();
+        expected.insert(
+            EnvLevelKey::logger(EnvLevelLogger::Default),
+            LevelFilter::MoreSevereEqual(Level::Info).into(),
+        );
+        expected.insert(
+            EnvLevelKey::logger(EnvLevelLogger::Named("gui".into())),
+            LevelFilter::MoreSevereEqual(Level::Warn).into(),
+        );
+        expected.insert(
+            EnvLevelKey::sink("file", EnvLevelLogger::Named("gui".into())),
+            LevelFilter::MoreSevereEqual(Level::Trace).into(),
+        );
+        expected.insert(
+            EnvLevelKey::sink("file", EnvLevelLogger::Default),
+            LevelFilter::MoreSevereEqual(Level::Debug).into(),
+        );
+        expected.insert(
+            EnvLevelKey::sink("file", EnvLevelLogger::Prefix("net.".into())),
+            EnvLevelFilter {
+                level_filter: LevelFilter::MoreSevereEqual(Level::Warn),
+                most_severe: Some(Level::Error),
+            },
+        );
+        expected.insert(
+            EnvLevelKey::logger(EnvLevelLogger::Named("app::db".into())),
+            LevelFilter::MoreSevereEqual(Level::Debug).into(),
+        );
+        expected.insert(
+            EnvLevelKey::sink("file", EnvLevelLogger::Named("app::db".into())),
+            LevelFilter::MoreSevereEqual(Level::Trace).into(),
+        );
+        assert_eq!(env_level, expected);
+
+        let sink_level =
+            |sink, kind| sink_level_inner(&env_level, sink, kind).map(|level| level.level_filter());
+        assert_eq!(
+            sink_level("file", LoggerKind::Other(Some("gui"))),
+            Some(LevelFilter::MoreSevereEqual(Level::Trace))
+        );
+        assert_eq!(
+            sink_level("file", LoggerKind::Default),
+            Some(LevelFilter::MoreSevereEqual(Level::Debug))
+        );
+        assert_eq!(
+            sink_level("file", LoggerKind::Other(Some("net.http"))),
+            Some(LevelFilter::MoreSevereEqual(Level::Warn))
+        );
+        assert_eq!(
+            sink_level("file", LoggerKind::Other(Some("app::db"))),
+            Some(LevelFilter::MoreSevereEqual(Level::Trace))
+        );
+        assert_eq!(sink_level("file", LoggerKind::Other(Some("other"))), None);
+        assert_eq!(sink_level("console", LoggerKind::Other(Some("gui"))), None);
+
+        // Sink directives do not change the levels of loggers.
+        assert_eq!(
+            level(&env_level, LoggerKind::Other(Some("gui"))),
+            Some(LevelFilter::MoreSevereEqual(Level::Warn))
+        );
+        assert_eq!(
+            level(&env_level, LoggerKind::Other(Some("app::db"))),
+            Some(LevelFilter::MoreSevereEqual(Level::Debug))
+        );
+        assert_eq!(level(&env_level, LoggerKind::Other(Some("net.http"))), None);
+
+        // Colons are part of logger names.
+        let env_level = from_str_inner("*=off,app:db=trace").unwrap();
+        assert!(!has_sink_directives(&env_level));
+        assert_eq!(
+            level(&env_level, LoggerKind::Other(Some("app:db"))),
+            Some(LevelFilter::MoreSevereEqual(Level::Trace))
+        );
+        assert_eq!(
+            level(&env_level, LoggerKind::Other(Some("db"))),
+            Some(LevelFilter::Off)
+        );
+
+        let (_, errors) =
+            parse("[]gui=info,[f*]gui=info,[file gui=info,[file]a=b=c,[file]gui=verbose..info");
+        assert_eq!(
+            errors
+                .iter()
+                .map(|err| err.kind().clone())
+                .collect::<Vec<_>>(),
+            vec![
+                EnvLevelDirectiveErrorKind::InvalidSyntax,
+                EnvLevelDirectiveErrorKind::InvalidSyntax,
+                EnvLevelDirectiveErrorKind::InvalidSyntax,
+                EnvLevelDirectiveErrorKind::InvalidSyntax,
+                EnvLevelDirectiveErrorKind::InvalidLevel("verbose..info".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn directive_errors() {
+        let (env_level, errors) = parse("info, gui=verbose,a=b=c,,gui=warn,*=error ,info");
+
+        let mut expected = HashMap::new();
+        expected.insert(
+            EnvLevelKey::logger(EnvLevelLogger::Default),
+            LevelFilter::MoreSevereEqual(Level::Info).into(),
+        );
+        expected.insert(
+            EnvLevelKey::logger(EnvLevelLogger::Named("gui".into())),
+            LevelFilter::MoreSevereEqual(Level::Warn).into(),
+        );
+        expected.insert(
+            EnvLevelKey::logger(EnvLevelLogger::AllExceptDefault),
+            LevelFilter::MoreSevereEqual(Level::Error).into(),
+        );
+        assert_eq!(env_level, expected);
+
+        let errors = errors
+            .iter()
+            .map(|err| (err.directive(), err.position(), err.kind().clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            errors,
+            vec![
+                (
+                    "gui=verbose",
+                    6,
+                    EnvLevelDirectiveErrorKind::InvalidLevel("verbose".into())
+                ),
+                ("a=b=c", 18, EnvLevelDirectiveErrorKind::InvalidSyntax),
+                ("info", 43, EnvLevelDirectiveErrorKind::Duplicated),
+            ]
+        );
+
+        assert_eq!(
+            from_str_inner("gui=verbose").unwrap_err().to_string(),
+            "parse environment variable error: unknown level 'verbose' in directive \
+             'gui=verbose' at position 0"
+        );
+    }
 }
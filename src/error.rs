@@ -1,6 +1,13 @@
 //! Provides error types.
 
-use std::{fmt, io, result};
+use std::{
+    fmt, io,
+    path::{Path, PathBuf},
+    result,
+    sync::Arc,
+};
+
+use crate::Level;
 
 use thiserror::Error;
 
@@ -50,6 +57,20 @@ pub enum Error {
     #[error("query file metadata error: {0}")]
     QueryFileMetadata(io::Error),
 
+    /// The variant returned by [`merge`] functions when an error occurs in
+    /// reading a file.
+    ///
+    /// [`merge`]: crate::merge
+    #[error("read file error: {0}")]
+    ReadFile(io::Error),
+
+    /// The variant returned by [`Sink`]s when an error occurs in reading a
+    /// directory.
+    ///
+    /// [`Sink`]: crate::sink::Sink
+    #[error("read directory error: {0}")]
+    ReadDirectory(io::Error),
+
     /// The variant returned by [`Sink`]s when an error occurs in renaming a
     /// file.
     ///
@@ -64,12 +85,282 @@ pub enum Error {
     #[error("remove file error: {0}")]
     RemoveFile(io::Error),
 
+    /// The variant passed to the error handler when an error occurs in
+    /// applying a [`ThreadConfig`] to a background thread.
+    ///
+    /// [`ThreadConfig`]: crate::ThreadConfig
+    #[error("configure thread error: {0}")]
+    ConfigureThread(io::Error),
+
     /// The variant returned by [`from_str`] when the string doesn't match any
     /// of the log levels.
     ///
     /// [`from_str`]: std::str::FromStr::from_str
     #[error("attempted to convert a string that doesn't match an existing log level: {0}")]
     ParseLevel(String),
+
+    /// The variant returned by [`Sink`]s forwarding records to multiple inner
+    /// sinks (e.g. [`GroupSink`]) when more than one of them fails.
+    ///
+    /// [`Sink`]: crate::sink::Sink
+    /// [`GroupSink`]: crate::sink::GroupSink
+    #[error("multiple errors: {}", join_errors(.0))]
+    Multiple(Vec<Error>),
+
+    /// The variant returned by builders and constructors when an argument is
+    /// invalid, instead of panicking or silently misbehaving.
+    #[error("invalid argument {0}")]
+    InvalidArgument(InvalidArgumentError),
+
+    /// The variant passed to error handlers by [`Logger`]s and returned by
+    /// [`Sink`]s forwarding records to multiple inner sinks (e.g.
+    /// [`GroupSink`]), wrapping an error of a sink with the identity of the
+    /// sink and the context of the record.
+    ///
+    /// [`Logger`]: crate::Logger
+    /// [`Sink`]: crate::sink::Sink
+    /// [`GroupSink`]: crate::sink::GroupSink
+    #[error("{0}")]
+    Sink(Box<SinkError>),
+
+    /// The variant returned by [`RotatingFileSink`] when a file cannot be
+    /// moved in rotating, after retrying and falling back to copying, because
+    /// it is in use by another process.
+    ///
+    /// [`RotatingFileSink`]: crate::sink::RotatingFileSink
+    #[error("{0}")]
+    RotateFile(Box<RotateFileError>),
+}
+
+impl Error {
+    // Wraps the error with the identity of the sink, unless it is already
+    // wrapped.
+    pub(crate) fn with_sink(
+        self,
+        sink: &dyn crate::sink::Sink,
+        level: Option<Level>,
+        logger_name: Option<&str>,
+    ) -> Error {
+        match self {
+            Error::Sink(_) => self,
+            error => Error::Sink(Box::new(SinkError {
+                sink: sink.describe(),
+                sink_name: sink.name(),
+                level,
+                logger_name: logger_name.map(str::to_owned),
+                error,
+            })),
+        }
+    }
+}
+
+/// The error type of [`Error::Sink`], an error of a sink with the identity
+/// of the sink and the context of the record.
+///
+/// It is displayed like:
+///
+/// ```text
+/// sink FileSink(/var/log/app.log) (logger app, level warn): write record error: Broken pipe
+/// ```
+///
+/// or with the name of the sink if it has one, see [`Sink::name`]:
+///
+/// ```text
+/// sink "audit" FileSink(/var/log/audit.log) (logger app, level warn): write record error: Broken pipe
+/// ```
+///
+/// [`Sink::name`]: crate::sink::Sink::name
+#[derive(Debug)]
+pub struct SinkError {
+    sink: String,
+    sink_name: Option<Arc<str>>,
+    level: Option<Level>,
+    logger_name: Option<String>,
+    error: Error,
+}
+
+impl SinkError {
+    /// Gets the description of the sink, see [`Sink::describe`].
+    ///
+    /// [`Sink::describe`]: crate::sink::Sink::describe
+    pub fn sink(&self) -> &str {
+        &self.sink
+    }
+
+    /// Gets the name of the sink, see [`Sink::name`].
+    ///
+    /// [`Sink::name`]: crate::sink::Sink::name
+    pub fn sink_name(&self) -> Option<&str> {
+        self.sink_name.as_deref()
+    }
+
+    /// Gets the level of the record being logged, or `None` if the error does
+    /// not occur in logging a record (e.g. in flushing).
+    pub fn level(&self) -> Option<Level> {
+        self.level
+    }
+
+    /// Gets the logger name of the record being logged, or the name of the
+    /// logger being flushed.
+    pub fn logger_name(&self) -> Option<&str> {
+        self.logger_name.as_deref()
+    }
+
+    /// Gets the error of the sink.
+    pub fn error(&self) -> &Error {
+        &self.error
+    }
+
+    /// Converts into the error of the sink.
+    pub fn into_error(self) -> Error {
+        self.error
+    }
+}
+
+impl fmt::Display for SinkError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.sink_name {
+            Some(sink_name) => write!(f, "sink {:?} {}", sink_name, self.sink)?,
+            None => write!(f, "sink {}", self.sink)?,
+        }
+        match (&self.logger_name, self.level) {
+            (Some(logger_name), Some(level)) => {
+                write!(f, " (logger {}, level {})", logger_name, level)?
+            }
+            (Some(logger_name), None) => write!(f, " (logger {})", logger_name)?,
+            (None, Some(level)) => write!(f, " (level {})", level)?,
+            (None, None) => {}
+        }
+        write!(f, ": {}", self.error)
+    }
+}
+
+impl std::error::Error for SinkError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+/// The error type of [`Error::RotateFile`], a failure of moving a file in
+/// rotating, with the paths and the errors of all attempts.
+///
+/// It is displayed like:
+///
+/// ```text
+/// rotate file error: cannot move logs/app.log to logs/app_1.log after 5 attempts: The process cannot access the file because it is being used by another process. (os error 32); copy fallback: ...
+/// ```
+#[derive(Debug)]
+pub struct RotateFileError {
+    pub(crate) from: PathBuf,
+    pub(crate) to: PathBuf,
+    pub(crate) attempts: u32,
+    pub(crate) error: io::Error,
+    pub(crate) fallback_error: Option<io::Error>,
+}
+
+impl RotateFileError {
+    /// Gets the path of the file being moved.
+    pub fn from(&self) -> &Path {
+        &self.from
+    }
+
+    /// Gets the path the file is being moved to.
+    pub fn to(&self) -> &Path {
+        &self.to
+    }
+
+    /// Gets the number of attempts to rename the file.
+    pub fn attempts(&self) -> u32 {
+        self.attempts
+    }
+
+    /// Gets the error of the last attempt to rename the file.
+    pub fn error(&self) -> &io::Error {
+        &self.error
+    }
+
+    /// Gets the error of copying and truncating the file instead of renaming
+    /// it, or `None` if the fallback was not attempted.
+    pub fn fallback_error(&self) -> Option<&io::Error> {
+        self.fallback_error.as_ref()
+    }
+}
+
+impl fmt::Display for RotateFileError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "rotate file error: cannot move {} to {} after {} attempts: {}",
+            self.from.display(),
+            self.to.display(),
+            self.attempts,
+            self.error
+        )?;
+        if let Some(fallback_error) = &self.fallback_error {
+            write!(f, "; copy fallback: {}", fallback_error)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for RotateFileError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+/// The error type of [`Error::InvalidArgument`], indicating which argument is
+/// invalid and why.
+#[derive(Error, Debug)]
+#[non_exhaustive]
+pub enum InvalidArgumentError {
+    /// The rotation policy of [`RotatingFileSink`] is invalid.
+    ///
+    /// [`RotatingFileSink`]: crate::sink::RotatingFileSink
+    #[error("'rotation policy': {0}")]
+    RotationPolicy(String),
+
+    /// The capacity of a queue (e.g. of [`AsyncSink`]) is invalid.
+    ///
+    /// [`AsyncSink`]: crate::sink::AsyncSink
+    #[error("'capacity': {0}")]
+    Capacity(String),
+
+    /// The interval of a periodic task (e.g. of [`ResourceMonitor`]) is
+    /// invalid.
+    ///
+    /// [`ResourceMonitor`]: crate::monitors::ResourceMonitor
+    #[error("'interval': {0}")]
+    Interval(String),
+
+    /// The path of a file sink (e.g. of [`FileSink`]) is already written by
+    /// another sink.
+    ///
+    /// [`FileSink`]: crate::sink::FileSink
+    #[error("'path': {0}")]
+    Path(String),
+
+    /// The list of sinks of a sink forwarding records (e.g. of [`AsyncSink`])
+    /// is empty.
+    ///
+    /// [`AsyncSink`]: crate::sink::AsyncSink
+    #[error("'sinks': {0}")]
+    Sinks(String),
+
+    /// The configuration of a sink or formatter constructed by the
+    /// [`registry`] is invalid.
+    ///
+    /// [`registry`]: crate::registry
+    #[error("'config': {0}")]
+    Config(String),
+}
+
+fn join_errors(errors: &[Error]) -> String {
+    errors
+        .iter()
+        .map(|err| err.to_string())
+        .collect::<Vec<_>>()
+        .join("; ")
 }
 
 /// The result type of this crate.
@@ -77,3 +368,39 @@ pub type Result<T> = result::Result<T, Error>;
 
 /// The error handler function type.
 pub type ErrorHandler = fn(Error);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{sink::Sink, test_utils::*};
+
+    #[test]
+    fn with_sink() {
+        let sink = CounterSink::new();
+        let err = Error::FormatRecord(fmt::Error).with_sink(&sink, Some(Level::Warn), Some("app"));
+        assert_eq!(
+            err.to_string(),
+            "sink CounterSink (logger app, level warn): format record error: \
+             an error occurred when formatting an argument"
+        );
+
+        let err = err.with_sink(&sink, None, None);
+        assert_eq!(
+            err.to_string(),
+            "sink CounterSink (logger app, level warn): format record error: \
+             an error occurred when formatting an argument"
+        );
+
+        let err = Error::FlushBuffer(io::ErrorKind::Other.into()).with_sink(&sink, None, None);
+        assert!(err
+            .to_string()
+            .starts_with("sink CounterSink: flush buffer error: "));
+
+        sink.set_name(Some("audit".into()));
+        let err = Error::FlushBuffer(io::ErrorKind::Other.into()).with_sink(&sink, None, None);
+        assert!(err
+            .to_string()
+            .starts_with("sink \"audit\" CounterSink: flush buffer error: "));
+        assert!(matches!(err, Error::Sink(err) if err.sink_name() == Some("audit")));
+    }
+}
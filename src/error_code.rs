@@ -0,0 +1,75 @@
+//! Provides error codes of log records.
+
+use std::{borrow::Cow, fmt};
+
+/// Represents a stable error code attached to a log record.
+///
+/// Unlike payloads, which are free to change, error codes identify kinds of
+/// errors stably, so that support teams and tooling can key on them, e.g. to
+/// link records to documentation. Formatters render them consistently (e.g.
+/// [`FullFormatter`] writes `[error[E1042]]`), and filters can match them via
+/// [`Record::code`].
+///
+/// See [`log!`] for how to attach them from log macros.
+///
+/// [`FullFormatter`]: crate::formatter::FullFormatter
+/// [`Record::code`]: crate::Record::code
+/// [`log!`]: crate::log
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub enum ErrorCode {
+    /// A numeric code.
+    Num(u32),
+    /// A string code, e.g. `E1042`.
+    Str(Cow<'static, str>),
+}
+
+impl ErrorCode {
+    /// Gets the numeric code, or `None` if it is a string code.
+    pub fn as_num(&self) -> Option<u32> {
+        match self {
+            ErrorCode::Num(code) => Some(*code),
+            ErrorCode::Str(_) => None,
+        }
+    }
+
+    /// Gets the string code, or `None` if it is a numeric code.
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            ErrorCode::Num(_) => None,
+            ErrorCode::Str(code) => Some(code),
+        }
+    }
+}
+
+impl fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ErrorCode::Num(code) => write!(f, "{}", code),
+            ErrorCode::Str(code) => f.write_str(code),
+        }
+    }
+}
+
+impl From<u32> for ErrorCode {
+    fn from(code: u32) -> Self {
+        ErrorCode::Num(code)
+    }
+}
+
+impl From<&'static str> for ErrorCode {
+    fn from(code: &'static str) -> Self {
+        ErrorCode::Str(Cow::Borrowed(code))
+    }
+}
+
+impl From<String> for ErrorCode {
+    fn from(code: String) -> Self {
+        ErrorCode::Str(Cow::Owned(code))
+    }
+}
+
+impl From<Cow<'static, str>> for ErrorCode {
+    fn from(code: Cow<'static, str>) -> Self {
+        ErrorCode::Str(code)
+    }
+}
@@ -0,0 +1,436 @@
+//! Provides a structured field of log records.
+
+use std::{borrow::Cow, cell::Cell, collections::HashSet, fmt, time::Duration};
+
+use once_cell::sync::Lazy;
+
+use crate::Record;
+
+// Keys of fields are interned when records are converted to owned ones (e.g.
+// by asynchronous sinks), so that keys do not allocate again and again. Keys
+// may be built at runtime, the number of interned ones is capped to bound the
+// leaked memory, and other keys are copied.
+const MAX_INTERNED_KEYS: usize = 1024;
+
+static INTERNED_KEYS: Lazy<spin::Mutex<HashSet<&'static str>>> = Lazy::new(Default::default);
+
+// Interned keys recently looked up by this thread, indexed by the addresses of
+// the looked up keys. Keys are mostly string literals at fixed addresses, so
+// this saves hashing them and locking the set most of the time.
+const RECENT_KEYS: usize = 32;
+
+thread_local! {
+    static RECENT_INTERNED_KEYS: [Cell<Option<&'static str>>; RECENT_KEYS] = Default::default();
+}
+
+fn intern_key(key: &str) -> Cow<'static, str> {
+    let index = key.as_ptr() as usize % RECENT_KEYS;
+    RECENT_INTERNED_KEYS
+        .try_with(|recent| {
+            if let Some(interned) = recent[index].get().filter(|interned| *interned == key) {
+                return Cow::Borrowed(interned);
+            }
+            let interned = intern_key_slow(key);
+            if let Cow::Borrowed(interned) = interned {
+                recent[index].set(Some(interned));
+            }
+            interned
+        })
+        .unwrap_or_else(|_| intern_key_slow(key))
+}
+
+fn intern_key_slow(key: &str) -> Cow<'static, str> {
+    let mut interned = INTERNED_KEYS.lock();
+    if let Some(key) = interned.get(key) {
+        return Cow::Borrowed(key);
+    }
+    if interned.len() >= MAX_INTERNED_KEYS {
+        return Cow::Owned(key.to_owned());
+    }
+    let key: &'static str = Box::leak(key.into());
+    interned.insert(key);
+    Cow::Borrowed(key)
+}
+
+/// Represents a key-value pair attached to a log record.
+///
+/// Fields carry structured data along with the payload, formatters decide how
+/// to present them (e.g. [`JsonFormatter`] writes them as JSON members).
+///
+/// [`JsonFormatter`]: crate::formatter::JsonFormatter
+#[derive(Clone, PartialEq, Debug)]
+pub struct Field<'a> {
+    key: Cow<'a, str>,
+    value: Value<'a>,
+}
+
+impl<'a> Field<'a> {
+    /// Constructs a `Field`.
+    pub fn new<K, V>(key: K, value: V) -> Self
+    where
+        K: Into<Cow<'a, str>>,
+        V: Into<Value<'a>>,
+    {
+        Self {
+            key: key.into(),
+            value: value.into(),
+        }
+    }
+
+    /// Gets the key.
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    /// Gets the value.
+    pub fn value(&self) -> &Value<'a> {
+        &self.value
+    }
+
+    pub(crate) fn to_owned(&self) -> Field<'static> {
+        Field {
+            key: intern_key(&self.key),
+            value: self.value.to_owned(),
+        }
+    }
+
+    pub(crate) fn as_borrowed(&self) -> Field<'_> {
+        Field {
+            key: Cow::Borrowed(&self.key),
+            value: self.value.as_borrowed(),
+        }
+    }
+}
+
+/// Represents a typed value of a [`Field`].
+///
+/// Values keep their types, so that formatters can present them properly (e.g.
+/// [`JsonFormatter`] writes numbers and booleans as JSON numbers and booleans
+/// rather than strings). Values of other types can be stored as strings via
+/// [`Value::display`].
+///
+/// The [`Display`] implementation writes strings as they are, numbers and
+/// booleans as Rust does, durations in the `Debug` format (e.g. `1.5s`), and
+/// maps as `{key=value, ...}`.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use spdlog::{Field, Record, Level, Value};
+///
+/// let record = Record::builder(Level::Info, "request served")
+///     .field("path", "/index.html")
+///     .field("status", 200u16)
+///     .field("cached", false)
+///     .field("elapsed", Duration::from_millis(12))
+///     .field(
+///         "client",
+///         Value::Map(vec![
+///             Field::new("ip", "127.0.0.1"),
+///             Field::new("port", 54321u16),
+///         ]),
+///     )
+///     .build();
+/// assert_eq!(record.fields()[1].value(), &Value::U64(200));
+/// ```
+///
+/// [`JsonFormatter`]: crate::formatter::JsonFormatter
+/// [`Display`]: std::fmt::Display
+#[derive(Clone, PartialEq, Debug)]
+pub enum Value<'a> {
+    /// A string.
+    Str(Cow<'a, str>),
+    /// A signed integer.
+    I64(i64),
+    /// An unsigned integer.
+    U64(u64),
+    /// A floating point number.
+    F64(f64),
+    /// A boolean.
+    Bool(bool),
+    /// A duration.
+    Duration(Duration),
+    /// A nested map of fields.
+    Map(Vec<Field<'a>>),
+}
+
+impl<'a> Value<'a> {
+    /// Constructs a string value from a value implementing [`Display`].
+    ///
+    /// [`Display`]: std::fmt::Display
+    pub fn display<T>(value: &T) -> Self
+    where
+        T: fmt::Display + ?Sized,
+    {
+        Value::Str(Cow::Owned(value.to_string()))
+    }
+
+    /// Gets the string if the value is a string.
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::Str(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn to_owned(&self) -> Value<'static> {
+        match self {
+            Value::Str(value) => Value::Str(Cow::Owned(value.to_string())),
+            Value::I64(value) => Value::I64(*value),
+            Value::U64(value) => Value::U64(*value),
+            Value::F64(value) => Value::F64(*value),
+            Value::Bool(value) => Value::Bool(*value),
+            Value::Duration(value) => Value::Duration(*value),
+            Value::Map(fields) => Value::Map(fields.iter().map(Field::to_owned).collect()),
+        }
+    }
+
+    pub(crate) fn as_borrowed(&self) -> Value<'_> {
+        match self {
+            Value::Str(value) => Value::Str(Cow::Borrowed(value)),
+            Value::I64(value) => Value::I64(*value),
+            Value::U64(value) => Value::U64(*value),
+            Value::F64(value) => Value::F64(*value),
+            Value::Bool(value) => Value::Bool(*value),
+            Value::Duration(value) => Value::Duration(*value),
+            Value::Map(fields) => Value::Map(fields.iter().map(Field::as_borrowed).collect()),
+        }
+    }
+}
+
+impl fmt::Display for Value<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Value::Str(value) => f.write_str(value),
+            Value::I64(value) => write!(f, "{}", value),
+            Value::U64(value) => write!(f, "{}", value),
+            Value::F64(value) => write!(f, "{}", value),
+            Value::Bool(value) => write!(f, "{}", value),
+            Value::Duration(value) => write!(f, "{:?}", value),
+            Value::Map(fields) => {
+                f.write_str("{")?;
+                for (index, field) in fields.iter().enumerate() {
+                    if index != 0 {
+                        f.write_str(", ")?;
+                    }
+                    write!(f, "{}={}", field.key(), field.value())?;
+                }
+                f.write_str("}")
+            }
+        }
+    }
+}
+
+macro_rules! impl_from {
+    ( $($variant:ident($as:ty): $($ty:ty),+;)+ ) => {
+        $($(
+            impl From<$ty> for Value<'_> {
+                fn from(value: $ty) -> Self {
+                    Value::$variant(value as $as)
+                }
+            }
+        )+)+
+    };
+}
+
+impl_from! {
+    I64(i64): i8, i16, i32, i64, isize;
+    U64(u64): u8, u16, u32, u64, usize;
+    F64(f64): f32, f64;
+}
+
+impl<'a> From<&'a str> for Value<'a> {
+    fn from(value: &'a str) -> Self {
+        Value::Str(Cow::Borrowed(value))
+    }
+}
+
+impl<'a> From<&'a String> for Value<'a> {
+    fn from(value: &'a String) -> Self {
+        Value::Str(Cow::Borrowed(value))
+    }
+}
+
+impl From<String> for Value<'_> {
+    fn from(value: String) -> Self {
+        Value::Str(Cow::Owned(value))
+    }
+}
+
+impl<'a> From<Cow<'a, str>> for Value<'a> {
+    fn from(value: Cow<'a, str>) -> Self {
+        Value::Str(value)
+    }
+}
+
+// Values out of the range of 64-bit integers are stored as strings.
+impl From<i128> for Value<'_> {
+    fn from(value: i128) -> Self {
+        i64::try_from(value).map_or_else(|_| Value::display(&value), Value::I64)
+    }
+}
+
+impl From<u128> for Value<'_> {
+    fn from(value: u128) -> Self {
+        u64::try_from(value).map_or_else(|_| Value::display(&value), Value::U64)
+    }
+}
+
+impl From<bool> for Value<'_> {
+    fn from(value: bool) -> Self {
+        Value::Bool(value)
+    }
+}
+
+impl From<Duration> for Value<'_> {
+    fn from(value: Duration) -> Self {
+        Value::Duration(value)
+    }
+}
+
+impl<'a> From<Vec<Field<'a>>> for Value<'a> {
+    fn from(fields: Vec<Field<'a>>) -> Self {
+        Value::Map(fields)
+    }
+}
+
+/// A condition on the fields of records.
+///
+/// It is used by [`FieldFilterSink`] to only pass matching records to its
+/// inner sink, e.g. to send only records with `tenant == "acme"` to the sink
+/// of that tenant. Conditions only look at the top-level fields of records,
+/// and integers of different signedness are compared by their values.
+///
+/// # Examples
+///
+/// ```
+/// use spdlog::{FieldFilter, Level, Record};
+///
+/// let filter = FieldFilter::All(vec![
+///     FieldFilter::equals("tenant", "acme"),
+///     FieldFilter::Not(Box::new(FieldFilter::present("health_check"))),
+/// ]);
+///
+/// assert!(filter.matches(&Record::builder(Level::Info, "hello").field("tenant", "acme").build()));
+/// assert!(!filter.matches(&Record::builder(Level::Info, "hello").field("tenant", "other").build()));
+/// ```
+///
+/// [`FieldFilterSink`]: crate::sink::FieldFilterSink
+#[derive(Clone, PartialEq, Debug)]
+pub enum FieldFilter {
+    /// Matches records that have a field with the key.
+    Present(String),
+    /// Matches records that have a field with the key and the value.
+    Equals(String, Value<'static>),
+    /// Matches records that do not match the inner condition.
+    Not(Box<FieldFilter>),
+    /// Matches records that match all of the inner conditions.
+    All(Vec<FieldFilter>),
+    /// Matches records that match any of the inner conditions.
+    Any(Vec<FieldFilter>),
+}
+
+impl FieldFilter {
+    /// Constructs a [`FieldFilter::Present`].
+    pub fn present<K>(key: K) -> Self
+    where
+        K: Into<String>,
+    {
+        FieldFilter::Present(key.into())
+    }
+
+    /// Constructs a [`FieldFilter::Equals`].
+    pub fn equals<K, V>(key: K, value: V) -> Self
+    where
+        K: Into<String>,
+        V: Into<Value<'static>>,
+    {
+        FieldFilter::Equals(key.into(), value.into())
+    }
+
+    /// Checks whether the record matches this condition.
+    pub fn matches(&self, record: &Record) -> bool {
+        match self {
+            FieldFilter::Present(key) => record.fields().iter().any(|field| field.key() == key),
+            FieldFilter::Equals(key, value) => record
+                .fields()
+                .iter()
+                .any(|field| field.key() == key && values_eq(field.value(), value)),
+            FieldFilter::Not(filter) => !filter.matches(record),
+            FieldFilter::All(filters) => filters.iter().all(|filter| filter.matches(record)),
+            FieldFilter::Any(filters) => filters.iter().any(|filter| filter.matches(record)),
+        }
+    }
+}
+
+fn values_eq(lhs: &Value, rhs: &Value) -> bool {
+    match (lhs, rhs) {
+        (Value::I64(lhs), Value::U64(rhs)) | (Value::U64(rhs), Value::I64(lhs)) => {
+            u64::try_from(*lhs) == Ok(*rhs)
+        }
+        _ => lhs == rhs,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Level;
+
+    #[test]
+    fn display() {
+        assert_eq!(Value::from("str").to_string(), "str");
+        assert_eq!(Value::from(-1i8).to_string(), "-1");
+        assert_eq!(Value::from(u64::MAX).to_string(), u64::MAX.to_string());
+        assert_eq!(Value::from(1.5f32).to_string(), "1.5");
+        assert_eq!(Value::from(true).to_string(), "true");
+        assert_eq!(Value::from(Duration::from_millis(1500)).to_string(), "1.5s");
+        assert_eq!(
+            Value::from(vec![Field::new("a", 1), Field::new("b", "c")]).to_string(),
+            "{a=1, b=c}"
+        );
+        assert_eq!(Value::display(&'x'), Value::Str("x".into()));
+    }
+
+    #[test]
+    fn interned_keys() {
+        let field = Field::new(String::from("interned_key"), 1).to_owned();
+        let other = Field::new("interned_key", 2).to_owned();
+        assert!(matches!(field.key, Cow::Borrowed(_)));
+        assert!(std::ptr::eq(field.key(), other.key()));
+        assert_eq!(field.key(), "interned_key");
+    }
+
+    #[test]
+    fn field_filter() {
+        let record = Record::builder(Level::Info, "payload")
+            .field("tenant", "acme")
+            .field("id", 1i32)
+            .build();
+
+        assert!(FieldFilter::present("tenant").matches(&record));
+        assert!(!FieldFilter::present("user").matches(&record));
+        assert!(FieldFilter::equals("tenant", "acme").matches(&record));
+        assert!(!FieldFilter::equals("tenant", "other").matches(&record));
+        assert!(FieldFilter::equals("id", 1u64).matches(&record));
+        assert!(!FieldFilter::equals("id", "1").matches(&record));
+        assert!(FieldFilter::Not(Box::new(FieldFilter::present("user"))).matches(&record));
+        assert!(FieldFilter::All(vec![
+            FieldFilter::present("tenant"),
+            FieldFilter::equals("id", 1)
+        ])
+        .matches(&record));
+        assert!(!FieldFilter::All(vec![
+            FieldFilter::present("tenant"),
+            FieldFilter::present("user")
+        ])
+        .matches(&record));
+        assert!(FieldFilter::Any(vec![
+            FieldFilter::present("user"),
+            FieldFilter::present("tenant")
+        ])
+        .matches(&record));
+    }
+}
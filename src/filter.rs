@@ -0,0 +1,142 @@
+//! Provides a trait for filtering records.
+
+use std::sync::Arc;
+
+use crate::{FieldFilter, LevelFilter, Record};
+
+/// The decision of a [`Filter`] on a record.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum Decision {
+    /// Logs the record without consulting the remaining filters.
+    Accept,
+    /// Leaves the decision to the remaining filters. A record is logged if all
+    /// filters are neutral.
+    Neutral,
+    /// Discards the record without consulting the remaining filters.
+    Reject,
+}
+
+impl From<bool> for Decision {
+    /// Converts `true` to [`Decision::Neutral`] and `false` to
+    /// [`Decision::Reject`], which is the decision of a plain condition.
+    fn from(matched: bool) -> Self {
+        if matched {
+            Decision::Neutral
+        } else {
+            Decision::Reject
+        }
+    }
+}
+
+/// A trait for filters deciding whether records are logged.
+///
+/// It unifies the filtering mechanisms of this crate, so that they can be
+/// combined into a chain via [`LoggerBuilder::filter`] and [`FilterSink`]. A
+/// chain is consulted in order until a filter accepts or rejects the record.
+///
+/// It is implemented for:
+///
+/// - [`LevelFilter`] and [`FieldFilter`], which are neutral on matching records
+///   and reject the others.
+///
+/// - Closures `Fn(&Record) -> Decision`, use [`Decision::from`] to convert a
+///   `bool` condition.
+///
+/// # Examples
+///
+/// ```
+/// use spdlog::{filter::Decision, prelude::*, FieldFilter};
+///
+/// let logger: Logger = Logger::builder()
+///     // Always logs records of the audit module.
+///     .filter(|record: &spdlog::Record| match record.source_location() {
+///         Some(srcloc) if srcloc.module_path().starts_with("app::audit") => Decision::Accept,
+///         _ => Decision::Neutral,
+///     })
+///     // Otherwise, discards records of health checks.
+///     .filter(FieldFilter::Not(Box::new(FieldFilter::present("health_check"))))
+///     .build();
+/// ```
+///
+/// [`LoggerBuilder::filter`]: crate::LoggerBuilder::filter
+/// [`FilterSink`]: crate::sink::FilterSink
+pub trait Filter: Send + Sync {
+    /// Decides whether the record is logged.
+    fn should_log(&self, record: &Record) -> Decision;
+}
+
+impl Filter for LevelFilter {
+    fn should_log(&self, record: &Record) -> Decision {
+        self.compare(record.level()).into()
+    }
+}
+
+impl Filter for FieldFilter {
+    fn should_log(&self, record: &Record) -> Decision {
+        self.matches(record).into()
+    }
+}
+
+impl<F> Filter for F
+where
+    F: Fn(&Record) -> Decision + Send + Sync,
+{
+    fn should_log(&self, record: &Record) -> Decision {
+        self(record)
+    }
+}
+
+impl Filter for Arc<dyn Filter> {
+    fn should_log(&self, record: &Record) -> Decision {
+        (**self).should_log(record)
+    }
+}
+
+// Consults the filters in order, returns whether the record is logged.
+pub(crate) fn apply<'a, I>(filters: I, record: &Record) -> bool
+where
+    I: IntoIterator<Item = &'a Arc<dyn Filter>>,
+{
+    for filter in filters {
+        match filter.should_log(record) {
+            Decision::Accept => return true,
+            Decision::Neutral => {}
+            Decision::Reject => return false,
+        }
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Level;
+
+    #[test]
+    fn chain() {
+        let filters: Vec<Arc<dyn Filter>> = vec![
+            Arc::new(|record: &Record| {
+                if record.payload() == "vip" {
+                    Decision::Accept
+                } else {
+                    Decision::Neutral
+                }
+            }),
+            Arc::new(LevelFilter::MoreSevereEqual(Level::Warn)),
+            Arc::new(FieldFilter::present("tenant")),
+        ];
+
+        let record = |level, payload| Record::builder(level, payload).build();
+        let with_tenant = |level, payload| {
+            Record::builder(level, payload)
+                .field("tenant", "acme")
+                .build()
+        };
+
+        assert!(apply(&filters, &record(Level::Trace, "vip")));
+        assert!(!apply(&filters, &with_tenant(Level::Info, "hello")));
+        assert!(!apply(&filters, &record(Level::Warn, "hello")));
+        assert!(apply(&filters, &with_tenant(Level::Warn, "hello")));
+        assert!(apply(&[], &record(Level::Trace, "hello")));
+    }
+}
@@ -0,0 +1,316 @@
+//! Provides HTTP access log formatters.
+
+use std::fmt::{self, Write};
+
+use crate::{
+    formatter::{FmtExtraInfo, Formatter, FormatterContext},
+    Error, Record, StringBuf, TimeZone, Value,
+};
+
+const MONTH_NAMES: [&str; 12] = [
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+
+/// An HTTP access log records formatter producing the [Common Log Format] of
+/// Apache and Nginx.
+///
+/// Log messages formatted by it look like:
+///
+/// ```text
+/// 127.0.0.1 - frank [10/Oct/2000:13:55:36 -0700] "GET /apache_pb.gif HTTP/1.0" 200 2326
+/// ```
+///
+/// The parts are taken from the fields of records:
+///
+/// | Part             | Field key     |
+/// |------------------|---------------|
+/// | remote address   | `remote_addr` |
+/// | authorized user  | `user`        |
+/// | request method   | `method`      |
+/// | request path     | `path`        |
+/// | request protocol | `protocol`    |
+/// | response status  | `status`      |
+/// | response size    | `bytes`       |
+///
+/// Missing parts are written as `-`, as well as a response size of `0`. If the
+/// method or the path is missing, the payload is written as the request line
+/// instead. Quoted parts are escaped in the same way as Apache, i.e. `"` and
+/// `\` are escaped by a backslash, and control characters are written as
+/// `\xhh`. The time is the time of the record, presented in the local time
+/// zone by default.
+///
+/// Other fields, the level and the logger name of records are ignored.
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::Arc;
+///
+/// use spdlog::{
+///     formatter::CommonLogFormatter,
+///     prelude::*,
+///     sink::{Sink, StdStream, StdStreamSink},
+///     terminal_style::StyleMode,
+/// };
+///
+/// let sink = StdStreamSink::new(StdStream::Stdout, StyleMode::Never);
+/// sink.set_formatter(Box::new(CommonLogFormatter::new()));
+/// let access_logger: Logger = Logger::builder().sink(Arc::new(sink)).build();
+///
+/// info!(
+///     logger: access_logger,
+///     remote_addr = "127.0.0.1",
+///     method = "GET",
+///     path = "/index.html",
+///     protocol = "HTTP/1.1",
+///     status = 200,
+///     bytes = 2326;
+///     ""
+/// );
+/// ```
+///
+/// [Common Log Format]: https://httpd.apache.org/docs/current/logs.html#common
+#[derive(Clone)]
+pub struct CommonLogFormatter {
+    time_zone: TimeZone,
+}
+
+impl CommonLogFormatter {
+    /// Constructs a `CommonLogFormatter`.
+    pub fn new() -> CommonLogFormatter {
+        CommonLogFormatter {
+            time_zone: TimeZone::Local,
+        }
+    }
+
+    /// Sets the time zone in which times are presented.
+    ///
+    /// The default is [`TimeZone::Local`].
+    #[must_use]
+    pub fn time_zone(mut self, time_zone: TimeZone) -> Self {
+        self.time_zone = time_zone;
+        self
+    }
+
+    fn format_impl(&self, record: &Record, dest: &mut StringBuf) -> fmt::Result {
+        let field = |key| {
+            record
+                .fields()
+                .iter()
+                .find(|field| field.key() == key)
+                .map(|field| field.value())
+        };
+
+        write_plain(dest, field("remote_addr"))?;
+        dest.write_str(" - ")?;
+        write_plain(dest, field("user"))?;
+
+        let time = self.time_zone.wall_time(record.time());
+        let offset = self.time_zone.utc_offset(record.time());
+        write!(
+            dest,
+            " [{:02}/{}/{}:{:02}:{:02}:{:02} {}{:02}{:02}] ",
+            time.day,
+            MONTH_NAMES[time.month as usize - 1],
+            time.year,
+            time.hour,
+            time.minute,
+            time.second,
+            if offset < 0 { '-' } else { '+' },
+            offset.abs() / 3600,
+            offset.abs() / 60 % 60,
+        )?;
+
+        dest.write_char('"')?;
+        match (field("method"), field("path")) {
+            (Some(method), Some(path)) => {
+                write_escaped(dest, &method.to_string())?;
+                dest.write_char(' ')?;
+                write_escaped(dest, &path.to_string())?;
+                if let Some(protocol) = field("protocol") {
+                    dest.write_char(' ')?;
+                    write_escaped(dest, &protocol.to_string())?;
+                }
+            }
+            _ if record.payload().is_empty() => dest.write_char('-')?,
+            _ => write_escaped(dest, record.payload())?,
+        }
+        dest.write_str("\" ")?;
+
+        write_plain(dest, field("status"))?;
+        dest.write_char(' ')?;
+        match field("bytes") {
+            Some(Value::I64(0)) | Some(Value::U64(0)) => dest.write_char('-'),
+            bytes => write_plain(dest, bytes),
+        }
+    }
+}
+
+impl Formatter for CommonLogFormatter {
+    fn format(
+        &self,
+        record: &Record,
+        dest: &mut StringBuf,
+        _ctx: &FormatterContext,
+    ) -> crate::Result<FmtExtraInfo> {
+        self.format_impl(record, dest)
+            .map_err(Error::FormatRecord)?;
+        Ok(FmtExtraInfo::new())
+    }
+}
+
+impl Default for CommonLogFormatter {
+    fn default() -> CommonLogFormatter {
+        CommonLogFormatter::new()
+    }
+}
+
+/// An HTTP access log records formatter producing the [Combined Log Format] of
+/// Apache and Nginx.
+///
+/// Log messages formatted by it look like:
+///
+/// ```text
+/// 127.0.0.1 - frank [10/Oct/2000:13:55:36 -0700] "GET /apache_pb.gif HTTP/1.0" 200 2326 "http://www.example.com/start.html" "Mozilla/4.08 [en] (Win98; I ;Nav)"
+/// ```
+///
+/// It is the [Common Log Format] followed by the referer and the user agent,
+/// which are taken from fields `referer` and `user_agent`. See
+/// [`CommonLogFormatter`] for the other parts.
+///
+/// [Combined Log Format]: https://httpd.apache.org/docs/current/logs.html#combined
+/// [Common Log Format]: https://httpd.apache.org/docs/current/logs.html#common
+#[derive(Clone, Default)]
+pub struct CombinedLogFormatter {
+    common: CommonLogFormatter,
+}
+
+impl CombinedLogFormatter {
+    /// Constructs a `CombinedLogFormatter`.
+    pub fn new() -> CombinedLogFormatter {
+        CombinedLogFormatter::default()
+    }
+
+    /// Sets the time zone in which times are presented.
+    ///
+    /// The default is [`TimeZone::Local`].
+    #[must_use]
+    pub fn time_zone(mut self, time_zone: TimeZone) -> Self {
+        self.common = self.common.time_zone(time_zone);
+        self
+    }
+
+    fn format_impl(&self, record: &Record, dest: &mut StringBuf) -> fmt::Result {
+        self.common.format_impl(record, dest)?;
+
+        for key in ["referer", "user_agent"] {
+            dest.write_str(" \"")?;
+            match record.fields().iter().find(|field| field.key() == key) {
+                Some(field) => write_escaped(dest, &field.value().to_string())?,
+                None => dest.write_char('-')?,
+            }
+            dest.write_char('"')?;
+        }
+        Ok(())
+    }
+}
+
+impl Formatter for CombinedLogFormatter {
+    fn format(
+        &self,
+        record: &Record,
+        dest: &mut StringBuf,
+        _ctx: &FormatterContext,
+    ) -> crate::Result<FmtExtraInfo> {
+        self.format_impl(record, dest)
+            .map_err(Error::FormatRecord)?;
+        Ok(FmtExtraInfo::new())
+    }
+}
+
+// Writes an unquoted part, whitespaces and control characters are replaced
+// with `_` so that parts stay separated.
+fn write_plain(dest: &mut StringBuf, value: Option<&Value>) -> fmt::Result {
+    let value = match value {
+        Some(value) => value.to_string(),
+        None => return dest.write_char('-'),
+    };
+    if value.is_empty() {
+        return dest.write_char('-');
+    }
+    for ch in value.chars() {
+        if ch.is_whitespace() || ch.is_control() {
+            dest.write_char('_')?;
+        } else {
+            dest.write_char(ch)?;
+        }
+    }
+    Ok(())
+}
+
+// Writes the content of a quoted part, escaped in the same way as Apache.
+fn write_escaped(dest: &mut StringBuf, value: &str) -> fmt::Result {
+    for ch in value.chars() {
+        match ch {
+            '"' => dest.write_str("\\\"")?,
+            '\\' => dest.write_str("\\\\")?,
+            ch if ch.is_ascii_control() => write!(dest, "\\x{:02x}", ch as u32)?,
+            ch => dest.write_char(ch)?,
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, SystemTime};
+
+    use super::*;
+    use crate::Level;
+
+    fn format<F: Formatter>(formatter: F, record: &Record) -> String {
+        let mut buf = StringBuf::new();
+        formatter
+            .format(record, &mut buf, &FormatterContext::default())
+            .unwrap();
+        buf.to_string()
+    }
+
+    #[test]
+    fn format_common() {
+        let mut record = Record::builder(Level::Info, "")
+            .field("remote_addr", "127.0.0.1")
+            .field("user", "frank")
+            .field("method", "GET")
+            .field("path", "/apache_pb.gif")
+            .field("protocol", "HTTP/1.0")
+            .field("status", 200)
+            .field("bytes", 2326u64)
+            .build();
+        record.set_time(SystemTime::UNIX_EPOCH + Duration::from_secs(971_186_136));
+
+        assert_eq!(
+            format(CommonLogFormatter::new().time_zone(TimeZone::Utc), &record),
+            r#"127.0.0.1 - frank [10/Oct/2000:13:55:36 +0000] "GET /apache_pb.gif HTTP/1.0" 200 2326"#
+        );
+    }
+
+    #[test]
+    fn format_combined() {
+        let mut record = Record::builder(Level::Info, "GET /\"quoted\" HTTP/1.1")
+            .field("remote_addr", "::1")
+            .field("status", 404)
+            .field("bytes", 0)
+            .field("user_agent", "curl/8.0\t(x)")
+            .build();
+        record.set_time(SystemTime::UNIX_EPOCH + Duration::from_secs(971_186_136));
+
+        assert_eq!(
+            format(
+                CombinedLogFormatter::new().time_zone(TimeZone::Utc),
+                &record
+            ),
+            r#"::1 - - [10/Oct/2000:13:55:36 +0000] "GET /\"quoted\" HTTP/1.1" 404 - "-" "curl/8.0\x09(x)""#
+        );
+    }
+}
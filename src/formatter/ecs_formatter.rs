@@ -0,0 +1,245 @@
+//! Provides an Elastic Common Schema (ECS) JSON formatter.
+
+use std::fmt::{self, Write};
+
+use crate::{
+    formatter::{json_formatter, BytesRendering, FmtExtraInfo, Formatter, FormatterContext},
+    Error, Record, StringBuf, TimeZone,
+};
+
+/// The version of [Elastic Common Schema] the output of [`EcsFormatter`]
+/// complies with.
+///
+/// [Elastic Common Schema]: https://www.elastic.co/guide/en/ecs/current/index.html
+pub const ECS_VERSION: &str = "8.11.0";
+
+/// An [Elastic Common Schema] (ECS) JSON log records formatter.
+///
+/// Each record is formatted as a single line JSON object following the
+/// [ECS logging] specification, so that records shipped to Elasticsearch (e.g.
+/// by Filebeat or Logstash) are indexed without any ingest pipeline
+/// configuration.
+///
+/// Log messages formatted by it look like this by default:
+///
+/// ```json
+/// {"@timestamp":"2021-12-23T01:23:45.067Z","log.level":"info","message":"log message","ecs.version":"8.11.0","event":{"sequence":42}}
+/// ```
+///
+/// If the logger has a name:
+///
+/// ```json
+/// {"@timestamp":"2021-12-23T01:23:45.067Z","log.level":"info","message":"log message","ecs.version":"8.11.0","event":{"sequence":42},"log":{"logger":"logger-name"}}
+/// ```
+///
+/// If crate feature `source-location` is enabled:
+///
+/// ```json
+/// {"@timestamp":"2021-12-23T01:23:45.067Z","log.level":"info","message":"log message","ecs.version":"8.11.0","event":{"sequence":42},"log":{"origin":{"file":{"name":"src/main.rs","line":2},"function":"crate::mod"}}}
+/// ```
+///
+/// If the record has fields, they are presented as members of the top-level
+/// object, so that fields with ECS names (e.g. `user.id` or
+/// `http.request.method`) are mapped to the corresponding ECS fields. Field
+/// values are presented in the same way as [`JsonFormatter`].
+///
+/// If the record has an [error code], it is presented as a string in the
+/// `error.code` key. If the record has a [backtrace], it is presented as a
+/// string in the `error.stack_trace` key.
+///
+/// The time is always presented in UTC.
+///
+/// [Elastic Common Schema]: https://www.elastic.co/guide/en/ecs/current/index.html
+/// [ECS logging]: https://www.elastic.co/guide/en/ecs-logging/overview/current/intro.html
+/// [`JsonFormatter`]: crate::formatter::JsonFormatter
+/// [error code]: crate::Record::code
+/// [backtrace]: crate::Record::backtrace
+#[derive(Clone, Default)]
+pub struct EcsFormatter {
+    bytes_rendering: BytesRendering,
+}
+
+impl EcsFormatter {
+    /// Constructs an `EcsFormatter`.
+    pub fn new() -> EcsFormatter {
+        EcsFormatter::default()
+    }
+
+    /// Sets how to render payloads of records constructed from raw bytes.
+    ///
+    /// The default is [`BytesRendering::LossyUtf8`].
+    #[must_use]
+    pub fn bytes_rendering(mut self, bytes_rendering: BytesRendering) -> Self {
+        self.bytes_rendering = bytes_rendering;
+        self
+    }
+
+    fn format_impl(
+        &self,
+        record: &Record,
+        dest: &mut StringBuf,
+    ) -> Result<FmtExtraInfo, fmt::Error> {
+        let time = TimeZone::Utc.wall_time(record.time());
+
+        write!(
+            dest,
+            "{{\"@timestamp\":\"{}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z\",\"log.level\":\"{}\"",
+            time.year,
+            time.month,
+            time.day,
+            time.hour,
+            time.minute,
+            time.second,
+            time.nanosecond / 1_000_000,
+            record.level().as_str(),
+        )?;
+
+        dest.write_str(",\"message\":")?;
+        json_formatter::write_json_str(dest, &self.bytes_rendering.render_payload(record))?;
+
+        write!(
+            dest,
+            ",\"ecs.version\":\"{}\",\"event\":{{\"sequence\":{}}}",
+            ECS_VERSION,
+            record.sequence()
+        )?;
+
+        if record.logger_name().is_some() || record.source_location().is_some() {
+            dest.write_str(",\"log\":{")?;
+            if let Some(logger_name) = record.logger_name() {
+                dest.write_str("\"logger\":")?;
+                json_formatter::write_json_str(dest, logger_name)?;
+                if record.source_location().is_some() {
+                    dest.write_char(',')?;
+                }
+            }
+            if let Some(srcloc) = record.source_location() {
+                if srcloc.is_hashed() {
+                    write!(
+                        dest,
+                        "\"origin\":{{\"file\":{{\"name\":\"#{:016x}\"}},\"function\":",
+                        srcloc.id()
+                    )?;
+                } else {
+                    dest.write_str("\"origin\":{\"file\":{\"name\":")?;
+                    json_formatter::write_json_str(dest, srcloc.file())?;
+                    write!(dest, ",\"line\":{}}},\"function\":", srcloc.line())?;
+                }
+                json_formatter::write_json_str(dest, srcloc.module_path())?;
+                dest.write_char('}')?;
+            }
+            dest.write_char('}')?;
+        }
+
+        if record.code().is_some() || record.backtrace().is_some() {
+            dest.write_str(",\"error\":{")?;
+            if let Some(code) = record.code() {
+                dest.write_str("\"code\":")?;
+                json_formatter::write_json_str(dest, &code.to_string())?;
+                if record.backtrace().is_some() {
+                    dest.write_char(',')?;
+                }
+            }
+            if let Some(backtrace) = record.backtrace() {
+                dest.write_str("\"stack_trace\":")?;
+                json_formatter::write_json_str(dest, &backtrace.to_string())?;
+            }
+            dest.write_char('}')?;
+        }
+
+        for field in record.fields() {
+            dest.write_char(',')?;
+            json_formatter::write_json_field(dest, field)?;
+        }
+
+        dest.write_char('}')?;
+
+        Ok(FmtExtraInfo::new())
+    }
+}
+
+impl Formatter for EcsFormatter {
+    fn format(
+        &self,
+        record: &Record,
+        dest: &mut StringBuf,
+        _ctx: &FormatterContext,
+    ) -> crate::Result<FmtExtraInfo> {
+        self.format_impl(record, dest).map_err(Error::FormatRecord)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Field, Level};
+
+    use std::time::{Duration, SystemTime};
+
+    #[test]
+    fn format() {
+        let mut record = Record::builder(Level::Warn, "test \"log\"")
+            .logger_name("logger-name")
+            .field("user.id", "alice")
+            .field("http.response.status_code", 503)
+            .field("map", vec![Field::new("a", 1.5)])
+            .build();
+        record.set_time(SystemTime::UNIX_EPOCH + Duration::from_millis(1_600_000_000_123));
+        let mut buf = StringBuf::new();
+        let extra_info = EcsFormatter::new()
+            .format(&record, &mut buf, &FormatterContext::default())
+            .unwrap();
+
+        assert_eq!(
+            format!(
+                r#"{{"@timestamp":"2020-09-13T12:26:40.123Z","log.level":"warn","message":"test \"log\"","ecs.version":"8.11.0","event":{{"sequence":{}}},"log":{{"logger":"logger-name"}},"user.id":"alice","http.response.status_code":503,"map":{{"a":1.5}}}}"#,
+                record.sequence()
+            ),
+            buf
+        );
+        assert_eq!(None, extra_info.style_range());
+    }
+
+    #[test]
+    fn format_code() {
+        let record = Record::builder(Level::Error, "hello").code(1042).build();
+        let mut buf = StringBuf::new();
+        EcsFormatter::new()
+            .format(&record, &mut buf, &FormatterContext::default())
+            .unwrap();
+
+        assert!(buf.ends_with(r#""error":{"code":"1042"}}"#));
+    }
+
+    #[test]
+    fn format_source_location() {
+        let record = Record::builder(Level::Info, "hello")
+            .source_location(Some(crate::SourceLocation::new(
+                "crate::module",
+                "src/module.rs",
+                42,
+                8,
+            )))
+            .build();
+        let mut buf = StringBuf::new();
+        EcsFormatter::new()
+            .format(&record, &mut buf, &FormatterContext::default())
+            .unwrap();
+
+        assert!(buf.ends_with(
+            r#""log":{"origin":{"file":{"name":"src/module.rs","line":42},"function":"crate::module"}}}"#
+        ));
+
+        let record = Record::builder(Level::Info, "hello")
+            .source_location(Some(crate::SourceLocation::__hashed("crate::module", 0xff)))
+            .build();
+        let mut buf = StringBuf::new();
+        EcsFormatter::new()
+            .format(&record, &mut buf, &FormatterContext::default())
+            .unwrap();
+
+        assert!(buf.ends_with(
+            r##""log":{"origin":{"file":{"name":"#00000000000000ff"},"function":"crate::module"}}}"##
+        ));
+    }
+}
@@ -0,0 +1,620 @@
+//! Provides a gallery of commonly requested formatters.
+
+use std::{
+    fmt::{self, Write},
+    ops::Range,
+};
+
+use crate::{
+    formatter::{BytesRendering, FmtExtraInfo, Formatter, FormatterContext, TimePrecision},
+    terminal_style::{Color, Style, StyleCode},
+    time_zone::WallTime,
+    Error, Field, Level, LevelNameStyle, Record, SourceLocation, StringBuf, TimeZone, Value, EOL,
+};
+
+/// A compact one-line log records formatter.
+///
+/// Log messages formatted by it look like:
+///
+///  - Default:
+///
+///    `01:23:45.067 I log message`
+///
+///  - If the logger has a name:
+///
+///    `01:23:45.067 I logger-name: log message`
+///
+///  - If the record has fields:
+///
+///    `01:23:45.067 I log message key1=value1 key2=value2`
+///
+/// Only the time of day is written, and source locations and backtraces are
+/// omitted, which suits interactive use where the date is known and the width
+/// of the terminal is limited.
+///
+/// This formatter is available with the crate feature `formatters-extra`.
+#[derive(Clone)]
+pub struct CompactFormatter {
+    time_zone: TimeZone,
+    time_precision: TimePrecision,
+    bytes_rendering: BytesRendering,
+}
+
+impl CompactFormatter {
+    /// Constructs a `CompactFormatter`.
+    ///
+    /// By default, times are presented in the local time zone with
+    /// milliseconds.
+    pub fn new() -> CompactFormatter {
+        CompactFormatter {
+            time_zone: TimeZone::Local,
+            time_precision: TimePrecision::Millis,
+            bytes_rendering: BytesRendering::LossyUtf8,
+        }
+    }
+
+    /// Sets the time zone in which times are presented.
+    #[must_use]
+    pub fn time_zone(mut self, time_zone: TimeZone) -> Self {
+        self.time_zone = time_zone;
+        self
+    }
+
+    /// Sets the precision of fractional seconds of times.
+    #[must_use]
+    pub fn time_precision(mut self, time_precision: TimePrecision) -> Self {
+        self.time_precision = time_precision;
+        self
+    }
+
+    /// Sets how to render payloads of records constructed from raw bytes.
+    #[must_use]
+    pub fn bytes_rendering(mut self, bytes_rendering: BytesRendering) -> Self {
+        self.bytes_rendering = bytes_rendering;
+        self
+    }
+
+    fn format_impl(
+        &self,
+        record: &Record,
+        dest: &mut StringBuf,
+    ) -> Result<FmtExtraInfo, fmt::Error> {
+        let time = self.time_zone.wall_time(record.time());
+        write_time_of_day(dest, &time, self.time_precision)?;
+        dest.write_char(' ')?;
+        let style_range = write_level(dest, record.level(), LevelNameStyle::Char)?;
+        dest.write_char(' ')?;
+
+        if let Some(logger_name) = record.logger_name() {
+            dest.write_str(logger_name)?;
+            dest.write_str(": ")?;
+        }
+        dest.write_str(&self.bytes_rendering.render_payload(record))?;
+        write_fields(dest, record.fields(), None)?;
+
+        Ok(FmtExtraInfo::builder().style_range(style_range).build())
+    }
+}
+
+impl Formatter for CompactFormatter {
+    fn format(
+        &self,
+        record: &Record,
+        dest: &mut StringBuf,
+        _ctx: &FormatterContext,
+    ) -> crate::Result<FmtExtraInfo> {
+        self.format_impl(record, dest).map_err(Error::FormatRecord)
+    }
+}
+
+impl Default for CompactFormatter {
+    fn default() -> CompactFormatter {
+        CompactFormatter::new()
+    }
+}
+
+/// A multi-line log records formatter with indentation, for human reading.
+///
+/// Log messages formatted by it look like:
+///
+/// ```text
+/// 2021-12-23 01:23:45.067 [info] [logger-name]
+///     log message
+///     at crate::mod, main.rs:2
+///     key1: value1
+///     key2:
+///         nested: value2
+/// ```
+///
+/// The header line is followed by the lines of the payload, the source
+/// location (if crate feature `source-location` is enabled), the fields (with
+/// nested maps on their own lines) and the [backtrace], each indented. Fields
+/// are written in [`Display`] representation.
+///
+/// This formatter is available with the crate feature `formatters-extra`.
+///
+/// [backtrace]: crate::Record::backtrace
+/// [`Display`]: std::fmt::Display
+#[derive(Clone)]
+pub struct PrettyFormatter {
+    time_zone: TimeZone,
+    time_precision: TimePrecision,
+    bytes_rendering: BytesRendering,
+    indent: usize,
+}
+
+impl PrettyFormatter {
+    /// Constructs a `PrettyFormatter`.
+    ///
+    /// By default, times are presented in the local time zone with
+    /// milliseconds, and lines are indented by 4 spaces.
+    pub fn new() -> PrettyFormatter {
+        PrettyFormatter {
+            time_zone: TimeZone::Local,
+            time_precision: TimePrecision::Millis,
+            bytes_rendering: BytesRendering::LossyUtf8,
+            indent: 4,
+        }
+    }
+
+    /// Sets the time zone in which times are presented.
+    #[must_use]
+    pub fn time_zone(mut self, time_zone: TimeZone) -> Self {
+        self.time_zone = time_zone;
+        self
+    }
+
+    /// Sets the precision of fractional seconds of times.
+    #[must_use]
+    pub fn time_precision(mut self, time_precision: TimePrecision) -> Self {
+        self.time_precision = time_precision;
+        self
+    }
+
+    /// Sets how to render payloads of records constructed from raw bytes.
+    #[must_use]
+    pub fn bytes_rendering(mut self, bytes_rendering: BytesRendering) -> Self {
+        self.bytes_rendering = bytes_rendering;
+        self
+    }
+
+    /// Sets the number of spaces per level of indentation.
+    #[must_use]
+    pub fn indent(mut self, indent: usize) -> Self {
+        self.indent = indent;
+        self
+    }
+
+    fn format_impl(
+        &self,
+        record: &Record,
+        dest: &mut StringBuf,
+    ) -> Result<FmtExtraInfo, fmt::Error> {
+        let time = self.time_zone.wall_time(record.time());
+        write_date_time(dest, &time, ' ', self.time_precision)?;
+        dest.write_str(" [")?;
+        let style_range = write_level(dest, record.level(), LevelNameStyle::Full)?;
+        dest.write_char(']')?;
+        if let Some(logger_name) = record.logger_name() {
+            dest.write_str(" [")?;
+            dest.write_str(logger_name)?;
+            dest.write_char(']')?;
+        }
+
+        for line in self.bytes_rendering.render_payload(record).lines() {
+            self.write_line_start(dest, 1)?;
+            dest.write_str(line)?;
+        }
+
+        if let Some(srcloc) = record.source_location() {
+            self.write_line_start(dest, 1)?;
+            dest.write_str("at ")?;
+            write_source_location(dest, srcloc)?;
+        }
+
+        self.write_fields(dest, record.fields(), 1)?;
+
+        if let Some(backtrace) = record.backtrace() {
+            self.write_line_start(dest, 1)?;
+            dest.write_str("backtrace:")?;
+            for line in backtrace.to_string().lines() {
+                self.write_line_start(dest, 2)?;
+                dest.write_str(line)?;
+            }
+        }
+
+        Ok(FmtExtraInfo::builder().style_range(style_range).build())
+    }
+
+    fn write_fields(&self, dest: &mut StringBuf, fields: &[Field], depth: usize) -> fmt::Result {
+        for field in fields {
+            self.write_line_start(dest, depth)?;
+            dest.write_str(field.key())?;
+            dest.write_char(':')?;
+            match field.value() {
+                Value::Map(fields) => self.write_fields(dest, fields, depth + 1)?,
+                value => write!(dest, " {}", value)?,
+            }
+        }
+        Ok(())
+    }
+
+    fn write_line_start(&self, dest: &mut StringBuf, depth: usize) -> fmt::Result {
+        dest.write_str(EOL)?;
+        write!(dest, "{:1$}", "", self.indent * depth)
+    }
+}
+
+impl Formatter for PrettyFormatter {
+    fn format(
+        &self,
+        record: &Record,
+        dest: &mut StringBuf,
+        _ctx: &FormatterContext,
+    ) -> crate::Result<FmtExtraInfo> {
+        self.format_impl(record, dest).map_err(Error::FormatRecord)
+    }
+}
+
+impl Default for PrettyFormatter {
+    fn default() -> PrettyFormatter {
+        PrettyFormatter::new()
+    }
+}
+
+/// A one-line log records formatter highlighting keys of fields.
+///
+/// Log messages are formatted like [`FullFormatter`], with keys of fields
+/// wrapped in ANSI escape codes of a style (by default, cyan text):
+///
+/// `[2021-12-23 01:23:45.067] [info] log message \x1b[36mkey1\x1b[m=value1`
+///
+/// Unlike the style of levels, which is applied by sinks only if the target
+/// supports it, the escape codes are always written by the formatter. Use it
+/// for sinks writing to terminals only.
+///
+/// This formatter is available with the crate feature `formatters-extra`.
+///
+/// [`FullFormatter`]: crate::formatter::FullFormatter
+#[derive(Clone)]
+pub struct KeyHighlightFormatter {
+    time_zone: TimeZone,
+    time_precision: TimePrecision,
+    bytes_rendering: BytesRendering,
+    key_style: StyleCode,
+}
+
+impl KeyHighlightFormatter {
+    /// Constructs a `KeyHighlightFormatter`.
+    ///
+    /// By default, times are presented in the local time zone with
+    /// milliseconds, and keys are highlighted in cyan.
+    pub fn new() -> KeyHighlightFormatter {
+        KeyHighlightFormatter {
+            time_zone: TimeZone::Local,
+            time_precision: TimePrecision::Millis,
+            bytes_rendering: BytesRendering::LossyUtf8,
+            key_style: Style::builder().color(Color::Cyan).build().code(),
+        }
+    }
+
+    /// Sets the time zone in which times are presented.
+    #[must_use]
+    pub fn time_zone(mut self, time_zone: TimeZone) -> Self {
+        self.time_zone = time_zone;
+        self
+    }
+
+    /// Sets the precision of fractional seconds of times.
+    #[must_use]
+    pub fn time_precision(mut self, time_precision: TimePrecision) -> Self {
+        self.time_precision = time_precision;
+        self
+    }
+
+    /// Sets how to render payloads of records constructed from raw bytes.
+    #[must_use]
+    pub fn bytes_rendering(mut self, bytes_rendering: BytesRendering) -> Self {
+        self.bytes_rendering = bytes_rendering;
+        self
+    }
+
+    /// Sets the style of keys of fields.
+    #[must_use]
+    pub fn key_style(mut self, style: Style) -> Self {
+        self.key_style = style.code();
+        self
+    }
+
+    fn format_impl(
+        &self,
+        record: &Record,
+        dest: &mut StringBuf,
+    ) -> Result<FmtExtraInfo, fmt::Error> {
+        let time = self.time_zone.wall_time(record.time());
+        dest.write_char('[')?;
+        write_date_time(dest, &time, ' ', self.time_precision)?;
+        dest.write_str("] [")?;
+        if let Some(logger_name) = record.logger_name() {
+            dest.write_str(logger_name)?;
+            dest.write_str("] [")?;
+        }
+        let style_range = write_level(dest, record.level(), LevelNameStyle::Full)?;
+        if let Some(srcloc) = record.source_location() {
+            dest.write_str("] [")?;
+            write_source_location(dest, srcloc)?;
+        }
+        dest.write_str("] ")?;
+
+        dest.write_str(&self.bytes_rendering.render_payload(record))?;
+        write_fields(dest, record.fields(), Some(&self.key_style))?;
+
+        Ok(FmtExtraInfo::builder().style_range(style_range).build())
+    }
+}
+
+impl Formatter for KeyHighlightFormatter {
+    fn format(
+        &self,
+        record: &Record,
+        dest: &mut StringBuf,
+        _ctx: &FormatterContext,
+    ) -> crate::Result<FmtExtraInfo> {
+        self.format_impl(record, dest).map_err(Error::FormatRecord)
+    }
+}
+
+impl Default for KeyHighlightFormatter {
+    fn default() -> KeyHighlightFormatter {
+        KeyHighlightFormatter::new()
+    }
+}
+
+/// A one-line log records formatter with [RFC 3339] times in UTC.
+///
+/// Log messages formatted by it look like:
+///
+///  - Default:
+///
+///    `2021-12-23T01:23:45.067Z info log message`
+///
+///  - If the logger has a name:
+///
+///    `2021-12-23T01:23:45.067Z info [logger-name] log message`
+///
+///  - If crate feature `source-location` is enabled:
+///
+///    `2021-12-23T01:23:45.067Z info [crate::mod, main.rs:2] log message`
+///
+///  - If the record has fields:
+///
+///    `2021-12-23T01:23:45.067Z info log message key1=value1 key2=value2`
+///
+/// The time is always presented in UTC, so lines from hosts in different time
+/// zones sort and compare correctly as text.
+///
+/// This formatter is available with the crate feature `formatters-extra`.
+///
+/// [RFC 3339]: https://www.rfc-editor.org/rfc/rfc3339
+#[derive(Clone, Default)]
+pub struct Rfc3339Formatter {
+    time_precision: TimePrecision,
+    bytes_rendering: BytesRendering,
+}
+
+impl Rfc3339Formatter {
+    /// Constructs a `Rfc3339Formatter`.
+    ///
+    /// By default, times are presented with milliseconds.
+    pub fn new() -> Rfc3339Formatter {
+        Rfc3339Formatter::default()
+    }
+
+    /// Sets the precision of fractional seconds of times.
+    #[must_use]
+    pub fn time_precision(mut self, time_precision: TimePrecision) -> Self {
+        self.time_precision = time_precision;
+        self
+    }
+
+    /// Sets how to render payloads of records constructed from raw bytes.
+    #[must_use]
+    pub fn bytes_rendering(mut self, bytes_rendering: BytesRendering) -> Self {
+        self.bytes_rendering = bytes_rendering;
+        self
+    }
+
+    fn format_impl(
+        &self,
+        record: &Record,
+        dest: &mut StringBuf,
+    ) -> Result<FmtExtraInfo, fmt::Error> {
+        let time = TimeZone::Utc.wall_time(record.time());
+        write_date_time(dest, &time, 'T', self.time_precision)?;
+        dest.write_str("Z ")?;
+        let style_range = write_level(dest, record.level(), LevelNameStyle::Full)?;
+        dest.write_char(' ')?;
+
+        if let Some(logger_name) = record.logger_name() {
+            dest.write_char('[')?;
+            dest.write_str(logger_name)?;
+            dest.write_str("] ")?;
+        }
+        if let Some(srcloc) = record.source_location() {
+            dest.write_char('[')?;
+            write_source_location(dest, srcloc)?;
+            dest.write_str("] ")?;
+        }
+        dest.write_str(&self.bytes_rendering.render_payload(record))?;
+        write_fields(dest, record.fields(), None)?;
+
+        Ok(FmtExtraInfo::builder().style_range(style_range).build())
+    }
+}
+
+impl Formatter for Rfc3339Formatter {
+    fn format(
+        &self,
+        record: &Record,
+        dest: &mut StringBuf,
+        _ctx: &FormatterContext,
+    ) -> crate::Result<FmtExtraInfo> {
+        self.format_impl(record, dest).map_err(Error::FormatRecord)
+    }
+}
+
+// Writes e.g. `2021-12-23 01:23:45.067` with the given separator between the
+// date and the time of day.
+fn write_date_time(
+    dest: &mut StringBuf,
+    time: &WallTime,
+    separator: char,
+    precision: TimePrecision,
+) -> fmt::Result {
+    write!(
+        dest,
+        "{}-{:02}-{:02}{}",
+        time.year, time.month, time.day, separator
+    )?;
+    write_time_of_day(dest, time, precision)
+}
+
+// Writes e.g. `01:23:45.067`.
+fn write_time_of_day(
+    dest: &mut StringBuf,
+    time: &WallTime,
+    precision: TimePrecision,
+) -> fmt::Result {
+    write!(
+        dest,
+        "{:02}:{:02}:{:02}",
+        time.hour, time.minute, time.second
+    )?;
+    precision.write_fraction(dest, time.nanosecond)
+}
+
+// Returns the range of the level name, to be used as the style range.
+fn write_level(
+    dest: &mut StringBuf,
+    level: Level,
+    style: LevelNameStyle,
+) -> Result<Range<usize>, fmt::Error> {
+    let begin = dest.len();
+    dest.write_str(level.name(style))?;
+    Ok(begin..dest.len())
+}
+
+// Writes e.g. `crate::mod, main.rs:2`, or the ID of a hashed location.
+fn write_source_location(dest: &mut StringBuf, srcloc: &SourceLocation) -> fmt::Result {
+    dest.write_str(srcloc.module_path())?;
+    if srcloc.is_hashed() {
+        write!(dest, ", #{:016x}", srcloc.id())
+    } else {
+        write!(dest, ", {}:{}", srcloc.file_name(), srcloc.line())
+    }
+}
+
+// Writes ` key1=value1 key2=value2`, with keys in the style if any.
+fn write_fields(
+    dest: &mut StringBuf,
+    fields: &[Field],
+    key_style: Option<&StyleCode>,
+) -> fmt::Result {
+    for field in fields {
+        dest.write_char(' ')?;
+        match key_style {
+            Some(style) => write!(dest, "{}{}{}", style.start, field.key(), style.end)?,
+            None => dest.write_str(field.key())?,
+        }
+        write!(dest, "={}", field.value())?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, SystemTime};
+
+    use super::*;
+
+    fn record() -> Record<'static> {
+        Record::builder(Level::Warn, "test log content")
+            .logger_name("logger")
+            .fields(vec![
+                Field::new("id", 42u64),
+                Field::new("peer", Value::Map(vec![Field::new("port", 80u64)])),
+            ])
+            .time(SystemTime::UNIX_EPOCH + Duration::from_millis(1_640_222_625_067))
+            .build()
+    }
+
+    fn format<F: Formatter>(formatter: F, record: &Record) -> (String, Option<Range<usize>>) {
+        let mut buf = StringBuf::new();
+        let extra_info = formatter
+            .format(record, &mut buf, &FormatterContext::default())
+            .unwrap();
+        (buf.to_string(), extra_info.style_range())
+    }
+
+    #[test]
+    fn format_compact() {
+        assert_eq!(
+            format(CompactFormatter::new().time_zone(TimeZone::Utc), &record()),
+            (
+                "01:23:45.067 W logger: test log content id=42 peer={port=80}".to_string(),
+                Some(13..14)
+            )
+        );
+    }
+
+    #[test]
+    fn format_pretty() {
+        let record = Record::builder(Level::Info, "line 1\nline 2")
+            .fields(record().fields().to_vec())
+            .build();
+        let (buf, style_range) = format(
+            PrettyFormatter::new().time_zone(TimeZone::Utc).indent(2),
+            &record,
+        );
+        assert_eq!(style_range, Some(25..29));
+        assert!(buf.ends_with(
+            &[
+                " [info]",
+                "  line 1",
+                "  line 2",
+                "  id: 42",
+                "  peer:",
+                "    port: 80",
+            ]
+            .join(EOL)
+        ));
+    }
+
+    #[test]
+    fn format_key_highlight() {
+        let (buf, style_range) = format(
+            KeyHighlightFormatter::new().time_zone(TimeZone::Utc),
+            &record(),
+        );
+        assert_eq!(
+            buf,
+            "[2021-12-23 01:23:45.067] [logger] [warn] test log content \x1b[36mid\x1b[m=42 \x1b[36mpeer\x1b[m={port=80}"
+        );
+        assert_eq!(style_range, Some(36..40));
+    }
+
+    #[test]
+    fn format_rfc3339() {
+        assert_eq!(
+            format(
+                Rfc3339Formatter::new().time_precision(TimePrecision::Micros),
+                &record()
+            ),
+            (
+                "2021-12-23T01:23:45.067000Z warn [logger] test log content id=42 peer={port=80}"
+                    .to_string(),
+                Some(28..32)
+            )
+        );
+    }
+}
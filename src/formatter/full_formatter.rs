@@ -2,14 +2,20 @@
 
 use std::{
     fmt::{self, Write},
-    time::SystemTime,
+    ops::Range,
+    sync::Arc,
+    time::{Duration, SystemTime},
 };
 
-use chrono::prelude::*;
+use once_cell::sync::Lazy;
 
 use crate::{
-    formatter::{FmtExtraInfo, Formatter},
-    Error, Record, StringBuf, EOL,
+    catalog::MessageCatalog,
+    formatter::{
+        BytesRendering, FmtExtraInfo, Formatter, FormatterContext, Padding, TimePrecision,
+    },
+    symbolization::SymbolMap,
+    time_zone, Error, LevelNameStyle, Record, StringBuf, TimeZone, EOL,
 };
 
 /// A full info log records formatter.
@@ -29,16 +35,65 @@ use crate::{
 ///  - If crate feature `source-location` is enabled:
 ///
 ///    `[2021-12-23 01:23:45.067] [info] [crate::mod, main.rs:2] log message`
+///
+///  - If the record has an [error code]:
+///
+///    `[2021-12-23 01:23:45.067] [error[E1042]] log message`
+///
+///  - If the record has fields:
+///
+///    `[2021-12-23 01:23:45.067] [info] log message key1=value1 key2=value2`
+///
+///  - If the elapsed time and the delta time are enabled by
+///    [`FullFormatterBuilder`]:
+///
+///    `[2021-12-23 01:23:45.067] [12.345678s] [+0.001234s] [info] log message`
+///
+///  - If the paddings of the logger name and the level are set by
+///    [`FullFormatterBuilder`]:
+///
+///    `[2021-12-23 01:23:45.067] [logger-n] [info    ] log message`
+///
+///  - If the level name style is set to [`LevelNameStyle::Short`] by
+///    [`FullFormatterBuilder`]:
+///
+///    `[2021-12-23 01:23:45.067] [inf] log message`
+///
+/// If the record has a [backtrace], it is written on the following lines,
+/// indented by 4 spaces.
+///
+/// The time is the [time of the record], which is taken once when the record
+/// is created, so a record written by multiple sinks shows the same instant in
+/// all of them. Each formatter presents it in its own time zone and precision,
+/// see [`FullFormatterBuilder::time_zone`] and
+/// [`FullFormatterBuilder::time_precision`].
+///
+/// [error code]: crate::Record::code
+/// [backtrace]: crate::Record::backtrace
+/// [time of the record]: crate::Record::time
 pub struct FullFormatter {
-    local_time_cacher: spin::Mutex<LocalTimeCacher>,
+    time_cacher: spin::Mutex<TimeCacher>,
+    time_precision: TimePrecision,
+    elapsed: bool,
+    bytes_rendering: BytesRendering,
+    logger_name_padding: Option<Padding>,
+    level_padding: Option<Padding>,
+    level_name_style: LevelNameStyle,
+    catalog: Option<Arc<dyn MessageCatalog>>,
+    symbol_map: Option<Arc<SymbolMap>>,
+    // `None` if the delta time is disabled
+    last_time: Option<spin::Mutex<Option<SystemTime>>>,
 }
 
 impl FullFormatter {
     /// Constructs a `FullFormatter`.
     pub fn new() -> FullFormatter {
-        FullFormatter {
-            local_time_cacher: spin::Mutex::new(LocalTimeCacher::new()),
-        }
+        FullFormatter::builder().build()
+    }
+
+    /// Constructs a [`FullFormatterBuilder`].
+    pub fn builder() -> FullFormatterBuilder {
+        FullFormatterBuilder::new()
     }
 
     fn format_impl(
@@ -47,47 +102,105 @@ impl FullFormatter {
         dest: &mut StringBuf,
     ) -> Result<FmtExtraInfo, fmt::Error> {
         {
-            let mut local_time_cacher = self.local_time_cacher.lock();
-            let time = local_time_cacher.get(record.time());
+            let mut time_cacher = self.time_cacher.lock();
+            let time = time_cacher.get(record.time());
             dest.write_str("[")?;
             dest.write_str(time.0)?;
-            dest.write_str(".")?;
-            write!(dest, "{:03}", time.1)?;
+            self.time_precision.write_fraction(dest, time.1)?;
             dest.write_str("] [")?;
         }
 
-        if let Some(logger_name) = record.logger_name() {
-            dest.write_str(logger_name)?;
-            dest.write_str("] [")?;
+        if self.elapsed {
+            let elapsed = duration_between(*crate::START_TIME, record.time());
+            write!(dest, "{:.6}s] [", elapsed.as_secs_f64())?;
         }
 
-        let style_range_begin = dest.len();
+        if let Some(last_time) = &self.last_time {
+            let delta = last_time
+                .lock()
+                .replace(record.time())
+                .map_or(Duration::ZERO, |last_time| {
+                    duration_between(last_time, record.time())
+                });
+            write!(dest, "+{:.6}s] [", delta.as_secs_f64())?;
+        }
 
-        dest.write_str(record.level().as_str())?;
+        if let Some(logger_name) = record.logger_name() {
+            write_padded(dest, logger_name, self.logger_name_padding)?;
+            dest.write_str("] [")?;
+        }
 
-        let style_range_end = dest.len();
+        let level_name = record.level().name(self.level_name_style);
+        let style_range = match record.code() {
+            Some(code) => {
+                let range = write_padded(
+                    dest,
+                    &format!("{}[{}]", level_name, code),
+                    self.level_padding,
+                )?;
+                range.start..(range.start + level_name.len()).min(range.end)
+            }
+            None => write_padded(dest, level_name, self.level_padding)?,
+        };
 
         if let Some(srcloc) = record.source_location() {
             dest.write_str("] [")?;
             dest.write_str(srcloc.module_path())?;
             dest.write_str(", ")?;
-            dest.write_str(srcloc.file_name())?;
-            dest.write_str(":")?;
-            write!(dest, "{}", srcloc.line())?;
+            let resolved = srcloc
+                .is_hashed()
+                .then(|| self.symbol_map.as_ref()?.resolve(srcloc.id()))
+                .flatten();
+            if let Some((file, line)) = resolved {
+                dest.write_str(file.rsplit(['/', '\\']).next().unwrap_or(file))?;
+                write!(dest, ":{}", line)?;
+            } else if srcloc.is_hashed() {
+                write!(dest, "#{:016x}", srcloc.id())?;
+            } else {
+                dest.write_str(srcloc.file_name())?;
+                dest.write_str(":")?;
+                write!(dest, "{}", srcloc.line())?;
+            }
         }
 
         dest.write_str("] ")?;
-        dest.write_str(record.payload())?;
-        dest.write_str(EOL)?;
+        let localized = self.catalog.as_ref().and_then(|catalog| {
+            let key = record.message_key()?;
+            catalog.resolve(key, record.fields())
+        });
+        match localized {
+            Some(message) => dest.write_str(&message)?,
+            None => dest.write_str(&self.bytes_rendering.render_payload(record))?,
+        }
+
+        for field in record.fields() {
+            dest.write_str(" ")?;
+            dest.write_str(field.key())?;
+            dest.write_str("=")?;
+            write!(dest, "{}", field.value())?;
+        }
+
+        if let Some(backtrace) = record.backtrace() {
+            for line in backtrace.to_string().lines() {
+                dest.write_str(EOL)?;
+                dest.write_str("    ")?;
+                dest.write_str(line)?;
+            }
+        }
 
         Ok(FmtExtraInfo {
-            style_range: Some(style_range_begin..style_range_end),
+            style_range: Some(style_range),
         })
     }
 }
 
 impl Formatter for FullFormatter {
-    fn format(&self, record: &Record, dest: &mut StringBuf) -> crate::Result<FmtExtraInfo> {
+    fn format(
+        &self,
+        record: &Record,
+        dest: &mut StringBuf,
+        _ctx: &FormatterContext,
+    ) -> crate::Result<FmtExtraInfo> {
         self.format_impl(record, dest).map_err(Error::FormatRecord)
     }
 }
@@ -98,43 +211,225 @@ impl Default for FullFormatter {
     }
 }
 
-#[derive(Clone, Default)]
-struct LocalTimeCacher {
-    last_secs: i64,
-    local_time_str: Option<String>,
+// Returns the range of the text without padding.
+fn write_padded(
+    dest: &mut StringBuf,
+    text: &str,
+    padding: Option<Padding>,
+) -> Result<Range<usize>, fmt::Error> {
+    match padding {
+        Some(padding) => padding.write(dest, text),
+        None => {
+            let begin = dest.len();
+            dest.write_str(text)?;
+            Ok(begin..dest.len())
+        }
+    }
 }
 
-impl LocalTimeCacher {
-    fn new() -> LocalTimeCacher {
-        LocalTimeCacher::default()
+// Returns zero if `later` is earlier than `earlier`.
+fn duration_between(earlier: SystemTime, later: SystemTime) -> Duration {
+    later.duration_since(earlier).unwrap_or(Duration::ZERO)
+}
+
+/// The builder of [`FullFormatter`].
+///
+/// # Examples
+///
+/// ```
+/// use spdlog::formatter::FullFormatter;
+///
+/// let formatter: FullFormatter = FullFormatter::builder()
+///     .elapsed(true)
+///     .delta(true)
+///     .build();
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct FullFormatterBuilder {
+    elapsed: bool,
+    delta: bool,
+    bytes_rendering: BytesRendering,
+    logger_name_padding: Option<Padding>,
+    level_padding: Option<Padding>,
+    level_name_style: LevelNameStyle,
+    time_zone: Option<TimeZone>,
+    time_precision: TimePrecision,
+    catalog: Option<Arc<dyn MessageCatalog>>,
+    symbol_map: Option<Arc<SymbolMap>>,
+}
+
+impl FullFormatterBuilder {
+    /// Constructs a `FullFormatterBuilder`.
+    ///
+    /// By default, neither the elapsed time nor the delta time is written.
+    pub fn new() -> Self {
+        Self::default()
     }
 
-    // Returns (local_time_in_sec, millisecond)
-    fn get(&mut self, system_time: SystemTime) -> (&str, u32) {
-        let utc_time: DateTime<Utc> = system_time.into();
-        let millisecond = utc_time.nanosecond() % 1_000_000_000 / 1_000_000;
-        (self.update(utc_time), millisecond)
+    /// Specifies whether to write the time elapsed since the program started.
+    ///
+    /// The start of the program is approximated by the time when the first
+    /// [`Logger`] or [`FullFormatter`] was constructed, which usually happens
+    /// early in `main`.
+    ///
+    /// [`Logger`]: crate::Logger
+    #[must_use]
+    pub fn elapsed(mut self, elapsed: bool) -> Self {
+        self.elapsed = elapsed;
+        self
+    }
+
+    /// Specifies whether to write the time elapsed since the previous record
+    /// formatted by this formatter.
+    ///
+    /// It is zero for the first record, and for records with a time earlier
+    /// than the previous one.
+    #[must_use]
+    pub fn delta(mut self, delta: bool) -> Self {
+        self.delta = delta;
+        self
+    }
+
+    /// Sets how to render payloads of records constructed from raw bytes.
+    ///
+    /// The default is [`BytesRendering::LossyUtf8`].
+    #[must_use]
+    pub fn bytes_rendering(mut self, bytes_rendering: BytesRendering) -> Self {
+        self.bytes_rendering = bytes_rendering;
+        self
+    }
+
+    /// Sets the padding of logger names.
+    ///
+    /// By default, logger names are not padded.
+    #[must_use]
+    pub fn logger_name_padding(mut self, padding: Padding) -> Self {
+        self.logger_name_padding = Some(padding);
+        self
     }
 
-    fn update(&mut self, utc_time: DateTime<Utc>) -> &str {
-        let secs = utc_time.timestamp();
+    /// Sets the padding of levels.
+    ///
+    /// By default, levels are not padded. The padding is not included in the
+    /// style range.
+    #[must_use]
+    pub fn level_padding(mut self, padding: Padding) -> Self {
+        self.level_padding = Some(padding);
+        self
+    }
+
+    /// Sets the style of level names.
+    ///
+    /// The default is [`LevelNameStyle::Full`].
+    #[must_use]
+    pub fn level_name_style(mut self, style: LevelNameStyle) -> Self {
+        self.level_name_style = style;
+        self
+    }
+
+    /// Sets the time zone in which times are presented.
+    ///
+    /// The default is [`TimeZone::Local`].
+    #[must_use]
+    pub fn time_zone(mut self, time_zone: TimeZone) -> Self {
+        self.time_zone = Some(time_zone);
+        self
+    }
+
+    /// Sets the precision of fractional seconds of times.
+    ///
+    /// The default is [`TimePrecision::Millis`].
+    #[must_use]
+    pub fn time_precision(mut self, time_precision: TimePrecision) -> Self {
+        self.time_precision = time_precision;
+        self
+    }
+
+    /// Sets the message catalog resolving [message keys] of records.
+    ///
+    /// Records with a message key known to the catalog are written with the
+    /// resolved message instead of the payload. By default, payloads are
+    /// always written. See the [`catalog`] module for details.
+    ///
+    /// [message keys]: crate::Record::message_key
+    /// [`catalog`]: crate::catalog
+    #[must_use]
+    pub fn catalog(mut self, catalog: Arc<dyn MessageCatalog>) -> Self {
+        self.catalog = Some(catalog);
+        self
+    }
 
-        if self.local_time_str.is_none() || self.last_secs != secs {
-            let local_time: DateTime<Local> = utc_time.into();
-            self.local_time_str = Some(format!(
-                // `local_time.format("%Y-%m-%d %H:%M:%S")` is slower than this way
+    /// Sets the symbol map resolving [hashed source locations].
+    ///
+    /// Hashed source locations known to the map are written as file names and
+    /// line numbers, like unhashed ones, which is useful for internal builds
+    /// shipped along with the map. By default, they are written as IDs. See
+    /// the [`symbolization`] module for details.
+    ///
+    /// [hashed source locations]: crate::SourceLocation::is_hashed
+    /// [`symbolization`]: crate::symbolization
+    #[must_use]
+    pub fn symbol_map(mut self, symbol_map: Arc<SymbolMap>) -> Self {
+        self.symbol_map = Some(symbol_map);
+        self
+    }
+
+    /// Builds a [`FullFormatter`].
+    pub fn build(self) -> FullFormatter {
+        Lazy::force(&crate::START_TIME);
+
+        FullFormatter {
+            time_cacher: spin::Mutex::new(TimeCacher::new(
+                self.time_zone.unwrap_or(TimeZone::Local),
+            )),
+            time_precision: self.time_precision,
+            elapsed: self.elapsed,
+            bytes_rendering: self.bytes_rendering,
+            logger_name_padding: self.logger_name_padding,
+            level_padding: self.level_padding,
+            level_name_style: self.level_name_style,
+            catalog: self.catalog,
+            symbol_map: self.symbol_map,
+            last_time: self.delta.then(|| spin::Mutex::new(None)),
+        }
+    }
+}
+
+#[derive(Clone)]
+struct TimeCacher {
+    time_zone: TimeZone,
+    last_secs: i64,
+    time_str: Option<String>,
+}
+
+impl TimeCacher {
+    fn new(time_zone: TimeZone) -> TimeCacher {
+        TimeCacher {
+            time_zone,
+            last_secs: 0,
+            time_str: None,
+        }
+    }
+
+    // Returns (time_in_sec, nanosecond)
+    fn get(&mut self, system_time: SystemTime) -> (&str, u32) {
+        let (secs, nanosecond) = time_zone::unix_time(system_time);
+
+        if self.time_str.is_none() || self.last_secs != secs {
+            let wall_time = self.time_zone.wall_time(system_time);
+            self.time_str = Some(format!(
                 "{}-{:02}-{:02} {:02}:{:02}:{:02}",
-                local_time.year(),
-                local_time.month(),
-                local_time.day(),
-                local_time.hour(),
-                local_time.minute(),
-                local_time.second(),
+                wall_time.year,
+                wall_time.month,
+                wall_time.day,
+                wall_time.hour,
+                wall_time.minute,
+                wall_time.second,
             ));
             self.last_secs = secs;
         }
 
-        self.local_time_str.as_ref().unwrap().as_str()
+        (self.time_str.as_ref().unwrap().as_str(), nanosecond)
     }
 }
 
@@ -142,23 +437,268 @@ impl LocalTimeCacher {
 mod tests {
 
     use super::*;
-    use crate::{Level, EOL};
+    use crate::{formatter::Alignment, test_utils::TEST_LOGS_PATH, Level, EOL};
+
+    use std::backtrace::Backtrace;
+
+    use chrono::{DateTime, Local};
 
     #[test]
     fn format() {
         let record = Record::new(Level::Warn, "test log content");
         let mut buf = StringBuf::new();
-        let extra_info = FullFormatter::new().format(&record, &mut buf).unwrap();
+        let extra_info = FullFormatter::new()
+            .format(&record, &mut buf, &FormatterContext::default())
+            .unwrap();
 
         let local_time: DateTime<Local> = record.time().into();
         assert_eq!(
             format!(
-                "[{}] [warn] test log content{}",
-                local_time.format("%Y-%m-%d %H:%M:%S.%3f"),
-                EOL
+                "[{}] [warn] test log content",
+                local_time.format("%Y-%m-%d %H:%M:%S.%3f")
             ),
             buf
         );
         assert_eq!(Some(27..31), extra_info.style_range());
     }
+
+    #[test]
+    fn format_elapsed_delta() {
+        let formatter = FullFormatter::builder().elapsed(true).delta(true).build();
+
+        let mut record = Record::new(Level::Info, "test log content");
+        record.set_time(*crate::START_TIME + Duration::from_millis(1500));
+        let mut buf = StringBuf::new();
+        formatter
+            .format(&record, &mut buf, &FormatterContext::default())
+            .unwrap();
+        assert!(buf.ends_with("] [1.500000s] [+0.000000s] [info] test log content"));
+
+        record.set_time(record.time() + Duration::from_micros(1234));
+        let mut buf = StringBuf::new();
+        formatter
+            .format(&record, &mut buf, &FormatterContext::default())
+            .unwrap();
+        assert!(buf.ends_with("] [1.501234s] [+0.001234s] [info] test log content"));
+    }
+
+    #[test]
+    fn format_bytes() {
+        let record = Record::builder_bytes(Level::Info, &b"\x01\xab"[..]).build();
+
+        let mut buf = StringBuf::new();
+        FullFormatter::new()
+            .format(&record, &mut buf, &FormatterContext::default())
+            .unwrap();
+        assert!(buf.ends_with("[info] \u{1}\u{fffd}"));
+
+        let mut buf = StringBuf::new();
+        FullFormatter::builder()
+            .bytes_rendering(BytesRendering::Hex)
+            .build()
+            .format(&record, &mut buf, &FormatterContext::default())
+            .unwrap();
+        assert!(buf.ends_with("[info] 01ab"));
+    }
+
+    #[test]
+    fn format_padding() {
+        let formatter = FullFormatter::builder()
+            .logger_name_padding(Padding::new(8, Alignment::Left).truncate(true))
+            .level_padding(Padding::new(8, Alignment::Right))
+            .build();
+
+        let record = Record::builder(Level::Warn, "test log content")
+            .logger_name("logger-name")
+            .build();
+        let mut buf = StringBuf::new();
+        let extra_info = formatter
+            .format(&record, &mut buf, &FormatterContext::default())
+            .unwrap();
+        assert!(buf.ends_with("] [logger-n] [    warn] test log content"));
+        assert_eq!(Some(42..46), extra_info.style_range());
+
+        let record = Record::builder(Level::Info, "test log content")
+            .logger_name("http")
+            .build();
+        let mut buf = StringBuf::new();
+        formatter
+            .format(&record, &mut buf, &FormatterContext::default())
+            .unwrap();
+        assert!(buf.ends_with("] [http    ] [    info] test log content"));
+    }
+
+    #[test]
+    fn format_level_name_style() {
+        let record = Record::new(Level::Warn, "test log content");
+
+        let mut buf = StringBuf::new();
+        FullFormatter::builder()
+            .level_name_style(LevelNameStyle::Char)
+            .build()
+            .format(&record, &mut buf, &FormatterContext::default())
+            .unwrap();
+        assert!(buf.ends_with("] [W] test log content"));
+
+        let mut buf = StringBuf::new();
+        let extra_info = FullFormatter::builder()
+            .level_name_style(LevelNameStyle::Emoji)
+            .build()
+            .format(&record, &mut buf, &FormatterContext::default())
+            .unwrap();
+        assert!(buf.ends_with("] [🚧] test log content"));
+        assert_eq!(Some(27..31), extra_info.style_range());
+    }
+
+    #[test]
+    fn format_code() {
+        let record = Record::builder(Level::Error, "test log content")
+            .code("E1042")
+            .build();
+
+        let mut buf = StringBuf::new();
+        let extra_info = FullFormatter::new()
+            .format(&record, &mut buf, &FormatterContext::default())
+            .unwrap();
+        assert!(buf.ends_with("] [error[E1042]] test log content"));
+        assert_eq!(Some(27..32), extra_info.style_range());
+
+        let mut buf = StringBuf::new();
+        FullFormatter::builder()
+            .level_padding(Padding::new(8, Alignment::Left).truncate(true))
+            .build()
+            .format(&record, &mut buf, &FormatterContext::default())
+            .unwrap();
+        assert!(buf.ends_with("] [error[E1] test log content"));
+    }
+
+    #[test]
+    fn format_source_location() {
+        let format = |srcloc| {
+            let record = Record::builder(Level::Info, "test log content")
+                .source_location(Some(srcloc))
+                .build();
+            let mut buf = StringBuf::new();
+            FullFormatter::new()
+                .format(&record, &mut buf, &FormatterContext::default())
+                .unwrap();
+            buf
+        };
+
+        let buf = format(crate::SourceLocation::new("app", "src/main.rs", 42, 8));
+        assert!(buf.ends_with("] [info] [app, main.rs:42] test log content"));
+
+        let buf = format(crate::SourceLocation::__hashed("app", 0x5f0b1ad3c0e6f3a2));
+        assert!(buf.ends_with("] [info] [app, #5f0b1ad3c0e6f3a2] test log content"));
+
+        let mut symbol_map = crate::symbolization::SymbolMap::new();
+        let path = TEST_LOGS_PATH.join("format_source_location.rs");
+        std::fs::write(&path, "fn main() {\n    info!(\"a\");\n}\n").unwrap();
+        symbol_map.add_source_file(&path).unwrap();
+        let id = crate::__source_location_id(&path.to_string_lossy(), 2);
+        let record = Record::builder(Level::Info, "test log content")
+            .source_location(Some(crate::SourceLocation::__hashed("app", id)))
+            .build();
+        let mut buf = StringBuf::new();
+        FullFormatter::builder()
+            .symbol_map(Arc::new(symbol_map))
+            .build()
+            .format(&record, &mut buf, &FormatterContext::default())
+            .unwrap();
+        assert!(buf.ends_with("] [info] [app, format_source_location.rs:2] test log content"));
+    }
+
+    #[test]
+    fn format_catalog() {
+        use crate::catalog::MapCatalog;
+
+        let formatter = FullFormatter::builder()
+            .catalog(Arc::new(
+                MapCatalog::new().message("disk.full", "Le disque {mount} est plein"),
+            ))
+            .build();
+        let format = |record: Record| {
+            let mut buf = StringBuf::new();
+            formatter
+                .format(&record, &mut buf, &FormatterContext::default())
+                .unwrap();
+            buf
+        };
+
+        let record = |key| {
+            Record::builder(Level::Warn, "disk /var is full")
+                .message_key(key)
+                .field("mount", "/var")
+                .build()
+        };
+        assert!(format(record("disk.full")).ends_with("] Le disque /var est plein mount=/var"));
+        assert!(format(record("unknown")).ends_with("] disk /var is full mount=/var"));
+
+        let mut buf = StringBuf::new();
+        FullFormatter::new()
+            .format(&record("disk.full"), &mut buf, &FormatterContext::default())
+            .unwrap();
+        assert!(buf.ends_with("] disk /var is full mount=/var"));
+    }
+
+    #[test]
+    fn format_time() {
+        let mut record = Record::new(Level::Info, "test log content");
+        record.set_time(SystemTime::UNIX_EPOCH + Duration::new(1_640_222_625, 67_890_123));
+
+        let format = |builder: FullFormatterBuilder| {
+            let mut buf = StringBuf::new();
+            builder
+                .build()
+                .format(&record, &mut buf, &FormatterContext::default())
+                .unwrap();
+            buf.to_string()
+        };
+
+        let local_time: DateTime<Local> = record.time().into();
+        assert!(format(FullFormatter::builder()).starts_with(&format!(
+            "[{}] ",
+            local_time.format("%Y-%m-%d %H:%M:%S.%3f")
+        )));
+
+        let utc = || FullFormatter::builder().time_zone(TimeZone::Utc);
+        for (precision, time) in [
+            (TimePrecision::Seconds, "[2021-12-23 01:23:45] "),
+            (TimePrecision::Millis, "[2021-12-23 01:23:45.067] "),
+            (TimePrecision::Micros, "[2021-12-23 01:23:45.067890] "),
+            (TimePrecision::Nanos, "[2021-12-23 01:23:45.067890123] "),
+        ] {
+            assert!(format(utc().time_precision(precision)).starts_with(time));
+        }
+    }
+
+    #[test]
+    fn format_fields() {
+        let record = Record::builder(Level::Info, "test log content")
+            .field("key1", "value1")
+            .field("key2", "value2")
+            .build();
+        let mut buf = StringBuf::new();
+        FullFormatter::new()
+            .format(&record, &mut buf, &FormatterContext::default())
+            .unwrap();
+
+        assert!(buf.ends_with("[info] test log content key1=value1 key2=value2"));
+    }
+
+    #[test]
+    fn format_backtrace() {
+        let record = Record::builder(Level::Error, "test log content")
+            .backtrace(Backtrace::force_capture())
+            .build();
+        let mut buf = StringBuf::new();
+        FullFormatter::new()
+            .format(&record, &mut buf, &FormatterContext::default())
+            .unwrap();
+
+        assert!(!buf.ends_with(EOL));
+        let mut lines = buf.split(EOL);
+        assert!(lines.next().unwrap().ends_with("[error] test log content"));
+        assert!(lines.all(|line| line.starts_with("    ")));
+    }
 }
@@ -0,0 +1,281 @@
+//! Provides a JSON formatter.
+
+use std::fmt::{self, Write};
+
+use crate::{
+    formatter::{BytesRendering, FmtExtraInfo, Formatter, FormatterContext},
+    Error, ErrorCode, Field, Record, StringBuf, TimeZone, Value,
+};
+
+/// A JSON log records formatter.
+///
+/// Each record is formatted as a single line JSON object, which is the format
+/// expected by most log collectors (e.g. in container environments).
+///
+/// Log messages formatted by it look like this by default:
+///
+/// ```json
+/// {"time":"2021-12-23T01:23:45.067Z","severity":"info","seq":42,"message":"log message"}
+/// ```
+///
+/// If the logger has a name:
+///
+/// ```json
+/// {"time":"2021-12-23T01:23:45.067Z","severity":"info","seq":42,"logger":"logger-name","message":"log message"}
+/// ```
+///
+/// If crate feature `source-location` is enabled:
+///
+/// ```json
+/// {"time":"2021-12-23T01:23:45.067Z","severity":"info","seq":42,"source":{"module_path":"crate::mod","file":"src/main.rs","line":2},"message":"log message"}
+/// ```
+///
+/// If the record has an [error code], it is presented in the `code` key before
+/// the message, as a number or a string. If the record has a [message key], it
+/// is presented in the `message_key` key before the message, so that records
+/// can be localized later.
+///
+/// If the record has fields, they are presented as members of the object in
+/// the `fields` key after the message. Field values keep their types, nested
+/// maps are presented as nested objects, and durations are presented as
+/// numbers of seconds.
+///
+/// If the record has a [backtrace], it is presented as a string in the
+/// `backtrace` key after the message.
+///
+/// The time is always presented in UTC. The `seq` key is the [sequence
+/// number] of the record, which orders records strictly even when their times
+/// collide.
+///
+/// [error code]: crate::Record::code
+/// [message key]: crate::Record::message_key
+/// [backtrace]: crate::Record::backtrace
+/// [sequence number]: crate::Record::sequence
+#[derive(Clone, Default)]
+pub struct JsonFormatter {
+    bytes_rendering: BytesRendering,
+}
+
+impl JsonFormatter {
+    /// Constructs a `JsonFormatter`.
+    pub fn new() -> JsonFormatter {
+        JsonFormatter::default()
+    }
+
+    /// Sets how to render payloads of records constructed from raw bytes.
+    ///
+    /// The default is [`BytesRendering::LossyUtf8`].
+    #[must_use]
+    pub fn bytes_rendering(mut self, bytes_rendering: BytesRendering) -> Self {
+        self.bytes_rendering = bytes_rendering;
+        self
+    }
+
+    fn format_impl(
+        &self,
+        record: &Record,
+        dest: &mut StringBuf,
+    ) -> Result<FmtExtraInfo, fmt::Error> {
+        let time = TimeZone::Utc.wall_time(record.time());
+
+        write!(
+            dest,
+            "{{\"time\":\"{}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z\",\"severity\":\"{}\",\"seq\":{}",
+            time.year,
+            time.month,
+            time.day,
+            time.hour,
+            time.minute,
+            time.second,
+            time.nanosecond / 1_000_000,
+            record.level().as_str(),
+            record.sequence()
+        )?;
+
+        if let Some(logger_name) = record.logger_name() {
+            dest.write_str(",\"logger\":")?;
+            write_json_str(dest, logger_name)?;
+        }
+
+        if let Some(srcloc) = record.source_location() {
+            dest.write_str(",\"source\":{\"module_path\":")?;
+            write_json_str(dest, srcloc.module_path())?;
+            if srcloc.is_hashed() {
+                write!(dest, ",\"id\":\"{:016x}\"}}", srcloc.id())?;
+            } else {
+                dest.write_str(",\"file\":")?;
+                write_json_str(dest, srcloc.file())?;
+                write!(dest, ",\"line\":{}}}", srcloc.line())?;
+            }
+        }
+
+        match record.code() {
+            Some(ErrorCode::Num(code)) => write!(dest, ",\"code\":{}", code)?,
+            Some(ErrorCode::Str(code)) => {
+                dest.write_str(",\"code\":")?;
+                write_json_str(dest, code)?;
+            }
+            None => {}
+        }
+
+        if let Some(message_key) = record.message_key() {
+            dest.write_str(",\"message_key\":")?;
+            write_json_str(dest, message_key)?;
+        }
+
+        dest.write_str(",\"message\":")?;
+        write_json_str(dest, &self.bytes_rendering.render_payload(record))?;
+
+        if !record.fields().is_empty() {
+            dest.write_str(",\"fields\":")?;
+            write_json_fields(dest, record.fields())?;
+        }
+
+        if let Some(backtrace) = record.backtrace() {
+            dest.write_str(",\"backtrace\":")?;
+            write_json_str(dest, &backtrace.to_string())?;
+        }
+
+        dest.write_str("}")?;
+
+        Ok(FmtExtraInfo::new())
+    }
+}
+
+impl Formatter for JsonFormatter {
+    fn format(
+        &self,
+        record: &Record,
+        dest: &mut StringBuf,
+        _ctx: &FormatterContext,
+    ) -> crate::Result<FmtExtraInfo> {
+        self.format_impl(record, dest).map_err(Error::FormatRecord)
+    }
+}
+
+// Writes a quoted and escaped JSON string.
+// Durations are written as numbers of seconds, and non-finite floating point
+// numbers are written as strings, since JSON has no representation of them.
+fn write_json_fields(dest: &mut StringBuf, fields: &[Field]) -> fmt::Result {
+    dest.write_char('{')?;
+    for (index, field) in fields.iter().enumerate() {
+        if index != 0 {
+            dest.write_char(',')?;
+        }
+        write_json_field(dest, field)?;
+    }
+    dest.write_char('}')
+}
+
+// Writes a field as a JSON object member, i.e. `"key":value`.
+pub(crate) fn write_json_field(dest: &mut StringBuf, field: &Field) -> fmt::Result {
+    write_json_str(dest, field.key())?;
+    dest.write_char(':')?;
+    match field.value() {
+        Value::Str(value) => write_json_str(dest, value),
+        Value::I64(value) => write!(dest, "{}", value),
+        Value::U64(value) => write!(dest, "{}", value),
+        Value::F64(value) if value.is_finite() => write!(dest, "{}", value),
+        Value::F64(value) => write_json_str(dest, &value.to_string()),
+        Value::Bool(value) => write!(dest, "{}", value),
+        Value::Duration(value) => write!(dest, "{}", value.as_secs_f64()),
+        Value::Map(fields) => write_json_fields(dest, fields),
+    }
+}
+
+pub(crate) fn write_json_str(dest: &mut StringBuf, value: &str) -> fmt::Result {
+    dest.write_char('"')?;
+
+    let mut unescaped_begin = 0;
+    for (index, ch) in value.char_indices() {
+        let escaped = match ch {
+            '"' => Some("\\\""),
+            '\\' => Some("\\\\"),
+            '\n' => Some("\\n"),
+            '\r' => Some("\\r"),
+            '\t' => Some("\\t"),
+            ch if (ch as u32) < 0x20 => None,
+            _ => continue,
+        };
+
+        dest.write_str(&value[unescaped_begin..index])?;
+        match escaped {
+            Some(escaped) => dest.write_str(escaped)?,
+            None => write!(dest, "\\u{:04x}", ch as u32)?,
+        }
+        unescaped_begin = index + ch.len_utf8();
+    }
+    dest.write_str(&value[unescaped_begin..])?;
+
+    dest.write_char('"')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Level;
+
+    use std::time::{Duration, SystemTime};
+
+    use chrono::{DateTime, Utc};
+
+    #[test]
+    fn format() {
+        let record = Record::builder(Level::Warn, "test \"log\"\n\\content\u{1}")
+            .logger_name("logger-name")
+            .field("key", "value")
+            .field("n", -1)
+            .field("ok", true)
+            .field("elapsed", Duration::from_millis(1500))
+            .field("map", vec![Field::new("a", 1.5)])
+            .build();
+        let mut buf = StringBuf::new();
+        let extra_info = JsonFormatter::new()
+            .format(&record, &mut buf, &FormatterContext::default())
+            .unwrap();
+
+        let time: DateTime<Utc> = record.time().into();
+        assert_eq!(
+            format!(
+                r#"{{"time":"{}","severity":"warn","seq":{},"logger":"logger-name","message":"test \"log\"\n\\content\u0001","fields":{{"key":"value","n":-1,"ok":true,"elapsed":1.5,"map":{{"a":1.5}}}}}}"#,
+                time.format("%Y-%m-%dT%H:%M:%S%.3fZ"),
+                record.sequence()
+            ),
+            buf
+        );
+        assert_eq!(None, extra_info.style_range());
+    }
+
+    #[test]
+    fn format_code() {
+        let format = |code: ErrorCode| {
+            let record = Record::builder(Level::Error, "failed").code(code).build();
+            let mut buf = StringBuf::new();
+            JsonFormatter::new()
+                .format(&record, &mut buf, &FormatterContext::default())
+                .unwrap();
+            buf
+        };
+
+        assert!(format(1042.into()).ends_with(r#","code":1042,"message":"failed"}"#));
+        assert!(format("E1042".into()).ends_with(r#","code":"E1042","message":"failed"}"#));
+    }
+
+    #[test]
+    fn format_historical_time() {
+        let mut record = Record::new(Level::Info, "replayed");
+        record.set_time(SystemTime::UNIX_EPOCH + Duration::from_millis(1_600_000_000_123));
+        let mut buf = StringBuf::new();
+        JsonFormatter::new()
+            .format(&record, &mut buf, &FormatterContext::default())
+            .unwrap();
+
+        assert_eq!(
+            format!(
+                r#"{{"time":"2020-09-13T12:26:40.123Z","severity":"info","seq":{},"message":"replayed"}}"#,
+                record.sequence()
+            ),
+            buf
+        );
+    }
+}
@@ -4,24 +4,334 @@
 //!
 //! [`Sink::set_formatter`]: crate::sink::Sink::set_formatter
 
+mod access_log_formatter;
+mod ecs_formatter;
+#[cfg(feature = "formatters-extra")]
+mod extra_formatter;
 mod full_formatter;
+mod json_formatter;
+mod truncating_formatter;
 
+pub use access_log_formatter::*;
+pub use ecs_formatter::*;
+#[cfg(feature = "formatters-extra")]
+pub use extra_formatter::*;
 pub use full_formatter::*;
+pub use json_formatter::*;
+pub use truncating_formatter::*;
 
-use std::ops::Range;
+use std::{
+    borrow::Cow,
+    fmt::{self, Write},
+    ops::Range,
+    result::Result as StdResult,
+};
 
 use crate::{Record, Result, StringBuf};
 
 /// A trait for log records formatters.
 ///
+/// Formatters write the body of records without a trailing line ending, sinks
+/// append the terminator or framing of their targets (e.g. the
+/// [`LineEnding`] of [`FileSink`]), so that sinks sending records as
+/// individual messages (e.g. datagrams or database rows) can use the same
+/// formatters. Line breaks inside a record (e.g. lines of backtraces) are
+/// written as the line ending of the target platform of the compilation.
+///
+/// Sinks pass a [`FormatterContext`] describing themselves, so that a single
+/// formatter instance shared by sinks can adapt its output to each of them.
+///
 /// # Examples
 ///
 /// See the implementation of [`FullFormatter`] and [./examples] directory.
 ///
+/// [`LineEnding`]: crate::sink::LineEnding
+/// [`FileSink`]: crate::sink::FileSink
 /// [./examples]: https://github.com/SpriteOvO/spdlog-rs/tree/main/examples
 pub trait Formatter: Send + Sync {
     /// Format a log record.
-    fn format(&self, record: &Record, dest: &mut StringBuf) -> Result<FmtExtraInfo>;
+    fn format(
+        &self,
+        record: &Record,
+        dest: &mut StringBuf,
+        ctx: &FormatterContext,
+    ) -> Result<FmtExtraInfo>;
+}
+
+/// Information about the sink a record is formatted for.
+///
+/// It is passed to [`Formatter::format`] by sinks. Formatters can use it to
+/// adapt their output, e.g. include the hostname only for sinks sending
+/// records over the network, or mark levels with text only if the sink does
+/// not support styles.
+///
+/// # Examples
+///
+/// ```
+/// use spdlog::formatter::FormatterContext;
+///
+/// let ctx = FormatterContext::new("FileSink")
+///     .with_sink_name(Some("app"))
+///     .with_destination("/var/log/app.log")
+///     .with_style_supported(false);
+/// assert_eq!(ctx.sink_type(), "FileSink");
+/// assert_eq!(ctx.sink_name(), Some("app"));
+/// assert_eq!(ctx.destination(), Some("/var/log/app.log"));
+/// assert!(!ctx.is_style_supported());
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct FormatterContext<'a> {
+    sink_type: &'a str,
+    sink_name: Option<&'a str>,
+    destination: Option<&'a str>,
+    style_supported: bool,
+}
+
+impl<'a> FormatterContext<'a> {
+    /// Constructs a `FormatterContext` for a sink of the type, e.g.
+    /// `FileSink`.
+    ///
+    /// The destination is unknown and styles are not supported by default.
+    pub fn new(sink_type: &'a str) -> FormatterContext<'a> {
+        FormatterContext {
+            sink_type,
+            sink_name: None,
+            destination: None,
+            style_supported: false,
+        }
+    }
+
+    /// Sets the name of the sink, see [`Sink::name`].
+    ///
+    /// [`Sink::name`]: crate::sink::Sink::name
+    #[must_use]
+    pub fn with_sink_name(mut self, sink_name: Option<&'a str>) -> Self {
+        self.sink_name = sink_name;
+        self
+    }
+
+    /// Sets the description of the destination of the sink, e.g. the path of
+    /// a file or `stderr`.
+    #[must_use]
+    pub fn with_destination(mut self, destination: &'a str) -> Self {
+        self.destination = Some(destination);
+        self
+    }
+
+    /// Sets whether the sink renders styles of the formatted record, see
+    /// [`FmtExtraInfo::style_range`].
+    #[must_use]
+    pub fn with_style_supported(mut self, style_supported: bool) -> Self {
+        self.style_supported = style_supported;
+        self
+    }
+
+    /// Gets the type of the sink, e.g. `FileSink`.
+    ///
+    /// It is empty if the record is not formatted for a sink, e.g. in tests.
+    pub fn sink_type(&self) -> &'a str {
+        self.sink_type
+    }
+
+    /// Gets the name of the sink, see [`Sink::name`].
+    ///
+    /// [`Sink::name`]: crate::sink::Sink::name
+    pub fn sink_name(&self) -> Option<&'a str> {
+        self.sink_name
+    }
+
+    /// Gets the description of the destination of the sink, e.g. the path of
+    /// a file or `stderr`.
+    pub fn destination(&self) -> Option<&'a str> {
+        self.destination
+    }
+
+    /// Whether the sink renders styles of the formatted record.
+    pub fn is_style_supported(&self) -> bool {
+        self.style_supported
+    }
+}
+
+/// Ways to render payloads of records constructed from raw bytes by
+/// [`Record::builder_bytes`].
+///
+/// Payloads of other records are always rendered as they are.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Default)]
+pub enum BytesRendering {
+    /// Converts the bytes to UTF-8 lossily, i.e. [`Record::payload`].
+    #[default]
+    LossyUtf8,
+    /// Renders the bytes as lowercase hexadecimal digits, e.g. `66ff`.
+    Hex,
+    /// Renders the bytes as standard Base64 with padding, e.g. `Zv8=`.
+    Base64,
+}
+
+impl BytesRendering {
+    /// Renders the payload of a record.
+    pub fn render_payload<'r>(self, record: &'r Record) -> Cow<'r, str> {
+        match (self, record.payload_bytes()) {
+            (Self::Hex, Some(bytes)) => Cow::Owned(hex(bytes)),
+            (Self::Base64, Some(bytes)) => Cow::Owned(base64(bytes)),
+            _ => Cow::Borrowed(record.payload()),
+        }
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    const DIGITS: &[u8; 16] = b"0123456789abcdef";
+
+    let mut res = String::with_capacity(bytes.len() * 2);
+    for byte in bytes {
+        res.push(DIGITS[(byte >> 4) as usize] as char);
+        res.push(DIGITS[(byte & 0xf) as usize] as char);
+    }
+    res
+}
+
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    let mut res = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let bits = chunk.iter().enumerate().fold(0u32, |bits, (i, &byte)| {
+            bits | (byte as u32) << (16 - i * 8)
+        });
+        for i in 0..4 {
+            if i <= chunk.len() {
+                res.push(ALPHABET[(bits >> (18 - i * 6) & 0x3f) as usize] as char);
+            } else {
+                res.push('=');
+            }
+        }
+    }
+    res
+}
+
+/// Precisions of fractional seconds of formatted times.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Default)]
+pub enum TimePrecision {
+    /// No fractional seconds, e.g. `01:23:45`.
+    Seconds,
+    /// Milliseconds, e.g. `01:23:45.067`.
+    #[default]
+    Millis,
+    /// Microseconds, e.g. `01:23:45.067890`.
+    Micros,
+    /// Nanoseconds, e.g. `01:23:45.067890123`.
+    Nanos,
+}
+
+impl TimePrecision {
+    // Writes the fractional seconds, including the leading `.`.
+    pub(crate) fn write_fraction(self, dest: &mut StringBuf, nanosecond: u32) -> fmt::Result {
+        match self {
+            Self::Seconds => Ok(()),
+            Self::Millis => write!(dest, ".{:03}", nanosecond / 1_000_000),
+            Self::Micros => write!(dest, ".{:06}", nanosecond / 1_000),
+            Self::Nanos => write!(dest, ".{:09}", nanosecond),
+        }
+    }
+}
+
+/// Alignments of text padded by [`Padding`].
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Default)]
+pub enum Alignment {
+    /// Aligns the text to the left, i.e. pads on the right.
+    #[default]
+    Left,
+    /// Aligns the text to the right, i.e. pads on the left.
+    Right,
+    /// Centers the text, the extra space goes to the right if the padding is
+    /// odd.
+    Center,
+}
+
+/// Padding of a text in formatted records, so that columns line up for human
+/// scanning.
+///
+/// The width is measured in `char`s. It corresponds to the padding specifiers
+/// of C++ spdlog patterns, e.g. `%-8l` is `Padding::new(8, Alignment::Left)`
+/// and `%16!n` is `Padding::new(16, Alignment::Right).truncate(true)`.
+///
+/// # Examples
+///
+/// ```
+/// use spdlog::{
+///     formatter::{Alignment, Padding},
+///     StringBuf,
+/// };
+///
+/// let mut buf = StringBuf::new();
+/// Padding::new(8, Alignment::Left).write(&mut buf, "info")?;
+/// buf.push('|');
+/// Padding::new(4, Alignment::Left)
+///     .truncate(true)
+///     .write(&mut buf, "logger-name")?;
+/// assert_eq!(buf, "info    |logg");
+/// # Ok::<(), std::fmt::Error>(())
+/// ```
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct Padding {
+    width: usize,
+    alignment: Alignment,
+    truncate: bool,
+}
+
+impl Padding {
+    /// Constructs a `Padding` that pads texts shorter than `width` and keeps
+    /// longer texts as they are.
+    pub const fn new(width: usize, alignment: Alignment) -> Padding {
+        Padding {
+            width,
+            alignment,
+            truncate: false,
+        }
+    }
+
+    /// Specifies whether to truncate texts longer than the width, keeping the
+    /// beginning of them.
+    #[must_use]
+    pub fn truncate(mut self, truncate: bool) -> Self {
+        self.truncate = truncate;
+        self
+    }
+
+    /// Gets the width.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Gets the alignment.
+    pub fn alignment(&self) -> Alignment {
+        self.alignment
+    }
+
+    /// Writes the padded text to `dest`, and returns the range (in bytes) of
+    /// the text without padding in `dest`, e.g. to be used as the style range.
+    pub fn write(&self, dest: &mut StringBuf, text: &str) -> StdResult<Range<usize>, fmt::Error> {
+        let mut text = text;
+        let mut len = text.chars().count();
+        if self.truncate && len > self.width {
+            let (end, _) = text.char_indices().nth(self.width).unwrap();
+            text = &text[..end];
+            len = self.width;
+        }
+
+        let fill = self.width.saturating_sub(len);
+        let (left, right) = match self.alignment {
+            Alignment::Left => (0, fill),
+            Alignment::Right => (fill, 0),
+            Alignment::Center => (fill / 2, fill - fill / 2),
+        };
+
+        write!(dest, "{:left$}", "")?;
+        let begin = dest.len();
+        dest.write_str(text)?;
+        let end = dest.len();
+        write!(dest, "{:right$}", "")?;
+        Ok(begin..end)
+    }
 }
 
 /// Extra information for formatted text.
@@ -89,3 +399,75 @@ impl FmtExtraInfoBuilder {
         self.info
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Level;
+
+    #[test]
+    fn bytes_rendering() {
+        let record = Record::builder_bytes(Level::Info, &b"\x66\xff"[..]).build();
+        assert_eq!(
+            BytesRendering::LossyUtf8.render_payload(&record),
+            "f\u{fffd}"
+        );
+        assert_eq!(BytesRendering::Hex.render_payload(&record), "66ff");
+        assert_eq!(BytesRendering::Base64.render_payload(&record), "Zv8=");
+
+        for (bytes, base64) in [
+            (&b""[..], ""),
+            (b"f", "Zg=="),
+            (b"fo", "Zm8="),
+            (b"foo", "Zm9v"),
+            (b"foob", "Zm9vYg=="),
+        ] {
+            let record = Record::builder_bytes(Level::Info, bytes).build();
+            assert_eq!(BytesRendering::Base64.render_payload(&record), base64);
+        }
+
+        let record = Record::new(Level::Info, "text");
+        assert_eq!(BytesRendering::Hex.render_payload(&record), "text");
+    }
+
+    #[test]
+    fn padding() {
+        let pad = |padding: Padding, text| {
+            let mut buf = StringBuf::new();
+            let range = padding.write(&mut buf, text).unwrap();
+            (buf.to_string(), range)
+        };
+
+        assert_eq!(
+            pad(Padding::new(6, Alignment::Left), "warn"),
+            ("warn  ".to_string(), 0..4)
+        );
+        assert_eq!(
+            pad(Padding::new(6, Alignment::Right), "warn"),
+            ("  warn".to_string(), 2..6)
+        );
+        assert_eq!(
+            pad(Padding::new(7, Alignment::Center), "warn"),
+            (" warn  ".to_string(), 1..5)
+        );
+        assert_eq!(
+            pad(Padding::new(2, Alignment::Right), "warn"),
+            ("warn".to_string(), 0..4)
+        );
+        assert_eq!(
+            pad(Padding::new(2, Alignment::Right).truncate(true), "warn"),
+            ("wa".to_string(), 0..2)
+        );
+        assert_eq!(
+            pad(
+                Padding::new(3, Alignment::Left).truncate(true),
+                "日本語ログ"
+            ),
+            ("日本語".to_string(), 0..9)
+        );
+        assert_eq!(
+            pad(Padding::new(4, Alignment::Left), "日本"),
+            ("日本  ".to_string(), 0..6)
+        );
+    }
+}
@@ -0,0 +1,129 @@
+//! Provides a formatter truncating oversized records.
+
+use std::fmt::Write;
+
+use crate::{
+    formatter::{FmtExtraInfo, Formatter, FormatterContext},
+    Error, Record, Result, StringBuf,
+};
+
+/// A formatter that truncates records formatted by an inner formatter if they
+/// exceed a maximum length.
+///
+/// An oversized record is cut to at most `max_record_bytes` bytes on a UTF-8
+/// boundary, and a suffix ` (truncated N bytes)` is appended. The suffix is
+/// not counted in the limit. It prevents a single accidental huge
+/// record (e.g. a debug dump of a large structure) from destroying log files
+/// and downstream parsers.
+///
+/// Set it to a sink to limit the length of records written by the sink.
+///
+/// # Examples
+///
+/// ```
+/// use spdlog::{
+///     formatter::{FullFormatter, TruncatingFormatter},
+///     sink::{Sink, StdStream, StdStreamSink},
+///     terminal_style::StyleMode,
+/// };
+///
+/// let sink = StdStreamSink::new(StdStream::Stdout, StyleMode::Auto);
+/// sink.set_formatter(Box::new(TruncatingFormatter::new(FullFormatter::new(), 4096)));
+/// ```
+pub struct TruncatingFormatter<F> {
+    formatter: F,
+    max_record_bytes: usize,
+}
+
+impl<F> TruncatingFormatter<F>
+where
+    F: Formatter,
+{
+    /// Constructs a `TruncatingFormatter` wrapping `formatter`.
+    pub fn new(formatter: F, max_record_bytes: usize) -> TruncatingFormatter<F> {
+        TruncatingFormatter {
+            formatter,
+            max_record_bytes,
+        }
+    }
+
+    /// Gets the maximum length in bytes of a record.
+    pub fn max_record_bytes(&self) -> usize {
+        self.max_record_bytes
+    }
+}
+
+impl<F> Formatter for TruncatingFormatter<F>
+where
+    F: Formatter,
+{
+    fn format(
+        &self,
+        record: &Record,
+        dest: &mut StringBuf,
+        ctx: &FormatterContext,
+    ) -> Result<FmtExtraInfo> {
+        let begin = dest.len();
+        let extra_info = self.formatter.format(record, dest, ctx)?;
+
+        let end = dest.len();
+        if end - begin <= self.max_record_bytes {
+            return Ok(extra_info);
+        }
+
+        let mut cut = begin + self.max_record_bytes;
+        while !dest.is_char_boundary(cut) {
+            cut -= 1;
+        }
+        let truncated_bytes = end - cut;
+
+        dest.truncate(cut);
+        write!(dest, " (truncated {} bytes)", truncated_bytes).map_err(Error::FormatRecord)?;
+
+        let mut builder = FmtExtraInfo::builder();
+        if let Some(style_range) = extra_info.style_range() {
+            if style_range.start < cut {
+                builder = builder.style_range(style_range.start..style_range.end.min(cut));
+            }
+        }
+        Ok(builder.build())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{formatter::FullFormatter, Level};
+
+    #[test]
+    fn truncate() {
+        let formatter = TruncatingFormatter::new(FullFormatter::new(), 64);
+
+        let mut buf = StringBuf::new();
+        formatter
+            .format(
+                &Record::new(Level::Info, "short"),
+                &mut buf,
+                &FormatterContext::default(),
+            )
+            .unwrap();
+        assert!(buf.ends_with("[info] short"));
+
+        let payload = "é".repeat(100);
+        let mut buf = StringBuf::new();
+        let extra_info = formatter
+            .format(
+                &Record::new(Level::Info, payload.as_str()),
+                &mut buf,
+                &FormatterContext::default(),
+            )
+            .unwrap();
+        let prefix_len = buf.find("é").unwrap();
+        let kept = (64 - prefix_len) / 2;
+        assert_eq!(
+            &buf[prefix_len..],
+            format!("{} (truncated {} bytes)", "é".repeat(kept), 200 - kept * 2)
+        );
+        assert_eq!(Some(27..31), extra_info.style_range());
+    }
+}
@@ -0,0 +1,89 @@
+//! Provides a hexdump of binary data.
+
+use std::fmt;
+
+use crate::EOL;
+
+/// Displays binary data as a hexdump with an offset column, hexadecimal bytes
+/// and printable ASCII characters, in the format of `hexdump -C`.
+///
+/// Each line of 16 bytes is preceded by a line ending, so that the dump starts
+/// on a new line when it is appended to a message. It is used by
+/// [`trace_hexdump!`], and can be used directly for other levels.
+///
+/// # Examples
+///
+/// ```
+/// use spdlog::{prelude::*, Hexdump};
+///
+/// let frame = b"Hello, world!\n";
+/// debug!("rx frame{}", Hexdump::new(frame));
+/// ```
+///
+/// The record looks like:
+///
+/// ```text
+/// [2021-12-23 01:23:45.067] [debug] rx frame
+/// 00000000  48 65 6c 6c 6f 2c 20 77  6f 72 6c 64 21 0a        |Hello, world!.|
+/// ```
+///
+/// [`trace_hexdump!`]: crate::trace_hexdump
+#[derive(Copy, Clone, Debug)]
+pub struct Hexdump<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Hexdump<'a> {
+    /// Constructs a `Hexdump`.
+    pub fn new(bytes: &'a [u8]) -> Hexdump<'a> {
+        Hexdump { bytes }
+    }
+}
+
+impl fmt::Display for Hexdump<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (index, line) in self.bytes.chunks(16).enumerate() {
+            write!(f, "{}{:08x} ", EOL, index * 16)?;
+
+            for column in 0..16 {
+                if column == 8 {
+                    f.write_str(" ")?;
+                }
+                match line.get(column) {
+                    Some(byte) => write!(f, " {:02x}", byte)?,
+                    None => f.write_str("   ")?,
+                }
+            }
+
+            f.write_str("  |")?;
+            for &byte in line {
+                let ch = if byte.is_ascii_graphic() || byte == b' ' {
+                    byte as char
+                } else {
+                    '.'
+                };
+                write!(f, "{}", ch)?;
+            }
+            f.write_str("|")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn format() {
+        assert_eq!(Hexdump::new(b"").to_string(), "");
+        assert_eq!(
+            Hexdump::new(b"Hello, world!\n0123456789").to_string(),
+            format!(
+                "{0}00000000  48 65 6c 6c 6f 2c 20 77  6f 72 6c 64 21 0a 30 31  |Hello, world!.01|\
+                 {0}00000010  32 33 34 35 36 37 38 39                           |23456789|",
+                EOL
+            )
+        );
+    }
+}
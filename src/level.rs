@@ -9,6 +9,12 @@ use crate::Error;
 pub(crate) const LOG_LEVEL_NAMES: [&str; Level::count()] =
     ["critical", "error", "warn", "info", "debug", "trace"];
 
+const LOG_LEVEL_SHORT_NAMES: [&str; Level::count()] = ["crt", "err", "wrn", "inf", "dbg", "trc"];
+
+const LOG_LEVEL_CHARS: [&str; Level::count()] = ["C", "E", "W", "I", "D", "T"];
+
+const LOG_LEVEL_EMOJIS: [&str; Level::count()] = ["💥", "❌", "🚧", "💬", "🐛", "🔍"];
+
 /// An enum representing log levels.
 ///
 /// Typical usage includes: specifying the `Level` of [`log!`], and comparing a
@@ -110,6 +116,28 @@ impl Level {
         LOG_LEVEL_NAMES[*self as usize]
     }
 
+    /// Returns the representation of the `Level` in the given style.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use spdlog::{Level, LevelNameStyle};
+    ///
+    /// assert_eq!(Level::Warn.name(LevelNameStyle::Full), "warn");
+    /// assert_eq!(Level::Warn.name(LevelNameStyle::Short), "wrn");
+    /// assert_eq!(Level::Warn.name(LevelNameStyle::Char), "W");
+    /// assert_eq!(Level::Warn.name(LevelNameStyle::Emoji), "🚧");
+    /// ```
+    pub fn name(&self, style: LevelNameStyle) -> &'static str {
+        let names = match style {
+            LevelNameStyle::Full => &LOG_LEVEL_NAMES,
+            LevelNameStyle::Short => &LOG_LEVEL_SHORT_NAMES,
+            LevelNameStyle::Char => &LOG_LEVEL_CHARS,
+            LevelNameStyle::Emoji => &LOG_LEVEL_EMOJIS,
+        };
+        names[*self as usize]
+    }
+
     /// Iterate through all supported logging levels.
     ///
     /// The order of iteration is from more severe to more verbose.
@@ -162,6 +190,26 @@ impl FromStr for Level {
     }
 }
 
+/// Styles of representing [`Level`]s in formatted records, see [`Level::name`].
+///
+/// Alternate styles are useful for compact CLI output. Only the full names are
+/// accepted by [`Level::from_str`].
+///
+/// [`Level::from_str`]: std::str::FromStr::from_str
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Default)]
+pub enum LevelNameStyle {
+    /// Full lowercase names, e.g. `critical` and `warn`, the same as
+    /// [`Level::as_str`].
+    #[default]
+    Full,
+    /// 3-character lowercase names, e.g. `crt` and `wrn`.
+    Short,
+    /// Single uppercase characters, e.g. `C` and `W`.
+    Char,
+    /// Single emojis, e.g. `💥` and `🚧`.
+    Emoji,
+}
+
 /// An enum representing log level logical filter conditions.
 ///
 /// A `LevelFilter` may be compared to a [`Level`] through
@@ -243,6 +291,40 @@ impl LevelFilter {
         }
     }
 
+    /// Constructs a `LevelFilter` from a verbosity, which is usually the
+    /// number of `-v` flags minus the number of `-q` flags of a command line
+    /// tool.
+    ///
+    /// Verbosity 0 is `MoreSevereEqual(Level::Info)`, each step up enables a
+    /// more verbose level and each step down disables the most verbose enabled
+    /// level, until all or no levels are enabled.
+    ///
+    /// See also [`init_verbosity`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use spdlog::{Level, LevelFilter};
+    ///
+    /// assert_eq!(LevelFilter::from_verbosity(0), LevelFilter::MoreSevereEqual(Level::Info));
+    /// assert_eq!(LevelFilter::from_verbosity(1), LevelFilter::MoreSevereEqual(Level::Debug));
+    /// assert_eq!(LevelFilter::from_verbosity(-2), LevelFilter::MoreSevereEqual(Level::Error));
+    /// assert_eq!(LevelFilter::from_verbosity(-4), LevelFilter::Off);
+    /// ```
+    ///
+    /// [`init_verbosity`]: crate::init_verbosity
+    pub const fn from_verbosity(verbosity: i8) -> LevelFilter {
+        match verbosity {
+            i8::MIN..=-4 => LevelFilter::Off,
+            -3 => LevelFilter::MoreSevereEqual(Level::Critical),
+            -2 => LevelFilter::MoreSevereEqual(Level::Error),
+            -1 => LevelFilter::MoreSevereEqual(Level::Warn),
+            0 => LevelFilter::MoreSevereEqual(Level::Info),
+            1 => LevelFilter::MoreSevereEqual(Level::Debug),
+            2..=i8::MAX => LevelFilter::All,
+        }
+    }
+
     pub(crate) fn from_str_for_env(text: &str) -> Option<LevelFilter> {
         if let Ok(level) = Level::from_str(text) {
             Some(LevelFilter::MoreSevereEqual(level))
@@ -314,6 +396,18 @@ mod tests {
         assert!(Level::from_str("notexist").is_err());
     }
 
+    #[test]
+    fn name() {
+        for level in Level::iter() {
+            assert_eq!(level.name(LevelNameStyle::Full), level.as_str());
+            assert_eq!(level.name(LevelNameStyle::Short).len(), 3);
+            assert_eq!(level.name(LevelNameStyle::Char).len(), 1);
+            assert_eq!(level.name(LevelNameStyle::Emoji).chars().count(), 1);
+        }
+        assert_eq!(Level::Critical.name(LevelNameStyle::Short), "crt");
+        assert_eq!(Level::Trace.name(LevelNameStyle::Char), "T");
+    }
+
     #[test]
     fn level_filter_from_str_for_env() {
         assert_eq!(
@@ -384,6 +478,27 @@ mod tests {
         assert!(LevelFilter::All.compare(Level::Error));
     }
 
+    #[test]
+    fn filter_from_verbosity() {
+        let levels = (-5..=3)
+            .map(LevelFilter::from_verbosity)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            levels,
+            vec![
+                LevelFilter::Off,
+                LevelFilter::Off,
+                LevelFilter::MoreSevereEqual(Level::Critical),
+                LevelFilter::MoreSevereEqual(Level::Error),
+                LevelFilter::MoreSevereEqual(Level::Warn),
+                LevelFilter::MoreSevereEqual(Level::Info),
+                LevelFilter::MoreSevereEqual(Level::Debug),
+                LevelFilter::All,
+                LevelFilter::All,
+            ]
+        );
+    }
+
     #[cfg(feature = "log")]
     #[test]
     fn filter_from_log() {
@@ -101,6 +101,27 @@
 //! debug, and info level logs in release builds with
 //! `features = ["level-debug", "release-level-warn"]`.
 //!
+//! Log levels can also be statically disabled per module via the environment
+//! variable `SPDLOG_RS_STATIC_LEVEL` at compile time, which is a
+//! comma-separated list of `module_path=level`. The level is one of `off`,
+//! `critical`, `error`, `warn`, `info`, `debug`, `trace` and `all`
+//! (case-insensitive). A module is filtered by the entry with the longest
+//! module path that is the module itself or one of its ancestors, and modules
+//! without a matching entry are not filtered. It is checked by the logging
+//! macros alongside `STATIC_LEVEL_FILTER`, and an invalid value results in a
+//! compile error.
+//!
+//! For example, a hot inner crate can compile out its trace and debug level
+//! logs while the rest of the workspace keeps them, with the following in
+//! `.cargo/config.toml`:
+//!
+//! ```toml
+//! [env]
+//! SPDLOG_RS_STATIC_LEVEL = "hot_crate=info,hot_crate::io::retry=all"
+//! ```
+//!
+//! Cargo rebuilds crates when the value changes.
+//!
 //! # Crate Feature Flags
 //!
 //! The following crate feature flags are available in addition to the filters.
@@ -112,12 +133,49 @@
 //!    information to appear in your binary file, you may prefer not to enable
 //!    it.
 //!
+//!  - `source-location-file-name` and `source-location-hashed` enable
+//!    `source-location` with pruned locations, recording only file names or
+//!    hashed IDs respectively, see [`SourceLocation`]. Hashed IDs can be
+//!    resolved with a map generated at build time, see [`symbolization`].
+//!
 //!  - `flexible-string` improves the performance of formatting records, however
 //!    contains unsafe code. For more details, see the documentation of
 //!    [`StringBuf`].
 //!
 //!  - `log` see [Compatible with log crate](#compatible-with-log-crate) above.
 //!
+//!  - `chrono` (enabled by default) uses [`chrono`] to query the local time
+//!    zone. If it is disabled, times are computed with only the standard
+//!    library (and the C library on Unix platforms) for users who want to avoid
+//!    the dependency, see [`TimeZone`] for the differences.
+//!
+//!  - `opentelemetry` enables [`OtelLogSink`], which emits records to the
+//!    [OpenTelemetry] Logs API, e.g. to export them via OTLP. It also attaches
+//!    the `trace_id` and `span_id` of the current span to records as fields, so
+//!    that logs written by any sink can be correlated with traces, see
+//!    [`Logger::log`].
+//!
+//!  - `clap` enables [`cli`], standard logging flags for command line tools
+//!    built with [clap].
+//!
+//!  - `capi` enables [`capi`], a C ABI to log to the default logger from C and
+//!    C++ code, e.g. to funnel logs of C++ spdlog in mixed codebases.
+//!
+//!  - `formatters-extra` enables a gallery of commonly requested formatters:
+//!    [`CompactFormatter`], [`PrettyFormatter`], [`KeyHighlightFormatter`] and
+//!    [`Rfc3339Formatter`].
+//!
+//!  - `self-profiling` measures the time spent in each stage of the logging
+//!    pipeline, see [`profiling`].
+//!
+//! [`chrono`]: https://docs.rs/chrono
+//! [`CompactFormatter`]: crate::formatter::CompactFormatter
+//! [`PrettyFormatter`]: crate::formatter::PrettyFormatter
+//! [`KeyHighlightFormatter`]: crate::formatter::KeyHighlightFormatter
+//! [`Rfc3339Formatter`]: crate::formatter::Rfc3339Formatter
+//! [OpenTelemetry]: https://opentelemetry.io
+//! [clap]: https://docs.rs/clap
+//!
 //! # Significant differences from C++ spdlog
 //!
 //! The significant differences between `spdlog-rs` and C++ `spdlog`[^1]:
@@ -155,6 +213,7 @@
 //! [log crate]: https://crates.io/crates/log
 //! [`FullFormatter`]: crate::formatter::FullFormatter
 //! [`RotatingFileSink`]: crate::sink::RotatingFileSink
+//! [`OtelLogSink`]: crate::sink::OtelLogSink
 //! [`Formatter`]: crate::formatter::Formatter
 //! [`RotationPolicy::Daily`]: crate::sink::RotationPolicy::Daily
 //! [`RotationPolicy::Hourly`]: crate::sink::RotationPolicy::Hourly
@@ -163,34 +222,71 @@
 #![cfg_attr(all(doc, CHANNEL_NIGHTLY), feature(doc_auto_cfg))]
 #![warn(missing_docs)]
 
+#[cfg(feature = "capi")]
+pub mod capi;
+pub mod catalog;
+#[cfg(feature = "clap")]
+pub mod cli;
 mod env_level;
 mod error;
+mod error_code;
+mod field;
+pub mod filter;
 pub mod formatter;
+mod hexdump;
 mod level;
 #[cfg(feature = "log")]
 mod log_crate_proxy;
 mod log_macros;
 mod logger;
+mod logger_group;
+mod logger_name;
+pub mod merge;
+pub mod monitors;
 mod periodic_worker;
+#[cfg(feature = "self-profiling")]
+pub mod profiling;
+#[cfg(not(feature = "self-profiling"))]
+mod profiling;
+mod rate_limiter;
 mod record;
+pub mod registry;
+mod self_log;
 pub mod sink;
 mod source_location;
+mod static_module_filter;
 #[doc(hidden)]
 pub mod string_buf;
+pub mod symbolization;
 pub mod terminal_style;
+pub mod test;
 #[cfg(test)]
 mod test_utils;
+mod thread_config;
+mod time_zone;
+mod trace_context;
 mod utils;
+pub mod web;
 
-pub use env_level::EnvLevelError;
+pub use env_level::{EnvLevelDirectiveError, EnvLevelDirectiveErrorKind, EnvLevelError};
 pub use error::*;
+pub use error_code::*;
+pub use field::*;
+pub use hexdump::*;
 pub use level::*;
 #[cfg(feature = "log")]
 pub use log_crate_proxy::LogCrateProxy;
 pub use logger::*;
+pub use logger_group::*;
+pub use logger_name::*;
+pub use rate_limiter::*;
 pub use record::*;
+pub use self_log::*;
 pub use source_location::*;
+pub use static_module_filter::*;
 pub use string_buf::StringBuf;
+pub use thread_config::*;
+pub use time_zone::*;
 
 /// Contains all log macros and common types.
 pub mod prelude {
@@ -198,12 +294,13 @@ pub mod prelude {
     pub use super::{Level, LevelFilter, Logger, LoggerBuilder};
 }
 
-use std::{result::Result as StdResult, sync::Arc};
+use std::{result::Result as StdResult, sync::Arc, time::SystemTime};
 
 use arc_swap::ArcSwap;
 use cfg_if::cfg_if;
 use once_cell::sync::Lazy;
 
+use formatter::JsonFormatter;
 use sink::{
     Sink, {StdStream, StdStreamSink},
 };
@@ -258,7 +355,14 @@ pub(crate) const EOL: &str = "\n";
 #[cfg(windows)]
 pub(crate) const EOL: &str = "\r\n";
 
-static DEFAULT_LOGGER: Lazy<ArcSwap<Logger>> = Lazy::new(|| {
+// An approximation of the time when the program started, see
+// `FullFormatterBuilder::elapsed`.
+static START_TIME: Lazy<SystemTime> = Lazy::new(SystemTime::now);
+
+static DEFAULT_LOGGER: Lazy<ArcSwap<Logger>> =
+    Lazy::new(|| ArcSwap::from_pointee(new_default_logger()));
+
+fn new_default_logger() -> Logger {
     let stdout = StdStreamSink::new(StdStream::Stdout, StyleMode::Auto);
     stdout.set_level_filter(LevelFilter::MoreVerbose(Level::Warn));
 
@@ -267,8 +371,8 @@ static DEFAULT_LOGGER: Lazy<ArcSwap<Logger>> = Lazy::new(|| {
 
     let sinks: [Arc<dyn Sink>; 2] = [Arc::new(stdout), Arc::new(stderr)];
 
-    ArcSwap::from_pointee(Logger::builder().sinks(sinks).build_default())
-});
+    Logger::builder().sinks(sinks).build_default()
+}
 
 /// Returns an [`Arc`] default logger.
 ///
@@ -276,6 +380,9 @@ static DEFAULT_LOGGER: Lazy<ArcSwap<Logger>> = Lazy::new(|| {
 /// and more verbose levels to `stdout`, and writing logs on `warn` level and
 /// more severe levels to `stderr`.
 ///
+/// Within [`test::with_isolated_default_logger`], the isolated default logger
+/// of the current thread is returned instead.
+///
 /// # Examples
 ///
 /// ```
@@ -295,7 +402,34 @@ static DEFAULT_LOGGER: Lazy<ArcSwap<Logger>> = Lazy::new(|| {
 /// critical!("this log will be written to `stderr`");
 /// ```
 pub fn default_logger() -> Arc<Logger> {
-    DEFAULT_LOGGER.load().clone()
+    test::isolated_default_logger().unwrap_or_else(|| DEFAULT_LOGGER.load().clone())
+}
+
+/// Constructs a logger for container environments.
+///
+/// The logger contains a single [`StdStreamSink`] writing all logs to `stdout`
+/// with [`JsonFormatter`], one JSON object per line with the level in the
+/// `severity` field. Unlike the default logger, logs are not split between
+/// `stdout` and `stderr`, since container log collectors usually treat
+/// everything on `stderr` as errors.
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::Arc;
+///
+/// spdlog::set_default_logger(Arc::new(spdlog::container_logger()));
+///
+/// spdlog::info!("this log will be written to `stdout` as JSON");
+/// spdlog::error!("this log will also be written to `stdout` as JSON");
+/// ```
+///
+/// [`JsonFormatter`]: crate::formatter::JsonFormatter
+pub fn container_logger() -> Logger {
+    let stdout = StdStreamSink::new(StdStream::Stdout, StyleMode::Never);
+    stdout.set_formatter(Box::new(JsonFormatter::new()));
+
+    Logger::builder().sink(Arc::new(stdout)).build()
 }
 
 /// Sets the given logger as the default logger, and returns the old default
@@ -314,7 +448,7 @@ pub fn default_logger() -> Arc<Logger> {
 /// info!(logger: old_logger, "this log will be handled by `old_logger`");
 /// ```
 pub fn swap_default_logger(logger: Arc<Logger>) -> Arc<Logger> {
-    DEFAULT_LOGGER.swap(logger)
+    test::swap_isolated_default_logger(logger).unwrap_or_else(|logger| DEFAULT_LOGGER.swap(logger))
 }
 
 /// Sets the given logger as the default logger.
@@ -361,12 +495,37 @@ pub fn set_default_logger(logger: Arc<Logger>) {
 ///   Specifies the level filter of loggers with name "example" as
 /// `LevelFilter::Off`.
 ///
+/// - `net.*=trace`
+///
+///   Specifies the level filter of loggers with names starting with "net." as
+/// `LevelFilter::MoreSevereEqual(Level::Trace)`, for hierarchical names like
+/// "net.http". A logger with name "net" is not matched. If multiple prefixes
+/// match a name, the longest one is respected, and a rule of the exact name
+/// takes precedence over prefixes.
+///
 /// - `*=error`
 ///
 ///   Specifies the level filter of all loggers (except the default logger) as
 /// `LevelFilter::MoreSevereEqual(Level::Error)` (respect the above rules if
 /// they are matched).
 ///
+/// - `gui=debug..warn`
+///
+///   Specifies the level filter of loggers with name "gui" as
+/// `LevelFilter::MoreSevereEqual(Level::Debug)`, and rejects records more
+/// severe than `Level::Warn`. The bounds are inclusive and can be in either
+/// order; `off` and `all` cannot be bounds. Levels can be ranges in all rules.
+///
+/// - `[file]gui=trace`, `[file]trace`
+///
+///   Specifies the level filter of the sink with name "file" for records of
+/// loggers with name "gui", or of the default logger. Any of the above rules
+/// can be prefixed with a sink name in brackets. The level filter of the
+/// logger still applies first, and the sink itself is not modified: it is
+/// wrapped in a [`FilterSink`] within the logger being built, so other loggers
+/// sharing it are not affected. Sinks added to the logger after it is built
+/// are not configured.
+///
 /// The level filter is not case-sensitive, and these rules are combinable,
 /// separated by commas. For example, these are legal:
 ///
@@ -387,6 +546,8 @@ pub fn set_default_logger(logger: Arc<Logger>) {
 ///
 /// However, the same rule cannot be specified more than once.
 ///
+/// [`FilterSink`]: crate::sink::FilterSink
+///
 /// # Examples
 ///
 /// - Environment variable `SPDLOG_RS_LEVEL` is not present:
@@ -461,7 +622,85 @@ pub fn set_default_logger(logger: Arc<Logger>) {
 ///   }
 ///   ```
 pub fn init_env_level() -> StdResult<bool, EnvLevelError> {
-    env_level::from_env("SPDLOG_RS_LEVEL")
+    env_level::from_env("SPDLOG_RS_LEVEL", false)
+}
+
+/// Initialize environment variable level filters, applying the valid rules
+/// even if there are invalid ones.
+///
+/// It is the same as [`init_env_level`], except that a typo in one rule does
+/// not disable the others. The invalid rules are still reported by
+/// [`EnvLevelError::ParseEnvVar`], which can be logged after the loggers are
+/// set up.
+///
+/// # Examples
+///
+/// ```
+/// use spdlog::prelude::*;
+///
+/// # std::env::set_var("SPDLOG_RS_LEVEL", "trace,network=verbose");
+/// if let Err(spdlog::EnvLevelError::ParseEnvVar(errors)) = spdlog::init_env_level_lenient() {
+///     for err in errors {
+///         warn!("ignored invalid rule of SPDLOG_RS_LEVEL: {}", err);
+///     }
+/// }
+///
+/// assert_eq!(
+///     spdlog::default_logger().level_filter(),
+///     LevelFilter::MoreSevereEqual(Level::Trace)
+/// );
+/// ```
+pub fn init_env_level_lenient() -> StdResult<bool, EnvLevelError> {
+    env_level::from_env("SPDLOG_RS_LEVEL", true)
+}
+
+/// Initialize the level filters from the verbosity flags of a command line
+/// tool and environment variable level filters.
+///
+/// It standardizes the common pattern of `-v`/`-q` flags: [`init_env_level`]
+/// is called first, and then if the flags are given (i.e. `verbose != quiet`),
+/// the level filter of the default logger is set to
+/// [`LevelFilter::from_verbosity`] of `verbose - quiet`, overriding the level
+/// of the default logger in the environment variable. Otherwise, the level in
+/// the environment variable (if any) is applied to the default logger, even if
+/// it was built before this call.
+///
+/// # Examples
+///
+/// ```
+/// use clap::Parser;
+/// use spdlog::prelude::*;
+///
+/// #[derive(Parser)]
+/// struct Args {
+///     /// More output per occurrence
+///     #[clap(short, long, parse(from_occurrences))]
+///     verbose: u8,
+///     /// Less output per occurrence
+///     #[clap(short, long, parse(from_occurrences))]
+///     quiet: u8,
+/// }
+///
+/// # fn main() -> Result<(), spdlog::EnvLevelError> {
+/// let args = Args::parse_from(["app", "-vv"]);
+/// spdlog::init_verbosity(args.verbose, args.quiet)?;
+///
+/// assert_eq!(spdlog::default_logger().level_filter(), LevelFilter::All);
+/// # Ok(()) }
+/// ```
+pub fn init_verbosity(verbose: u8, quiet: u8) -> StdResult<(), EnvLevelError> {
+    init_env_level()?;
+
+    let verbosity = (verbose as i16 - quiet as i16).clamp(i8::MIN as i16, i8::MAX as i16) as i8;
+    let level_filter = if verbosity != 0 {
+        Some(LevelFilter::from_verbosity(verbosity))
+    } else {
+        env_level::logger_level(env_level::LoggerKind::Default).map(|level| level.level_filter())
+    };
+    if let Some(level_filter) = level_filter {
+        default_logger().set_level_filter(level_filter);
+    }
+    Ok(())
 }
 
 /// Initialize log crate proxy.
@@ -492,35 +731,57 @@ pub fn log_crate_proxy() -> &'static LogCrateProxy {
 }
 
 fn default_error_handler(from: impl AsRef<str>, error: Error) {
-    let date = chrono::Local::now()
-        .format("%Y-%m-%d %H:%M:%S.%3f")
-        .to_string();
-
-    eprintln!(
-        "[*** SPDLOG-RS UNHANDLED ERROR ***] [{}] [{}] {}",
-        date,
-        from.as_ref(),
-        error
-    );
+    self_log::error(from.as_ref(), error);
 }
 
 // Used at log macros
+//
+// Macros check the level filter inline and only call this function if the
+// level is enabled. It is kept out of line and marked cold, so that the code
+// building records is moved away from the hot path of call sites.
 #[doc(hidden)]
+#[allow(clippy::too_many_arguments)]
+#[cold]
+#[inline(never)]
 pub fn __log(
     logger: &Logger,
     level: Level,
     srcloc: Option<SourceLocation>,
+    backtrace: bool,
+    lazy: bool,
+    style: Option<terminal_style::Style>,
+    code: Option<ErrorCode>,
+    message_key: Option<&str>,
+    fields: &[Field],
     fmt_args: std::fmt::Arguments,
 ) {
+    if lazy && !logger.sinks_should_log(level) {
+        return;
+    }
+
     // use `Cow` to avoid allocation as much as we can
     let payload: std::borrow::Cow<str> = match fmt_args.as_str() {
         Some(literal_str) => literal_str.into(), // no format arguments, so it is a `&'static str`
         None => fmt_args.to_string().into(),
     };
 
-    let mut builder = Record::builder(level, payload).source_location(srcloc);
-    if let Some(logger_name) = logger.name() {
-        builder = builder.logger_name(logger_name);
+    let mut builder = Record::builder(level, payload)
+        .source_location(srcloc)
+        .fields(fields.iter().map(Field::as_borrowed));
+    if let Some(logger_name) = logger.name_handle() {
+        builder = builder.logger_name_handle(logger_name);
+    }
+    if backtrace || logger.backtrace_level_filter().compare(level) {
+        builder = builder.backtrace(std::backtrace::Backtrace::force_capture());
+    }
+    if let Some(style) = style {
+        builder = builder.style(style);
+    }
+    if let Some(code) = code {
+        builder = builder.code(code);
+    }
+    if let Some(message_key) = message_key {
+        builder = builder.message_key(message_key);
     }
     logger.log(&builder.build());
 }
@@ -556,4 +817,217 @@ mod tests {
             vec!["hello".to_string(), "rust".to_string()]
         );
     }
+
+    #[test]
+    fn trace_hexdump() {
+        let test_sink = Arc::new(CounterSink::new());
+        let test_logger = test_logger_builder()
+            .sink(test_sink.clone())
+            .level_filter(LevelFilter::All)
+            .build();
+
+        let frame = [0x41u8, 0x00];
+        trace_hexdump!(logger: test_logger, frame, "rx frame {}", 1);
+        assert_eq!(
+            test_sink.payloads(),
+            vec![format!(
+                "rx frame 1{}00000000  41 00{}|A.|",
+                EOL,
+                " ".repeat(45)
+            )]
+        );
+    }
+
+    #[test]
+    fn lazy_formatting() {
+        struct Counted<'a>(&'a std::cell::Cell<usize>);
+
+        impl std::fmt::Display for Counted<'_> {
+            fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                self.0.set(self.0.get() + 1);
+                f.write_str("counted")
+            }
+        }
+
+        let test_sink = Arc::new(CounterSink::new());
+        test_sink.set_level_filter(LevelFilter::MoreSevereEqual(Level::Warn));
+        let test_logger = test_logger_builder().sink(test_sink.clone()).build();
+
+        let count = std::cell::Cell::new(0);
+        info!(logger: test_logger, "{}", Counted(&count));
+        assert_eq!(count.get(), 1);
+        info!(logger: test_logger, lazy: true, "{}", Counted(&count));
+        assert_eq!(count.get(), 1);
+        warn!(logger: test_logger, lazy: true, "{}", Counted(&count));
+        assert_eq!(count.get(), 2);
+    }
+
+    #[test]
+    fn macro_fields() {
+        let test_sink = Arc::new(CounterSink::new());
+        let test_logger = test_logger_builder().sink(test_sink.clone()).build();
+
+        let (id, name, elapsed) = (
+            42,
+            String::from("alice"),
+            std::time::Duration::from_millis(1500),
+        );
+        info!(logger: test_logger, user.id = id, user.name = &name, elapsed_ms = elapsed.as_millis(),
+            debug = ?Some(1), display = %'x'; "handled request {}", 1);
+        warn!(logger: test_logger, key = "value", "tracing-style {}", 2);
+        log!(logger: test_logger, Level::Error, key = 1; "runtime level");
+        error!(logger: test_logger, concat!("no ", "fields"));
+
+        let records = test_sink.records();
+        let fields = records
+            .iter()
+            .map(|record| {
+                record
+                    .fields()
+                    .iter()
+                    .map(|field| format!("{}={}", field.key(), field.value()))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            test_sink.payloads(),
+            vec![
+                "handled request 1",
+                "tracing-style 2",
+                "runtime level",
+                "no fields"
+            ]
+        );
+        assert_eq!(
+            fields,
+            vec![
+                vec![
+                    "user.id=42",
+                    "user.name=alice",
+                    "elapsed_ms=1500",
+                    "debug=Some(1)",
+                    "display=x"
+                ],
+                vec!["key=value"],
+                vec!["key=1"],
+                vec![]
+            ]
+        );
+        assert_eq!(records[0].fields()[2].value(), &Value::U64(1500));
+    }
+
+    #[test]
+    fn macro_style() {
+        use terminal_style::{Color, Style};
+
+        let test_sink = Arc::new(CounterSink::new());
+        let test_logger = test_logger_builder().sink(test_sink.clone()).build();
+        let style = Style::builder().color(Color::Green).build();
+
+        info!(logger: test_logger, style: style.clone(), "styled");
+        log!(logger: test_logger, style: style.clone(), Level::Warn, key = 1; "styled");
+        info!(logger: test_logger, "plain");
+
+        let styles = test_sink
+            .records()
+            .iter()
+            .map(|record| record.style().cloned())
+            .collect::<Vec<_>>();
+        assert_eq!(styles, vec![Some(style.clone()), Some(style), None]);
+    }
+
+    #[test]
+    fn macro_code() {
+        let test_sink = Arc::new(CounterSink::new());
+        let test_logger = test_logger_builder().sink(test_sink.clone()).build();
+
+        let code = String::from("runtime");
+        error!(logger: test_logger, code: E1042, "ident");
+        warn!(logger: test_logger, code: 503, key = 1; "literal");
+        log!(logger: test_logger, code: code.clone(), Level::Info, "expr");
+        info!(logger: test_logger, "none");
+
+        let codes = test_sink
+            .records()
+            .iter()
+            .map(|record| record.code().cloned())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            codes,
+            vec![
+                Some(ErrorCode::from("E1042")),
+                Some(ErrorCode::Num(503)),
+                Some(ErrorCode::from(code)),
+                None
+            ]
+        );
+    }
+
+    #[test]
+    fn macro_message_key() {
+        let test_sink = Arc::new(CounterSink::new());
+        let test_logger = test_logger_builder().sink(test_sink.clone()).build();
+
+        let path = "/var";
+        warn!(logger: test_logger, message_key: "disk.full", mount = path; "disk {} is full", path);
+        info!(logger: test_logger, "no key");
+
+        let records = test_sink.records();
+        assert_eq!(records[0].message_key(), Some("disk.full"));
+        assert_eq!(records[0].payload(), "disk /var is full");
+        assert_eq!(records[1].message_key(), None);
+    }
+
+    #[test]
+    fn macro_degrade() {
+        let test_sink = Arc::new(CounterSink::new());
+        let test_logger = test_logger_builder()
+            .sink(test_sink.clone())
+            .level_filter(LevelFilter::All)
+            .build();
+
+        for i in 0..3 {
+            // Records filtered out do not consume the first level.
+            test_logger.set_level_filter(if i == 0 {
+                LevelFilter::Off
+            } else {
+                LevelFilter::All
+            });
+            warn!(logger: test_logger, degrade: Level::Debug, "static {}", i);
+            log!(logger: test_logger, degrade: Level::Trace, Level::Error, "runtime {}", i);
+        }
+
+        let records = test_sink
+            .records()
+            .iter()
+            .map(|record| format!("{} {}", record.level(), record.payload()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            records,
+            vec![
+                "warn static 1",
+                "error runtime 1",
+                "debug static 2",
+                "trace runtime 2"
+            ]
+        );
+    }
+
+    #[test]
+    fn runtime_level() {
+        let test_sink = Arc::new(CounterSink::new());
+        let test_logger = test_logger_builder().sink(test_sink.clone()).build();
+
+        for level in Level::iter() {
+            assert_eq!(
+                log_enabled!(logger: test_logger, level),
+                LevelFilter::MoreSevereEqual(Level::Info).compare(level)
+            );
+            log!(logger: test_logger, level, "{}", level);
+        }
+        assert_eq!(
+            test_sink.payloads(),
+            vec!["critical", "error", "warn", "info"]
+        );
+    }
 }
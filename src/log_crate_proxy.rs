@@ -91,5 +91,6 @@ mod tests {
             sink.payloads(),
             vec!["hello".to_string(), "world".to_string()]
         );
+        assert_eq!(sink.records()[0].target(), Some(module_path!()));
     }
 }
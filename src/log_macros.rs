@@ -19,18 +19,158 @@
 ///     data.0, data.1, private_data);
 /// ```
 ///
+///
+/// # Backtrace
+///
+/// All log macros accept an optional `backtrace: <bool>` argument after the
+/// optional `logger: <logger>` argument. If it is `true`, a [`Backtrace`] is
+/// captured and attached to the record regardless of
+/// [`Logger::backtrace_level_filter`].
+///
+/// ```
+/// use spdlog::prelude::*;
+///
+/// # let app_events = spdlog::default_logger();
+/// error!(backtrace: true, "Unexpected state");
+/// log!(logger: app_events, backtrace: true, Level::Warn, "Unexpected state");
+/// ```
+///
+/// # Lazy formatting
+///
+/// Records filtered out by the logger are never formatted, but the sink level
+/// filters are only checked after the record is built. All log macros accept
+/// an optional `lazy: <bool>` argument after the optional `logger: <logger>`
+/// and `backtrace: <bool>` arguments. If it is `true`, the format arguments
+/// are not formatted unless at least one sink of the logger would accept the
+/// record, which avoids expensive formatting (e.g. `{:?}` of large values)
+/// when all sinks would discard it anyway.
+///
+/// ```
+/// use spdlog::prelude::*;
+///
+/// # let app_events = spdlog::default_logger();
+/// # let large_state = vec![0u8; 1024];
+/// debug!(lazy: true, "State: {:?}", large_state);
+/// log!(logger: app_events, lazy: true, Level::Trace, "State: {:?}", large_state);
+/// ```
+///
+/// # Style
+///
+/// All log macros accept an optional `style: <Style>` argument along with the
+/// other optional arguments. Sinks writing to terminals (e.g.
+/// [`StdStreamSink`]) render the whole record in the style instead of the level
+/// style, while other sinks ignore it. It is useful for occasional highlights
+/// in command line tools.
+///
+/// ```
+/// use spdlog::{
+///     prelude::*,
+///     terminal_style::{Color, Style},
+/// };
+///
+/// info!(style: Style::builder().color(Color::Green).build(), "deployment complete");
+/// ```
+///
+/// # Error codes
+///
+/// All log macros accept an optional `code: <code>` argument along with the
+/// other optional arguments, which attaches an [`ErrorCode`] to the record. A
+/// bare identifier is taken as a string code literally, other expressions are
+/// converted by [`ErrorCode::from`], e.g. integer literals into numeric codes.
+/// Formatters render the code consistently, and filters can match it by
+/// [`Record::code`].
+///
+/// ```
+/// use spdlog::prelude::*;
+///
+/// # let path = "/etc/app.toml";
+/// error!(code: E1042, "failed to parse config file {}", path);
+/// warn!(code: 503, "upstream unavailable");
+/// ```
+///
+/// # Message keys
+///
+/// All log macros accept an optional `message_key: <&str>` argument along with
+/// the other optional arguments. The format arguments are still the canonical
+/// message of the record, while formatters configured with a message catalog
+/// resolve the key with the fields as arguments, e.g. to localize user-facing
+/// output. See the [`catalog`] module for details.
+///
+/// ```
+/// use spdlog::prelude::*;
+///
+/// # let path = "/var";
+/// warn!(message_key: "disk.full", mount = path; "disk {} is full", path);
+/// ```
+///
+/// # Degrading levels
+///
+/// All log macros accept an optional `degrade: <Level>` argument along with the
+/// other optional arguments. The first record of the call site passing the
+/// level filter of the logger is logged at the level of the macro, and all
+/// subsequent records of the call site are logged at the given level. It
+/// surfaces the first occurrence of a recurring problem prominently without
+/// flooding the logs with the following ones.
+///
+/// ```
+/// use spdlog::prelude::*;
+///
+/// # let peers = ["127.0.0.1:8080"];
+/// for peer in peers {
+///     // `warn` the first time, `debug` thereafter.
+///     warn!(degrade: Level::Debug, "connection reset by {}", peer);
+/// }
+/// ```
+///
+/// # Fields
+///
+/// All log macros accept fields before the format arguments, in the form of
+/// `key = value` separated by commas and terminated by a semicolon (or a comma,
+/// as the `tracing` crate does). Keys can be dotted paths such as `user.id`.
+/// Values are converted into [`Value`]s, or formatted by [`Debug`] with the
+/// sigil `?` and by [`Display`] with the sigil `%`. Fields are only evaluated
+/// if the record is not filtered out by the logger.
+///
+/// ```
+/// use spdlog::prelude::*;
+///
+/// # let (id, peer, elapsed) = (42, "127.0.0.1:8080", std::time::Duration::from_millis(3));
+/// # let headers = vec!["Accept"];
+/// info!(user.id = id, elapsed_ms = elapsed.as_millis(); "handled request");
+/// debug!(peer = %peer, headers = ?headers; "request headers");
+/// log!(Level::Warn, retry = true, "retrying");
+/// ```
+///
+/// # Runtime levels
+///
+/// The level can be a runtime value, records are still checked against the
+/// compile time filters and the level filter of the logger before anything is
+/// formatted. Use [`log_enabled!`] to guard expensive data collection.
+///
+/// ```
+/// use spdlog::{log, Level};
+///
+/// let level = if cfg!(debug_assertions) { Level::Debug } else { Level::Info };
+/// log!(level, "Started");
+/// ```
+///
 /// [`Level`]: crate::Level
+/// [`ErrorCode`]: crate::ErrorCode
+/// [`ErrorCode::from`]: crate::ErrorCode::from
+/// [`Record::code`]: crate::Record::code
+/// [`catalog`]: crate::catalog
+/// [`StdStreamSink`]: crate::sink::StdStreamSink
+/// [`Value`]: crate::Value
+/// [`Debug`]: std::fmt::Debug
+/// [`Display`]: std::fmt::Display
+/// [`log_enabled!`]: crate::log_enabled
+/// [`Backtrace`]: std::backtrace::Backtrace
+/// [`Logger::backtrace_level_filter`]: crate::Logger::backtrace_level_filter
 #[macro_export]
 macro_rules! log {
-    (logger: $logger:expr, $level:expr, $($arg:tt)+) => ({
-        let logger = &$logger;
-        const LEVEL: $crate::Level = $level;
-        const SHOULD_LOG: bool = $crate::STATIC_LEVEL_FILTER.__compare_const(LEVEL);
-        if SHOULD_LOG && logger.should_log(LEVEL) {
-            $crate::__log(logger, LEVEL, $crate::source_location_current!(), format_args!($($arg)+));
-        }
-    });
-    ($level:expr, $($arg:tt)+) => ($crate::log!(logger: $crate::default_logger(), $level, $($arg)+))
+    ($($input:tt)+) => (
+        $crate::__log_impl!(@opts($crate::default_logger(), false, false, ::core::option::Option::None, ::core::option::Option::None, ::core::option::Option::None, [], []) $($input)+)
+    )
 }
 
 /// Logs a message at the critical level.
@@ -48,11 +188,8 @@ macro_rules! log {
 /// ```
 #[macro_export]
 macro_rules! critical {
-    (logger: $logger:expr, $($arg:tt)+) => (
-        $crate::log!(logger: $logger, $crate::Level::Critical, $($arg)+)
-    );
-    ($($arg:tt)+) => (
-        $crate::log!($crate::Level::Critical, $($arg)+)
+    ($($input:tt)+) => (
+        $crate::__log_impl!(@opts($crate::default_logger(), false, false, ::core::option::Option::None, ::core::option::Option::None, ::core::option::Option::None, [], [$crate::Level::Critical]) $($input)+)
     )
 }
 
@@ -71,11 +208,8 @@ macro_rules! critical {
 /// ```
 #[macro_export]
 macro_rules! error {
-    (logger: $logger:expr, $($arg:tt)+) => (
-        $crate::log!(logger: $logger, $crate::Level::Error, $($arg)+)
-    );
-    ($($arg:tt)+) => (
-        $crate::log!($crate::Level::Error, $($arg)+)
+    ($($input:tt)+) => (
+        $crate::__log_impl!(@opts($crate::default_logger(), false, false, ::core::option::Option::None, ::core::option::Option::None, ::core::option::Option::None, [], [$crate::Level::Error]) $($input)+)
     )
 }
 
@@ -94,11 +228,8 @@ macro_rules! error {
 /// ```
 #[macro_export]
 macro_rules! warn {
-    (logger: $logger:expr, $($arg:tt)+) => (
-        $crate::log!(logger: $logger, $crate::Level::Warn, $($arg)+)
-    );
-    ($($arg:tt)+) => (
-        $crate::log!($crate::Level::Warn, $($arg)+)
+    ($($input:tt)+) => (
+        $crate::__log_impl!(@opts($crate::default_logger(), false, false, ::core::option::Option::None, ::core::option::Option::None, ::core::option::Option::None, [], [$crate::Level::Warn]) $($input)+)
     )
 }
 
@@ -119,11 +250,8 @@ macro_rules! warn {
 /// ```
 #[macro_export]
 macro_rules! info {
-    (logger: $logger:expr, $($arg:tt)+) => (
-        $crate::log!(logger: $logger, $crate::Level::Info, $($arg)+)
-    );
-    ($($arg:tt)+) => (
-        $crate::log!($crate::Level::Info, $($arg)+)
+    ($($input:tt)+) => (
+        $crate::__log_impl!(@opts($crate::default_logger(), false, false, ::core::option::Option::None, ::core::option::Option::None, ::core::option::Option::None, [], [$crate::Level::Info]) $($input)+)
     )
 }
 
@@ -143,11 +271,8 @@ macro_rules! info {
 /// ```
 #[macro_export]
 macro_rules! debug {
-    (logger: $logger:expr, $($arg:tt)+) => (
-        $crate::log!(logger: $logger, $crate::Level::Debug, $($arg)+)
-    );
-    ($($arg:tt)+) => (
-        $crate::log!($crate::Level::Debug, $($arg)+)
+    ($($input:tt)+) => (
+        $crate::__log_impl!(@opts($crate::default_logger(), false, false, ::core::option::Option::None, ::core::option::Option::None, ::core::option::Option::None, [], [$crate::Level::Debug]) $($input)+)
     )
 }
 
@@ -169,10 +294,226 @@ macro_rules! debug {
 /// ```
 #[macro_export]
 macro_rules! trace {
-    (logger: $logger:expr, $($arg:tt)+) => (
-        $crate::log!(logger: $logger, $crate::Level::Trace, $($arg)+)
-    );
-    ($($arg:tt)+) => (
-        $crate::log!($crate::Level::Trace, $($arg)+)
+    ($($input:tt)+) => (
+        $crate::__log_impl!(@opts($crate::default_logger(), false, false, ::core::option::Option::None, ::core::option::Option::None, ::core::option::Option::None, [], [$crate::Level::Trace]) $($input)+)
     )
 }
+
+/// Logs a hexdump of binary data at the trace level.
+///
+/// The message is followed by the dump of the bytes, see [`Hexdump`] for the
+/// format. Like other log macros, nothing is formatted if
+/// the record is filtered out by the level filters.
+///
+/// # Examples
+///
+/// ```
+/// use spdlog::trace_hexdump;
+///
+/// # let app_events = spdlog::default_logger();
+/// let (frame, port) = (b"\x01\x02\x03\x04", 22);
+///
+/// trace_hexdump!(frame, "rx frame on port {}", port);
+/// trace_hexdump!(logger: app_events, frame, "rx frame on port {}", port);
+/// ```
+///
+/// [`Hexdump`]: crate::Hexdump
+#[macro_export]
+macro_rules! trace_hexdump {
+    (logger: $logger:expr, $bytes:expr, $($arg:tt)+) => (
+        $crate::trace!(
+            logger: $logger,
+            "{}{}",
+            format_args!($($arg)+),
+            $crate::Hexdump::new(&$bytes[..])
+        )
+    );
+    ($bytes:expr, $($arg:tt)+) => (
+        $crate::trace_hexdump!(logger: $crate::default_logger(), $bytes, $($arg)+)
+    );
+}
+
+/// Checks whether a record at the given [`Level`] would be logged.
+///
+/// It checks the level against the compile time filters (see the crate level
+/// documentation) and [`Logger::should_log`], i.e. the level filters of the
+/// logger and its sinks. The default logger is checked if no logger is
+/// specified. The level can be a runtime value.
+///
+/// It can be used to guard expensive data collection that is only needed for
+/// logging.
+///
+/// # Examples
+///
+/// ```
+/// use spdlog::{debug, log_enabled, Level};
+///
+/// # let app_events = spdlog::default_logger();
+/// # fn collect_stats() -> u64 { 42 }
+/// if log_enabled!(Level::Debug) {
+///     let stats = collect_stats();
+///     debug!("Stats: {}", stats);
+/// }
+///
+/// let level = Level::Trace;
+/// if log_enabled!(logger: app_events, level) {
+///     // ...
+/// }
+/// ```
+///
+/// [`Level`]: crate::Level
+/// [`Logger::should_log`]: crate::Logger::should_log
+#[macro_export]
+macro_rules! log_enabled {
+    (logger: $logger:expr, $level:expr) => ({
+        let level: $crate::Level = $level;
+        $crate::__log_impl!(@static_enabled level) && $logger.should_log(level)
+    });
+    ($level:expr) => (
+        $crate::log_enabled!(logger: $crate::default_logger(), $level)
+    );
+}
+
+// Parses the optional arguments `logger: <expr>`, `backtrace: <expr>`,
+// `lazy: <expr>`, `style: <expr>`, `code: <ident | expr>`,
+// `message_key: <expr>` and `degrade: <expr>` into
+// `@opts(logger, backtrace, lazy, style, code, message_key, [degrade],
+// [level])`, then the fields into `@fields(opts) [fields]`, and finally logs
+// the record. The level given by the level macros is a constant, so disabled
+// records are compiled out. The level given to `log!` is parsed from the input
+// and can be a runtime value, so is the level of a call site with `degrade`,
+// which is only switched to the degraded level once a record has been logged at
+// the first level.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __log_impl {
+    (@static_enabled $level:expr) => ({
+        const MODULE_LEVEL_FILTER: $crate::LevelFilter = $crate::__static_module_level_filter(
+            option_env!("SPDLOG_RS_STATIC_LEVEL"),
+            module_path!(),
+        );
+        $crate::STATIC_LEVEL_FILTER.__compare_const($level)
+            && MODULE_LEVEL_FILTER.__compare_const($level)
+    });
+    (@opts($_logger:expr, $backtrace:expr, $lazy:expr, $style:expr, $code:expr, $message_key:expr, [$($degrade:expr)?], [$($level:expr)?]) logger: $logger:expr, $($rest:tt)+) => (
+        $crate::__log_impl!(@opts($logger, $backtrace, $lazy, $style, $code, $message_key, [$($degrade)?], [$($level)?]) $($rest)+)
+    );
+    (@opts($logger:expr, $_backtrace:expr, $lazy:expr, $style:expr, $code:expr, $message_key:expr, [$($degrade:expr)?], [$($level:expr)?]) backtrace: $backtrace:expr, $($rest:tt)+) => (
+        $crate::__log_impl!(@opts($logger, $backtrace, $lazy, $style, $code, $message_key, [$($degrade)?], [$($level)?]) $($rest)+)
+    );
+    (@opts($logger:expr, $backtrace:expr, $_lazy:expr, $style:expr, $code:expr, $message_key:expr, [$($degrade:expr)?], [$($level:expr)?]) lazy: $lazy:expr, $($rest:tt)+) => (
+        $crate::__log_impl!(@opts($logger, $backtrace, $lazy, $style, $code, $message_key, [$($degrade)?], [$($level)?]) $($rest)+)
+    );
+    (@opts($logger:expr, $backtrace:expr, $lazy:expr, $_style:expr, $code:expr, $message_key:expr, [$($degrade:expr)?], [$($level:expr)?]) style: $style:expr, $($rest:tt)+) => (
+        $crate::__log_impl!(@opts($logger, $backtrace, $lazy, ::core::option::Option::Some($style), $code, $message_key, [$($degrade)?], [$($level)?]) $($rest)+)
+    );
+    (@opts($logger:expr, $backtrace:expr, $lazy:expr, $style:expr, $_code:expr, $message_key:expr, [$($degrade:expr)?], [$($level:expr)?]) code: $code:ident, $($rest:tt)+) => (
+        $crate::__log_impl!(@opts($logger, $backtrace, $lazy, $style, ::core::option::Option::Some($crate::ErrorCode::from(stringify!($code))), $message_key, [$($degrade)?], [$($level)?]) $($rest)+)
+    );
+    (@opts($logger:expr, $backtrace:expr, $lazy:expr, $style:expr, $_code:expr, $message_key:expr, [$($degrade:expr)?], [$($level:expr)?]) code: $code:expr, $($rest:tt)+) => (
+        $crate::__log_impl!(@opts($logger, $backtrace, $lazy, $style, ::core::option::Option::Some($crate::ErrorCode::from($code)), $message_key, [$($degrade)?], [$($level)?]) $($rest)+)
+    );
+    (@opts($logger:expr, $backtrace:expr, $lazy:expr, $style:expr, $code:expr, $_message_key:expr, [$($degrade:expr)?], [$($level:expr)?]) message_key: $message_key:expr, $($rest:tt)+) => (
+        $crate::__log_impl!(@opts($logger, $backtrace, $lazy, $style, $code, ::core::option::Option::Some($message_key), [$($degrade)?], [$($level)?]) $($rest)+)
+    );
+    (@opts($logger:expr, $backtrace:expr, $lazy:expr, $style:expr, $code:expr, $message_key:expr, [$($_degrade:expr)?], [$($level:expr)?]) degrade: $degrade:expr, $($rest:tt)+) => (
+        $crate::__log_impl!(@opts($logger, $backtrace, $lazy, $style, $code, $message_key, [$degrade], [$($level)?]) $($rest)+)
+    );
+    (@opts($logger:expr, $backtrace:expr, $lazy:expr, $style:expr, $code:expr, $message_key:expr, [], []) $level:expr, $($rest:tt)+) => (
+        $crate::__log_impl!(@fields($logger, $backtrace, $lazy, $style, $code, $message_key, (runtime $level)) [] $($rest)+)
+    );
+    (@opts($logger:expr, $backtrace:expr, $lazy:expr, $style:expr, $code:expr, $message_key:expr, [$degrade:expr], []) $level:expr, $($rest:tt)+) => (
+        $crate::__log_impl!(@fields($logger, $backtrace, $lazy, $style, $code, $message_key, (degrade $level, $degrade)) [] $($rest)+)
+    );
+    (@opts($logger:expr, $backtrace:expr, $lazy:expr, $style:expr, $code:expr, $message_key:expr, [], [$level:expr]) $($rest:tt)+) => (
+        $crate::__log_impl!(@fields($logger, $backtrace, $lazy, $style, $code, $message_key, (const $level)) [] $($rest)+)
+    );
+    (@opts($logger:expr, $backtrace:expr, $lazy:expr, $style:expr, $code:expr, $message_key:expr, [$degrade:expr], [$level:expr]) $($rest:tt)+) => (
+        $crate::__log_impl!(@fields($logger, $backtrace, $lazy, $style, $code, $message_key, (degrade $level, $degrade)) [] $($rest)+)
+    );
+    (@fields $opts:tt [$($field:tt)*] $key:ident $(. $key_rest:ident)* = ? $value:expr, $($rest:tt)+) => (
+        $crate::__log_impl!(@fields $opts [$($field)* (debug $key $(. $key_rest)* = $value)] $($rest)+)
+    );
+    (@fields $opts:tt [$($field:tt)*] $key:ident $(. $key_rest:ident)* = ? $value:expr; $($rest:tt)+) => (
+        $crate::__log_impl!(@log $opts [$($field)* (debug $key $(. $key_rest)* = $value)] $($rest)+)
+    );
+    (@fields $opts:tt [$($field:tt)*] $key:ident $(. $key_rest:ident)* = % $value:expr, $($rest:tt)+) => (
+        $crate::__log_impl!(@fields $opts [$($field)* (display $key $(. $key_rest)* = $value)] $($rest)+)
+    );
+    (@fields $opts:tt [$($field:tt)*] $key:ident $(. $key_rest:ident)* = % $value:expr; $($rest:tt)+) => (
+        $crate::__log_impl!(@log $opts [$($field)* (display $key $(. $key_rest)* = $value)] $($rest)+)
+    );
+    (@fields $opts:tt [$($field:tt)*] $key:ident $(. $key_rest:ident)* = $value:expr, $($rest:tt)+) => (
+        $crate::__log_impl!(@fields $opts [$($field)* (value $key $(. $key_rest)* = $value)] $($rest)+)
+    );
+    (@fields $opts:tt [$($field:tt)*] $key:ident $(. $key_rest:ident)* = $value:expr; $($rest:tt)+) => (
+        $crate::__log_impl!(@log $opts [$($field)* (value $key $(. $key_rest)* = $value)] $($rest)+)
+    );
+    (@fields $opts:tt [$($field:tt)*] $($arg:tt)+) => (
+        $crate::__log_impl!(@log $opts [$($field)*] $($arg)+)
+    );
+    (@field ($kind:ident $key:ident $(. $key_rest:ident)* = $value:expr)) => (
+        $crate::Field::new(
+            concat!(stringify!($key) $(, ".", stringify!($key_rest))*),
+            $crate::__log_impl!(@value $kind $value),
+        )
+    );
+    (@value debug $value:expr) => ($crate::Value::display(&format_args!("{:?}", $value)));
+    (@value display $value:expr) => ($crate::Value::display(&$value));
+    (@value value $value:expr) => ($value);
+    (@log($logger:expr, $backtrace:expr, $lazy:expr, $style:expr, $code:expr, $message_key:expr, (degrade $first:expr, $then:expr)) [$($field:tt)*] $($arg:tt)+) => ({
+        static FIRST: ::core::sync::atomic::AtomicBool = ::core::sync::atomic::AtomicBool::new(true);
+        let logger = &$logger;
+        let first: $crate::Level = $first;
+        // The first record is only consumed if it is logged at the first level.
+        let level = if FIRST.load(::core::sync::atomic::Ordering::Relaxed)
+            && $crate::__log_impl!(@static_enabled first)
+            && logger.__level_enabled(first)
+            && FIRST.swap(false, ::core::sync::atomic::Ordering::Relaxed)
+        {
+            first
+        } else {
+            $then
+        };
+        $crate::__log_impl!(@log(logger, $backtrace, $lazy, $style, $code, $message_key, (runtime level)) [$($field)*] $($arg)+)
+    });
+    (@log($logger:expr, $backtrace:expr, $lazy:expr, $style:expr, $code:expr, $message_key:expr, (runtime $level:expr)) [$($field:tt)*] $($arg:tt)+) => ({
+        let logger = &$logger;
+        let level: $crate::Level = $level;
+        if $crate::__log_impl!(@static_enabled level) && logger.__level_enabled(level) {
+            $crate::__log(
+                logger,
+                level,
+                $crate::source_location_current!(),
+                $backtrace,
+                $lazy,
+                $style,
+                $code,
+                $message_key,
+                &[$($crate::__log_impl!(@field $field)),*],
+                format_args!($($arg)+),
+            );
+        }
+    });
+    (@log($logger:expr, $backtrace:expr, $lazy:expr, $style:expr, $code:expr, $message_key:expr, (const $level:expr)) [$($field:tt)*] $($arg:tt)+) => ({
+        let logger = &$logger;
+        const LEVEL: $crate::Level = $level;
+        const SHOULD_LOG: bool = $crate::STATIC_LEVEL_FILTER.__compare_const(LEVEL)
+            && $crate::__static_module_level_filter(option_env!("SPDLOG_RS_STATIC_LEVEL"), module_path!())
+                .__compare_const(LEVEL);
+        if SHOULD_LOG && logger.__level_enabled(LEVEL) {
+            $crate::__log(
+                logger,
+                LEVEL,
+                $crate::source_location_current!(),
+                $backtrace,
+                $lazy,
+                $style,
+                $code,
+                $message_key,
+                &[$($crate::__log_impl!(@field $field)),*],
+                format_args!($($arg)+),
+            );
+        }
+    });
+}
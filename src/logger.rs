@@ -1,17 +1,30 @@
 //! Provides a logger structure.
 
 use std::{
-    sync::{atomic::Ordering, Arc, Mutex},
-    time::Duration,
+    borrow::Cow,
+    cell::Cell,
+    mem,
+    ops::{Deref, DerefMut},
+    sync::{
+        atomic::{AtomicU64, AtomicU8, Ordering},
+        Arc, Mutex, Weak,
+    },
+    time::{Duration, SystemTime},
 };
 
+use arc_swap::ArcSwap;
 use atomic::Atomic;
+use once_cell::sync::Lazy;
 
 use crate::{
     env_level,
+    filter::{self, Filter},
     periodic_worker::PeriodicWorker,
-    sink::{Sink, Sinks},
-    Error, ErrorHandler, Level, LevelFilter, Record,
+    profiling::{self, Stage},
+    rate_limiter::{Acquire, RateLimiter},
+    sink::{FilterSink, Sink, Sinks, WeakSink},
+    trace_context, Error, ErrorHandler, Field, InvalidArgumentError, Level, LevelFilter,
+    LoggerName, Record, RecordFlags, Result, Value,
 };
 
 /// A logger structure.
@@ -28,27 +41,42 @@ use crate::{
 /// use std::time::Duration;
 /// use spdlog::prelude::*;
 ///
+/// # fn main() -> Result<(), spdlog::Error> {
 /// # let custom_logger: Arc<Logger> = spdlog::default_logger();
 /// let default_logger: Arc<Logger> = spdlog::default_logger();
 /// default_logger.set_level_filter(LevelFilter::All);
-/// default_logger.set_flush_period(Some(Duration::from_secs(10)));
+/// default_logger.set_flush_period(Some(Duration::from_secs(10)))?;
 /// info!("logging with default logger");
 ///
 /// custom_logger.set_level_filter(LevelFilter::All);
-/// custom_logger.set_flush_period(Some(Duration::from_secs(10)));
+/// custom_logger.set_flush_period(Some(Duration::from_secs(10)))?;
 /// info!(logger: custom_logger, "logging with custom logger");
+/// # Ok(()) }
 /// ```
 ///
 /// For more examples, see [./examples] directory.
 ///
 /// [./examples]: https://github.com/SpriteOvO/spdlog-rs/tree/main/examples
 pub struct Logger {
-    name: Option<String>,
+    name: Option<LoggerName>,
     level_filter: Atomic<LevelFilter>,
-    sinks: Sinks,
+    // A bit mask of the levels enabled by `level_filter`, indexed by levels, so
+    // that call sites check levels with a bit test.
+    enabled_levels: AtomicU8,
+    sinks: ArcSwap<Sinks>,
     flush_level_filter: Atomic<LevelFilter>,
+    backtrace_level_filter: Atomic<LevelFilter>,
     periodic_flusher: Mutex<Option<PeriodicWorker>>,
+    heartbeat: Mutex<Option<PeriodicWorker>>,
+    level_boost: Mutex<LevelBoost>,
     error_handler: spin::RwLock<Option<ErrorHandler>>,
+    fields: Vec<Field<'static>>,
+    filters: Vec<Arc<dyn Filter>>,
+    rate_limiter: Option<Arc<RateLimiter>>,
+    clock_policy: ClockPolicy,
+    // The latest time of records seen by this logger, in nanoseconds since the
+    // Unix epoch. Only tracked if the clock policy is not `ClockPolicy::AsIs`.
+    latest_time: AtomicU64,
 }
 
 impl Logger {
@@ -61,14 +89,24 @@ impl Logger {
     ///
     /// Returns `None` if the logger does not have a name.
     pub fn name(&self) -> Option<&str> {
-        self.name.as_ref().map(|s| s.as_ref())
+        self.name.as_ref().map(LoggerName::as_str)
+    }
+
+    /// Gets the handle of the logger name, for cheap comparisons and cloning.
+    ///
+    /// Returns `None` if the logger does not have a name.
+    pub fn name_handle(&self) -> Option<&LoggerName> {
+        self.name.as_ref()
     }
 
     /// Determines if a log message with the specified level would be
     /// logged.
     ///
-    /// This allows callers to avoid expensive computation of log message
-    /// arguments if the message would be discarded anyway.
+    /// It returns `true` if the level is accepted by the level filter of the
+    /// logger and at least one of its sinks. This allows callers (e.g. manual
+    /// integrations building records themselves) to avoid expensive
+    /// computation of log message arguments if the message would be discarded
+    /// anyway.
     ///
     /// # Examples
     ///
@@ -91,17 +129,139 @@ impl Logger {
     /// assert_eq!(logger.should_log(Level::Error), true);
     /// ```
     pub fn should_log(&self, level: Level) -> bool {
-        self.level_filter().compare(level)
+        self.level_filter().compare(level) && self.sinks_should_log(level)
+    }
+
+    // Returns `true` if at least one of the sinks would log the level.
+    pub(crate) fn sinks_should_log(&self, level: Level) -> bool {
+        self.sinks.load().iter().any(|sink| sink.should_log(level))
+    }
+
+    /// Gets the fields attached to all records logged by this logger.
+    ///
+    /// See [`LoggerBuilder::field`].
+    pub fn fields(&self) -> &[Field<'static>] {
+        &self.fields
     }
 
     /// Logs a record.
     ///
+    /// If crate feature `opentelemetry` is enabled and there is a valid
+    /// OpenTelemetry span in the current context, fields `trace_id` and
+    /// `span_id` of the span are attached to the record after the fields of the
+    /// logger, unless the record already has a `trace_id` field.
+    ///
+    /// Records with a time earlier than a previous record are handled according
+    /// to the [`ClockPolicy`] of the logger, and records exceeding the rate
+    /// are suppressed if a [`RateLimiter`] is set.
+    ///
     /// Users usually do not use this function directly, use log macros instead.
     pub fn log(&self, record: &Record) {
-        if !self.should_log(record.level()) {
+        if !self.level_filter().compare(record.level()) {
+            return;
+        }
+
+        if let Some(rate_limiter) = &self.rate_limiter {
+            match rate_limiter.acquire(record.level()) {
+                Acquire::Suppressed => return,
+                Acquire::Allowed => {}
+                Acquire::AllowedWithReport(suppressed) => self.report_suppressed(suppressed),
+            }
+        }
+
+        self.log_unlimited(record);
+    }
+
+    fn report_suppressed(&self, suppressed: u64) {
+        if !self.level_filter().compare(Level::Warn) {
             return;
         }
-        self.sink_record(record);
+
+        let mut builder = Record::builder(Level::Warn, "records suppressed by rate limit")
+            .field("suppressed", suppressed)
+            .flags(RecordFlags::INTERNAL | RecordFlags::SUMMARY);
+        if let Some(logger_name) = self.name_handle() {
+            builder = builder.logger_name_handle(logger_name);
+        }
+        self.log_unlimited(&builder.build());
+    }
+
+    fn log_unlimited(&self, record: &Record) {
+        let trace_fields = trace_context::current_fields(record);
+        let backwards = self.clock_backwards(record.time());
+        let clock_field = match (self.clock_policy, backwards) {
+            (ClockPolicy::Mark, Some(backwards)) => Some(Field::new("clock_backwards", backwards)),
+            _ => None,
+        };
+
+        if self.fields.is_empty() && trace_fields.is_none() && backwards.is_none() {
+            self.sink_record(record);
+        } else {
+            let mut record = record.with_leading_fields(
+                self.fields
+                    .iter()
+                    .chain(trace_fields.iter().flatten())
+                    .chain(clock_field.iter())
+                    .map(Field::as_borrowed),
+            );
+            if let (ClockPolicy::Clamp, Some(backwards)) = (self.clock_policy, backwards) {
+                record.set_time(record.time() + backwards);
+            }
+            self.sink_record(&record);
+        }
+    }
+
+    /// Forwards a record logged by another logger to this logger.
+    ///
+    /// The record is logged as [`Logger::log`] does, with the fields of this
+    /// logger placed before the fields of the record. The logger name, time,
+    /// sequence number and source location of the record are kept, so that
+    /// the record shows where it originated, e.g. a logger of a library
+    /// forwarding its records into the root logger of the application. See
+    /// [`ForwardSink`] for forwarding all records of a logger.
+    ///
+    /// Loggers forwarding records to each other in a cycle would forward them
+    /// endlessly, so records forwarded more than 8 times in a nested manner
+    /// on a thread are dropped.
+    ///
+    /// [`ForwardSink`]: crate::sink::ForwardSink
+    pub fn forward(&self, record: &Record) {
+        const MAX_DEPTH: usize = 8;
+
+        thread_local! {
+            static DEPTH: Cell<usize> = const { Cell::new(0) };
+        }
+
+        DEPTH.with(|depth| {
+            if depth.get() >= MAX_DEPTH {
+                return;
+            }
+            depth.set(depth.get() + 1);
+            self.log(record);
+            depth.set(depth.get() - 1);
+        });
+    }
+
+    /// Gets the clock policy.
+    ///
+    /// See [`LoggerBuilder::clock_policy`].
+    pub fn clock_policy(&self) -> ClockPolicy {
+        self.clock_policy
+    }
+
+    // Returns how far the time goes backwards from the latest time seen by this
+    // logger, or `None` if it does not or the clock policy is
+    // `ClockPolicy::AsIs`.
+    fn clock_backwards(&self, time: SystemTime) -> Option<Duration> {
+        if self.clock_policy == ClockPolicy::AsIs {
+            return None;
+        }
+
+        let nanos = time
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map_or(0, |since_epoch| since_epoch.as_nanos() as u64);
+        let latest = self.latest_time.fetch_max(nanos, Ordering::Relaxed);
+        (nanos < latest).then(|| Duration::from_nanos(latest - nanos))
     }
 
     /// Flushes any buffered records.
@@ -110,12 +270,57 @@ impl Logger {
     /// policies. See also [`Logger::flush_level_filter`] and
     /// [`Logger::set_flush_period`].
     ///
+    /// If a [`RateLimiter`] is set, suppressed records not reported yet are
+    /// reported before flushing.
+    ///
     /// Note that it is expensive, calling it frequently will affect
     /// performance.
     pub fn flush(&self) {
+        if let Some(suppressed) = self
+            .rate_limiter
+            .as_ref()
+            .and_then(|rate_limiter| rate_limiter.take_suppressed())
+        {
+            self.report_suppressed(suppressed);
+        }
         self.flush_sinks();
     }
 
+    /// Flushes any buffered records and shuts down the sinks for a clean exit,
+    /// see [`Sink::shutdown`].
+    ///
+    /// For example, [`FileSink`]s with a footer write it, so that the logs of a
+    /// clean exit can be told apart from the ones of a crash. It is needed for
+    /// the default logger in particular, whose sinks are never dropped.
+    ///
+    /// The logger can still be used after it, but records may be written after
+    /// the footers.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use spdlog::prelude::*;
+    ///
+    /// info!("exiting");
+    /// spdlog::default_logger().shutdown();
+    /// ```
+    ///
+    /// [`FileSink`]: crate::sink::FileSink
+    pub fn shutdown(&self) {
+        if let Some(suppressed) = self
+            .rate_limiter
+            .as_ref()
+            .and_then(|rate_limiter| rate_limiter.take_suppressed())
+        {
+            self.report_suppressed(suppressed);
+        }
+        self.sinks.load().iter().for_each(|sink| {
+            if let Err(err) = sink.shutdown() {
+                self.handle_error(err.with_sink(&**sink, None, self.name()));
+            }
+        });
+    }
+
     /// Gets the flush level filter.
     pub fn flush_level_filter(&self) -> LevelFilter {
         self.flush_level_filter.load(Ordering::Relaxed)
@@ -150,7 +355,40 @@ impl Logger {
             .store(level_filter, Ordering::Relaxed);
     }
 
+    /// Gets the backtrace level filter.
+    pub fn backtrace_level_filter(&self) -> LevelFilter {
+        self.backtrace_level_filter.load(Ordering::Relaxed)
+    }
+
+    /// Sets a backtrace level filter.
+    ///
+    /// When logging a new record via log macros, capture a [`Backtrace`] and
+    /// attach it to the record if this filter condition is true. Formatters
+    /// render it below the payload, see [`Record::backtrace`].
+    ///
+    /// Capturing a backtrace is expensive, it is usually only enabled for
+    /// severe levels. The default is [`LevelFilter::Off`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::sync::Arc;
+    /// use spdlog::prelude::*;
+    ///
+    /// # let logger: Arc<Logger> = spdlog::default_logger();
+    /// logger.set_backtrace_level_filter(LevelFilter::MoreSevereEqual(Level::Error));
+    /// warn!(logger: logger, "without a backtrace");
+    /// error!(logger: logger, "with a backtrace");
+    /// ```
+    ///
+    /// [`Backtrace`]: std::backtrace::Backtrace
+    pub fn set_backtrace_level_filter(&self, level_filter: LevelFilter) {
+        self.backtrace_level_filter
+            .store(level_filter, Ordering::Relaxed);
+    }
+
     /// Gets the log filter level.
+    #[inline]
     pub fn level_filter(&self) -> LevelFilter {
         self.level_filter.load(Ordering::Relaxed)
     }
@@ -162,6 +400,103 @@ impl Logger {
     /// See [`Logger::should_log`].
     pub fn set_level_filter(&self, level_filter: LevelFilter) {
         self.level_filter.store(level_filter, Ordering::Relaxed);
+        self.update_enabled_levels();
+    }
+
+    // Used at log macros
+    #[doc(hidden)]
+    #[inline]
+    pub fn __level_enabled(&self, level: Level) -> bool {
+        self.enabled_levels.load(Ordering::Relaxed) & (1 << level as u8) != 0
+    }
+
+    fn update_enabled_levels(&self) {
+        // Concurrent setters may store their masks in another order than their
+        // filters, so the mask is stored until it matches the latest filter.
+        loop {
+            let level_filter = self.level_filter();
+            self.enabled_levels
+                .store(enabled_levels(level_filter), Ordering::Relaxed);
+            if self.level_filter() == level_filter {
+                break;
+            }
+        }
+    }
+
+    /// Temporarily sets the log filter level, and restores the current one
+    /// after the duration.
+    ///
+    /// It is useful for operational workflows like "turn on debug logs for 5
+    /// minutes", without the risk of leaving verbose logging on. Calling it
+    /// again during a boost replaces the boost, and the level before the first
+    /// boost is still restored. If the level filter is changed by
+    /// [`Logger::set_level_filter`] during a boost, it is not restored.
+    ///
+    /// This function receives a `&Arc<Self>`. Calling it will spawn a new
+    /// thread.
+    ///
+    /// # Errors
+    ///
+    /// If `duration` is zero, [`Error::InvalidArgument`] will be returned and
+    /// the level filter is not changed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// # use std::sync::Arc;
+    /// # use spdlog::prelude::*;
+    ///
+    /// # fn main() -> Result<(), spdlog::Error> {
+    /// # let logger: Arc<Logger> = spdlog::default_logger();
+    /// logger.boost_level(LevelFilter::All, Duration::from_secs(5 * 60))?;
+    /// # Ok(()) }
+    /// ```
+    pub fn boost_level(
+        self: &Arc<Self>,
+        level_filter: LevelFilter,
+        duration: Duration,
+    ) -> Result<()> {
+        if duration.is_zero() {
+            return Err(Error::InvalidArgument(InvalidArgumentError::Interval(
+                "expect `duration` to be non-zero".to_string(),
+            )));
+        }
+
+        // Dropping the worker waits for its thread, which may be locking the boost.
+        let old_worker = self.level_boost.lock().unwrap().worker.take();
+        drop(old_worker);
+
+        let weak = Arc::downgrade(self);
+        let callback = move || {
+            if let Some(strong) = weak.upgrade() {
+                strong.end_level_boost();
+            }
+            false
+        };
+
+        let mut level_boost = self.level_boost.lock().unwrap();
+        let original = match level_boost.levels {
+            Some((original, _)) => original,
+            None => self.level_filter(),
+        };
+        level_boost.levels = Some((original, level_filter));
+        self.set_level_filter(level_filter);
+        level_boost.worker = Some(PeriodicWorker::new(callback, duration));
+        Ok(())
+    }
+
+    fn end_level_boost(&self) {
+        if let Some((original, boosted)) = self.level_boost.lock().unwrap().levels.take() {
+            // the level filter is not restored if it has been changed by users
+            if self
+                .level_filter
+                .compare_exchange(boosted, original, Ordering::Relaxed, Ordering::Relaxed)
+                .is_ok()
+            {
+                self.update_enabled_levels();
+            }
+        }
     }
 
     /// Sets periodic flush.
@@ -172,12 +507,15 @@ impl Logger {
     /// This auto-flush policy can work with [`Logger::set_flush_level_filter`]
     /// together.
     ///
-    /// # Panics
+    /// # Errors
     ///
-    ///  - Panics if `interval` is zero.
+    /// If `interval` is zero, [`Error::InvalidArgument`] will be returned and
+    /// the previous periodic flush is kept.
     ///
-    ///  - Panics if this function is called with `Some` value and then clones
-    ///    the `Logger` instead of the `Arc<Logger>`.
+    /// # Panics
+    ///
+    /// Panics if this function is called with `Some` value and then clones the
+    /// `Logger` instead of the `Arc<Logger>`.
     ///
     /// # Examples
     ///
@@ -186,14 +524,24 @@ impl Logger {
     /// # use std::sync::Arc;
     /// # use spdlog::prelude::*;
     ///
+    /// # fn main() -> Result<(), spdlog::Error> {
     /// # let logger: Arc<Logger> = spdlog::default_logger();
     /// // From now on, auto-flush the `logger` buffer every 10 seconds.
-    /// logger.set_flush_period(Some(Duration::from_secs(10)));
+    /// logger.set_flush_period(Some(Duration::from_secs(10)))?;
     ///
     /// // Remove periodic auto-flush.
-    /// logger.set_flush_period(None);
+    /// logger.set_flush_period(None)?;
+    /// # Ok(()) }
     /// ```
-    pub fn set_flush_period(self: &Arc<Self>, interval: Option<Duration>) {
+    pub fn set_flush_period(self: &Arc<Self>, interval: Option<Duration>) -> Result<()> {
+        if let Some(interval) = interval {
+            if interval.is_zero() {
+                return Err(Error::InvalidArgument(InvalidArgumentError::Interval(
+                    "expect `interval` to be non-zero".to_string(),
+                )));
+            }
+        }
+
         let mut periodic_flusher = self.periodic_flusher.lock().unwrap();
 
         *periodic_flusher = None;
@@ -203,7 +551,7 @@ impl Logger {
             let callback = move || {
                 let strong = weak.upgrade();
                 if let Some(strong) = strong {
-                    strong.flush_sinks();
+                    strong.flush();
                     true
                 } else {
                     false // All `Arc`s are dropped, return `false` to quit the
@@ -212,23 +560,221 @@ impl Logger {
             };
             *periodic_flusher = Some(PeriodicWorker::new(callback, interval));
         }
+        Ok(())
+    }
+
+    /// Emits a heartbeat record periodically.
+    ///
+    /// This function receives a `&Arc<Self>`. Calling it will spawn a new
+    /// thread, and replace the previous heartbeat if any.
+    ///
+    /// Each heartbeat record is logged at `level` with the payload returned by
+    /// `message_fn`, and a field `heartbeat` with the number of the heartbeat
+    /// starting from 1. Log pipelines can detect a silent or dead process by
+    /// the absence of heartbeats, and a gap in the numbers reveals missing
+    /// records.
+    ///
+    /// # Errors
+    ///
+    /// If `interval` is zero, [`Error::InvalidArgument`] will be returned and
+    /// the previous heartbeat is kept.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this function is called and then clones the `Logger` instead
+    /// of the `Arc<Logger>`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// # use std::sync::Arc;
+    /// # use spdlog::prelude::*;
+    ///
+    /// # fn main() -> Result<(), spdlog::Error> {
+    /// # let logger: Arc<Logger> = spdlog::default_logger();
+    /// logger.heartbeat_every(Duration::from_secs(60), Level::Info, || {
+    ///     "alive".to_string()
+    /// })?;
+    ///
+    /// // Stop emitting heartbeats.
+    /// logger.stop_heartbeat();
+    /// # Ok(()) }
+    /// ```
+    pub fn heartbeat_every<F>(
+        self: &Arc<Self>,
+        interval: Duration,
+        level: Level,
+        message_fn: F,
+    ) -> Result<()>
+    where
+        F: Fn() -> String + Send + Sync + 'static,
+    {
+        if interval.is_zero() {
+            return Err(Error::InvalidArgument(InvalidArgumentError::Interval(
+                "expect `interval` to be non-zero".to_string(),
+            )));
+        }
+
+        let mut heartbeat = self.heartbeat.lock().unwrap();
+
+        *heartbeat = None;
+
+        let weak = Arc::downgrade(self);
+        let count = AtomicU64::new(0);
+        let callback = move || {
+            let strong = weak.upgrade();
+            if let Some(strong) = strong {
+                let count = count.fetch_add(1, Ordering::Relaxed) + 1;
+                if strong.should_log(level) {
+                    let mut builder = Record::builder(level, message_fn())
+                        .field("heartbeat", count)
+                        .flags(RecordFlags::INTERNAL);
+                    if let Some(logger_name) = strong.name_handle() {
+                        builder = builder.logger_name_handle(logger_name);
+                    }
+                    strong.log(&builder.build());
+                }
+                true
+            } else {
+                false // All `Arc`s are dropped, return `false` to quit the
+                      // worker thread.
+            }
+        };
+        *heartbeat = Some(PeriodicWorker::new(callback, interval));
+        Ok(())
+    }
+
+    /// Stops the heartbeat started by [`Logger::heartbeat_every`].
+    pub fn stop_heartbeat(&self) {
+        let old_heartbeat = self.heartbeat.lock().unwrap().take();
+        drop(old_heartbeat);
     }
 
-    /// Gets a reference to sinks in the logger.
-    pub fn sinks(&self) -> &[Arc<dyn Sink>] {
-        &self.sinks
+    /// Gets sinks in the logger.
+    ///
+    /// The returned value is a snapshot, it is not affected by later calls to
+    /// [`Logger::set_sinks`]. Since sinks can be replaced while other threads
+    /// are logging, it is an owned [`Arc`] instead of a slice borrowed from
+    /// the logger, use `logger.sinks().iter()` to iterate over it.
+    pub fn sinks(&self) -> Arc<Sinks> {
+        self.sinks.load_full()
+    }
+
+    /// Finds a sink in the logger by its name, see [`Sink::name`].
+    ///
+    /// Only sinks directly in the logger are searched, not inner sinks of
+    /// sinks forwarding records (e.g. [`GroupSink`]).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    ///
+    /// use spdlog::{prelude::*, sink::FileSink};
+    ///
+    /// # fn main() -> Result<(), spdlog::Error> {
+    /// # let dir = std::env::temp_dir().join("spdlog-rs-doctest");
+    /// let app = FileSink::builder(dir.join("app.log")).name("app").build()?;
+    /// let audit = FileSink::builder(dir.join("audit.log")).name("audit").build()?;
+    /// let logger: Logger = Logger::builder()
+    ///     .sink(Arc::new(app))
+    ///     .sink(Arc::new(audit))
+    ///     .build();
+    ///
+    /// let audit = logger.sink_by_name("audit").unwrap();
+    /// audit.set_level_filter(LevelFilter::MoreSevereEqual(Level::Warn));
+    /// # Ok(()) }
+    /// ```
+    ///
+    /// [`GroupSink`]: crate::sink::GroupSink
+    pub fn sink_by_name(&self, name: &str) -> Option<Arc<dyn Sink>> {
+        self.sinks()
+            .iter()
+            .find(|sink| sink.name().as_deref() == Some(name))
+            .cloned()
     }
 
     /// Gets a mutable reference to sinks in the logger.
-    pub fn sinks_mut(&mut self) -> &mut Sinks {
-        &mut self.sinks
+    ///
+    /// The returned value dereferences to [`Sinks`], and the changes are
+    /// applied to the logger when it is dropped.
+    pub fn sinks_mut(&mut self) -> impl DerefMut<Target = Sinks> + '_ {
+        struct SinksMut<'a> {
+            slot: &'a ArcSwap<Sinks>,
+            sinks: Arc<Sinks>,
+        }
+
+        impl Deref for SinksMut<'_> {
+            type Target = Sinks;
+
+            fn deref(&self) -> &Sinks {
+                &self.sinks
+            }
+        }
+
+        impl DerefMut for SinksMut<'_> {
+            fn deref_mut(&mut self) -> &mut Sinks {
+                Arc::make_mut(&mut self.sinks)
+            }
+        }
+
+        impl Drop for SinksMut<'_> {
+            fn drop(&mut self) {
+                self.slot.store(mem::take(&mut self.sinks));
+            }
+        }
+
+        SinksMut {
+            slot: &self.sinks,
+            sinks: self.sinks.swap(Arc::default()),
+        }
+    }
+
+    /// Replaces all sinks in the logger at once, and returns the old sinks.
+    ///
+    /// Each record is logged either to all old sinks or to all new sinks,
+    /// never to a mix of them. It is useful for reconfiguring logging at
+    /// runtime, e.g. switching from bootstrap sinks to the sinks configured by
+    /// command line arguments.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    ///
+    /// use spdlog::{
+    ///     prelude::*,
+    ///     sink::{Sink, StdStream, StdStreamSink},
+    ///     terminal_style::StyleMode,
+    /// };
+    ///
+    /// # let logger: Arc<Logger> = spdlog::default_logger();
+    /// let stderr: Arc<dyn Sink> = Arc::new(StdStreamSink::new(StdStream::Stderr, StyleMode::Auto));
+    /// logger.set_sinks([stderr]);
+    /// ```
+    pub fn set_sinks<I>(&self, sinks: I) -> Arc<Sinks>
+    where
+        I: IntoIterator<Item = Arc<dyn Sink>>,
+    {
+        self.sinks.swap(Arc::new(sinks.into_iter().collect()))
+    }
+
+    // Adds a sink atomically, unlike `set_sinks` with a modified snapshot.
+    #[cfg(feature = "capi")]
+    pub(crate) fn push_sink(&self, sink: Arc<dyn Sink>) {
+        self.sinks.rcu(|sinks| {
+            let mut sinks = Sinks::clone(sinks);
+            sinks.push(sink.clone());
+            sinks
+        });
     }
 
     /// Sets a error handler.
     ///
     /// If an error occurs while logging or flushing, this handler will be
-    /// called. If no handler is set, the error will be output to the terminal
-    /// and then ignored.
+    /// called. If no handler is set, the error will be reported to the
+    /// [self-log target] (by default, output to the terminal) and then ignored.
     ///
     /// # Examples
     ///
@@ -239,26 +785,36 @@ impl Logger {
     ///     panic!("spdlog-rs error: {}", err)
     /// }));
     /// ```
+    ///
+    /// [self-log target]: crate::SelfLogTarget
     pub fn set_error_handler(&self, handler: Option<ErrorHandler>) {
         *self.error_handler.write() = handler;
     }
 
     fn sink_record(&self, record: &Record) {
-        self.sinks.iter().for_each(|sink| {
-            if let Err(err) = sink.log(record) {
-                self.handle_error(err);
+        if !profiling::measure(Stage::Filter, || filter::apply(&self.filters, record)) {
+            return;
+        }
+
+        self.sinks.load().iter().for_each(|sink| {
+            if let Err(err) = profiling::measure(Stage::Write, || sink.log(record)) {
+                self.handle_error(err.with_sink(
+                    &**sink,
+                    Some(record.level()),
+                    record.logger_name(),
+                ));
             }
         });
 
         if self.should_flush(record) {
-            self.flush();
+            self.flush_sinks();
         }
     }
 
     fn flush_sinks(&self) {
-        self.sinks.iter().for_each(|sink| {
-            if let Err(err) = sink.flush() {
-                self.handle_error(err);
+        self.sinks.load().iter().for_each(|sink| {
+            if let Err(err) = profiling::measure(Stage::Flush, || sink.flush()) {
+                self.handle_error(err.with_sink(&**sink, None, self.name()));
             }
         });
     }
@@ -268,10 +824,7 @@ impl Logger {
             handler(err)
         } else {
             crate::default_error_handler(
-                format!(
-                    "Logger ({})",
-                    self.name.as_ref().map_or("*no name*", String::as_str)
-                ),
+                format!("Logger ({})", self.name().unwrap_or("*no name*")),
                 err,
             );
         }
@@ -287,8 +840,9 @@ impl Clone for Logger {
     ///
     /// # Panics
     ///
-    /// Panics if [`Logger::set_flush_period`] is called with `Some` value and
-    /// then clones the `Logger` instead of the `Arc<Logger>`.
+    /// Panics if [`Logger::set_flush_period`] is called with `Some` value or
+    /// [`Logger::heartbeat_every`] is called, and then clones the `Logger`
+    /// instead of the `Arc<Logger>`.
     fn clone(&self) -> Self {
         if self.periodic_flusher.lock().unwrap().is_some() {
             panic!(
@@ -296,36 +850,110 @@ impl Clone for Logger {
                  clone a `Arc<Logger>` instead."
             );
         }
+        if self.heartbeat.lock().unwrap().is_some() {
+            panic!(
+                "you can't clone a `Logger` with a heartbeat, \
+                 clone a `Arc<Logger>` instead."
+            );
+        }
 
         Logger {
             name: self.name.clone(),
             level_filter: Atomic::new(self.level_filter()),
-            sinks: self.sinks.clone(),
+            enabled_levels: AtomicU8::new(enabled_levels(self.level_filter())),
+            sinks: ArcSwap::new(self.sinks()),
             flush_level_filter: Atomic::new(self.flush_level_filter()),
+            backtrace_level_filter: Atomic::new(self.backtrace_level_filter()),
             periodic_flusher: Mutex::new(None),
+            heartbeat: Mutex::new(None),
+            level_boost: Mutex::new(LevelBoost::default()),
             error_handler: spin::RwLock::new(*self.error_handler.read()),
+            fields: self.fields.clone(),
+            filters: self.filters.clone(),
+            rate_limiter: self.rate_limiter.clone(),
+            clock_policy: self.clock_policy,
+            latest_time: AtomicU64::new(self.latest_time.load(Ordering::Relaxed)),
         }
     }
 }
 
+/// Policies for records with a time earlier than a previous record, e.g. after
+/// the system clock is stepped backwards by NTP.
+///
+/// Without handling, such records are written with out-of-order times, and may
+/// be written to files already rotated by time (see [`RotatingFileSink`]).
+///
+/// The policy is set by [`LoggerBuilder::clock_policy`], and is applied to the
+/// records logged by the logger. Records are compared with the latest time
+/// seen by the logger.
+///
+/// [`RotatingFileSink`]: crate::sink::RotatingFileSink
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Default)]
+pub enum ClockPolicy {
+    /// Logs records with their times as they are.
+    #[default]
+    AsIs,
+    /// Replaces the times of records with the latest time seen, so that times
+    /// never go backwards. Records logged until the clock catches up share
+    /// the same time.
+    Clamp,
+    /// Logs records with their times as they are, and attaches a field
+    /// `clock_backwards` with the [`Duration`] the time goes backwards by.
+    Mark,
+}
+
+fn enabled_levels(level_filter: LevelFilter) -> u8 {
+    Level::iter()
+        .filter(|level| level_filter.compare(*level))
+        .fold(0, |mask, level| mask | 1 << level as u8)
+}
+
+#[derive(Default)]
+struct LevelBoost {
+    // the original and the boosted level filters
+    levels: Option<(LevelFilter, LevelFilter)>,
+    worker: Option<PeriodicWorker>,
+}
+
 /// The builder of [`Logger`].
 #[derive(Clone)]
 pub struct LoggerBuilder {
     logger: Logger,
+    sink_wrappers: Vec<Arc<SinkWrapper>>,
+    // the sinks added before wrapping and the numbers of wrappers applied to
+    // them, to detect duplicates
+    added_sinks: Vec<(Arc<dyn Sink>, usize)>,
 }
 
+type SinkWrapper = dyn Fn(Arc<dyn Sink>) -> Arc<dyn Sink> + Send + Sync;
+
 impl LoggerBuilder {
     /// Constructs a `LoggerBuilder`.
     pub fn new() -> Self {
+        Lazy::force(&crate::START_TIME);
+
         Self {
             logger: Logger {
                 name: None,
                 level_filter: Atomic::new(LevelFilter::MoreSevereEqual(Level::Info)),
-                sinks: vec![],
+                enabled_levels: AtomicU8::new(enabled_levels(LevelFilter::MoreSevereEqual(
+                    Level::Info,
+                ))),
+                sinks: ArcSwap::from_pointee(vec![]),
                 flush_level_filter: Atomic::new(LevelFilter::Off),
+                backtrace_level_filter: Atomic::new(LevelFilter::Off),
                 periodic_flusher: Mutex::new(None),
+                heartbeat: Mutex::new(None),
+                level_boost: Mutex::new(LevelBoost::default()),
                 error_handler: spin::RwLock::new(None),
+                fields: vec![],
+                filters: vec![],
+                rate_limiter: None,
+                clock_policy: ClockPolicy::AsIs,
+                latest_time: AtomicU64::new(0),
             },
+            sink_wrappers: vec![],
+            added_sinks: vec![],
         }
     }
 
@@ -362,7 +990,7 @@ impl LoggerBuilder {
             panic!("logger name cannot start or end with a whitespace");
         }
 
-        self.logger.name = Some(name);
+        self.logger.name = Some(LoggerName::new(name));
         self
     }
 
@@ -374,17 +1002,138 @@ impl LoggerBuilder {
     }
 
     /// Add a [`Sink`].
+    ///
+    /// The sink is wrapped by the wrappers set by [`LoggerBuilder::wrap_sinks`]
+    /// before this call.
+    ///
+    /// Adding a sink that has already been added (the same [`Arc`], compared by
+    /// pointer), including by [`LoggerBuilder::route`], does nothing, otherwise
+    /// every record would be written to it twice. A sink added again after
+    /// [`LoggerBuilder::wrap_sinks`] is not a duplicate, since it is
+    /// wrapped differently.
     pub fn sink(&mut self, sink: Arc<dyn Sink>) -> &mut Self {
-        self.logger.sinks.push(sink);
+        if self.mark_added(&sink) {
+            let sink = self.wrap_sink(sink);
+            self.logger.sinks_mut().push(sink);
+        }
         self
     }
 
+    // Returns `false` if the sink has already been added.
+    fn mark_added(&mut self, sink: &Arc<dyn Sink>) -> bool {
+        let wrapper_count = self.sink_wrappers.len();
+        let is_added = |(added, added_wrapper_count): &(Arc<dyn Sink>, usize)| {
+            Arc::as_ptr(added) as *const () == Arc::as_ptr(sink) as *const ()
+                && *added_wrapper_count == wrapper_count
+        };
+        if self.added_sinks.iter().any(is_added) {
+            return false;
+        }
+        self.added_sinks.push((sink.clone(), wrapper_count));
+        true
+    }
+
     /// Add multiple [`Sink`]s.
+    ///
+    /// The sinks are wrapped by the wrappers set by
+    /// [`LoggerBuilder::wrap_sinks`] before this call. Sinks that have already
+    /// been added are skipped, see [`LoggerBuilder::sink`].
     pub fn sinks<I>(&mut self, sinks: I) -> &mut Self
     where
         I: IntoIterator<Item = Arc<dyn Sink>>,
     {
-        self.logger.sinks.append(&mut sinks.into_iter().collect());
+        for sink in sinks {
+            self.sink(sink);
+        }
+        self
+    }
+
+    /// Add a [`Sink`] by a weak reference.
+    ///
+    /// It is a shortcut of adding a [`WeakSink`], the logger skips the sink
+    /// once it has been dropped.
+    ///
+    /// [`WeakSink`]: crate::sink::WeakSink
+    pub fn weak_sink(&mut self, sink: Weak<dyn Sink>) -> &mut Self {
+        self.sink(Arc::new(WeakSink::new(sink)))
+    }
+
+    /// Add a [`Sink`] that only logs records matching the level filter.
+    ///
+    /// The sink is added wrapped in a [`FilterSink`] with the level filter, so
+    /// the level filter of the sink itself is left unchanged and the sink can
+    /// be shared with other loggers. Note that the logger level filter still
+    /// applies first.
+    ///
+    /// Routing a sink that has already been added does nothing, see
+    /// [`LoggerBuilder::sink`].
+    ///
+    /// # Examples
+    ///
+    /// Errors additionally go to `errors.log`:
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    ///
+    /// use spdlog::{
+    ///     prelude::*,
+    ///     sink::{FileSink, StdStream, StdStreamSink},
+    ///     terminal_style::StyleMode,
+    /// };
+    ///
+    /// # fn main() -> Result<(), spdlog::Error> {
+    /// # let path = std::env::temp_dir().join("spdlog-rs-doctest").join("errors.log");
+    /// let logger: Logger = Logger::builder()
+    ///     .sink(Arc::new(StdStreamSink::new(StdStream::Stdout, StyleMode::Auto)))
+    ///     .route(
+    ///         LevelFilter::MoreSevereEqual(Level::Error),
+    ///         Arc::new(FileSink::new(path, false)?),
+    ///     )
+    ///     .build();
+    /// # Ok(()) }
+    /// ```
+    ///
+    /// [`FilterSink`]: crate::sink::FilterSink
+    pub fn route(&mut self, level_filter: LevelFilter, sink: Arc<dyn Sink>) -> &mut Self {
+        if self.mark_added(&sink) {
+            let filter_sink = FilterSink::new(sink);
+            filter_sink.set_level_filter(level_filter);
+            let sink = self.wrap_sink(Arc::new(filter_sink));
+            self.logger.sinks_mut().push(sink);
+        }
+        self
+    }
+
+    /// Sets a wrapper that is applied to every sink added afterwards.
+    ///
+    /// It is useful for applying a decorator sink (e.g. sampling,
+    /// deduplication, redaction) to all sinks, instead of wrapping each sink
+    /// manually. Sinks added before this call are not affected. If this
+    /// function is called multiple times, the wrappers are applied in the
+    /// order they are set, i.e. the last one is the outermost.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    ///
+    /// use spdlog::{
+    ///     prelude::*,
+    ///     sink::{AsyncSink, Sink, StdStream, StdStreamSink},
+    ///     terminal_style::StyleMode,
+    /// };
+    ///
+    /// let logger: Logger = Logger::builder()
+    ///     .wrap_sinks(|sink| Arc::new(AsyncSink::builder().sink(sink).build().unwrap()))
+    ///     .sink(Arc::new(StdStreamSink::new(StdStream::Stdout, StyleMode::Auto)))
+    ///     .sink(Arc::new(StdStreamSink::new(StdStream::Stderr, StyleMode::Auto)))
+    ///     .build();
+    /// ```
+    pub fn wrap_sinks<F>(&mut self, wrapper: F) -> &mut Self
+    where
+        F: Fn(Arc<dyn Sink>) -> Arc<dyn Sink> + Send + Sync + 'static,
+    {
+        self.sink_wrappers.push(Arc::new(wrapper));
         self
     }
 
@@ -395,6 +1144,85 @@ impl LoggerBuilder {
         self
     }
 
+    /// Sets the backtrace level filter.
+    ///
+    /// See [`Logger::set_backtrace_level_filter`].
+    #[allow(unused_mut)]
+    pub fn backtrace_level_filter(&mut self, level_filter: LevelFilter) -> &mut Self {
+        self.logger.set_backtrace_level_filter(level_filter);
+        self
+    }
+
+    /// Sets the policy for records with a time earlier than a previous record.
+    ///
+    /// The default is [`ClockPolicy::AsIs`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use spdlog::{prelude::*, ClockPolicy};
+    ///
+    /// let logger: Logger = Logger::builder()
+    ///     .clock_policy(ClockPolicy::Clamp)
+    ///     .build();
+    /// ```
+    pub fn clock_policy(&mut self, clock_policy: ClockPolicy) -> &mut Self {
+        self.logger.clock_policy = clock_policy;
+        self
+    }
+
+    /// Adds a field attached to all records logged by the logger.
+    ///
+    /// It is useful for constant metadata such as the service name. The fields
+    /// are placed before the fields of the records, and are borrowed by the
+    /// records rather than copied.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use spdlog::prelude::*;
+    ///
+    /// let logger: Logger = Logger::builder()
+    ///     .field("service", "billing")
+    ///     .field("instance", 3)
+    ///     .build();
+    /// ```
+    pub fn field<K, V>(&mut self, key: K, value: V) -> &mut Self
+    where
+        K: Into<Cow<'static, str>>,
+        V: Into<Value<'static>>,
+    {
+        self.logger.fields.push(Field::new(key, value));
+        self
+    }
+
+    /// Adds a [`Filter`] deciding whether records are logged.
+    ///
+    /// Filters are consulted in the order they are added, after the level
+    /// filter of the logger and before the sinks. Records passed to filters
+    /// include the fields added by [`LoggerBuilder::field`]. See [`Filter`] for
+    /// how their decisions are combined.
+    ///
+    /// # Examples
+    ///
+    /// See [`Filter`].
+    pub fn filter<F>(&mut self, filter: F) -> &mut Self
+    where
+        F: Filter + 'static,
+    {
+        self.logger.filters.push(Arc::new(filter));
+        self
+    }
+
+    /// Sets the rate limiter of records.
+    ///
+    /// By default, records are not rate limited. See [`RateLimiter`] for
+    /// details.
+    pub fn rate_limiter(&mut self, rate_limiter: Arc<RateLimiter>) -> &mut Self {
+        self.logger.rate_limiter = Some(rate_limiter);
+        self
+    }
+
     /// Sets the error handler.
     #[allow(unused_mut)]
     pub fn error_handler(&mut self, handler: ErrorHandler) -> &mut Self {
@@ -402,6 +1230,12 @@ impl LoggerBuilder {
         self
     }
 
+    fn wrap_sink(&self, sink: Arc<dyn Sink>) -> Arc<dyn Sink> {
+        self.sink_wrappers
+            .iter()
+            .fold(sink, |sink, wrapper| wrapper(sink))
+    }
+
     /// Builds a [`Logger`].
     pub fn build(&mut self) -> Logger {
         self.build_inner(false)
@@ -412,36 +1246,58 @@ impl LoggerBuilder {
     }
 
     fn build_inner(&mut self, is_default: bool) -> Logger {
-        let res = self.logger.clone();
-        let level = if is_default {
-            env_level::logger_level(env_level::LoggerKind::Default)
-        } else {
-            env_level::logger_level(env_level::LoggerKind::Other(res.name()))
-        };
-        if let Some(level) = level {
-            res.set_level_filter(level);
-        }
+        let mut res = self.logger.clone();
+        env_level::with_env_level(|env_level| apply_env_level(&mut res, env_level, is_default));
         res
     }
 
     #[cfg(test)]
     fn build_inner_for_test(&mut self, env_level: &str, is_default: bool) -> Logger {
-        let res = self.logger.clone();
-        let level = if is_default {
-            env_level::logger_level_inner(
-                &env_level::from_str_inner(env_level).unwrap(),
-                env_level::LoggerKind::Default,
-            )
-        } else {
-            env_level::logger_level_inner(
-                &env_level::from_str_inner(env_level).unwrap(),
-                env_level::LoggerKind::Other(res.name()),
-            )
+        let mut res = self.logger.clone();
+        let env_level = env_level::from_str_inner(env_level).unwrap();
+        apply_env_level(&mut res, &env_level, is_default);
+        res
+    }
+}
+
+// Applies the directives of `SPDLOG_RS_LEVEL` to a logger being built.
+//
+// The upper bound of a level range is enforced by a filter that runs before the
+// filters of the logger, and sinks with per-sink directives are wrapped in a
+// `FilterSink` rather than modified, since they may be shared with other
+// loggers.
+fn apply_env_level(logger: &mut Logger, env_level: &env_level::EnvLevel, is_default: bool) {
+    let name = logger.name().map(str::to_owned);
+    let kind = if is_default {
+        env_level::LoggerKind::Default
+    } else {
+        env_level::LoggerKind::Other(name.as_deref())
+    };
+
+    if let Some(level) = env_level::logger_level_inner(env_level, kind.clone()) {
+        logger.set_level_filter(level.level_filter());
+        if let Some(filter) = level.severity_filter() {
+            logger.filters.insert(0, Arc::new(filter));
+        }
+    }
+
+    if !env_level::has_sink_directives(env_level) {
+        return;
+    }
+    for sink in logger.sinks_mut().iter_mut() {
+        let Some(sink_name) = sink.name() else {
+            continue;
         };
-        if let Some(level) = level {
-            res.set_level_filter(level);
+        let Some(level) = env_level::sink_level_inner(env_level, &sink_name, kind.clone()) else {
+            continue;
+        };
+        let mut filter_sink = FilterSink::new(sink.clone());
+        if let Some(filter) = level.severity_filter() {
+            filter_sink = filter_sink.filter(filter);
         }
-        res
+        filter_sink.set_level_filter(level.level_filter());
+        filter_sink.set_name(Some(sink_name.to_string()));
+        *sink = Arc::new(filter_sink);
     }
 }
 
@@ -454,7 +1310,13 @@ impl Default for LoggerBuilder {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{prelude::*, test_utils::*};
+    use crate::{
+        filter::Decision,
+        prelude::*,
+        sink::{AsyncSink, FilterSink},
+        test_utils::*,
+        FieldFilter,
+    };
 
     use std::{thread, time::Duration};
 
@@ -493,12 +1355,430 @@ mod tests {
         test_sink.reset();
     }
 
+    #[test]
+    fn backtrace() {
+        let test_sink = Arc::new(CounterSink::new());
+        let test_logger = test_logger_builder()
+            .sink(test_sink.clone())
+            .level_filter(LevelFilter::All)
+            .backtrace_level_filter(LevelFilter::MoreSevereEqual(Level::Error))
+            .build();
+
+        warn!(logger: test_logger, "");
+        error!(logger: test_logger, "");
+        info!(logger: test_logger, backtrace: true, "");
+        log!(logger: test_logger, backtrace: false, Level::Debug, "");
+
+        assert_eq!(
+            test_sink
+                .records()
+                .iter()
+                .map(|record| record.backtrace().is_some())
+                .collect::<Vec<_>>(),
+            vec![false, true, true, false]
+        );
+    }
+
+    #[test]
+    fn set_sinks() {
+        let old_sink = Arc::new(CounterSink::new());
+        let new_sink = Arc::new(CounterSink::new());
+        let test_logger = test_logger_builder().sink(old_sink.clone()).build();
+
+        info!(logger: test_logger, "1");
+        let old_sinks = test_logger.set_sinks([new_sink.clone() as Arc<dyn Sink>]);
+        info!(logger: test_logger, "2");
+
+        assert_eq!(old_sinks.len(), 1);
+        assert_eq!(test_logger.sinks().len(), 1);
+        assert_eq!(old_sink.payloads(), vec!["1"]);
+        assert_eq!(new_sink.payloads(), vec!["2"]);
+    }
+
+    #[test]
+    fn duplicate_sinks() {
+        let sink = Arc::new(CounterSink::new());
+        let other_sink = Arc::new(CounterSink::new());
+        let test_logger = test_logger_builder()
+            .sink(sink.clone())
+            .sinks([
+                sink.clone() as Arc<dyn Sink>,
+                other_sink.clone(),
+                sink.clone(),
+            ])
+            .wrap_sinks(|sink| Arc::new(FilterSink::new(sink)))
+            .sink(other_sink.clone())
+            .sink(other_sink.clone())
+            .build();
+
+        info!(logger: test_logger, "hello");
+        assert_eq!(test_logger.sinks().len(), 3);
+        assert_eq!(sink.log_count(), 1);
+        assert_eq!(other_sink.log_count(), 2);
+    }
+
+    #[test]
+    fn duplicate_routed_sinks() {
+        let sink = Arc::new(CounterSink::new());
+        let test_logger = test_logger_builder()
+            .sink(sink.clone())
+            .route(LevelFilter::MoreSevereEqual(Level::Error), sink.clone())
+            .build();
+
+        info!(logger: test_logger, "info");
+        error!(logger: test_logger, "error");
+        assert_eq!(test_logger.sinks().len(), 1);
+        assert_eq!(sink.level_filter(), LevelFilter::All);
+        assert_eq!(sink.payloads(), vec!["info", "error"]);
+
+        let sink = Arc::new(CounterSink::new());
+        let test_logger = test_logger_builder()
+            .route(LevelFilter::MoreSevereEqual(Level::Error), sink.clone())
+            .sink(sink.clone())
+            .build();
+
+        info!(logger: test_logger, "info");
+        error!(logger: test_logger, "error");
+        assert_eq!(test_logger.sinks().len(), 1);
+        assert_eq!(sink.payloads(), vec!["error"]);
+    }
+
+    #[test]
+    fn route() {
+        let all_sink = Arc::new(CounterSink::new());
+        let error_sink = Arc::new(CounterSink::new());
+        let test_logger = test_logger_builder()
+            .sink(all_sink.clone())
+            .route(
+                LevelFilter::MoreSevereEqual(Level::Error),
+                error_sink.clone(),
+            )
+            .build();
+
+        info!(logger: test_logger, "info");
+        error!(logger: test_logger, "error");
+
+        assert_eq!(test_logger.sinks().len(), 2);
+        assert_eq!(all_sink.payloads(), vec!["info", "error"]);
+        assert_eq!(error_sink.payloads(), vec!["error"]);
+        assert_eq!(error_sink.level_filter(), LevelFilter::All);
+    }
+
+    #[test]
+    fn should_log() {
+        let test_sink = Arc::new(CounterSink::new());
+        test_sink.set_level_filter(LevelFilter::MoreSevereEqual(Level::Warn));
+        let test_logger = test_logger_builder().sink(test_sink.clone()).build();
+
+        assert!(test_logger.should_log(Level::Error));
+        assert!(!test_logger.should_log(Level::Info));
+        test_sink.set_level_filter(LevelFilter::All);
+        assert!(test_logger.should_log(Level::Info));
+        assert!(!test_logger.should_log(Level::Debug));
+        assert!(!Logger::builder().build().should_log(Level::Error));
+    }
+
+    #[test]
+    fn zero_intervals() {
+        let info = LevelFilter::MoreSevereEqual(Level::Info);
+        let test_logger = Arc::new(test_logger_builder().level_filter(info).build());
+        let is_interval_error = |res: Result<()>| {
+            matches!(
+                res,
+                Err(Error::InvalidArgument(InvalidArgumentError::Interval(_)))
+            )
+        };
+
+        assert!(is_interval_error(
+            test_logger.boost_level(LevelFilter::All, Duration::ZERO)
+        ));
+        assert_eq!(test_logger.level_filter(), info);
+        assert!(is_interval_error(
+            test_logger.set_flush_period(Some(Duration::ZERO))
+        ));
+        assert!(is_interval_error(test_logger.heartbeat_every(
+            Duration::ZERO,
+            Level::Info,
+            String::new
+        )));
+    }
+
+    #[test]
+    fn level_enabled() {
+        let test_logger = test_logger_builder().build();
+        for level_filter in [
+            LevelFilter::Off,
+            LevelFilter::Equal(Level::Warn),
+            LevelFilter::NotEqual(Level::Warn),
+            LevelFilter::MoreSevere(Level::Info),
+            LevelFilter::MoreVerboseEqual(Level::Debug),
+            LevelFilter::All,
+        ] {
+            test_logger.set_level_filter(level_filter);
+            for level in Level::iter() {
+                assert_eq!(
+                    test_logger.__level_enabled(level),
+                    level_filter.compare(level)
+                );
+            }
+            assert_eq!(
+                test_logger.clone().__level_enabled(Level::Info),
+                level_filter.compare(Level::Info)
+            );
+        }
+    }
+
+    #[test]
+    fn boost_level() {
+        let info = LevelFilter::MoreSevereEqual(Level::Info);
+        let test_logger = Arc::new(test_logger_builder().level_filter(info).build());
+
+        test_logger
+            .boost_level(LevelFilter::All, Duration::from_millis(100))
+            .unwrap();
+        test_logger
+            .boost_level(
+                LevelFilter::MoreSevereEqual(Level::Debug),
+                Duration::from_millis(100),
+            )
+            .unwrap();
+        assert_eq!(
+            test_logger.level_filter(),
+            LevelFilter::MoreSevereEqual(Level::Debug)
+        );
+        std::thread::sleep(Duration::from_millis(300));
+        assert_eq!(test_logger.level_filter(), info);
+        assert!(test_logger.__level_enabled(Level::Info));
+        assert!(!test_logger.__level_enabled(Level::Debug));
+
+        test_logger
+            .boost_level(LevelFilter::All, Duration::from_millis(100))
+            .unwrap();
+        test_logger.set_level_filter(LevelFilter::Off);
+        std::thread::sleep(Duration::from_millis(300));
+        assert_eq!(test_logger.level_filter(), LevelFilter::Off);
+    }
+
+    #[test]
+    fn fields() {
+        let test_sink = Arc::new(CounterSink::new());
+        let test_logger = test_logger_builder()
+            .sink(test_sink.clone())
+            .field("service", "billing")
+            .field("instance", 3)
+            .build();
+        assert_eq!(test_logger.fields().len(), 2);
+
+        info!(logger: test_logger, "hello");
+        test_logger.log(
+            &Record::builder(Level::Info, "world")
+                .field("key", "value")
+                .build(),
+        );
+
+        let fields = test_sink
+            .records()
+            .iter()
+            .map(|record| {
+                record
+                    .fields()
+                    .iter()
+                    .map(|field| format!("{}={}", field.key(), field.value()))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            fields,
+            vec![
+                vec!["service=billing", "instance=3"],
+                vec!["service=billing", "instance=3", "key=value"]
+            ]
+        );
+    }
+
+    #[test]
+    fn same_time_across_sinks() {
+        let sinks = [Arc::new(CounterSink::new()), Arc::new(CounterSink::new())];
+        let async_sink = Arc::new(AsyncSink::builder().sink(sinks[1].clone()).build().unwrap());
+        let test_logger = test_logger_builder()
+            .sink(sinks[0].clone())
+            .sink(async_sink)
+            .build();
+
+        for i in 0..100 {
+            info!(logger: test_logger, "{}", i);
+        }
+        // Joins the worker thread of the `AsyncSink`
+        drop(test_logger);
+
+        let times = |sink: &CounterSink| {
+            sink.records()
+                .iter()
+                .map(|record| record.time())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(times(&sinks[0]).len(), 100);
+        assert_eq!(times(&sinks[0]), times(&sinks[1]));
+    }
+
+    #[test]
+    fn rate_limiter() {
+        let test_sink = Arc::new(CounterSink::new());
+        let rate_limiter = Arc::new(
+            RateLimiter::builder()
+                .rate(1)
+                .burst(2)
+                .report_interval(Duration::ZERO)
+                .build(),
+        );
+        let test_logger = test_logger_builder()
+            .sink(test_sink.clone())
+            .rate_limiter(rate_limiter.clone())
+            .build();
+
+        for i in 0..4 {
+            info!(logger: test_logger, "info {}", i);
+        }
+        error!(logger: test_logger, "error");
+
+        assert_eq!(
+            test_sink.payloads(),
+            vec![
+                "info 0",
+                "info 1",
+                "records suppressed by rate limit",
+                "error"
+            ]
+        );
+        assert_eq!(test_sink.records()[2].fields()[0].value(), &Value::U64(2));
+        assert_eq!(rate_limiter.total_suppressed(), 2);
+
+        // Reported on flush without waiting for the next record.
+        info!(logger: test_logger, "info 4");
+        assert_eq!(test_sink.log_count(), 4);
+        test_logger.flush();
+        assert_eq!(
+            test_sink.payloads().last().unwrap(),
+            "records suppressed by rate limit"
+        );
+        assert_eq!(test_sink.records()[4].fields()[0].value(), &Value::U64(1));
+        test_logger.flush();
+        assert_eq!(test_sink.log_count(), 5);
+    }
+
+    #[test]
+    fn clock_policy() {
+        let now = SystemTime::now();
+        let times = [
+            now,
+            now + Duration::from_secs(2),
+            now + Duration::from_secs(1),
+            now + Duration::from_secs(3),
+        ];
+
+        let log = |clock_policy| {
+            let test_sink = Arc::new(CounterSink::new());
+            let test_logger = test_logger_builder()
+                .sink(test_sink.clone())
+                .clock_policy(clock_policy)
+                .build();
+            for time in times {
+                test_logger.log(&Record::builder(Level::Info, "hello").time(time).build());
+            }
+            test_sink.records()
+        };
+
+        let records = log(ClockPolicy::AsIs);
+        assert!(records.iter().map(|record| record.time()).eq(times));
+        assert!(records.iter().all(|record| record.fields().is_empty()));
+
+        let records = log(ClockPolicy::Clamp);
+        assert!(records
+            .iter()
+            .map(|record| record.time())
+            .eq([times[0], times[1], times[1], times[3]]));
+        assert!(records.iter().all(|record| record.fields().is_empty()));
+
+        let records = log(ClockPolicy::Mark);
+        assert!(records.iter().map(|record| record.time()).eq(times));
+        let marks = records
+            .iter()
+            .map(|record| {
+                record
+                    .fields()
+                    .iter()
+                    .map(|field| format!("{}={}", field.key(), field.value()))
+                    .next()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            marks,
+            [None, None, Some("clock_backwards=1s".to_string()), None]
+        );
+    }
+
+    #[test]
+    fn filters() {
+        let test_sink = Arc::new(CounterSink::new());
+        let test_logger = test_logger_builder()
+            .sink(test_sink.clone())
+            .field("service", "billing")
+            .filter(|record: &Record| {
+                if record.payload() == "always" {
+                    Decision::Accept
+                } else {
+                    Decision::Neutral
+                }
+            })
+            .filter(FieldFilter::equals("service", "billing"))
+            .filter(FieldFilter::Not(Box::new(FieldFilter::present("noisy"))))
+            .build();
+
+        info!(logger: test_logger, "a");
+        info!(logger: test_logger, noisy = true; "b");
+        info!(logger: test_logger, noisy = true; "always");
+        debug!(logger: test_logger, "always");
+        assert_eq!(test_sink.payloads(), vec!["a", "always"]);
+    }
+
+    #[test]
+    fn wrap_sinks() {
+        let inner_sinks = Arc::new(Mutex::new(vec![]));
+
+        let test_logger = {
+            let inner_sinks = inner_sinks.clone();
+            test_logger_builder()
+                .sink(Arc::new(CounterSink::new()))
+                .wrap_sinks(move |sink| {
+                    inner_sinks.lock().unwrap().push(sink);
+                    Arc::new(CounterSink::new())
+                })
+                .sink(Arc::new(CounterSink::new()))
+                .sinks([
+                    Arc::new(CounterSink::new()) as Arc<dyn Sink>,
+                    Arc::new(CounterSink::new()),
+                ])
+                .build()
+        };
+
+        assert_eq!(test_logger.sinks().len(), 4);
+        assert_eq!(inner_sinks.lock().unwrap().len(), 3);
+        for (sink, inner) in test_logger.sinks()[1..]
+            .iter()
+            .zip(inner_sinks.lock().unwrap().iter())
+        {
+            assert!(!Arc::ptr_eq(sink, inner));
+        }
+    }
+
     #[test]
     fn periodic_flush() {
         let test_sink = Arc::new(CounterSink::new());
         let test_logger = Arc::new(Logger::builder().sink(test_sink.clone()).build());
 
-        test_logger.set_flush_period(Some(Duration::from_secs(1)));
+        test_logger
+            .set_flush_period(Some(Duration::from_secs(1)))
+            .unwrap();
 
         assert_eq!(test_sink.flush_count(), 0);
 
@@ -508,17 +1788,44 @@ mod tests {
         thread::sleep(Duration::from_millis(1250));
         assert_eq!(test_sink.flush_count(), 2);
 
-        test_logger.set_flush_period(None);
+        test_logger.set_flush_period(None).unwrap();
 
         thread::sleep(Duration::from_millis(1250));
         assert_eq!(test_sink.flush_count(), 2);
 
-        test_logger.set_flush_period(Some(Duration::from_secs(1)));
+        test_logger
+            .set_flush_period(Some(Duration::from_secs(1)))
+            .unwrap();
 
         thread::sleep(Duration::from_millis(1250));
         assert_eq!(test_sink.flush_count(), 3);
     }
 
+    #[test]
+    fn heartbeat() {
+        let test_sink = Arc::new(CounterSink::new());
+        let test_logger = Arc::new(test_logger_builder().sink(test_sink.clone()).build());
+
+        test_logger
+            .heartbeat_every(Duration::from_millis(100), Level::Info, || {
+                "alive".to_string()
+            })
+            .unwrap();
+        thread::sleep(Duration::from_millis(250));
+        test_logger.stop_heartbeat();
+
+        let records = test_sink.records();
+        assert_eq!(records.len(), 2);
+        for (i, record) in records.iter().enumerate() {
+            assert_eq!(record.payload(), "alive");
+            assert_eq!(record.fields()[0].key(), "heartbeat");
+            assert_eq!(record.fields()[0].value().to_string(), (i + 1).to_string());
+        }
+
+        thread::sleep(Duration::from_millis(250));
+        assert_eq!(test_sink.log_count(), 2);
+    }
+
     #[test]
     fn builder_name() {
         LoggerBuilder::new().name("hello-world");
@@ -692,4 +1999,39 @@ mod tests {
             NAMED("name") => LevelFilter::All,
         );
     }
+
+    #[test]
+    fn env_level_ranges_and_sinks() {
+        let file_sink = Arc::new(CounterSink::new());
+        file_sink.set_name(Some("file".into()));
+        let console_sink = Arc::new(CounterSink::new());
+        console_sink.set_name(Some("console".into()));
+        let test_logger = test_logger_builder()
+            .name("gui")
+            .sink(file_sink.clone())
+            .sink(console_sink.clone())
+            .build_inner_for_test("gui=trace..warn,[file]gui=info", false);
+
+        trace!(logger: test_logger, "trace");
+        info!(logger: test_logger, "info");
+        warn!(logger: test_logger, "warn");
+        error!(logger: test_logger, "error");
+
+        assert_eq!(
+            test_logger.level_filter(),
+            LevelFilter::MoreSevereEqual(Level::Trace)
+        );
+        assert_eq!(console_sink.payloads(), vec!["trace", "info", "warn"]);
+        assert_eq!(file_sink.payloads(), vec!["info", "warn"]);
+        // The sink is wrapped, not modified, since it may be shared.
+        assert_eq!(file_sink.level_filter(), LevelFilter::All);
+        assert_eq!(test_logger.sinks()[0].name().as_deref(), Some("file"));
+
+        let other_logger = test_logger_builder()
+            .name("other")
+            .sink(file_sink.clone())
+            .build_inner_for_test("gui=trace..warn,[file]gui=info", false);
+        info!(logger: other_logger, "other");
+        assert_eq!(file_sink.payloads(), vec!["info", "warn", "other"]);
+    }
 }
@@ -0,0 +1,153 @@
+//! Provides a group of loggers configured together.
+
+use std::sync::{Arc, Weak};
+
+use crate::{formatter::Formatter, LevelFilter, Logger};
+
+/// A group of loggers, to broadcast configuration changes to all of them at
+/// runtime.
+///
+/// Applications often have a logger per subsystem, which must be adjusted
+/// together, e.g. when the verbosity is changed by an operator. A group holds
+/// weak references to its loggers, so it does not keep them alive, and
+/// dropped loggers are removed from the group automatically.
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::Arc;
+///
+/// use spdlog::{formatter::FullFormatter, prelude::*, LoggerGroup};
+///
+/// let network: Arc<Logger> = Arc::new(Logger::builder().name("network").build());
+/// let storage: Arc<Logger> = Arc::new(Logger::builder().name("storage").build());
+///
+/// let group = LoggerGroup::new();
+/// group.add(&network);
+/// group.add(&storage);
+///
+/// // Turns on debug logs of all subsystems.
+/// group.set_level_filter_all(LevelFilter::MoreSevereEqual(Level::Debug));
+/// group.set_formatter_all(|| Box::new(FullFormatter::builder().elapsed(true).build()));
+/// group.flush_all();
+/// ```
+#[derive(Default)]
+pub struct LoggerGroup {
+    loggers: spin::Mutex<Vec<Weak<Logger>>>,
+}
+
+impl LoggerGroup {
+    /// Constructs an empty `LoggerGroup`.
+    pub fn new() -> LoggerGroup {
+        LoggerGroup::default()
+    }
+
+    /// Adds a logger to the group. Adding a logger already in the group does
+    /// nothing.
+    pub fn add(&self, logger: &Arc<Logger>) {
+        let logger = Arc::downgrade(logger);
+        let mut loggers = self.loggers.lock();
+        loggers.retain(|weak| weak.strong_count() > 0);
+        if !loggers.iter().any(|weak| weak.ptr_eq(&logger)) {
+            loggers.push(logger);
+        }
+    }
+
+    /// Removes a logger from the group, and returns `true` if it was in the
+    /// group.
+    pub fn remove(&self, logger: &Arc<Logger>) -> bool {
+        let logger = Arc::downgrade(logger);
+        let mut loggers = self.loggers.lock();
+        let mut removed = false;
+        loggers.retain(|weak| {
+            let matched = weak.ptr_eq(&logger);
+            removed |= matched;
+            !matched && weak.strong_count() > 0
+        });
+        removed
+    }
+
+    /// Gets the loggers in the group that are still alive, in the order they
+    /// were added.
+    pub fn loggers(&self) -> Vec<Arc<Logger>> {
+        let mut loggers = self.loggers.lock();
+        let alive = loggers.iter().filter_map(Weak::upgrade).collect::<Vec<_>>();
+        loggers.retain(|weak| weak.strong_count() > 0);
+        alive
+    }
+
+    /// Sets the log filter level of all loggers in the group.
+    ///
+    /// See [`Logger::set_level_filter`].
+    pub fn set_level_filter_all(&self, level_filter: LevelFilter) {
+        self.loggers()
+            .iter()
+            .for_each(|logger| logger.set_level_filter(level_filter));
+    }
+
+    /// Flushes all loggers in the group.
+    ///
+    /// See [`Logger::flush`].
+    pub fn flush_all(&self) {
+        self.loggers().iter().for_each(|logger| logger.flush());
+    }
+
+    /// Sets the formatter of all sinks of all loggers in the group.
+    ///
+    /// A formatter is constructed by `formatter` for each sink, since sinks own
+    /// their formatters. A sink shared by multiple loggers gets a formatter
+    /// for each of them, and keeps the last one.
+    pub fn set_formatter_all<F>(&self, formatter: F)
+    where
+        F: Fn() -> Box<dyn Formatter>,
+    {
+        for logger in self.loggers() {
+            logger
+                .sinks()
+                .iter()
+                .for_each(|sink| sink.set_formatter(formatter()));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+    use crate::{test_utils::*, Level};
+
+    #[test]
+    fn broadcast() {
+        let sink = Arc::new(CounterSink::new());
+        let first = Arc::new(test_logger_builder().sink(sink.clone()).build());
+        let second = Arc::new(test_logger_builder().sink(sink.clone()).build());
+        let third = Arc::new(test_logger_builder().sink(sink.clone()).build());
+
+        let group = LoggerGroup::new();
+        group.add(&first);
+        group.add(&second);
+        group.add(&second);
+        group.add(&third);
+        assert!(group.remove(&third));
+        assert!(!group.remove(&third));
+        assert_eq!(group.loggers().len(), 2);
+
+        group.set_level_filter_all(LevelFilter::MoreSevere(Level::Warn));
+        assert!(!first.should_log(Level::Warn));
+        assert!(!second.should_log(Level::Warn));
+        assert!(third.should_log(Level::Warn));
+
+        drop(second);
+        assert_eq!(group.loggers().len(), 1);
+        group.flush_all();
+        assert_eq!(sink.flush_count(), 1);
+
+        let count = AtomicUsize::new(0);
+        group.set_formatter_all(|| {
+            count.fetch_add(1, Ordering::Relaxed);
+            Box::new(NoModFormatter::new())
+        });
+        assert_eq!(count.load(Ordering::Relaxed), 1);
+    }
+}
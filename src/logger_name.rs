@@ -0,0 +1,148 @@
+//! Provides interned logger names.
+
+use std::{
+    borrow::Borrow,
+    collections::HashSet,
+    fmt,
+    hash::{Hash, Hasher},
+    sync::Arc,
+};
+
+use once_cell::sync::Lazy;
+
+static INTERNED: Lazy<spin::Mutex<HashSet<Arc<str>>>> = Lazy::new(Default::default);
+
+/// A cheap handle of a logger name.
+///
+/// Logger names are interned, all loggers with the same name share one
+/// allocation, and records refer to the name of their logger without copying
+/// it. Cloning a `LoggerName` only increments a reference count, and comparing
+/// two interned names compares pointers, so a [`Filter`] can hold the names it
+/// is interested in and check [`Record::logger_name_handle`] cheaply at high
+/// rates.
+///
+/// Interned names are never freed, which is fine for the bounded set of names
+/// of loggers in a program.
+///
+/// # Examples
+///
+/// ```
+/// use spdlog::{
+///     filter::{Decision, Filter},
+///     LoggerName, Record,
+/// };
+///
+/// struct MuteLogger(LoggerName);
+///
+/// impl Filter for MuteLogger {
+///     fn should_log(&self, record: &Record) -> Decision {
+///         (record.logger_name_handle() != Some(&self.0)).into()
+///     }
+/// }
+///
+/// let filter = MuteLogger(LoggerName::new("network"));
+/// # let _ = filter;
+/// ```
+///
+/// [`Filter`]: crate::filter::Filter
+/// [`Record::logger_name_handle`]: crate::Record::logger_name_handle
+#[derive(Clone)]
+pub struct LoggerName(Arc<str>);
+
+impl LoggerName {
+    /// Constructs a `LoggerName` by interning the name.
+    pub fn new<S>(name: S) -> LoggerName
+    where
+        S: AsRef<str>,
+    {
+        let name = name.as_ref();
+        let mut interned = INTERNED.lock();
+        if let Some(name) = interned.get(name) {
+            return LoggerName(name.clone());
+        }
+        let name = Arc::<str>::from(name);
+        interned.insert(name.clone());
+        LoggerName(name)
+    }
+
+    // Constructs a `LoggerName` without interning, for names that are not of
+    // loggers, e.g. names set on records by bridges.
+    pub(crate) fn uninterned(name: &str) -> LoggerName {
+        LoggerName(Arc::from(name))
+    }
+
+    /// Gets the name as a string.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl PartialEq for LoggerName {
+    fn eq(&self, other: &LoggerName) -> bool {
+        Arc::ptr_eq(&self.0, &other.0) || self.0 == other.0
+    }
+}
+
+impl Eq for LoggerName {}
+
+impl PartialEq<str> for LoggerName {
+    fn eq(&self, other: &str) -> bool {
+        &*self.0 == other
+    }
+}
+
+impl PartialEq<&str> for LoggerName {
+    fn eq(&self, other: &&str) -> bool {
+        &*self.0 == *other
+    }
+}
+
+impl Hash for LoggerName {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.hash(state)
+    }
+}
+
+impl AsRef<str> for LoggerName {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Borrow<str> for LoggerName {
+    fn borrow(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Debug for LoggerName {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(&*self.0, f)
+    }
+}
+
+impl fmt::Display for LoggerName {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn interning() {
+        let name = LoggerName::new("interning");
+        let other = LoggerName::new(String::from("interning"));
+        assert!(Arc::ptr_eq(&name.0, &other.0));
+        assert_eq!(name, other);
+
+        let uninterned = LoggerName::uninterned("interning");
+        assert!(!Arc::ptr_eq(&name.0, &uninterned.0));
+        assert_eq!(name, uninterned);
+        assert_ne!(name, LoggerName::new("interning2"));
+        assert_eq!(name, "interning");
+        assert_eq!(format!("{} {:?}", name, name), "interning \"interning\"");
+    }
+}
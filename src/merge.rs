@@ -0,0 +1,195 @@
+//! Provides utilities to merge log files.
+//!
+//! # Examples
+//!
+//! Merges the JSON log files of multiple processes for a postmortem:
+//!
+//! ```no_run
+//! # fn main() -> Result<(), spdlog::Error> {
+//! spdlog::merge::merge_json_files(
+//!     ["logs/server.json", "logs/worker-1.json", "logs/worker-2.json"],
+//!     "logs/merged.json",
+//! )?;
+//! # Ok(()) }
+//! ```
+
+use std::{
+    cmp::Reverse,
+    collections::BinaryHeap,
+    fs::File,
+    io::{BufRead, BufReader, BufWriter, Write},
+    path::Path,
+};
+
+use crate::{utils, Error, Result};
+
+/// Merges lines formatted by [`JsonFormatter`] from multiple sources into
+/// time order, and writes them to `dest`.
+///
+/// Lines are ordered by their `time` key, and then by their `seq` key (the
+/// [sequence number]), so that records of the same process are ordered
+/// strictly even when their times collide. Lines of the same source are never
+/// reordered, i.e. each source is expected to be in time order already, which
+/// is the case for a file written by a single sink. Lines that cannot be
+/// recognized are kept right after the line preceding them.
+///
+/// # Errors
+///
+/// If an error occurs reading a source, [`Error::ReadFile`] will be returned.
+/// If an error occurs writing to `dest`, [`Error::WriteRecord`] will be
+/// returned.
+///
+/// [`JsonFormatter`]: crate::formatter::JsonFormatter
+/// [sequence number]: crate::Record::sequence
+pub fn merge_json<I, R, W>(sources: I, mut dest: W) -> Result<()>
+where
+    I: IntoIterator<Item = R>,
+    R: BufRead,
+    W: Write,
+{
+    let mut sources = sources
+        .into_iter()
+        .map(|source| Source {
+            lines: source.lines(),
+            key: SortKey::default(),
+        })
+        .collect::<Vec<_>>();
+
+    // The source index breaks ties, lines from earlier sources come first.
+    let mut heap = BinaryHeap::new();
+    for (index, source) in sources.iter_mut().enumerate() {
+        if let Some(line) = source.next_line()? {
+            heap.push(Reverse((source.key.clone(), index, line)));
+        }
+    }
+
+    while let Some(Reverse((_, index, line))) = heap.pop() {
+        dest.write_all(line.as_bytes())
+            .and_then(|_| dest.write_all(b"\n"))
+            .map_err(Error::WriteRecord)?;
+
+        let source = &mut sources[index];
+        if let Some(line) = source.next_line()? {
+            heap.push(Reverse((source.key.clone(), index, line)));
+        }
+    }
+
+    dest.flush().map_err(Error::FlushBuffer)
+}
+
+/// Merges log files written with [`JsonFormatter`] into time order, and writes
+/// them to the file `dest`.
+///
+/// The file `dest` is truncated if it exists. See [`merge_json`] for the
+/// ordering.
+///
+/// # Errors
+///
+/// If an error occurs opening or reading a file, [`Error::OpenFile`] or
+/// [`Error::ReadFile`] will be returned. If an error occurs creating or
+/// writing `dest`, [`Error::CreateDirectory`], [`Error::OpenFile`] or
+/// [`Error::WriteRecord`] will be returned.
+///
+/// [`JsonFormatter`]: crate::formatter::JsonFormatter
+pub fn merge_json_files<I, P, D>(paths: I, dest: D) -> Result<()>
+where
+    I: IntoIterator<Item = P>,
+    P: AsRef<Path>,
+    D: AsRef<Path>,
+{
+    let sources = paths
+        .into_iter()
+        .map(|path| File::open(path).map(BufReader::new))
+        .collect::<std::result::Result<Vec<_>, _>>()
+        .map_err(Error::OpenFile)?;
+
+    let dest = BufWriter::new(utils::open_file(dest, true)?);
+    merge_json(sources, dest)
+}
+
+struct Source<R> {
+    lines: std::io::Lines<R>,
+    // The key of the last recognized line.
+    key: SortKey,
+}
+
+impl<R: BufRead> Source<R> {
+    fn next_line(&mut self) -> Result<Option<String>> {
+        match self.lines.next() {
+            Some(Ok(line)) => {
+                if let Some(key) = SortKey::parse(&line) {
+                    self.key = key;
+                }
+                Ok(Some(line))
+            }
+            Some(Err(err)) => Err(Error::ReadFile(err)),
+            None => Ok(None),
+        }
+    }
+}
+
+// The time is formatted as `2021-12-23T01:23:45.067Z` in UTC, so it can be
+// compared as a string.
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Default, Debug)]
+struct SortKey {
+    time: String,
+    seq: u64,
+}
+
+impl SortKey {
+    // `JsonFormatter` always writes `time` and `seq` before any user-provided
+    // strings, and quotes in strings are escaped, so the first occurrences are
+    // the ones written by it.
+    fn parse(line: &str) -> Option<SortKey> {
+        let time = line.split_once("\"time\":\"")?.1.split_once('"')?.0;
+        let seq = line
+            .split_once("\"seq\":")
+            .and_then(|(_, rest)| {
+                let end = rest
+                    .find(|ch: char| !ch.is_ascii_digit())
+                    .unwrap_or(rest.len());
+                rest[..end].parse().ok()
+            })
+            .unwrap_or(0);
+
+        Some(SortKey {
+            time: time.to_owned(),
+            seq,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn merge() {
+        let a = concat!(
+            r#"{"time":"2021-12-23T01:23:45.001Z","severity":"info","seq":1,"message":"a1"}"#,
+            "\n",
+            r#"{"time":"2021-12-23T01:23:45.003Z","severity":"info","seq":4,"message":"a2"}"#,
+            "\n",
+            "not json\n",
+        );
+        let b = concat!(
+            r#"{"time":"2021-12-23T01:23:45.002Z","severity":"info","seq":2,"message":"b1"}"#,
+            "\n",
+            r#"{"time":"2021-12-23T01:23:45.003Z","severity":"info","seq":3,"message":"b2"}"#,
+            "\n",
+        );
+
+        let mut dest = vec![];
+        merge_json([a.as_bytes(), b.as_bytes()], &mut dest).unwrap();
+
+        let messages = String::from_utf8(dest)
+            .unwrap()
+            .lines()
+            .map(|line| match line.split_once("\"message\":\"") {
+                Some((_, rest)) => rest.split_once('"').unwrap().0.to_owned(),
+                None => line.to_owned(),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(messages, vec!["a1", "b1", "b2", "a2", "not json"]);
+    }
+}
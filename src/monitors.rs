@@ -0,0 +1,230 @@
+//! Provides monitors periodically logging diagnostics of the process.
+
+use std::{sync::Arc, time::Duration};
+
+use crate::{
+    periodic_worker::PeriodicWorker, Error, Field, InvalidArgumentError, Level, Logger, Record,
+    RecordFlags, Result,
+};
+
+/// Resource usage of the current process.
+///
+/// Values that are not available on the current platform are `None`. Currently
+/// all of them are available on Linux only, where they are read from `/proc`.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Default)]
+pub struct ResourceUsage {
+    rss_bytes: Option<u64>,
+    fd_count: Option<u64>,
+    thread_count: Option<u64>,
+}
+
+impl ResourceUsage {
+    /// Queries the resource usage of the current process.
+    pub fn current() -> ResourceUsage {
+        #[allow(unused_mut)]
+        let mut usage = ResourceUsage::default();
+
+        #[cfg(target_os = "linux")]
+        {
+            if let Ok(status) = std::fs::read_to_string("/proc/self/status") {
+                for line in status.lines() {
+                    let parse = |value: &str| value.split_whitespace().next()?.parse::<u64>().ok();
+                    if let Some(value) = line.strip_prefix("VmRSS:") {
+                        usage.rss_bytes = parse(value).map(|kib| kib * 1024);
+                    } else if let Some(value) = line.strip_prefix("Threads:") {
+                        usage.thread_count = parse(value);
+                    }
+                }
+            }
+            usage.fd_count = std::fs::read_dir("/proc/self/fd")
+                .ok()
+                .map(|entries| entries.count() as u64);
+        }
+
+        usage
+    }
+
+    /// Gets the resident set size in bytes.
+    pub fn rss_bytes(&self) -> Option<u64> {
+        self.rss_bytes
+    }
+
+    /// Gets the number of open file descriptors.
+    pub fn fd_count(&self) -> Option<u64> {
+        self.fd_count
+    }
+
+    /// Gets the number of threads.
+    pub fn thread_count(&self) -> Option<u64> {
+        self.thread_count
+    }
+
+    fn fields(&self) -> impl Iterator<Item = Field<'static>> {
+        [
+            ("rss_bytes", self.rss_bytes),
+            ("fd_count", self.fd_count),
+            ("thread_count", self.thread_count),
+        ]
+        .into_iter()
+        .filter_map(|(key, value)| value.map(|value| Field::new(key, value)))
+    }
+}
+
+/// A monitor periodically logging the [`ResourceUsage`] of the process.
+///
+/// Each record has the payload `resource usage` and the available values as
+/// fields `rss_bytes`, `fd_count` and `thread_count`. It is useful as a
+/// built-in heartbeat with diagnostics, e.g. to spot memory or file
+/// descriptor leaks from logs.
+///
+/// The monitor runs on a background thread until it is dropped.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use spdlog::{monitors::ResourceMonitor, prelude::*};
+///
+/// # fn main() -> Result<(), spdlog::Error> {
+/// let _monitor = ResourceMonitor::builder()
+///     .level(Level::Debug)
+///     .interval(Duration::from_secs(30))
+///     .build()?;
+/// # Ok(()) }
+/// ```
+pub struct ResourceMonitor {
+    _worker: PeriodicWorker,
+}
+
+impl ResourceMonitor {
+    /// Constructs a [`ResourceMonitorBuilder`].
+    pub fn builder() -> ResourceMonitorBuilder {
+        ResourceMonitorBuilder {
+            logger: None,
+            level: Level::Info,
+            interval: Duration::from_secs(60),
+        }
+    }
+}
+
+/// The builder of [`ResourceMonitor`].
+#[derive(Clone)]
+pub struct ResourceMonitorBuilder {
+    logger: Option<Arc<Logger>>,
+    level: Level,
+    interval: Duration,
+}
+
+impl ResourceMonitorBuilder {
+    /// Sets the logger to log to.
+    ///
+    /// By default, records are logged to the default logger at the time of
+    /// logging, see [`default_logger`].
+    ///
+    /// [`default_logger`]: crate::default_logger
+    #[must_use]
+    pub fn logger(mut self, logger: Arc<Logger>) -> Self {
+        self.logger = Some(logger);
+        self
+    }
+
+    /// Sets the level of records.
+    ///
+    /// The default is [`Level::Info`].
+    #[must_use]
+    pub fn level(mut self, level: Level) -> Self {
+        self.level = level;
+        self
+    }
+
+    /// Sets the interval between records.
+    ///
+    /// The default is 60 seconds.
+    #[must_use]
+    pub fn interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Builds a [`ResourceMonitor`] and starts it.
+    ///
+    /// # Errors
+    ///
+    /// If the interval is zero, [`Error::InvalidArgument`] will be returned.
+    pub fn build(self) -> Result<ResourceMonitor> {
+        if self.interval.is_zero() {
+            return Err(Error::InvalidArgument(InvalidArgumentError::Interval(
+                "expect `interval` to be non-zero".to_string(),
+            )));
+        }
+
+        let callback = move || {
+            let logger = self.logger.clone().unwrap_or_else(crate::default_logger);
+            if logger.should_log(self.level) {
+                let fields = ResourceUsage::current().fields().collect::<Vec<_>>();
+                let mut builder = Record::builder(self.level, "resource usage")
+                    .fields(fields.iter().map(Field::as_borrowed))
+                    .flags(RecordFlags::INTERNAL);
+                if let Some(logger_name) = logger.name() {
+                    builder = builder.logger_name(logger_name);
+                }
+                logger.log(&builder.build());
+            }
+            true
+        };
+
+        Ok(ResourceMonitor {
+            _worker: PeriodicWorker::new(callback, self.interval),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+
+    use super::*;
+    use crate::test_utils::*;
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn current() {
+        let usage = ResourceUsage::current();
+        assert!(usage.rss_bytes().unwrap() > 0);
+        assert!(usage.fd_count().unwrap() >= 3);
+        assert!(usage.thread_count().unwrap() >= 1);
+    }
+
+    #[test]
+    fn zero_interval() {
+        assert!(matches!(
+            ResourceMonitor::builder().interval(Duration::ZERO).build(),
+            Err(Error::InvalidArgument(InvalidArgumentError::Interval(_)))
+        ));
+    }
+
+    #[test]
+    fn monitor() {
+        let test_sink = Arc::new(CounterSink::new());
+        let test_logger = Arc::new(test_logger_builder().sink(test_sink.clone()).build());
+
+        let monitor = ResourceMonitor::builder()
+            .logger(test_logger)
+            .interval(Duration::from_millis(10))
+            .build()
+            .unwrap();
+        thread::sleep(Duration::from_millis(100));
+        drop(monitor);
+
+        let count = test_sink.log_count();
+        assert!(count > 0);
+        assert!(test_sink
+            .payloads()
+            .iter()
+            .all(|payload| payload == "resource usage"));
+
+        thread::sleep(Duration::from_millis(50));
+        assert_eq!(test_sink.log_count(), count);
+    }
+}
@@ -4,13 +4,16 @@ use std::{
     time::Duration,
 };
 
+use crate::thread_config;
+
 pub struct PeriodicWorker {
     thread: Option<thread::JoinHandle<()>>,
     active: Arc<(Mutex<bool>, Condvar)>,
 }
 
 impl PeriodicWorker {
-    // Panic if the `interval.is_zero()` is `true`.
+    // Callers validate the interval and return `Error::InvalidArgument`, a zero
+    // interval here is a bug.
     #[allow(clippy::mutex_atomic)]
     pub fn new(callback: impl Fn() -> bool + Send + Sync + 'static, interval: Duration) -> Self {
         if interval.is_zero() {
@@ -18,29 +21,41 @@ impl PeriodicWorker {
         }
 
         let active = Arc::new((Mutex::new(true), Condvar::new()));
+        let stop = {
+            let active = active.clone();
+            move || Self::stop(&active)
+        };
 
         Self {
             active: active.clone(),
-            thread: Some(thread::spawn(move || loop {
-                let guard = active.0.lock().unwrap();
-                let (_, res) = active
-                    .1
-                    .wait_timeout_while(guard, interval, |active| *active)
-                    .unwrap();
-
-                if !res.timed_out() || !callback() {
-                    return;
-                }
-            })),
+            thread: Some(thread_config::default_thread_config().spawn(
+                "spdlog-rs periodic".to_string(),
+                stop,
+                move || loop {
+                    let guard = active.0.lock().unwrap();
+                    let (_, res) = active
+                        .1
+                        .wait_timeout_while(guard, interval, |active| *active)
+                        .unwrap();
+
+                    if !res.timed_out() || !callback() {
+                        return;
+                    }
+                },
+            )),
         }
     }
+
+    #[allow(clippy::mutex_atomic)]
+    fn stop(active: &(Mutex<bool>, Condvar)) {
+        *active.0.lock().unwrap() = false;
+        active.1.notify_all();
+    }
 }
 
 impl Drop for PeriodicWorker {
-    #[allow(clippy::mutex_atomic)]
     fn drop(&mut self) {
-        *self.active.0.lock().unwrap() = false;
-        self.active.1.notify_all();
+        Self::stop(&self.active);
         self.thread
             .take()
             .unwrap()
@@ -0,0 +1,256 @@
+//! Provides measurements of the logging pipeline itself.
+//!
+//! This module is available with the crate feature `self-profiling`. When it
+//! is enabled, the time spent in each [`Stage`] of the pipeline is recorded
+//! into a global [`Histogram`] per stage, so that the overhead of logging can
+//! be quantified in the actual workload rather than in benchmarks.
+//!
+//! The time of a stage excludes the time of stages nested in it, e.g.
+//! [`Stage::Write`] excludes the formatting done by the sink. Measuring costs
+//! two reads of the monotonic clock and a few relaxed atomic operations per
+//! stage. Without the feature, nothing is measured and there is no cost.
+//!
+//! # Examples
+//!
+//! ```
+//! # #[cfg(feature = "self-profiling")]
+//! # fn main() {
+//! use spdlog::{
+//!     prelude::*,
+//!     profiling::{self, Stage},
+//! };
+//!
+//! info!("hello, world");
+//!
+//! let format = profiling::histogram(Stage::Format);
+//! println!(
+//!     "formatted {} records, p99 {:?}, max {:?}",
+//!     format.count(),
+//!     format.percentile(0.99),
+//!     format.max()
+//! );
+//! # }
+//! # #[cfg(not(feature = "self-profiling"))]
+//! # fn main() {}
+//! ```
+
+#[cfg(feature = "self-profiling")]
+use std::{
+    cell::Cell,
+    sync::atomic::{AtomicU64, Ordering},
+    time::{Duration, Instant},
+};
+
+/// Stages of the logging pipeline.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum Stage {
+    /// Applying the [`Filter`]s of a logger to a record.
+    ///
+    /// [`Filter`]: crate::filter::Filter
+    Filter,
+    /// Formatting a record by the formatter of a sink.
+    Format,
+    /// Logging a record to a sink by a logger, excluding formatting.
+    ///
+    /// For sinks writing on a background thread (e.g. [`AsyncSink`]), it is the
+    /// time of handing the record over, which is the cost on the logging
+    /// thread.
+    ///
+    /// [`AsyncSink`]: crate::sink::AsyncSink
+    Write,
+    /// Flushing a sink by a logger.
+    Flush,
+}
+
+#[cfg(feature = "self-profiling")]
+impl Stage {
+    const ALL: [Stage; 4] = [Stage::Filter, Stage::Format, Stage::Write, Stage::Flush];
+}
+
+// Bucket `i` counts durations in `[2^(i-1), 2^i)` nanoseconds, bucket 0 counts
+// zero durations.
+#[cfg(feature = "self-profiling")]
+const BUCKETS: usize = 65;
+
+/// A snapshot of the histogram of durations of a [`Stage`].
+///
+/// Durations are counted in buckets of powers of two nanoseconds, so
+/// percentiles are upper bounds with a relative error of up to 100%, which is
+/// enough to tell orders of magnitude apart.
+#[cfg(feature = "self-profiling")]
+#[derive(Clone, Debug)]
+pub struct Histogram {
+    buckets: [u64; BUCKETS],
+    count: u64,
+    sum: u64,
+    max: u64,
+}
+
+#[cfg(feature = "self-profiling")]
+impl Histogram {
+    /// Gets the number of measurements.
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// Gets the total duration of measurements.
+    pub fn total(&self) -> Duration {
+        Duration::from_nanos(self.sum)
+    }
+
+    /// Gets the mean duration, or zero if there is no measurement.
+    pub fn mean(&self) -> Duration {
+        Duration::from_nanos(self.sum.checked_div(self.count).unwrap_or(0))
+    }
+
+    /// Gets the maximum duration, or zero if there is no measurement.
+    pub fn max(&self) -> Duration {
+        Duration::from_nanos(self.max)
+    }
+
+    /// Gets an upper bound of the given percentile of durations, where
+    /// `percentile` is in range `[0.0, 1.0]`, or zero if there is no
+    /// measurement.
+    pub fn percentile(&self, percentile: f64) -> Duration {
+        let rank = (self.count as f64 * percentile.clamp(0.0, 1.0))
+            .ceil()
+            .max(1.0) as u64;
+        let mut seen = 0;
+        for (index, &count) in self.buckets.iter().enumerate() {
+            seen += count;
+            if seen >= rank {
+                let upper = if index == 0 {
+                    0
+                } else {
+                    u64::MAX >> (64 - index)
+                };
+                return Duration::from_nanos(upper.min(self.max));
+            }
+        }
+        Duration::ZERO
+    }
+}
+
+#[cfg(feature = "self-profiling")]
+struct AtomicHistogram {
+    buckets: [AtomicU64; BUCKETS],
+    count: AtomicU64,
+    sum: AtomicU64,
+    max: AtomicU64,
+}
+
+#[cfg(feature = "self-profiling")]
+impl AtomicHistogram {
+    #[allow(clippy::declare_interior_mutable_const)]
+    const NEW: AtomicHistogram = {
+        const ZERO: AtomicU64 = AtomicU64::new(0);
+        AtomicHistogram {
+            buckets: [ZERO; BUCKETS],
+            count: ZERO,
+            sum: ZERO,
+            max: ZERO,
+        }
+    };
+
+    fn record(&self, nanos: u64) {
+        let index = (u64::BITS - nanos.leading_zeros()) as usize;
+        self.buckets[index].fetch_add(1, Ordering::Relaxed);
+        self.count.fetch_add(1, Ordering::Relaxed);
+        self.sum.fetch_add(nanos, Ordering::Relaxed);
+        self.max.fetch_max(nanos, Ordering::Relaxed);
+    }
+
+    fn snapshot(&self) -> Histogram {
+        Histogram {
+            buckets: std::array::from_fn(|index| self.buckets[index].load(Ordering::Relaxed)),
+            count: self.count.load(Ordering::Relaxed),
+            sum: self.sum.load(Ordering::Relaxed),
+            max: self.max.load(Ordering::Relaxed),
+        }
+    }
+
+    fn reset(&self) {
+        self.buckets
+            .iter()
+            .chain([&self.count, &self.sum, &self.max])
+            .for_each(|value| value.store(0, Ordering::Relaxed));
+    }
+}
+
+#[cfg(feature = "self-profiling")]
+static HISTOGRAMS: [AtomicHistogram; 4] = [AtomicHistogram::NEW; 4];
+
+/// Gets a snapshot of the histogram of durations of a stage.
+#[cfg(feature = "self-profiling")]
+pub fn histogram(stage: Stage) -> Histogram {
+    HISTOGRAMS[stage as usize].snapshot()
+}
+
+/// Resets the histograms of all stages, e.g. after warming up.
+#[cfg(feature = "self-profiling")]
+pub fn reset() {
+    Stage::ALL
+        .iter()
+        .for_each(|&stage| HISTOGRAMS[stage as usize].reset());
+}
+
+// Runs `f` and records its duration to the stage, excluding the durations
+// recorded by nested calls.
+#[inline]
+pub(crate) fn measure<R>(stage: Stage, f: impl FnOnce() -> R) -> R {
+    #[cfg(feature = "self-profiling")]
+    {
+        thread_local! {
+            // The total duration measured by nested calls.
+            static NESTED: Cell<u64> = const { Cell::new(0) };
+        }
+
+        let outer = NESTED.with(|nested| nested.replace(0));
+        let start = Instant::now();
+        let result = f();
+        let elapsed = start.elapsed().as_nanos() as u64;
+        NESTED.with(|nested| {
+            HISTOGRAMS[stage as usize].record(elapsed.saturating_sub(nested.get()));
+            nested.set(outer + elapsed);
+        });
+        result
+    }
+
+    #[cfg(not(feature = "self-profiling"))]
+    {
+        let _ = stage;
+        f()
+    }
+}
+
+#[cfg(all(test, feature = "self-profiling"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn percentile() {
+        let histogram = AtomicHistogram::NEW;
+        [0, 3, 100, 1000].iter().for_each(|&n| histogram.record(n));
+        let histogram = histogram.snapshot();
+
+        assert_eq!(histogram.count(), 4);
+        assert_eq!(histogram.mean(), Duration::from_nanos(275));
+        assert_eq!(histogram.max(), Duration::from_nanos(1000));
+        assert_eq!(histogram.percentile(0.0), Duration::ZERO);
+        assert_eq!(histogram.percentile(0.25), Duration::ZERO);
+        assert_eq!(histogram.percentile(0.5), Duration::from_nanos(3));
+        assert_eq!(histogram.percentile(0.75), Duration::from_nanos(127));
+        assert_eq!(histogram.percentile(1.0), Duration::from_nanos(1000));
+    }
+
+    #[test]
+    fn nested() {
+        measure(Stage::Flush, || {
+            measure(Stage::Filter, || {
+                std::thread::sleep(Duration::from_millis(100))
+            })
+        });
+        assert!(histogram(Stage::Filter).max() >= Duration::from_millis(100));
+        assert!(histogram(Stage::Flush).max() < Duration::from_millis(100));
+    }
+}
@@ -0,0 +1,238 @@
+//! Provides a rate limiter of records.
+
+use std::{
+    sync::atomic::{AtomicU64, Ordering},
+    time::{Duration, Instant},
+};
+
+use crate::{Level, LevelFilter};
+
+/// A token bucket rate limiter of records.
+///
+/// It protects services from log storms by suppressing records exceeding a
+/// rate. The bucket holds up to a burst of tokens, and is refilled at the rate
+/// continuously. Each record consumes a token, and is suppressed if the bucket
+/// is empty. Records of exempted levels (by default, [`Level::Error`] and more
+/// severe) are always logged and do not consume tokens.
+///
+/// The number of suppressed records is reported periodically: when a record
+/// is logged and records were suppressed since the last report, a record at
+/// [`Level::Warn`] with the payload `records suppressed by rate limit` and the
+/// number as a field `suppressed` is logged before it, at most once per
+/// report interval. Suppressed records not reported yet are also reported when
+/// the logger is flushed, manually or periodically (see
+/// [`Logger::set_flush_period`]), so that the report of the last burst is not
+/// held back until the next record is logged.
+///
+/// A rate limiter is set to a logger by [`LoggerBuilder::rate_limiter`]. It
+/// counts records passing the level filter of the logger. Sharing one rate
+/// limiter between loggers limits their records as a whole, e.g. for a process
+/// wide limit.
+///
+/// # Examples
+///
+/// ```
+/// use std::{sync::Arc, time::Duration};
+///
+/// use spdlog::{prelude::*, RateLimiter};
+///
+/// let rate_limiter = RateLimiter::builder()
+///     .rate(1000)
+///     .exempt(LevelFilter::MoreSevereEqual(Level::Warn))
+///     .report_interval(Duration::from_secs(60))
+///     .build();
+///
+/// let logger: Logger = Logger::builder()
+///     .rate_limiter(Arc::new(rate_limiter))
+///     .build();
+/// ```
+///
+/// [`LoggerBuilder::rate_limiter`]: crate::LoggerBuilder::rate_limiter
+/// [`Logger::set_flush_period`]: crate::Logger::set_flush_period
+pub struct RateLimiter {
+    rate: f64,
+    burst: f64,
+    exempt: LevelFilter,
+    report_interval: Duration,
+    state: spin::Mutex<State>,
+    total_suppressed: AtomicU64,
+}
+
+struct State {
+    tokens: f64,
+    last_refill: Instant,
+    suppressed: u64,
+    last_report: Instant,
+}
+
+// The result of acquiring a token.
+pub(crate) enum Acquire {
+    Suppressed,
+    Allowed,
+    // Allowed, and the number of suppressed records should be reported.
+    AllowedWithReport(u64),
+}
+
+impl RateLimiter {
+    /// Constructs a [`RateLimiterBuilder`].
+    pub fn builder() -> RateLimiterBuilder {
+        RateLimiterBuilder {
+            rate: 1000,
+            burst: None,
+            exempt: LevelFilter::MoreSevereEqual(Level::Error),
+            report_interval: Duration::from_secs(10),
+        }
+    }
+
+    /// Gets the total number of records suppressed by this rate limiter.
+    pub fn total_suppressed(&self) -> u64 {
+        self.total_suppressed.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn acquire(&self, level: Level) -> Acquire {
+        let now = Instant::now();
+        let mut state = self.state.lock();
+
+        if !self.exempt.compare(level) {
+            let elapsed = now.saturating_duration_since(state.last_refill);
+            state.tokens = (state.tokens + elapsed.as_secs_f64() * self.rate).min(self.burst);
+            state.last_refill = now;
+
+            if state.tokens < 1.0 {
+                state.suppressed += 1;
+                self.total_suppressed.fetch_add(1, Ordering::Relaxed);
+                return Acquire::Suppressed;
+            }
+            state.tokens -= 1.0;
+        }
+
+        if state.suppressed != 0
+            && now.saturating_duration_since(state.last_report) >= self.report_interval
+        {
+            state.last_report = now;
+            Acquire::AllowedWithReport(std::mem::take(&mut state.suppressed))
+        } else {
+            Acquire::Allowed
+        }
+    }
+
+    // Takes the number of suppressed records not reported yet, regardless of the
+    // report interval.
+    pub(crate) fn take_suppressed(&self) -> Option<u64> {
+        let mut state = self.state.lock();
+        if state.suppressed == 0 {
+            return None;
+        }
+        state.last_report = Instant::now();
+        Some(std::mem::take(&mut state.suppressed))
+    }
+}
+
+/// The builder of [`RateLimiter`].
+#[derive(Clone, Debug)]
+pub struct RateLimiterBuilder {
+    rate: u32,
+    burst: Option<u32>,
+    exempt: LevelFilter,
+    report_interval: Duration,
+}
+
+impl RateLimiterBuilder {
+    /// Sets the maximum number of records per second in the long run.
+    ///
+    /// The default is 1000.
+    #[must_use]
+    pub fn rate(mut self, records_per_sec: u32) -> Self {
+        self.rate = records_per_sec;
+        self
+    }
+
+    /// Sets the maximum number of records logged in a burst, i.e. the capacity
+    /// of the bucket.
+    ///
+    /// The default is the same as the rate.
+    #[must_use]
+    pub fn burst(mut self, burst: u32) -> Self {
+        self.burst = Some(burst);
+        self
+    }
+
+    /// Sets the levels exempted from rate limiting.
+    ///
+    /// The default is `LevelFilter::MoreSevereEqual(Level::Error)`.
+    #[must_use]
+    pub fn exempt(mut self, exempt: LevelFilter) -> Self {
+        self.exempt = exempt;
+        self
+    }
+
+    /// Sets the minimum interval between reports of suppressed records.
+    ///
+    /// The default is 10 seconds.
+    #[must_use]
+    pub fn report_interval(mut self, interval: Duration) -> Self {
+        self.report_interval = interval;
+        self
+    }
+
+    /// Builds a [`RateLimiter`].
+    ///
+    /// The bucket is full initially.
+    pub fn build(self) -> RateLimiter {
+        let burst = self.burst.unwrap_or(self.rate) as f64;
+        let now = Instant::now();
+        RateLimiter {
+            rate: self.rate as f64,
+            burst,
+            exempt: self.exempt,
+            report_interval: self.report_interval,
+            state: spin::Mutex::new(State {
+                tokens: burst,
+                last_refill: now,
+                suppressed: 0,
+                last_report: now,
+            }),
+            total_suppressed: AtomicU64::new(0),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn acquire() {
+        let rate_limiter = RateLimiter::builder()
+            .rate(1)
+            .burst(2)
+            .report_interval(Duration::ZERO)
+            .build();
+
+        assert!(matches!(
+            rate_limiter.acquire(Level::Info),
+            Acquire::Allowed
+        ));
+        assert!(matches!(
+            rate_limiter.acquire(Level::Info),
+            Acquire::Allowed
+        ));
+        assert!(matches!(
+            rate_limiter.acquire(Level::Info),
+            Acquire::Suppressed
+        ));
+        assert!(matches!(
+            rate_limiter.acquire(Level::Warn),
+            Acquire::Suppressed
+        ));
+        assert!(matches!(
+            rate_limiter.acquire(Level::Error),
+            Acquire::AllowedWithReport(2)
+        ));
+        assert!(matches!(
+            rate_limiter.acquire(Level::Critical),
+            Acquire::Allowed
+        ));
+        assert_eq!(rate_limiter.total_suppressed(), 2);
+    }
+}
@@ -1,11 +1,25 @@
 //! Provides a log record structure.
 
 use std::{
+    backtrace::Backtrace,
     borrow::{Borrow, Cow},
+    fmt,
+    ops::{BitOr, BitOrAssign},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
     time::SystemTime,
 };
 
-use crate::{Level, SourceLocation};
+use smallvec::SmallVec;
+
+use crate::{terminal_style::Style, ErrorCode, Field, Level, LoggerName, SourceLocation, Value};
+
+// Records with up to this number of fields store them inline, so that adding
+// fields does not allocate on the logging path. Inline fields enlarge every
+// record, including the ones without fields, so the number is kept small.
+const INLINE_FIELDS: usize = 4;
 
 /// Represents a log record.
 ///
@@ -16,16 +30,50 @@ use crate::{Level, SourceLocation};
 /// manipulate these structures in order to process log records. `Record`s are
 /// automatically created by log macros and so are not seen by log users.
 ///
+/// Libraries bridging other logging facilities or protocols can construct
+/// records with arbitrary contents by [`Record::builder`].
+///
 /// [`Logger::log`]: crate::logger::Logger::log
 /// [`Sink::log`]: crate::sink::Sink::log
 /// [`log!`]: crate::log
 #[derive(Clone, Debug)]
 pub struct Record<'a> {
-    logger_name: Option<&'a str>,
+    logger_name: Option<LoggerNameRef<'a>>,
     level: Level,
     payload: Cow<'a, str>,
+    payload_bytes: Option<Cow<'a, [u8]>>,
     source_location: Option<SourceLocation>,
+    target: Option<&'a str>,
+    fields: SmallVec<[Field<'a>; INLINE_FIELDS]>,
     time: SystemTime,
+    sequence: u64,
+    backtrace: Option<Arc<Backtrace>>,
+    style: Option<Style>,
+    code: Option<ErrorCode>,
+    message_key: Option<&'a str>,
+    flags: RecordFlags,
+}
+
+// The logger name of a record, a handle if it is set from a logger.
+#[derive(Copy, Clone, Debug)]
+enum LoggerNameRef<'a> {
+    Str(&'a str),
+    Handle(&'a LoggerName),
+}
+
+impl<'a> LoggerNameRef<'a> {
+    fn as_str(self) -> &'a str {
+        match self {
+            Self::Str(name) => name,
+            Self::Handle(name) => name.as_str(),
+        }
+    }
+}
+
+static NEXT_SEQUENCE: AtomicU64 = AtomicU64::new(0);
+
+fn next_sequence() -> u64 {
+    NEXT_SEQUENCE.fetch_add(1, Ordering::Relaxed)
 }
 
 impl<'a> Record<'a> {
@@ -42,16 +90,21 @@ impl<'a> Record<'a> {
             logger_name: None,
             level,
             payload: payload.into(),
+            payload_bytes: None,
             source_location: None,
+            target: None,
+            fields: SmallVec::new(),
             time: SystemTime::now(),
+            sequence: next_sequence(),
+            backtrace: None,
+            style: None,
+            code: None,
+            message_key: None,
+            flags: RecordFlags::empty(),
         }
     }
 
     /// Constructs a [`RecordBuilder`].
-    ///
-    /// Typically users should only use it for testing [`Sink`].
-    ///
-    /// [`Sink`]: crate::sink::Sink
     pub fn builder<S>(level: Level, payload: S) -> RecordBuilder<'a>
     where
         S: Into<Cow<'a, str>>,
@@ -59,9 +112,40 @@ impl<'a> Record<'a> {
         RecordBuilder::new(level, payload)
     }
 
+    /// Constructs a [`RecordBuilder`] for a record whose payload is raw bytes,
+    /// e.g. a protocol frame.
+    ///
+    /// [`Record::payload`] of the record is the bytes converted to UTF-8
+    /// lossily, invalid sequences are replaced with `U+FFFD`. Formatters may
+    /// render the bytes in other ways, see [`BytesRendering`].
+    ///
+    /// [`BytesRendering`]: crate::formatter::BytesRendering
+    pub fn builder_bytes<B>(level: Level, payload: B) -> RecordBuilder<'a>
+    where
+        B: Into<Cow<'a, [u8]>>,
+    {
+        let payload = payload.into();
+        let mut builder = RecordBuilder::new(level, String::from_utf8_lossy(&payload).into_owned());
+        builder.record.payload_bytes = Some(payload);
+        builder
+    }
+
     /// Gets the logger name.
     pub fn logger_name(&self) -> Option<&'a str> {
-        self.logger_name
+        self.logger_name.map(LoggerNameRef::as_str)
+    }
+
+    /// Gets the handle of the logger name, for cheap comparisons.
+    ///
+    /// Returns `None` if the record has no logger name, or the name is set as
+    /// a string by [`RecordBuilder::logger_name`] rather than from a logger.
+    /// Records borrowed from a [`RecordOwned`] always have a handle if they
+    /// have a name.
+    pub fn logger_name_handle(&self) -> Option<&'a LoggerName> {
+        match self.logger_name {
+            Some(LoggerNameRef::Handle(name)) => Some(name),
+            _ => None,
+        }
     }
 
     /// Gets the level.
@@ -74,45 +158,240 @@ impl<'a> Record<'a> {
         self.payload.borrow()
     }
 
+    /// Gets the raw bytes of the payload.
+    ///
+    /// Returns `None` if the record was not constructed by
+    /// [`Record::builder_bytes`].
+    pub fn payload_bytes(&self) -> Option<&[u8]> {
+        self.payload_bytes.as_deref()
+    }
+
     /// Gets the source location.
     pub fn source_location(&self) -> Option<&SourceLocation> {
         self.source_location.as_ref()
     }
 
-    /// Gets the time when the record was created.
+    /// Gets the target.
+    ///
+    /// It is usually set by bridges of other logging facilities, e.g. the
+    /// target of records forwarded from the `log` crate.
+    pub fn target(&self) -> Option<&'a str> {
+        self.target
+    }
+
+    /// Gets the fields.
+    pub fn fields(&self) -> &[Field<'a>] {
+        &self.fields
+    }
+
+    // Returns a record borrowing this one, with the given fields placed before
+    // the fields of this one.
+    pub(crate) fn with_leading_fields<'b, I>(&'b self, fields: I) -> Record<'b>
+    where
+        I: IntoIterator<Item = Field<'b>>,
+    {
+        Record {
+            logger_name: self.logger_name,
+            level: self.level,
+            payload: Cow::Borrowed(&self.payload),
+            payload_bytes: self.payload_bytes.as_deref().map(Cow::Borrowed),
+            source_location: self.source_location.clone(),
+            target: self.target,
+            fields: fields
+                .into_iter()
+                .chain(self.fields.iter().map(Field::as_borrowed))
+                .collect(),
+            time: self.time,
+            sequence: self.sequence,
+            backtrace: self.backtrace.clone(),
+            style: self.style.clone(),
+            code: self.code.clone(),
+            message_key: self.message_key,
+            flags: self.flags,
+        }
+    }
+
+    /// Gets the time of the record.
+    ///
+    /// It is the time when the record was created, unless it is overridden by
+    /// [`RecordBuilder::time`] or [`Record::set_time`]. Formatters and sinks
+    /// (e.g. time-based rotation of [`RotatingFileSink`]) use this time
+    /// instead of the current time.
+    ///
+    /// [`RotatingFileSink`]: crate::sink::RotatingFileSink
     pub fn time(&self) -> SystemTime {
         self.time
     }
 
+    /// Gets the sequence number of the record.
+    ///
+    /// Each record is stamped with a process-wide number when it is created,
+    /// which is greater than the numbers of all records created before it. It
+    /// allows strictly ordering records even when their times collide or the
+    /// system clock steps backwards. The numbers are not contiguous, since
+    /// records may be filtered out after they are created.
+    pub fn sequence(&self) -> u64 {
+        self.sequence
+    }
+
+    /// Gets the backtrace captured when the record was created.
+    ///
+    /// Returns `None` if no backtrace was captured. See
+    /// [`Logger::set_backtrace_level_filter`] and [`log!`] for how to capture
+    /// it.
+    ///
+    /// [`Logger::set_backtrace_level_filter`]: crate::Logger::set_backtrace_level_filter
+    /// [`log!`]: crate::log
+    pub fn backtrace(&self) -> Option<&Backtrace> {
+        self.backtrace.as_deref()
+    }
+
+    /// Gets the style overriding the level style.
+    ///
+    /// Sinks writing to terminals (e.g. [`StdStreamSink`]) render the record in
+    /// this style if it is set, other sinks ignore it. See [`log!`] for how to
+    /// set it from log macros.
+    ///
+    /// [`StdStreamSink`]: crate::sink::StdStreamSink
+    /// [`log!`]: crate::log
+    pub fn style(&self) -> Option<&Style> {
+        self.style.as_ref()
+    }
+
+    /// Gets the error code.
+    ///
+    /// See [`log!`] for how to attach it from log macros.
+    ///
+    /// [`log!`]: crate::log
+    pub fn code(&self) -> Option<&ErrorCode> {
+        self.code.as_ref()
+    }
+
+    /// Gets the message key.
+    ///
+    /// The payload is the canonical message of the record, formatters
+    /// configured with a message catalog resolve the key instead. See the
+    /// [`catalog`] module for details.
+    ///
+    /// [`catalog`]: crate::catalog
+    pub fn message_key(&self) -> Option<&'a str> {
+        self.message_key
+    }
+
+    /// Gets the flags of the record.
+    ///
+    /// See [`RecordFlags`] for the meanings of flags.
+    pub fn flags(&self) -> RecordFlags {
+        self.flags
+    }
+
+    /// Converts to a [`RecordOwned`].
+    ///
+    /// It is useful when a record needs to be stored or sent to another thread,
+    /// e.g. by asynchronous sinks.
+    pub fn to_owned(&self) -> RecordOwned {
+        RecordOwned {
+            logger_name: self.logger_name.map(|name| match name {
+                LoggerNameRef::Str(name) => LoggerName::uninterned(name),
+                LoggerNameRef::Handle(name) => name.clone(),
+            }),
+            level: self.level,
+            payload: self.payload.to_string(),
+            payload_bytes: self.payload_bytes.as_ref().map(|bytes| bytes.to_vec()),
+            source_location: self.source_location.clone(),
+            target: self.target.map(|s| s.to_owned()),
+            fields: self.fields.iter().map(Field::to_owned).collect(),
+            time: self.time,
+            sequence: self.sequence,
+            backtrace: self.backtrace.clone(),
+            style: self.style.clone(),
+            code: self.code.clone(),
+            message_key: self.message_key.map(|s| s.to_owned()),
+            flags: self.flags,
+        }
+    }
+
     #[cfg(feature = "log")]
     pub(crate) fn from_log_crate_record(
         logger: &'a crate::Logger,
-        record: &log::Record,
+        record: &log::Record<'a>,
         time: SystemTime,
     ) -> Self {
         let args = record.args();
 
         Self {
-            logger_name: logger.name(),
+            logger_name: logger.name_handle().map(LoggerNameRef::Handle),
             level: record.level().into(),
             payload: match args.as_str() {
                 Some(literal_str) => literal_str.into(),
                 None => args.to_string().into(),
             },
+            payload_bytes: None,
             source_location: None, // `module_path` and `file` in `log::Record` are not `'static`
+            target: Some(record.target()),
+            fields: SmallVec::new(),
             time,
+            sequence: next_sequence(),
+            backtrace: None,
+            style: None,
+            code: None,
+            message_key: None,
+            flags: RecordFlags::empty(),
         }
     }
 
-    #[cfg(test)]
-    pub(crate) fn set_time(&mut self, new: SystemTime) {
+    /// Sets the time of the record.
+    ///
+    /// It is useful for replaying or importing events from external sources
+    /// with their original times.
+    pub fn set_time(&mut self, new: SystemTime) {
         self.time = new;
     }
+
+    /// Sets the flags of the record.
+    ///
+    /// It is useful for middleware (e.g. sinks forwarding records) marking the
+    /// records passing through it, use [`RecordFlags::insert`] to keep the
+    /// existing flags:
+    ///
+    /// ```
+    /// use spdlog::{prelude::*, Record, RecordFlags};
+    ///
+    /// let mut record = Record::new(Level::Info, "replayed from the journal");
+    /// let mut flags = record.flags();
+    /// flags.insert(RecordFlags::REPLAYED);
+    /// record.set_flags(flags);
+    /// assert!(record.flags().contains(RecordFlags::REPLAYED));
+    /// ```
+    pub fn set_flags(&mut self, flags: RecordFlags) {
+        self.flags = flags;
+    }
 }
 
 /// The builder of [`Record`].
 ///
-/// Typically users should only use it for testing [`Sink`].
+/// Besides testing [`Sink`]s, it allows libraries bridging other logging
+/// facilities or protocols to synthesize records, including ones with a
+/// historical time.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::{Duration, SystemTime};
+///
+/// use spdlog::{prelude::*, Record};
+///
+/// let time = SystemTime::UNIX_EPOCH + Duration::from_secs(1_600_000_000);
+/// let record: Record = Record::builder(Level::Info, "request handled")
+///     .logger_name("bridge")
+///     .target("http::server")
+///     .field("status", "200")
+///     .time(time)
+///     .build();
+///
+/// # let logger = spdlog::default_logger();
+/// logger.log(&record);
+/// ```
 ///
 /// [`Sink`]: crate::sink::Sink
 #[derive(Clone, Debug)]
@@ -124,10 +403,6 @@ impl<'a> RecordBuilder<'a> {
     /// Constructs a `RecordBuilder`.
     ///
     /// The default value of [`Record`] is the same as [`Record::new()`].
-    ///
-    /// Typically users should only use it for testing [`Sink`].
-    ///
-    /// [`Sink`]: crate::sink::Sink
     pub fn new<S>(level: Level, payload: S) -> Self
     where
         S: Into<Cow<'a, str>>,
@@ -140,7 +415,36 @@ impl<'a> RecordBuilder<'a> {
     /// Sets the logger name.
     #[must_use]
     pub fn logger_name(mut self, logger_name: &'a str) -> Self {
-        self.record.logger_name = Some(logger_name);
+        self.record.logger_name = Some(LoggerNameRef::Str(logger_name));
+        self
+    }
+
+    /// Sets the logger name from a handle, e.g. [`Logger::name_handle`].
+    ///
+    /// [`Logger::name_handle`]: crate::Logger::name_handle
+    #[must_use]
+    pub fn logger_name_handle(mut self, logger_name: &'a LoggerName) -> Self {
+        self.record.logger_name = Some(LoggerNameRef::Handle(logger_name));
+        self
+    }
+
+    /// Sets the level.
+    #[must_use]
+    pub fn level(mut self, level: Level) -> Self {
+        self.record.level = level;
+        self
+    }
+
+    /// Sets the payload.
+    ///
+    /// It discards the raw bytes set by [`Record::builder_bytes`].
+    #[must_use]
+    pub fn payload<S>(mut self, payload: S) -> Self
+    where
+        S: Into<Cow<'a, str>>,
+    {
+        self.record.payload = payload.into();
+        self.record.payload_bytes = None;
         self
     }
 
@@ -153,8 +457,460 @@ impl<'a> RecordBuilder<'a> {
         self
     }
 
+    /// Sets the target.
+    #[must_use]
+    pub fn target(mut self, target: &'a str) -> Self {
+        self.record.target = Some(target);
+        self
+    }
+
+    /// Adds a field.
+    ///
+    /// Up to 4 fields are stored inline in the record without heap
+    /// allocations. Borrowed keys and values (e.g. string literals) are not
+    /// copied.
+    #[must_use]
+    pub fn field<K, V>(mut self, key: K, value: V) -> Self
+    where
+        K: Into<Cow<'a, str>>,
+        V: Into<Value<'a>>,
+    {
+        self.record.fields.push(Field::new(key, value));
+        self
+    }
+
+    /// Adds multiple fields.
+    #[must_use]
+    pub fn fields<I>(mut self, fields: I) -> Self
+    where
+        I: IntoIterator<Item = Field<'a>>,
+    {
+        self.record.fields.extend(fields);
+        self
+    }
+
+    /// Sets the time.
+    ///
+    /// By default, it is the time when the builder was constructed. Setting a
+    /// historical time is useful for replaying or importing events from
+    /// external sources with their original times.
+    #[must_use]
+    pub fn time(mut self, time: SystemTime) -> Self {
+        self.record.time = time;
+        self
+    }
+
+    /// Sets the sequence number.
+    ///
+    /// By default, a new number is stamped when the builder is constructed,
+    /// see [`Record::sequence`]. Setting it is useful for replaying records
+    /// with their original sequence numbers.
+    #[must_use]
+    pub fn sequence(mut self, sequence: u64) -> Self {
+        self.record.sequence = sequence;
+        self
+    }
+
+    /// Attaches a backtrace.
+    #[must_use]
+    pub fn backtrace(mut self, backtrace: Backtrace) -> Self {
+        self.record.backtrace = Some(Arc::new(backtrace));
+        self
+    }
+
+    /// Sets the style overriding the level style.
+    ///
+    /// See [`Record::style`].
+    #[must_use]
+    pub fn style(mut self, style: Style) -> Self {
+        self.record.style = Some(style);
+        self
+    }
+
+    /// Sets the error code.
+    ///
+    /// See [`Record::code`].
+    #[must_use]
+    pub fn code<C>(mut self, code: C) -> Self
+    where
+        C: Into<ErrorCode>,
+    {
+        self.record.code = Some(code.into());
+        self
+    }
+
+    /// Sets the message key.
+    ///
+    /// See [`Record::message_key`].
+    #[must_use]
+    pub fn message_key(mut self, message_key: &'a str) -> Self {
+        self.record.message_key = Some(message_key);
+        self
+    }
+
+    /// Sets the flags.
+    ///
+    /// See [`Record::flags`].
+    #[must_use]
+    pub fn flags(mut self, flags: RecordFlags) -> Self {
+        self.record.flags = flags;
+        self
+    }
+
     /// Builds a [`Record`].
     pub fn build(self) -> Record<'a> {
         self.record
     }
 }
+
+/// An owned version of [`Record`].
+///
+/// It can be constructed by [`Record::to_owned`], and converted back to a
+/// [`Record`] by [`RecordOwned::as_record`].
+#[derive(Clone, Debug)]
+pub struct RecordOwned {
+    logger_name: Option<LoggerName>,
+    level: Level,
+    payload: String,
+    payload_bytes: Option<Vec<u8>>,
+    source_location: Option<SourceLocation>,
+    target: Option<String>,
+    fields: Vec<Field<'static>>,
+    time: SystemTime,
+    sequence: u64,
+    backtrace: Option<Arc<Backtrace>>,
+    style: Option<Style>,
+    code: Option<ErrorCode>,
+    message_key: Option<String>,
+    flags: RecordFlags,
+}
+
+impl RecordOwned {
+    /// Gets a [`Record`] that borrows from this `RecordOwned`.
+    pub fn as_record(&self) -> Record<'_> {
+        Record {
+            logger_name: self.logger_name.as_ref().map(LoggerNameRef::Handle),
+            level: self.level,
+            payload: Cow::Borrowed(&self.payload),
+            payload_bytes: self.payload_bytes.as_deref().map(Cow::Borrowed),
+            source_location: self.source_location.clone(),
+            target: self.target.as_deref(),
+            fields: self.fields.iter().map(Field::as_borrowed).collect(),
+            time: self.time,
+            sequence: self.sequence,
+            backtrace: self.backtrace.clone(),
+            style: self.style.clone(),
+            code: self.code.clone(),
+            message_key: self.message_key.as_deref(),
+            flags: self.flags,
+        }
+    }
+
+    /// Gets the logger name.
+    pub fn logger_name(&self) -> Option<&str> {
+        self.logger_name.as_ref().map(LoggerName::as_str)
+    }
+
+    /// Gets the level.
+    pub fn level(&self) -> Level {
+        self.level
+    }
+
+    /// Gets the payload.
+    pub fn payload(&self) -> &str {
+        &self.payload
+    }
+
+    /// Gets the raw bytes of the payload.
+    pub fn payload_bytes(&self) -> Option<&[u8]> {
+        self.payload_bytes.as_deref()
+    }
+
+    /// Gets the source location.
+    pub fn source_location(&self) -> Option<&SourceLocation> {
+        self.source_location.as_ref()
+    }
+
+    /// Gets the target.
+    pub fn target(&self) -> Option<&str> {
+        self.target.as_deref()
+    }
+
+    /// Gets the fields.
+    pub fn fields(&self) -> &[Field<'static>] {
+        &self.fields
+    }
+
+    /// Gets the time of the record.
+    pub fn time(&self) -> SystemTime {
+        self.time
+    }
+
+    /// Gets the sequence number of the record.
+    pub fn sequence(&self) -> u64 {
+        self.sequence
+    }
+
+    /// Gets the backtrace captured when the record was created.
+    pub fn backtrace(&self) -> Option<&Backtrace> {
+        self.backtrace.as_deref()
+    }
+
+    /// Gets the style overriding the level style.
+    pub fn style(&self) -> Option<&Style> {
+        self.style.as_ref()
+    }
+
+    /// Gets the error code.
+    pub fn code(&self) -> Option<&ErrorCode> {
+        self.code.as_ref()
+    }
+
+    /// Gets the message key.
+    pub fn message_key(&self) -> Option<&str> {
+        self.message_key.as_deref()
+    }
+
+    /// Gets the flags.
+    pub fn flags(&self) -> RecordFlags {
+        self.flags
+    }
+}
+
+/// A set of flags marking records that are not ordinary log messages.
+///
+/// Flags are set by the crate and by middleware (e.g. sinks forwarding
+/// records), and can be inspected by sinks and formatters, instead of
+/// recognizing such records by their payloads. E.g. a sink sending alerts can
+/// skip [`RecordFlags::INTERNAL`] records, and a formatter can mark
+/// [`RecordFlags::REPLAYED`] records.
+///
+/// # Examples
+///
+/// ```
+/// use spdlog::{prelude::*, Record, RecordFlags};
+///
+/// let record = Record::builder(Level::Warn, "100 records suppressed")
+///     .flags(RecordFlags::INTERNAL | RecordFlags::SUMMARY)
+///     .build();
+/// assert!(record.flags().contains(RecordFlags::SUMMARY));
+/// assert!(!record.flags().contains(RecordFlags::REPLAYED));
+/// ```
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Default)]
+pub struct RecordFlags(u8);
+
+impl RecordFlags {
+    /// The record is logged by `spdlog-rs` itself, e.g. a report of
+    /// [`RateLimiter`] or a heartbeat of [`Logger::heartbeat_every`].
+    ///
+    /// [`RateLimiter`]: crate::RateLimiter
+    /// [`Logger::heartbeat_every`]: crate::Logger::heartbeat_every
+    pub const INTERNAL: RecordFlags = RecordFlags(1);
+
+    /// The record has been logged before and is logged again, e.g. buffered
+    /// records flushed by [`BufferUntilConfiguredSink`].
+    ///
+    /// [`BufferUntilConfiguredSink`]: crate::sink::BufferUntilConfiguredSink
+    pub const REPLAYED: RecordFlags = RecordFlags(1 << 1);
+
+    /// The record summarizes other records that are suppressed or sampled
+    /// out, e.g. the number of records suppressed by [`RateLimiter`].
+    ///
+    /// [`RateLimiter`]: crate::RateLimiter
+    pub const SUMMARY: RecordFlags = RecordFlags(1 << 2);
+
+    const NAMES: [(RecordFlags, &'static str); 3] = [
+        (RecordFlags::INTERNAL, "INTERNAL"),
+        (RecordFlags::REPLAYED, "REPLAYED"),
+        (RecordFlags::SUMMARY, "SUMMARY"),
+    ];
+
+    /// Constructs a `RecordFlags` without any flag.
+    pub const fn empty() -> RecordFlags {
+        RecordFlags(0)
+    }
+
+    /// Whether no flag is set.
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// Whether all flags in `other` are set.
+    pub const fn contains(self, other: RecordFlags) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Sets the flags in `other`.
+    pub fn insert(&mut self, other: RecordFlags) {
+        self.0 |= other.0;
+    }
+
+    /// Clears the flags in `other`.
+    pub fn remove(&mut self, other: RecordFlags) {
+        self.0 &= !other.0;
+    }
+}
+
+impl BitOr for RecordFlags {
+    type Output = RecordFlags;
+
+    fn bitor(self, rhs: RecordFlags) -> RecordFlags {
+        RecordFlags(self.0 | rhs.0)
+    }
+}
+
+impl BitOrAssign for RecordFlags {
+    fn bitor_assign(&mut self, rhs: RecordFlags) {
+        self.insert(rhs);
+    }
+}
+
+impl fmt::Debug for RecordFlags {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut names = RecordFlags::NAMES
+            .iter()
+            .filter(|(flag, _)| self.contains(*flag))
+            .map(|(_, name)| *name);
+        match names.next() {
+            Some(first) => {
+                f.write_str(first)?;
+                names.try_for_each(|name| write!(f, " | {}", name))
+            }
+            None => f.write_str("(empty)"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::{mem, time::Duration};
+
+    #[test]
+    fn builder() {
+        let time = SystemTime::UNIX_EPOCH + Duration::from_secs(1_600_000_000);
+        let record = Record::builder(Level::Info, "payload")
+            .level(Level::Warn)
+            .payload(String::from("replaced"))
+            .logger_name("logger")
+            .target("target")
+            .field("key1", "value1")
+            .fields([Field::new("key2", String::from("value2"))])
+            .time(time)
+            .sequence(42)
+            .code("E1042")
+            .message_key("key")
+            .flags(RecordFlags::REPLAYED)
+            .build();
+
+        let owned = record.to_owned();
+        for record in [record, owned.as_record()] {
+            assert_eq!(record.level(), Level::Warn);
+            assert_eq!(record.payload(), "replaced");
+            assert_eq!(record.logger_name(), Some("logger"));
+            assert_eq!(record.target(), Some("target"));
+            assert_eq!(
+                record
+                    .fields()
+                    .iter()
+                    .map(|field| (field.key(), field.value().as_str().unwrap()))
+                    .collect::<Vec<_>>(),
+                vec![("key1", "value1"), ("key2", "value2")]
+            );
+            assert_eq!(record.time(), time);
+            assert_eq!(record.sequence(), 42);
+            assert_eq!(record.code(), Some(&ErrorCode::from("E1042")));
+            assert_eq!(record.message_key(), Some("key"));
+            assert_eq!(record.flags(), RecordFlags::REPLAYED);
+        }
+    }
+
+    #[test]
+    fn flags() {
+        let mut flags = RecordFlags::INTERNAL | RecordFlags::SUMMARY;
+        assert!(flags.contains(RecordFlags::INTERNAL));
+        assert!(!flags.contains(RecordFlags::INTERNAL | RecordFlags::REPLAYED));
+        assert_eq!(format!("{:?}", flags), "INTERNAL | SUMMARY");
+
+        flags.remove(RecordFlags::INTERNAL | RecordFlags::SUMMARY);
+        assert!(flags.is_empty());
+        assert_eq!(format!("{:?}", flags), "(empty)");
+
+        flags |= RecordFlags::REPLAYED;
+        assert_eq!(flags, RecordFlags::REPLAYED);
+        assert!(Record::new(Level::Info, "payload").flags().is_empty());
+    }
+
+    #[test]
+    fn logger_name_handle() {
+        let name = LoggerName::new("logger");
+        let record = Record::builder(Level::Info, "payload")
+            .logger_name_handle(&name)
+            .build();
+        let owned = record.to_owned();
+        for record in [record, owned.as_record()] {
+            assert_eq!(record.logger_name(), Some("logger"));
+            assert_eq!(record.logger_name_handle(), Some(&name));
+        }
+
+        let record = Record::builder(Level::Info, "payload")
+            .logger_name("logger")
+            .build();
+        assert_eq!(record.logger_name_handle(), None);
+        assert_eq!(
+            record.to_owned().as_record().logger_name_handle(),
+            Some(&name)
+        );
+    }
+
+    #[test]
+    fn inline_fields() {
+        let mut builder = Record::builder(Level::Info, "payload");
+        for index in 0..INLINE_FIELDS {
+            builder = builder.field("key", index);
+        }
+        let record = builder.build();
+        assert!(!record.fields.spilled());
+
+        let owned = record.to_owned();
+        assert!(!owned.as_record().fields.spilled());
+
+        let mut builder = Record::builder(Level::Info, "payload");
+        for index in 0..=INLINE_FIELDS {
+            builder = builder.field("key", index);
+        }
+        assert!(builder.build().fields.spilled());
+    }
+
+    #[test]
+    fn record_size() {
+        // Records are moved and copied on every log call, inline fields must not
+        // bloat the records without fields.
+        assert!(
+            mem::size_of::<Record>()
+                <= mem::size_of::<RecordOwned>() + INLINE_FIELDS * mem::size_of::<Field>()
+        );
+        #[cfg(target_pointer_width = "64")]
+        assert!(mem::size_of::<Record>() <= 512);
+    }
+
+    #[test]
+    fn builder_bytes() {
+        let record = Record::builder_bytes(Level::Info, &b"frame\xff"[..]).build();
+        let owned = record.to_owned();
+        for record in [record, owned.as_record()] {
+            assert_eq!(record.payload(), "frame\u{fffd}");
+            assert_eq!(record.payload_bytes(), Some(&b"frame\xff"[..]));
+        }
+    }
+
+    #[test]
+    fn sequence() {
+        let records = (0..3)
+            .map(|_| Record::new(Level::Info, ""))
+            .collect::<Vec<_>>();
+        assert!(records[0].sequence() < records[1].sequence());
+        assert!(records[1].sequence() < records[2].sequence());
+    }
+}
@@ -0,0 +1,560 @@
+//! Provides a registry of sink and formatter types, to construct them from
+//! configuration.
+//!
+//! Each type is registered with a name and a constructor taking [`Params`],
+//! the parameters of one sink or formatter in a configuration. Built-in types
+//! are registered by default, and user-defined types can be registered by
+//! [`register_sink`] and [`register_formatter`], so that they are configurable
+//! in the same way.
+//!
+//! The registry does not depend on a configuration format. A loader of
+//! TOML, YAML or any other format converts the table of a sink to [`Params`],
+//! with scalar values as strings, and calls [`build_sink`] with the type name.
+//!
+//! # Built-in types
+//!
+//! Parameters applied to all sinks by [`build_sink`]:
+//!
+//!  - `level`: level filter, e.g. `debug`, `all` or `off`.
+//!  - `name`: the name of the sink, see [`Sink::name`].
+//!  - `formatter`: the type name of the formatter, parameters prefixed with
+//!    `formatter.` are passed to it.
+//!
+//! Sinks:
+//!
+//!  - `std_stream`: [`StdStreamSink`], with `stream` (`stdout` or `stderr`,
+//!    required) and `style_mode` (`always`, `auto` or `never`).
+//!  - `file`: [`FileSink`], with `path` (required) and `truncate`.
+//!  - `rotating_file`: [`RotatingFileSink`], with `path` (required), `policy`
+//!    (`file_size`, `daily` or `hourly`, required), `max_size` (required for
+//!    `file_size`), `hour`, `minute`, `max_files`, `rotate_on_open` and
+//!    `date_directories`.
+//!
+//! Formatters:
+//!
+//!  - `full`: [`FullFormatter`], with `elapsed` and `delta`.
+//!  - `json`, `ecs`, `common_log` and `combined_log`: [`JsonFormatter`],
+//!    [`EcsFormatter`], [`CommonLogFormatter`] and [`CombinedLogFormatter`],
+//!    without parameters.
+//!  - With the crate feature `formatters-extra`, `compact`, `pretty`,
+//!    `key_highlight` and `rfc3339`, without parameters.
+//!
+//! # Examples
+//!
+//! ```
+//! use std::sync::Arc;
+//!
+//! use spdlog::{
+//!     prelude::*,
+//!     registry::{self, Params},
+//!     sink::{Sink, StdStream, StdStreamSink},
+//!     terminal_style::StyleMode,
+//! };
+//!
+//! # fn main() -> Result<(), spdlog::Error> {
+//! // A user-defined sink type.
+//! registry::register_sink("app_console", |params: &Params| {
+//!     let stream = match params.get("stream") {
+//!         Some("stderr") => StdStream::Stderr,
+//!         _ => StdStream::Stdout,
+//!     };
+//!     Ok(Arc::new(StdStreamSink::new(stream, StyleMode::Never)) as Arc<dyn Sink>)
+//! });
+//!
+//! // Parameters of the sink, e.g. from a `[[sinks]]` table of a TOML file.
+//! let params = Params::new()
+//!     .with("stream", "stderr")
+//!     .with("level", "warn")
+//!     .with("formatter", "full")
+//!     .with("formatter.elapsed", "true");
+//! let sink = registry::build_sink("app_console", &params)?;
+//! assert!(!sink.should_log(Level::Info));
+//! # Ok(()) }
+//! ```
+//!
+//! [`Sink::name`]: crate::sink::Sink::name
+
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, BTreeSet, HashMap},
+    fmt,
+    path::PathBuf,
+    str::FromStr,
+    sync::Arc,
+};
+
+use once_cell::sync::Lazy;
+
+use crate::{
+    formatter::{
+        CombinedLogFormatter, CommonLogFormatter, EcsFormatter, Formatter, FullFormatter,
+        JsonFormatter,
+    },
+    sink::{FileSink, RotatingFileSink, RotationPolicy, Sink, StdStream, StdStreamSink},
+    terminal_style::StyleMode,
+    Error, InvalidArgumentError, LevelFilter, Result,
+};
+
+/// The constructor of a registered sink type.
+pub type SinkConstructor = dyn Fn(&Params) -> Result<Arc<dyn Sink>> + Send + Sync;
+
+/// The constructor of a registered formatter type.
+pub type FormatterConstructor = dyn Fn(&Params) -> Result<Box<dyn Formatter>> + Send + Sync;
+
+#[derive(Default)]
+struct Registry {
+    sinks: HashMap<String, Arc<SinkConstructor>>,
+    formatters: HashMap<String, Arc<FormatterConstructor>>,
+}
+
+static REGISTRY: Lazy<spin::RwLock<Registry>> = Lazy::new(|| {
+    let mut registry = Registry::default();
+    register_builtins(&mut registry);
+    spin::RwLock::new(registry)
+});
+
+/// Parameters of a sink or formatter in a configuration.
+///
+/// Values are strings, converted by constructors with [`Params::parse`].
+/// Parameters not read by the constructor are rejected by [`build_sink`] and
+/// [`build_formatter`], so that misspelled parameters are not silently
+/// ignored.
+#[derive(Clone, Debug, Default)]
+pub struct Params {
+    entries: BTreeMap<String, String>,
+    used: RefCell<BTreeSet<String>>,
+}
+
+impl Params {
+    /// Constructs empty `Params`.
+    pub fn new() -> Params {
+        Params::default()
+    }
+
+    /// Adds a parameter, replacing the previous value of the key.
+    #[must_use]
+    pub fn with<K, V>(mut self, key: K, value: V) -> Self
+    where
+        K: Into<String>,
+        V: Into<String>,
+    {
+        self.insert(key, value);
+        self
+    }
+
+    /// Adds a parameter, replacing the previous value of the key.
+    pub fn insert<K, V>(&mut self, key: K, value: V)
+    where
+        K: Into<String>,
+        V: Into<String>,
+    {
+        self.entries.insert(key.into(), value.into());
+    }
+
+    /// Gets the value of a parameter.
+    pub fn get(&self, key: &str) -> Option<&str> {
+        let value = self.entries.get(key)?;
+        self.used.borrow_mut().insert(key.to_string());
+        Some(value)
+    }
+
+    /// Gets the value of a required parameter.
+    ///
+    /// # Errors
+    ///
+    /// If the parameter is missing, [`Error::InvalidArgument`] will be
+    /// returned.
+    pub fn require(&self, key: &str) -> Result<&str> {
+        self.get(key)
+            .ok_or_else(|| config_error(format!("missing parameter '{}'", key)))
+    }
+
+    /// Parses the value of a parameter, or returns `None` if it is missing.
+    ///
+    /// # Errors
+    ///
+    /// If the value cannot be parsed, [`Error::InvalidArgument`] will be
+    /// returned.
+    pub fn parse<T>(&self, key: &str) -> Result<Option<T>>
+    where
+        T: FromStr,
+        T::Err: fmt::Display,
+    {
+        self.get(key)
+            .map(|value| {
+                value.parse().map_err(|err| {
+                    config_error(format!(
+                        "invalid value '{}' of parameter '{}': {}",
+                        value, key, err
+                    ))
+                })
+            })
+            .transpose()
+    }
+
+    /// Gets the parameters with keys starting with `prefix` and a dot, with the
+    /// prefix and the dot removed from the keys.
+    pub fn prefixed(&self, prefix: &str) -> Params {
+        let prefix = format!("{}.", prefix);
+        let mut params = Params::new();
+        for (key, value) in &self.entries {
+            if let Some(stripped) = key.strip_prefix(&prefix) {
+                self.used.borrow_mut().insert(key.clone());
+                params.insert(stripped, value.clone());
+            }
+        }
+        params
+    }
+
+    /// Gets an iterator over the keys of the parameters.
+    pub fn keys(&self) -> impl Iterator<Item = &str> {
+        self.entries.keys().map(String::as_str)
+    }
+
+    fn check_unused(&self) -> Result<()> {
+        let used = self.used.borrow();
+        match self.keys().find(|key| !used.contains(*key)) {
+            Some(key) => Err(config_error(format!("unknown parameter '{}'", key))),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Registers a sink type, replacing the previous constructor of the name.
+///
+/// Parameters applied to all sinks (see the [module level
+/// documentation](self)) are handled by [`build_sink`] and not passed to the
+/// constructor.
+pub fn register_sink<S, F>(name: S, constructor: F)
+where
+    S: Into<String>,
+    F: Fn(&Params) -> Result<Arc<dyn Sink>> + Send + Sync + 'static,
+{
+    REGISTRY
+        .write()
+        .sinks
+        .insert(name.into(), Arc::new(constructor));
+}
+
+/// Registers a formatter type, replacing the previous constructor of the name.
+pub fn register_formatter<S, F>(name: S, constructor: F)
+where
+    S: Into<String>,
+    F: Fn(&Params) -> Result<Box<dyn Formatter>> + Send + Sync + 'static,
+{
+    REGISTRY
+        .write()
+        .formatters
+        .insert(name.into(), Arc::new(constructor));
+}
+
+/// Gets the names of registered sink types, in alphabetical order.
+pub fn sink_names() -> Vec<String> {
+    let mut names = REGISTRY.read().sinks.keys().cloned().collect::<Vec<_>>();
+    names.sort();
+    names
+}
+
+/// Gets the names of registered formatter types, in alphabetical order.
+pub fn formatter_names() -> Vec<String> {
+    let mut names = REGISTRY
+        .read()
+        .formatters
+        .keys()
+        .cloned()
+        .collect::<Vec<_>>();
+    names.sort();
+    names
+}
+
+/// Constructs a sink of a registered type from its parameters.
+///
+/// # Errors
+///
+/// If the type is not registered, a parameter is missing, invalid or unknown,
+/// [`Error::InvalidArgument`] will be returned. Errors returned by the
+/// constructor (e.g. [`Error::OpenFile`]) are returned as is.
+pub fn build_sink(name: &str, params: &Params) -> Result<Arc<dyn Sink>> {
+    // Not holding the lock while constructing, constructors may build inner
+    // sinks from the registry.
+    let constructor = REGISTRY
+        .read()
+        .sinks
+        .get(name)
+        .cloned()
+        .ok_or_else(|| config_error(format!("unknown sink type '{}'", name)))?;
+
+    let params = params.clone();
+    let level_filter = params
+        .get("level")
+        .map(|level| {
+            LevelFilter::from_str_for_env(level).ok_or_else(|| {
+                config_error(format!("invalid value '{}' of parameter 'level'", level))
+            })
+        })
+        .transpose()?;
+    let sink_name = params.get("name").map(str::to_string);
+    let formatter = params
+        .get("formatter")
+        .map(|formatter| build_formatter(formatter, &params.prefixed("formatter")))
+        .transpose()?;
+
+    let sink = constructor(&params).map_err(|err| in_type("sink", name, err))?;
+    params
+        .check_unused()
+        .map_err(|err| in_type("sink", name, err))?;
+
+    if let Some(level_filter) = level_filter {
+        sink.set_level_filter(level_filter);
+    }
+    if sink_name.is_some() {
+        sink.set_name(sink_name);
+    }
+    if let Some(formatter) = formatter {
+        sink.set_formatter(formatter);
+    }
+    Ok(sink)
+}
+
+/// Constructs a formatter of a registered type from its parameters.
+///
+/// # Errors
+///
+/// If the type is not registered, a parameter is missing, invalid or unknown,
+/// [`Error::InvalidArgument`] will be returned.
+pub fn build_formatter(name: &str, params: &Params) -> Result<Box<dyn Formatter>> {
+    let constructor = REGISTRY
+        .read()
+        .formatters
+        .get(name)
+        .cloned()
+        .ok_or_else(|| config_error(format!("unknown formatter type '{}'", name)))?;
+
+    let params = params.clone();
+    let formatter = constructor(&params).map_err(|err| in_type("formatter", name, err))?;
+    params
+        .check_unused()
+        .map_err(|err| in_type("formatter", name, err))?;
+    Ok(formatter)
+}
+
+fn config_error(reason: String) -> Error {
+    Error::InvalidArgument(InvalidArgumentError::Config(reason))
+}
+
+// Prefixes configuration errors with the type, other errors are returned as is.
+fn in_type(kind: &str, name: &str, err: Error) -> Error {
+    match err {
+        Error::InvalidArgument(InvalidArgumentError::Config(reason)) => {
+            config_error(format!("{} '{}': {}", kind, name, reason))
+        }
+        err => err,
+    }
+}
+
+fn register_builtins(registry: &mut Registry) {
+    let mut sink = |name: &str, constructor: Arc<SinkConstructor>| {
+        registry.sinks.insert(name.to_string(), constructor);
+    };
+
+    sink(
+        "std_stream",
+        Arc::new(|params| {
+            let std_stream = match params.require("stream")? {
+                "stdout" => StdStream::Stdout,
+                "stderr" => StdStream::Stderr,
+                stream => {
+                    return Err(config_error(format!(
+                        "unknown stream '{}', expected one of stdout, stderr",
+                        stream
+                    )))
+                }
+            };
+            let style_mode = match params.get("style_mode").unwrap_or("auto") {
+                "always" => StyleMode::Always,
+                "auto" => StyleMode::Auto,
+                "never" => StyleMode::Never,
+                style_mode => {
+                    return Err(config_error(format!(
+                        "unknown style mode '{}', expected one of always, auto, never",
+                        style_mode
+                    )))
+                }
+            };
+            Ok(Arc::new(StdStreamSink::new(std_stream, style_mode)))
+        }),
+    );
+    sink(
+        "file",
+        Arc::new(|params| {
+            let sink = FileSink::builder(params.require("path")?)
+                .truncate(params.parse("truncate")?.unwrap_or(false))
+                .build()?;
+            Ok(Arc::new(sink))
+        }),
+    );
+    sink(
+        "rotating_file",
+        Arc::new(|params| {
+            let path = PathBuf::from(params.require("path")?);
+            let rotation_policy = match params.require("policy")? {
+                "file_size" => RotationPolicy::FileSize(
+                    params
+                        .parse("max_size")?
+                        .ok_or_else(|| config_error("missing parameter 'max_size'".to_string()))?,
+                ),
+                "daily" => RotationPolicy::Daily {
+                    hour: params.parse("hour")?.unwrap_or(0),
+                    minute: params.parse("minute")?.unwrap_or(0),
+                },
+                "hourly" => RotationPolicy::Hourly,
+                policy => {
+                    return Err(config_error(format!(
+                        "unknown policy '{}', expected one of file_size, daily, hourly",
+                        policy
+                    )))
+                }
+            };
+            let sink = RotatingFileSink::builder(path, rotation_policy)
+                .max_files(params.parse("max_files")?.unwrap_or(0))
+                .rotate_on_open(params.parse("rotate_on_open")?.unwrap_or(false))
+                .date_directories(params.parse("date_directories")?.unwrap_or(false))
+                .build()?;
+            Ok(Arc::new(sink))
+        }),
+    );
+
+    let mut formatter = |name: &str, constructor: Arc<FormatterConstructor>| {
+        registry.formatters.insert(name.to_string(), constructor);
+    };
+
+    formatter(
+        "full",
+        Arc::new(|params| {
+            let formatter = FullFormatter::builder()
+                .elapsed(params.parse("elapsed")?.unwrap_or(false))
+                .delta(params.parse("delta")?.unwrap_or(false))
+                .build();
+            Ok(Box::new(formatter))
+        }),
+    );
+    formatter("json", Arc::new(|_| Ok(Box::new(JsonFormatter::new()))));
+    formatter("ecs", Arc::new(|_| Ok(Box::new(EcsFormatter::new()))));
+    formatter(
+        "common_log",
+        Arc::new(|_| Ok(Box::new(CommonLogFormatter::new()))),
+    );
+    formatter(
+        "combined_log",
+        Arc::new(|_| Ok(Box::new(CombinedLogFormatter::new()))),
+    );
+    #[cfg(feature = "formatters-extra")]
+    {
+        use crate::formatter::{
+            CompactFormatter, KeyHighlightFormatter, PrettyFormatter, Rfc3339Formatter,
+        };
+
+        formatter(
+            "compact",
+            Arc::new(|_| Ok(Box::new(CompactFormatter::new()))),
+        );
+        formatter("pretty", Arc::new(|_| Ok(Box::new(PrettyFormatter::new()))));
+        formatter(
+            "key_highlight",
+            Arc::new(|_| Ok(Box::new(KeyHighlightFormatter::new()))),
+        );
+        formatter(
+            "rfc3339",
+            Arc::new(|_| Ok(Box::new(Rfc3339Formatter::new()))),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{test_utils::*, Level, Record};
+
+    #[test]
+    fn builtins() {
+        let names = sink_names();
+        assert!(["file", "rotating_file", "std_stream"]
+            .iter()
+            .all(|name| names.iter().any(|n| n == name)));
+        assert!(formatter_names().iter().any(|name| name == "json"));
+
+        let path = TEST_LOGS_PATH.join("registry_file.log");
+        let params = Params::new()
+            .with("path", path.to_str().unwrap())
+            .with("truncate", "true")
+            .with("level", "warn")
+            .with("name", "audit")
+            .with("formatter", "json");
+        let sink = build_sink("file", &params).unwrap();
+        assert_eq!(sink.name().as_deref(), Some("audit"));
+        assert!(!sink.should_log(Level::Info));
+        sink.log(&Record::new(Level::Warn, "from registry"))
+            .unwrap();
+        sink.flush().unwrap();
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert!(content.contains("\"message\":\"from registry\""));
+    }
+
+    #[test]
+    fn errors() {
+        let err = |name: &str, params: Params| build_sink(name, &params).err().unwrap().to_string();
+
+        assert!(err("nonexistent", Params::new()).contains("unknown sink type 'nonexistent'"));
+        assert!(err("file", Params::new()).contains("sink 'file': missing parameter 'path'"));
+        assert!(err(
+            "std_stream",
+            Params::new()
+                .with("stream", "stdout")
+                .with("colour", "never")
+        )
+        .contains("unknown parameter 'colour'"));
+        assert!(err(
+            "rotating_file",
+            Params::new()
+                .with(
+                    "path",
+                    TEST_LOGS_PATH
+                        .join("registry_rotating.log")
+                        .to_str()
+                        .unwrap()
+                )
+                .with("policy", "file_size")
+                .with("max_size", "big")
+        )
+        .contains("invalid value 'big' of parameter 'max_size'"));
+        assert!(err(
+            "std_stream",
+            Params::new()
+                .with("stream", "stdout")
+                .with("formatter", "full")
+                .with("formatter.elapse", "true")
+        )
+        .contains("formatter 'full': unknown parameter 'elapse'"));
+    }
+
+    #[test]
+    fn user_defined() {
+        register_sink("registry_test_counter", |params| {
+            let sink = CounterSink::new();
+            if params.parse("disabled")?.unwrap_or(false) {
+                sink.set_level_filter(LevelFilter::Off);
+            }
+            Ok(Arc::new(sink) as Arc<dyn Sink>)
+        });
+        assert!(sink_names()
+            .iter()
+            .any(|name| name == "registry_test_counter"));
+
+        let sink = build_sink(
+            "registry_test_counter",
+            &Params::new().with("disabled", "true"),
+        )
+        .unwrap();
+        assert!(!sink.should_log(Level::Critical));
+    }
+}
@@ -0,0 +1,258 @@
+//! Provides the self-log channel, diagnostics of `spdlog-rs` itself.
+
+use std::{cell::Cell, mem, sync::Arc, time::SystemTime};
+
+use once_cell::sync::Lazy;
+
+use crate::{Error, Field, Level, Logger, Record, RecordFlags, TimeZone};
+
+static TARGET: Lazy<spin::RwLock<SelfLogTarget>> = Lazy::new(Default::default);
+
+thread_local! {
+    static DISPATCHING: Cell<bool> = const { Cell::new(false) };
+}
+
+/// The destination of diagnostics of `spdlog-rs` itself.
+///
+/// Besides errors that are not handled by an error handler (e.g. errors in
+/// background threads and when sinks are dropped), `spdlog-rs` reports events
+/// operators may be interested in, such as rotations of log files and records
+/// dropped by [`AsyncSink`] because its buffer is full.
+///
+/// By default, errors are written to `stderr` and other events are discarded.
+/// They can be routed to a logger instead, to get them in the same pipeline as
+/// the logs of the application.
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::Arc;
+///
+/// use spdlog::{
+///     prelude::*,
+///     sink::{StdStream, StdStreamSink},
+///     terminal_style::StyleMode,
+///     SelfLogTarget,
+/// };
+///
+/// let sink = Arc::new(StdStreamSink::new(StdStream::Stderr, StyleMode::Auto));
+/// let self_logger = Arc::new(Logger::builder().name("spdlog").sink(sink).build());
+///
+/// spdlog::set_self_log_target(SelfLogTarget::Logger(self_logger));
+/// # spdlog::set_self_log_target(SelfLogTarget::Stderr);
+/// ```
+///
+/// [`AsyncSink`]: crate::sink::AsyncSink
+#[derive(Clone, Default)]
+pub enum SelfLogTarget {
+    /// Errors are written to `stderr`, other events are discarded.
+    ///
+    /// This is the default.
+    #[default]
+    Stderr,
+    /// All diagnostics are logged to the logger.
+    ///
+    /// Records are flagged with [`RecordFlags::INTERNAL`], their targets are
+    /// the components reporting them (e.g. `RotatingFileSink`), and their
+    /// logger names are the name of the logger. Errors are logged at
+    /// [`Level::Error`], events at their own levels, subject to the level
+    /// filter of the logger.
+    ///
+    /// If logging a diagnostic causes another one (e.g. the logger writes to a
+    /// failing sink), the nested error is written to `stderr` and the nested
+    /// event is discarded, instead of recursing.
+    Logger(Arc<Logger>),
+    /// All diagnostics are discarded.
+    Off,
+}
+
+/// Sets the destination of diagnostics of `spdlog-rs` itself.
+///
+/// See [`SelfLogTarget`] for details.
+pub fn set_self_log_target(target: SelfLogTarget) {
+    swap_self_log_target(target);
+}
+
+/// Sets the destination of diagnostics of `spdlog-rs` itself, and returns the
+/// previous one.
+///
+/// See [`SelfLogTarget`] for details.
+pub fn swap_self_log_target(target: SelfLogTarget) -> SelfLogTarget {
+    mem::replace(&mut *TARGET.write(), target)
+}
+
+// Reports an error that is not handled by an error handler.
+pub(crate) fn error(from: &str, error: Error) {
+    match dispatch_target() {
+        Some(SelfLogTarget::Logger(logger)) => {
+            let payload = error.to_string();
+            dispatch(
+                &logger,
+                Level::Error,
+                from,
+                &payload,
+                &[],
+                RecordFlags::INTERNAL,
+            );
+        }
+        Some(SelfLogTarget::Off) => {}
+        Some(SelfLogTarget::Stderr) | None => write_stderr(from, &error),
+    }
+}
+
+// Reports an event, which is discarded unless the target is a logger.
+pub(crate) fn event(level: Level, from: &str, message: &str, fields: &[Field], flags: RecordFlags) {
+    if let Some(SelfLogTarget::Logger(logger)) = dispatch_target() {
+        dispatch(
+            &logger,
+            level,
+            from,
+            message,
+            fields,
+            flags | RecordFlags::INTERNAL,
+        );
+    }
+}
+
+// Returns `None` if called while dispatching a diagnostic on this thread.
+fn dispatch_target() -> Option<SelfLogTarget> {
+    if DISPATCHING.with(Cell::get) {
+        None
+    } else {
+        Some(TARGET.read().clone())
+    }
+}
+
+fn dispatch(
+    logger: &Logger,
+    level: Level,
+    from: &str,
+    message: &str,
+    fields: &[Field],
+    flags: RecordFlags,
+) {
+    struct Reset;
+
+    impl Drop for Reset {
+        fn drop(&mut self) {
+            DISPATCHING.with(|dispatching| dispatching.set(false));
+        }
+    }
+
+    if !logger.should_log(level) {
+        return;
+    }
+
+    DISPATCHING.with(|dispatching| dispatching.set(true));
+    let _reset = Reset;
+
+    let mut builder = Record::builder(level, message)
+        .target(from)
+        .fields(fields.iter().map(Field::as_borrowed))
+        .flags(flags);
+    if let Some(logger_name) = logger.name_handle() {
+        builder = builder.logger_name_handle(logger_name);
+    }
+    logger.log(&builder.build());
+}
+
+fn write_stderr(from: &str, error: &Error) {
+    let now = TimeZone::Local.wall_time(SystemTime::now());
+    let date = format!(
+        "{}-{:02}-{:02} {:02}:{:02}:{:02}.{:03}",
+        now.year,
+        now.month,
+        now.day,
+        now.hour,
+        now.minute,
+        now.second,
+        now.nanosecond / 1_000_000
+    );
+
+    eprintln!(
+        "[*** SPDLOG-RS UNHANDLED ERROR ***] [{}] [{}] {}",
+        date, from, error
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, io};
+
+    use super::*;
+    use crate::{
+        sink::{RotatingFileSink, RotationPolicy, Sink},
+        test_utils::*,
+    };
+
+    // All cases are in one test, since the target is global.
+    #[test]
+    fn route_to_logger() {
+        let sink = Arc::new(CounterSink::new());
+        let logger = Arc::new(
+            test_logger_builder()
+                .name("spdlog")
+                .sink(sink.clone())
+                .build(),
+        );
+
+        let previous = swap_self_log_target(SelfLogTarget::Logger(logger));
+        error(
+            "SelfLogTest",
+            Error::FlushBuffer(io::Error::other("broken")),
+        );
+        event(
+            Level::Info,
+            "SelfLogTest",
+            "something happened",
+            &[Field::new("count", 3)],
+            RecordFlags::SUMMARY,
+        );
+
+        let base_path = TEST_LOGS_PATH.join("self_log").join("rotating.log");
+        let _ = fs::remove_dir_all(base_path.parent().unwrap());
+        let rotating =
+            RotatingFileSink::new(&base_path, RotationPolicy::FileSize(8), 2, false).unwrap();
+        rotating.set_formatter(Box::new(NoModFormatter::new()));
+        rotating.log(&Record::new(Level::Info, "12345")).unwrap();
+        rotating.log(&Record::new(Level::Info, "67890")).unwrap();
+
+        set_self_log_target(SelfLogTarget::Off);
+        error(
+            "SelfLogTest",
+            Error::FlushBuffer(io::Error::other("discarded")),
+        );
+        set_self_log_target(previous);
+
+        // Diagnostics of other tests running in parallel may be routed too.
+        let records = sink.records();
+        let rotations = records
+            .iter()
+            .filter(|record| {
+                record.target() == Some("RotatingFileSink")
+                    && record.fields()[0].value().to_string() == base_path.display().to_string()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(rotations.len(), 1);
+        assert_eq!(rotations[0].payload(), "log file rotated");
+
+        let records = records
+            .into_iter()
+            .filter(|record| record.target() == Some("SelfLogTest"))
+            .collect::<Vec<_>>();
+        assert_eq!(records.len(), 2);
+
+        assert_eq!(records[0].level(), Level::Error);
+        assert!(records[0].payload().contains("broken"));
+        assert_eq!(records[0].logger_name(), Some("spdlog"));
+        assert_eq!(records[0].flags(), RecordFlags::INTERNAL);
+
+        assert_eq!(records[1].level(), Level::Info);
+        assert_eq!(records[1].payload(), "something happened");
+        assert_eq!(records[1].fields()[0].key(), "count");
+        assert_eq!(
+            records[1].flags(),
+            RecordFlags::INTERNAL | RecordFlags::SUMMARY
+        );
+    }
+}
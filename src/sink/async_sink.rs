@@ -0,0 +1,791 @@
+//! Provides an asynchronous sink.
+
+use std::{
+    collections::VecDeque,
+    io,
+    sync::{
+        atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering},
+        mpsc::{self, RecvTimeoutError},
+        Arc, Condvar, Mutex,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use atomic::Atomic;
+
+use crate::{
+    formatter::Formatter,
+    self_log,
+    sink::{OverflowPolicy, Sink, Sinks},
+    thread_config::{self, ThreadConfig},
+    Error, Field, InvalidArgumentError, Level, LevelFilter, Record, RecordFlags, RecordOwned,
+    Result,
+};
+
+/// A sink that writes records to its inner sinks on a background thread.
+///
+/// Logging to this sink only pushes the record into a bounded buffer, the
+/// records are then written by a dedicated thread, so that slow targets do not
+/// block the logging threads. When the buffer is full, the configured
+/// [`OverflowPolicy`] applies.
+///
+/// Records are formatted by the inner sinks, so `AsyncSink` does not use a
+/// formatter itself. [`Sink::swap_formatter`] on it does nothing and returns
+/// the given formatter directly, set the formatter of inner sinks instead.
+///
+/// Flushing only pushes a flush request into the buffer. When the sink is
+/// dropped, all buffered records are written and inner sinks are flushed
+/// before the background thread exits.
+///
+/// Errors that occur in the background thread are reported to the
+/// [self-log target] and then ignored. Records dropped by the overflow policy
+/// are reported to it as a summary once the buffer has space again.
+///
+/// A write timeout can be set by [`AsyncSinkBuilder::write_timeout`], so that
+/// a hung target (e.g. an unresponsive NFS mount) cannot block the logging
+/// threads forever under [`OverflowPolicy::Block`].
+///
+/// The background thread is named `spdlog-rs async-sink N` by default, where
+/// `N` counts the built `AsyncSink`s from 0. It can be found by
+/// [`background_threads`] and stopped by [`BackgroundThread::stop`], after
+/// which records logged to this sink are discarded.
+///
+/// [self-log target]: crate::SelfLogTarget
+/// [`background_threads`]: crate::background_threads
+/// [`BackgroundThread::stop`]: crate::BackgroundThread::stop
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::Arc;
+///
+/// use spdlog::{
+///     prelude::*,
+///     sink::{AsyncSink, FileSink, OverflowPolicy},
+/// };
+///
+/// # fn main() -> Result<(), spdlog::Error> {
+/// # let path = std::env::temp_dir().join("spdlog-rs-doctest").join("async_sink.log");
+/// let file_sink = Arc::new(FileSink::new(path, true)?);
+/// let async_sink = AsyncSink::builder()
+///     .sink(file_sink)
+///     .capacity(1024)
+///     .overflow_policy(OverflowPolicy::DropIncoming)
+///     .build()?;
+///
+/// let logger: Logger = Logger::builder().sink(Arc::new(async_sink)).build();
+/// info!(logger: logger, "written on a background thread");
+/// # Ok(()) }
+/// ```
+pub struct AsyncSink {
+    level_filter: Atomic<LevelFilter>,
+    name: spin::RwLock<Option<Arc<str>>>,
+    overflow_policy: OverflowPolicy,
+    write_timeout: Option<Duration>,
+    queue: Arc<Queue>,
+    thread: Option<thread::JoinHandle<()>>,
+}
+
+impl AsyncSink {
+    /// Constructs a [`AsyncSinkBuilder`].
+    pub fn builder() -> AsyncSinkBuilder {
+        AsyncSinkBuilder::new()
+    }
+
+    /// Gets the overflow policy.
+    pub fn overflow_policy(&self) -> OverflowPolicy {
+        self.overflow_policy
+    }
+
+    /// Gets the write timeout.
+    pub fn write_timeout(&self) -> Option<Duration> {
+        self.write_timeout
+    }
+
+    fn worker(queue: Arc<Queue>, sinks: Sinks) {
+        let flush = || {
+            sinks.iter().for_each(|sink| {
+                if let Err(err) = sink.flush() {
+                    crate::default_error_handler("AsyncSink", err);
+                }
+            })
+        };
+
+        loop {
+            match queue.pop() {
+                Task::Log(record) => {
+                    let record = record.as_record();
+                    sinks.iter().for_each(|sink| {
+                        if let Err(err) = sink.log(&record) {
+                            crate::default_error_handler("AsyncSink", err);
+                        }
+                    });
+                }
+                Task::Flush => flush(),
+                Task::Shutdown(done) => {
+                    let mut errors = sinks
+                        .iter()
+                        .filter_map(|sink| {
+                            sink.shutdown()
+                                .err()
+                                .map(|err| err.with_sink(&**sink, None, None))
+                        })
+                        .collect::<Vec<_>>();
+                    let res = match errors.len() {
+                        0 => Ok(()),
+                        1 => Err(errors.pop().unwrap()),
+                        _ => Err(Error::Multiple(errors)),
+                    };
+                    // The caller may have given up waiting.
+                    _ = done.send(res);
+                }
+                Task::Terminate => {
+                    flush();
+                    queue.close();
+                    return;
+                }
+            }
+            queue.done();
+        }
+    }
+}
+
+impl Sink for AsyncSink {
+    fn log(&self, record: &Record) -> Result<()> {
+        if !self.should_log(record.level()) {
+            return Ok(());
+        }
+
+        match self.queue.push(
+            Task::Log(record.to_owned()),
+            self.overflow_policy,
+            self.write_timeout,
+        ) {
+            // Reports dropped records once the buffer has space again.
+            Ok(true) => {
+                let dropped = self.queue.dropped.swap(0, Ordering::Relaxed);
+                if dropped > 0 {
+                    self_log::event(
+                        Level::Warn,
+                        "AsyncSink",
+                        "records dropped by overflow policy",
+                        &[Field::new("dropped", dropped)],
+                        RecordFlags::SUMMARY,
+                    );
+                }
+                Ok(())
+            }
+            Ok(false) => {
+                self.queue.dropped.fetch_add(1, Ordering::Relaxed);
+                Ok(())
+            }
+            // Reports a timeout once until the background thread makes progress.
+            Err(err) if !self.queue.timeout_reported.swap(true, Ordering::Relaxed) => Err(err),
+            Err(_) => Ok(()),
+        }
+    }
+
+    fn flush(&self) -> Result<()> {
+        self.queue.push_control(Task::Flush);
+        Ok(())
+    }
+
+    // Unlike `flush`, it waits for the background thread to write the buffered
+    // records and shut down the inner sinks, so that they are finalized when it
+    // returns.
+    fn shutdown(&self) -> Result<()> {
+        let (sender, receiver) = mpsc::channel();
+        self.queue.push_control(Task::Shutdown(sender));
+
+        // The sender is dropped without a result if the queue is closed.
+        let res = match self.write_timeout {
+            None => receiver.recv().ok(),
+            Some(write_timeout) => loop {
+                match receiver.recv_timeout(write_timeout) {
+                    Ok(res) => break Some(res),
+                    Err(RecvTimeoutError::Disconnected) => break None,
+                    Err(RecvTimeoutError::Timeout) if self.queue.is_stuck(write_timeout) => {
+                        return Err(Error::FlushBuffer(io::Error::new(
+                            io::ErrorKind::TimedOut,
+                            "background thread is stuck in writing, the sinks are not shut down",
+                        )));
+                    }
+                    Err(RecvTimeoutError::Timeout) => {}
+                }
+            },
+        };
+        res.unwrap_or(Ok(()))
+    }
+
+    fn level_filter(&self) -> LevelFilter {
+        self.level_filter.load(Ordering::Relaxed)
+    }
+
+    fn set_level_filter(&self, level_filter: LevelFilter) {
+        self.level_filter.store(level_filter, Ordering::Relaxed);
+    }
+
+    fn name(&self) -> Option<Arc<str>> {
+        self.name.read().clone()
+    }
+
+    fn set_name(&self, name: Option<String>) {
+        *self.name.write() = name.map(Arc::from);
+    }
+
+    fn swap_formatter(&self, formatter: Box<dyn Formatter>) -> Box<dyn Formatter> {
+        formatter
+    }
+}
+
+impl Drop for AsyncSink {
+    fn drop(&mut self) {
+        self.queue.push_control(Task::Terminate);
+        let thread = self.thread.take().unwrap();
+
+        if let Some(write_timeout) = self.write_timeout {
+            if !self.queue.wait_closed(write_timeout) {
+                // Detaches the background thread, it exits if the write ever returns.
+                crate::default_error_handler(
+                    "AsyncSink",
+                    Error::FlushBuffer(io::Error::new(
+                        io::ErrorKind::TimedOut,
+                        "background thread is stuck in writing, buffered records are discarded",
+                    )),
+                );
+                return;
+            }
+        }
+        thread.join().expect("AsyncSink: worker thread panicked");
+    }
+}
+
+/// The builder of [`AsyncSink`].
+#[derive(Clone)]
+pub struct AsyncSinkBuilder {
+    sinks: Sinks,
+    capacity: usize,
+    overflow_policy: OverflowPolicy,
+    write_timeout: Option<Duration>,
+    thread_config: Option<ThreadConfig>,
+}
+
+impl AsyncSinkBuilder {
+    /// Constructs a `AsyncSinkBuilder`.
+    ///
+    /// By default, the capacity is 8192 records and the overflow policy is
+    /// [`OverflowPolicy::Block`].
+    pub fn new() -> Self {
+        Self {
+            sinks: vec![],
+            capacity: 8192,
+            overflow_policy: OverflowPolicy::Block,
+            write_timeout: None,
+            thread_config: None,
+        }
+    }
+
+    /// Add a [`Sink`].
+    #[must_use]
+    pub fn sink(mut self, sink: Arc<dyn Sink>) -> Self {
+        self.sinks.push(sink);
+        self
+    }
+
+    /// Add multiple [`Sink`]s.
+    #[must_use]
+    pub fn sinks<I>(mut self, sinks: I) -> Self
+    where
+        I: IntoIterator<Item = Arc<dyn Sink>>,
+    {
+        self.sinks.extend(sinks);
+        self
+    }
+
+    /// Sets the maximum number of buffered records.
+    #[must_use]
+    pub fn capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity;
+        self
+    }
+
+    /// Sets the overflow policy.
+    #[must_use]
+    pub fn overflow_policy(mut self, overflow_policy: OverflowPolicy) -> Self {
+        self.overflow_policy = overflow_policy;
+        self
+    }
+
+    /// Sets the write timeout.
+    ///
+    /// Under [`OverflowPolicy::Block`], a logging thread waits for space in the
+    /// buffer at most until the background thread has been writing a single
+    /// record (or flushing) for the timeout. After that, the incoming record is
+    /// discarded, and an [`Error::WriteRecord`] of [`io::ErrorKind::TimedOut`]
+    /// is returned for the first discarded record until the background thread
+    /// makes progress again. Records logged while the background thread is
+    /// still stuck are discarded without waiting.
+    ///
+    /// When the sink is dropped, the background thread is detached instead of
+    /// joined if it gets stuck for the timeout.
+    ///
+    /// By default, there is no timeout.
+    #[must_use]
+    pub fn write_timeout(mut self, timeout: Duration) -> Self {
+        self.write_timeout = Some(timeout);
+        self
+    }
+
+    /// Sets the configuration of the background thread.
+    ///
+    /// By default, the configuration set by [`set_default_thread_config`] is
+    /// used.
+    ///
+    /// [`set_default_thread_config`]: crate::set_default_thread_config
+    #[must_use]
+    pub fn thread_config(mut self, thread_config: ThreadConfig) -> Self {
+        self.thread_config = Some(thread_config);
+        self
+    }
+
+    /// Builds a [`AsyncSink`] and spawns its background thread.
+    ///
+    /// # Errors
+    ///
+    /// If the capacity is zero or no sink is added, [`Error::InvalidArgument`]
+    /// will be returned.
+    pub fn build(self) -> Result<AsyncSink> {
+        if self.capacity == 0 {
+            return Err(Error::InvalidArgument(InvalidArgumentError::Capacity(
+                "expect `capacity` to be (0, usize::MAX] but 0".to_string(),
+            )));
+        }
+        if self.sinks.is_empty() {
+            return Err(Error::InvalidArgument(InvalidArgumentError::Sinks(
+                "expect at least one sink".to_string(),
+            )));
+        }
+
+        let queue = Arc::new(Queue::new(self.capacity));
+        let sinks = self.sinks;
+        let thread_config = self
+            .thread_config
+            .unwrap_or_else(thread_config::default_thread_config);
+        let name = format!(
+            "spdlog-rs async-sink {}",
+            ASYNC_SINK_COUNTER.fetch_add(1, Ordering::Relaxed)
+        );
+        let stop = {
+            let queue = queue.clone();
+            move || queue.push_control(Task::Terminate)
+        };
+
+        Ok(AsyncSink {
+            level_filter: Atomic::new(LevelFilter::All),
+            name: spin::RwLock::new(None),
+            overflow_policy: self.overflow_policy,
+            write_timeout: self.write_timeout,
+            queue: queue.clone(),
+            thread: Some(thread_config.spawn(name, stop, move || AsyncSink::worker(queue, sinks))),
+        })
+    }
+}
+
+impl Default for AsyncSinkBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Records are not boxed, to avoid an extra allocation for each record.
+#[allow(clippy::large_enum_variant)]
+enum Task {
+    Log(RecordOwned),
+    Flush,
+    Shutdown(mpsc::Sender<Result<()>>),
+    Terminate,
+}
+
+static ASYNC_SINK_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+struct Queue {
+    tasks: Mutex<VecDeque<Task>>,
+    closed: AtomicBool,
+    capacity: usize,
+    not_empty: Condvar,
+    not_full: Condvar,
+    // The time the worker popped the task it is processing.
+    busy_since: spin::Mutex<Option<Instant>>,
+    timeout_reported: AtomicBool,
+    // The number of records dropped since the last report.
+    dropped: AtomicU64,
+}
+
+impl Queue {
+    fn new(capacity: usize) -> Self {
+        Self {
+            // The capacity is a bound rather than the expected length, the
+            // queue grows on demand instead of preallocating it.
+            tasks: Mutex::new(VecDeque::new()),
+            closed: AtomicBool::new(false),
+            capacity,
+            not_empty: Condvar::new(),
+            not_full: Condvar::new(),
+            busy_since: spin::Mutex::new(None),
+            timeout_reported: AtomicBool::new(false),
+            dropped: AtomicU64::new(0),
+        }
+    }
+
+    // Returns `false` if a record was dropped, or an error if it was dropped
+    // because the write timeout was exceeded.
+    fn push(
+        &self,
+        task: Task,
+        overflow_policy: OverflowPolicy,
+        write_timeout: Option<Duration>,
+    ) -> Result<bool> {
+        let mut tasks = self.tasks.lock().unwrap();
+        let mut res = true;
+
+        if tasks.len() >= self.capacity {
+            let is_full = |tasks: &mut VecDeque<Task>| {
+                tasks.len() >= self.capacity && !self.closed.load(Ordering::Relaxed)
+            };
+            match overflow_policy {
+                OverflowPolicy::Block => match write_timeout {
+                    None => tasks = self.not_full.wait_while(tasks, is_full).unwrap(),
+                    // The timeout is measured from the start of the task the worker is
+                    // processing, so it is recomputed after each wakeup, and waiting behind
+                    // other producers while the worker makes progress does not time out.
+                    Some(write_timeout) => {
+                        while is_full(&mut tasks) {
+                            let timeout = self.busy_since.lock().map_or(write_timeout, |since| {
+                                write_timeout.saturating_sub(since.elapsed())
+                            });
+                            if timeout.is_zero() {
+                                return Err(Error::WriteRecord(io::Error::new(
+                                    io::ErrorKind::TimedOut,
+                                    "background thread is stuck in writing, the record is discarded",
+                                )));
+                            }
+                            tasks = self.not_full.wait_timeout(tasks, timeout).unwrap().0;
+                        }
+                    }
+                },
+                OverflowPolicy::DropIncoming => return Ok(false),
+                OverflowPolicy::DropOldest => {
+                    // control tasks are never dropped, if there are only control tasks, the
+                    // incoming record is dropped instead to keep the queue bounded
+                    match tasks.iter().position(|task| matches!(task, Task::Log(_))) {
+                        Some(index) => _ = tasks.remove(index),
+                        None => return Ok(false),
+                    }
+                    res = false;
+                }
+            }
+        }
+
+        if self.closed.load(Ordering::Relaxed) {
+            return Ok(false);
+        }
+
+        tasks.push_back(task);
+        self.not_empty.notify_one();
+        Ok(res)
+    }
+
+    // Control tasks are pushed regardless of the capacity.
+    fn push_control(&self, task: Task) {
+        let mut tasks = self.tasks.lock().unwrap();
+        if !self.closed.load(Ordering::Relaxed) {
+            tasks.push_back(task);
+            self.not_empty.notify_one();
+        }
+    }
+
+    // Called by the worker before it exits, tasks pushed after that are
+    // discarded.
+    fn close(&self) {
+        let mut tasks = self.tasks.lock().unwrap();
+        self.closed.store(true, Ordering::Relaxed);
+        tasks.clear();
+        self.not_full.notify_all();
+    }
+
+    fn pop(&self) -> Task {
+        let tasks = self.tasks.lock().unwrap();
+        let mut tasks = self
+            .not_empty
+            .wait_while(tasks, |tasks| tasks.is_empty())
+            .unwrap();
+
+        let task = tasks.pop_front().unwrap();
+        *self.busy_since.lock() = Some(Instant::now());
+        self.not_full.notify_one();
+        task
+    }
+
+    // Called by the worker after it processed a popped task.
+    fn done(&self) {
+        *self.busy_since.lock() = None;
+        self.timeout_reported.store(false, Ordering::Relaxed);
+    }
+
+    // Returns `true` if the worker has been processing a single task for the
+    // timeout.
+    fn is_stuck(&self, timeout: Duration) -> bool {
+        self.busy_since
+            .lock()
+            .is_some_and(|since| since.elapsed() >= timeout)
+    }
+
+    // Waits for the worker to close the queue. Returns `false` if the worker
+    // has been processing a single task for the timeout.
+    fn wait_closed(&self, timeout: Duration) -> bool {
+        let mut tasks = self.tasks.lock().unwrap();
+        loop {
+            if self.closed.load(Ordering::Relaxed) {
+                return true;
+            }
+            if self.is_stuck(timeout) {
+                return false;
+            }
+            tasks = self.not_full.wait_timeout(tasks, timeout).unwrap().0;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{prelude::*, test_utils::*};
+
+    #[test]
+    fn send_sync() {
+        assert_send::<AsyncSink>();
+        assert_sync::<AsyncSink>();
+    }
+
+    #[test]
+    fn log_and_drop() {
+        let counter_sink = Arc::new(CounterSink::new());
+        let async_sink = Arc::new(
+            AsyncSink::builder()
+                .sink(counter_sink.clone())
+                .build()
+                .unwrap(),
+        );
+        let logger = test_logger_builder().sink(async_sink.clone()).build();
+
+        for i in 0..100 {
+            info!(logger: logger, "{}", i);
+        }
+        logger.flush();
+
+        drop((logger, async_sink));
+        assert_eq!(counter_sink.log_count(), 100);
+        assert_eq!(
+            counter_sink.payloads(),
+            (0..100).map(|i| i.to_string()).collect::<Vec<_>>()
+        );
+        assert_eq!(counter_sink.flush_count(), 2);
+    }
+
+    #[test]
+    fn invalid_arguments() {
+        let counter_sink = Arc::new(CounterSink::new());
+        assert!(matches!(
+            AsyncSink::builder().sink(counter_sink).capacity(0).build(),
+            Err(Error::InvalidArgument(InvalidArgumentError::Capacity(_)))
+        ));
+        assert!(matches!(
+            AsyncSink::builder().build(),
+            Err(Error::InvalidArgument(InvalidArgumentError::Sinks(_)))
+        ));
+    }
+
+    #[test]
+    fn stop() {
+        let counter_sink = Arc::new(CounterSink::new());
+        let async_sink = Arc::new(
+            AsyncSink::builder()
+                .sink(counter_sink.clone())
+                .capacity(1)
+                .thread_config(ThreadConfig::new().name("spdlog-rs async-sink stop test"))
+                .build()
+                .unwrap(),
+        );
+        let logger = test_logger_builder().sink(async_sink.clone()).build();
+
+        let thread = crate::background_threads()
+            .into_iter()
+            .find(|thread| thread.name() == "spdlog-rs async-sink stop test")
+            .unwrap();
+
+        info!(logger: logger, "before");
+        thread.stop();
+        thread::sleep(std::time::Duration::from_millis(100));
+
+        // Records are discarded after stopping, logging does not block though the
+        // capacity is exceeded.
+        for _ in 0..3 {
+            info!(logger: logger, "after");
+        }
+
+        drop((logger, async_sink));
+        assert_eq!(counter_sink.payloads(), vec!["before"]);
+        assert_eq!(counter_sink.flush_count(), 1);
+        assert!(crate::background_threads()
+            .iter()
+            .all(|thread| thread.name() != "spdlog-rs async-sink stop test"));
+    }
+
+    #[test]
+    fn overflow_policy() {
+        let payloads = |queue: &Queue| {
+            queue
+                .tasks
+                .lock()
+                .unwrap()
+                .iter()
+                .map(|task| match task {
+                    Task::Log(record) => record.payload().to_string(),
+                    Task::Flush => "flush".to_string(),
+                    Task::Shutdown(_) => "shutdown".to_string(),
+                    Task::Terminate => "terminate".to_string(),
+                })
+                .collect::<Vec<_>>()
+        };
+        let task = |payload| Task::Log(Record::new(Level::Info, payload).to_owned());
+
+        let queue = Queue::new(2);
+        let push =
+            |queue: &Queue, task, overflow_policy| queue.push(task, overflow_policy, None).unwrap();
+        assert!(push(&queue, task("a"), OverflowPolicy::DropIncoming));
+        assert!(push(&queue, task("b"), OverflowPolicy::DropIncoming));
+        assert!(!push(&queue, task("c"), OverflowPolicy::DropIncoming));
+        assert_eq!(payloads(&queue), vec!["a", "b"]);
+
+        let queue = Queue::new(2);
+        queue.push_control(Task::Flush);
+        assert!(push(&queue, task("a"), OverflowPolicy::DropOldest));
+        assert!(!push(&queue, task("b"), OverflowPolicy::DropOldest));
+        assert!(!push(&queue, task("c"), OverflowPolicy::DropOldest));
+        assert_eq!(payloads(&queue), vec!["flush", "c"]);
+
+        let queue = Queue::new(2);
+        queue.push_control(Task::Flush);
+        queue.push_control(Task::Flush);
+        assert!(!push(&queue, task("a"), OverflowPolicy::DropOldest));
+        assert_eq!(payloads(&queue), vec!["flush", "flush"]);
+    }
+
+    #[test]
+    fn write_timeout() {
+        // Hangs on the first record for a while.
+        struct HangingSink {
+            hung: AtomicBool,
+            counter: CounterSink,
+        }
+
+        impl Sink for HangingSink {
+            fn log(&self, record: &Record) -> Result<()> {
+                if !self.hung.swap(true, Ordering::Relaxed) {
+                    thread::sleep(Duration::from_millis(300));
+                }
+                self.counter.log(record)
+            }
+
+            fn flush(&self) -> Result<()> {
+                self.counter.flush()
+            }
+
+            fn level_filter(&self) -> LevelFilter {
+                LevelFilter::All
+            }
+
+            fn set_level_filter(&self, _level_filter: LevelFilter) {}
+
+            fn swap_formatter(&self, formatter: Box<dyn Formatter>) -> Box<dyn Formatter> {
+                formatter
+            }
+        }
+
+        let hanging_sink = Arc::new(HangingSink {
+            hung: AtomicBool::new(false),
+            counter: CounterSink::new(),
+        });
+        let async_sink = AsyncSink::builder()
+            .sink(hanging_sink.clone())
+            .capacity(1)
+            .write_timeout(Duration::from_millis(50))
+            .build()
+            .unwrap();
+        let log = |payload| async_sink.log(&Record::new(Level::Info, payload));
+
+        assert!(log("a").is_ok());
+        thread::sleep(Duration::from_millis(20));
+        assert!(log("b").is_ok());
+        assert!(matches!(
+            log("c"),
+            Err(Error::WriteRecord(err)) if err.kind() == io::ErrorKind::TimedOut
+        ));
+        assert!(log("d").is_ok());
+
+        // Dropping while the background thread is still stuck would detach it.
+        thread::sleep(Duration::from_millis(400));
+        drop(async_sink);
+        assert_eq!(hanging_sink.counter.payloads(), vec!["a", "b"]);
+    }
+
+    #[test]
+    fn write_timeout_with_slow_worker() {
+        // Takes a while for each record, but never longer than the timeout.
+        struct SlowSink(CounterSink);
+
+        impl Sink for SlowSink {
+            fn log(&self, record: &Record) -> Result<()> {
+                thread::sleep(Duration::from_millis(30));
+                self.0.log(record)
+            }
+
+            fn flush(&self) -> Result<()> {
+                self.0.flush()
+            }
+
+            fn level_filter(&self) -> LevelFilter {
+                LevelFilter::All
+            }
+
+            fn set_level_filter(&self, _level_filter: LevelFilter) {}
+
+            fn swap_formatter(&self, formatter: Box<dyn Formatter>) -> Box<dyn Formatter> {
+                formatter
+            }
+        }
+
+        let slow_sink = Arc::new(SlowSink(CounterSink::new()));
+        let async_sink = AsyncSink::builder()
+            .sink(slow_sink.clone())
+            .capacity(1)
+            .write_timeout(Duration::from_millis(100))
+            .build()
+            .unwrap();
+
+        // Producers wait much longer than the timeout in total.
+        thread::scope(|scope| {
+            for _ in 0..8 {
+                scope.spawn(|| {
+                    for _ in 0..4 {
+                        async_sink.log(&Record::new(Level::Info, "record")).unwrap();
+                    }
+                });
+            }
+        });
+
+        drop(async_sink);
+        assert_eq!(slow_sink.0.log_count(), 32);
+    }
+}
@@ -0,0 +1,333 @@
+//! Provides a sink adapting asynchronous targets.
+
+use std::{
+    future::Future,
+    mem,
+    pin::{pin, Pin},
+    sync::{atomic::Ordering, Arc},
+    task::{Context, Poll, Wake, Waker},
+    thread,
+};
+
+use atomic::Atomic;
+
+use crate::{
+    formatter::{Formatter, FormatterContext, FullFormatter},
+    profiling::{self, Stage},
+    sink::Sink,
+    LevelFilter, Record, Result, StringBuf,
+};
+
+/// A target that writes records asynchronously.
+///
+/// It is the asynchronous counterpart of the write part of [`Sink`], for
+/// targets built on asynchronous clients (e.g. HTTP, database or message queue
+/// clients). Wrap it in an [`AsyncTargetSink`] to use it as a [`Sink`].
+///
+/// Implementations can use `async fn` for the methods.
+///
+/// # Examples
+///
+/// See [`AsyncTargetSink`].
+pub trait AsyncTarget: Send + Sync + 'static {
+    /// Writes a record, `formatted` is the record formatted by the formatter of
+    /// the [`AsyncTargetSink`], without a trailing line ending.
+    fn log(&self, record: &Record, formatted: &str) -> impl Future<Output = Result<()>>;
+
+    /// Flushes any buffered records.
+    ///
+    /// The default implementation does nothing.
+    fn flush(&self) -> impl Future<Output = Result<()>> {
+        async { Ok(()) }
+    }
+}
+
+type BlockOn =
+    dyn for<'a> Fn(Pin<Box<dyn Future<Output = Result<()>> + 'a>>) -> Result<()> + Send + Sync;
+
+/// A sink that writes records to an [`AsyncTarget`].
+///
+/// Records are formatted on the logging thread, and then the future returned
+/// by the target is run to completion on the same thread. By default, futures
+/// are run by a minimal built-in executor, which is enough for targets that do
+/// not depend on a specific runtime. For targets that need a runtime context
+/// (e.g. clients built on Tokio), set a custom executor via
+/// [`AsyncTargetSinkBuilder::block_on`], e.g. with a `tokio::runtime::Handle`,
+/// `|future| handle.block_on(future)`.
+///
+/// Since logging waits for the future, wrap this sink in an [`AsyncSink`] to
+/// write records on a background thread, so that slow targets do not block
+/// the logging threads.
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::Arc;
+///
+/// use spdlog::{
+///     prelude::*,
+///     sink::{AsyncSink, AsyncTarget, AsyncTargetSink},
+///     Record,
+/// };
+///
+/// # struct HttpClient;
+/// # impl HttpClient {
+/// #     async fn post(&self, _url: &str, _body: &str) -> spdlog::Result<()> { Ok(()) }
+/// # }
+/// struct HttpTarget {
+///     client: HttpClient,
+/// }
+///
+/// impl AsyncTarget for HttpTarget {
+///     async fn log(&self, _record: &Record<'_>, formatted: &str) -> spdlog::Result<()> {
+///         self.client.post("http://localhost:3100/logs", formatted).await
+///     }
+/// }
+///
+/// # fn main() -> Result<(), spdlog::Error> {
+/// let target_sink = AsyncTargetSink::new(HttpTarget { client: HttpClient });
+/// let async_sink = AsyncSink::builder().sink(Arc::new(target_sink)).build()?;
+///
+/// let logger: Logger = Logger::builder().sink(Arc::new(async_sink)).build();
+/// info!(logger: logger, "posted on a background thread");
+/// # Ok(()) }
+/// ```
+///
+/// [`AsyncSink`]: crate::sink::AsyncSink
+pub struct AsyncTargetSink<T> {
+    level_filter: Atomic<LevelFilter>,
+    name: spin::RwLock<Option<Arc<str>>>,
+    formatter: spin::RwLock<Box<dyn Formatter>>,
+    target: T,
+    block_on: Option<Box<BlockOn>>,
+}
+
+impl<T> AsyncTargetSink<T>
+where
+    T: AsyncTarget,
+{
+    /// Constructs a `AsyncTargetSink` running futures by the built-in executor.
+    pub fn new(target: T) -> AsyncTargetSink<T> {
+        AsyncTargetSink::builder(target).build()
+    }
+
+    /// Constructs a [`AsyncTargetSinkBuilder`].
+    pub fn builder(target: T) -> AsyncTargetSinkBuilder<T> {
+        AsyncTargetSinkBuilder {
+            target,
+            block_on: None,
+        }
+    }
+
+    /// Gets a reference to the target.
+    pub fn target(&self) -> &T {
+        &self.target
+    }
+
+    fn run<'a, F>(&self, future: F) -> Result<()>
+    where
+        F: Future<Output = Result<()>> + 'a,
+    {
+        match &self.block_on {
+            Some(block_on) => block_on(Box::pin(future)),
+            None => block_on(future),
+        }
+    }
+}
+
+impl<T> Sink for AsyncTargetSink<T>
+where
+    T: AsyncTarget,
+{
+    fn log(&self, record: &Record) -> Result<()> {
+        if !self.should_log(record.level()) {
+            return Ok(());
+        }
+
+        let name = self.name.read();
+        let ctx = FormatterContext::new("AsyncTargetSink").with_sink_name(name.as_deref());
+        let mut string_buf = StringBuf::new();
+        profiling::measure(Stage::Format, || {
+            self.formatter.read().format(record, &mut string_buf, &ctx)
+        })?;
+
+        self.run(self.target.log(record, &string_buf))
+    }
+
+    fn flush(&self) -> Result<()> {
+        self.run(self.target.flush())
+    }
+
+    fn level_filter(&self) -> LevelFilter {
+        self.level_filter.load(Ordering::Relaxed)
+    }
+
+    fn set_level_filter(&self, level_filter: LevelFilter) {
+        self.level_filter.store(level_filter, Ordering::Relaxed);
+    }
+
+    fn name(&self) -> Option<Arc<str>> {
+        self.name.read().clone()
+    }
+
+    fn set_name(&self, name: Option<String>) {
+        *self.name.write() = name.map(Arc::from);
+    }
+
+    fn swap_formatter(&self, mut formatter: Box<dyn Formatter>) -> Box<dyn Formatter> {
+        mem::swap(&mut *self.formatter.write(), &mut formatter);
+        formatter
+    }
+}
+
+/// The builder of [`AsyncTargetSink`].
+pub struct AsyncTargetSinkBuilder<T> {
+    target: T,
+    block_on: Option<Box<BlockOn>>,
+}
+
+impl<T> AsyncTargetSinkBuilder<T>
+where
+    T: AsyncTarget,
+{
+    /// Sets a custom executor running futures returned by the target to
+    /// completion.
+    ///
+    /// It is called on the logging thread for each record and flush. By
+    /// default, a minimal built-in executor is used.
+    #[must_use]
+    pub fn block_on<F>(mut self, block_on: F) -> Self
+    where
+        F: for<'a> Fn(Pin<Box<dyn Future<Output = Result<()>> + 'a>>) -> Result<()>
+            + Send
+            + Sync
+            + 'static,
+    {
+        self.block_on = Some(Box::new(block_on));
+        self
+    }
+
+    /// Builds a [`AsyncTargetSink`].
+    pub fn build(self) -> AsyncTargetSink<T> {
+        AsyncTargetSink {
+            level_filter: Atomic::new(LevelFilter::All),
+            name: spin::RwLock::new(None),
+            formatter: spin::RwLock::new(Box::new(FullFormatter::new())),
+            target: self.target,
+            block_on: self.block_on,
+        }
+    }
+}
+
+// Runs a future to completion on the current thread, parking the thread while
+// the future is pending.
+fn block_on<F>(future: F) -> F::Output
+where
+    F: Future,
+{
+    struct ThreadWaker(thread::Thread);
+
+    impl Wake for ThreadWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.unpark();
+        }
+    }
+
+    let mut future = pin!(future);
+    let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+    let mut context = Context::from_waker(&waker);
+    loop {
+        match future.as_mut().poll(&mut context) {
+            Poll::Ready(output) => return output,
+            Poll::Pending => thread::park(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    };
+
+    use super::*;
+    use crate::{prelude::*, test_utils::*};
+
+    // Yields once, waking itself from another thread.
+    struct YieldOnce(bool);
+
+    impl Future for YieldOnce {
+        type Output = ();
+
+        fn poll(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<()> {
+            if self.0 {
+                return Poll::Ready(());
+            }
+            self.0 = true;
+            let waker = cx.waker().clone();
+            thread::spawn(move || waker.wake());
+            Poll::Pending
+        }
+    }
+
+    #[derive(Default)]
+    struct TestTarget {
+        records: Mutex<Vec<String>>,
+        flush_count: AtomicUsize,
+    }
+
+    impl AsyncTarget for TestTarget {
+        async fn log(&self, record: &Record<'_>, formatted: &str) -> Result<()> {
+            YieldOnce(false).await;
+            assert!(formatted.contains(record.payload()));
+            self.records.lock().unwrap().push(formatted.to_string());
+            Ok(())
+        }
+
+        async fn flush(&self) -> Result<()> {
+            YieldOnce(false).await;
+            self.flush_count.fetch_add(1, Ordering::Relaxed);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn send_sync() {
+        assert_send::<AsyncTargetSink<TestTarget>>();
+        assert_sync::<AsyncTargetSink<TestTarget>>();
+    }
+
+    #[test]
+    fn log_and_flush() {
+        let block_on_count = Arc::new(AtomicUsize::new(0));
+        let sinks = [
+            Arc::new(AsyncTargetSink::new(TestTarget::default())),
+            Arc::new(
+                AsyncTargetSink::builder(TestTarget::default())
+                    .block_on({
+                        let block_on_count = block_on_count.clone();
+                        move |future| {
+                            block_on_count.fetch_add(1, Ordering::Relaxed);
+                            block_on(future)
+                        }
+                    })
+                    .build(),
+            ),
+        ];
+
+        for sink in sinks {
+            let logger = test_logger_builder().sink(sink.clone()).build();
+            info!(logger: logger, "hello");
+            warn!(logger: logger, "world");
+            logger.flush();
+
+            let records = sink.target().records.lock().unwrap();
+            assert_eq!(records.len(), 2);
+            assert!(records[0].ends_with("hello"));
+            assert!(records[1].ends_with("world"));
+            assert_eq!(sink.target().flush_count.load(Ordering::Relaxed), 1);
+        }
+        assert_eq!(block_on_count.load(Ordering::Relaxed), 3);
+    }
+}
@@ -0,0 +1,249 @@
+//! Provides a sink buffering records until the final sinks are configured.
+
+use std::{
+    collections::VecDeque,
+    mem,
+    sync::{atomic::Ordering, Arc, Mutex},
+};
+
+use atomic::Atomic;
+
+use crate::{
+    formatter::Formatter,
+    sink::{Sink, Sinks},
+    LevelFilter, Record, RecordFlags, RecordOwned, Result,
+};
+
+/// A sink that buffers records until the final sinks are configured, and then
+/// forwards records to them.
+///
+/// It is useful for records logged at startup, before logging is fully
+/// configured (e.g. before the configuration file is parsed). Instead of being
+/// lost or written to a wrong place, such records are kept in memory, and
+/// replayed into the final sinks when [`BufferUntilConfiguredSink::configure`]
+/// is called. After that, all records are forwarded to the final sinks
+/// directly.
+///
+/// At most `capacity` records are buffered, the oldest one is discarded when
+/// the buffer is full.
+///
+/// Records are formatted by the final sinks, so `BufferUntilConfiguredSink`
+/// does not use a formatter itself. [`Sink::swap_formatter`] on it does
+/// nothing and returns the given formatter directly.
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::Arc;
+///
+/// use spdlog::{
+///     prelude::*,
+///     sink::{BufferUntilConfiguredSink, Sink, StdStream, StdStreamSink},
+///     terminal_style::StyleMode,
+/// };
+///
+/// # fn main() -> Result<(), spdlog::Error> {
+/// let bootstrap = Arc::new(BufferUntilConfiguredSink::new(1024));
+/// let logger: Logger = Logger::builder().sink(bootstrap.clone()).build();
+///
+/// info!(logger: logger, "parsing the configuration file");
+///
+/// // ... parse the configuration file and build the final sinks ...
+/// let stdout: Arc<dyn Sink> = Arc::new(StdStreamSink::new(StdStream::Stdout, StyleMode::Auto));
+///
+/// // The record above is written to stdout now.
+/// bootstrap.configure([stdout])?;
+/// # Ok(()) }
+/// ```
+pub struct BufferUntilConfiguredSink {
+    level_filter: Atomic<LevelFilter>,
+    name: spin::RwLock<Option<Arc<str>>>,
+    capacity: usize,
+    state: Mutex<State>,
+}
+
+enum State {
+    Buffering(VecDeque<RecordOwned>),
+    Configured(Arc<Sinks>),
+}
+
+impl BufferUntilConfiguredSink {
+    /// Constructs a `BufferUntilConfiguredSink` buffering at most `capacity`
+    /// records.
+    pub fn new(capacity: usize) -> BufferUntilConfiguredSink {
+        BufferUntilConfiguredSink {
+            level_filter: Atomic::new(LevelFilter::All),
+            name: spin::RwLock::new(None),
+            capacity,
+            state: Mutex::new(State::Buffering(VecDeque::new())),
+        }
+    }
+
+    /// Sets the final sinks, replays buffered records into them, and forwards
+    /// all subsequent records to them.
+    ///
+    /// Calling it again replaces the final sinks, nothing is replayed in this
+    /// case.
+    ///
+    /// # Errors
+    ///
+    /// If an error occurs replaying a buffered record, the rest records are
+    /// still replayed, and the last error is returned.
+    pub fn configure<I>(&self, sinks: I) -> Result<()>
+    where
+        I: IntoIterator<Item = Arc<dyn Sink>>,
+    {
+        let sinks = Arc::new(sinks.into_iter().collect::<Sinks>());
+
+        // Hold the lock while replaying, so that records logged concurrently
+        // are not forwarded before the buffered ones.
+        let mut state = self.state.lock().unwrap();
+        let buffered = match mem::replace(&mut *state, State::Configured(sinks.clone())) {
+            State::Buffering(buffered) => buffered,
+            State::Configured(_) => return Ok(()),
+        };
+
+        let mut result = Ok(());
+        for record in buffered {
+            let mut record = record.as_record();
+            record.set_flags(record.flags() | RecordFlags::REPLAYED);
+            if let Err(err) = log_to(&sinks, &record) {
+                result = Err(err);
+            }
+        }
+        result
+    }
+
+    /// Determines whether [`BufferUntilConfiguredSink::configure`] has been
+    /// called.
+    pub fn is_configured(&self) -> bool {
+        matches!(*self.state.lock().unwrap(), State::Configured(_))
+    }
+
+    /// Gets the number of currently buffered records.
+    pub fn buffered_count(&self) -> usize {
+        match &*self.state.lock().unwrap() {
+            State::Buffering(buffered) => buffered.len(),
+            State::Configured(_) => 0,
+        }
+    }
+
+    // Does nothing while buffering.
+    fn for_each_sink<F>(&self, f: F) -> Result<()>
+    where
+        F: Fn(&dyn Sink) -> Result<()>,
+    {
+        let sinks = match &*self.state.lock().unwrap() {
+            State::Buffering(_) => return Ok(()),
+            State::Configured(sinks) => sinks.clone(),
+        };
+
+        let mut result = Ok(());
+        for sink in sinks.iter() {
+            if let Err(err) = f(&**sink) {
+                result = Err(err);
+            }
+        }
+        result
+    }
+}
+
+impl Sink for BufferUntilConfiguredSink {
+    fn log(&self, record: &Record) -> Result<()> {
+        if !self.should_log(record.level()) {
+            return Ok(());
+        }
+
+        let sinks = match &mut *self.state.lock().unwrap() {
+            State::Buffering(buffered) => {
+                if self.capacity == 0 {
+                    return Ok(());
+                }
+                if buffered.len() >= self.capacity {
+                    buffered.pop_front();
+                }
+                buffered.push_back(record.to_owned());
+                return Ok(());
+            }
+            State::Configured(sinks) => sinks.clone(),
+        };
+
+        log_to(&sinks, record)
+    }
+
+    fn flush(&self) -> Result<()> {
+        self.for_each_sink(|sink| sink.flush())
+    }
+
+    fn shutdown(&self) -> Result<()> {
+        self.for_each_sink(|sink| sink.shutdown())
+    }
+
+    fn level_filter(&self) -> LevelFilter {
+        self.level_filter.load(Ordering::Relaxed)
+    }
+
+    fn set_level_filter(&self, level_filter: LevelFilter) {
+        self.level_filter.store(level_filter, Ordering::Relaxed);
+    }
+
+    fn name(&self) -> Option<Arc<str>> {
+        self.name.read().clone()
+    }
+
+    fn set_name(&self, name: Option<String>) {
+        *self.name.write() = name.map(Arc::from);
+    }
+
+    fn swap_formatter(&self, formatter: Box<dyn Formatter>) -> Box<dyn Formatter> {
+        formatter
+    }
+}
+
+fn log_to(sinks: &Sinks, record: &Record) -> Result<()> {
+    let mut result = Ok(());
+    for sink in sinks {
+        if let Err(err) = sink.log(record) {
+            result = Err(err);
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{prelude::*, test_utils::*};
+
+    #[test]
+    fn replay() {
+        let bootstrap = Arc::new(BufferUntilConfiguredSink::new(2));
+        let logger = test_logger_builder().sink(bootstrap.clone()).build();
+
+        info!(logger: logger, "1");
+        info!(logger: logger, "2");
+        info!(logger: logger, "3");
+        assert_eq!(bootstrap.buffered_count(), 2);
+        assert!(!bootstrap.is_configured());
+
+        let final_sink = Arc::new(CounterSink::new());
+        bootstrap
+            .configure([final_sink.clone() as Arc<dyn Sink>])
+            .unwrap();
+        assert!(bootstrap.is_configured());
+        assert_eq!(final_sink.payloads(), vec!["2", "3"]);
+
+        info!(logger: logger, "4");
+        logger.flush();
+        assert_eq!(final_sink.payloads(), vec!["2", "3", "4"]);
+        assert_eq!(final_sink.flush_count(), 1);
+        assert_eq!(
+            final_sink
+                .records()
+                .iter()
+                .map(|record| record.flags().contains(RecordFlags::REPLAYED))
+                .collect::<Vec<_>>(),
+            vec![true, true, false]
+        );
+    }
+}
@@ -0,0 +1,197 @@
+//! Provides a sink writing to consoles through a callback.
+
+use std::{
+    convert::Infallible,
+    mem,
+    sync::{atomic::Ordering, Arc},
+};
+
+use atomic::Atomic;
+
+use crate::{
+    formatter::{Formatter, FormatterContext, FullFormatter},
+    profiling::{self, Stage},
+    sink::Sink,
+    terminal_style::{self, LevelStyleCodes, Style, StyleMode},
+    Level, LevelFilter, Record, Result, StringBuf,
+};
+
+type ConsoleWriter = dyn Fn(&str) + Send + Sync;
+
+/// A sink that writes records to a console through a `println`-like callback.
+///
+/// Each record is formatted into a line without a trailing line ending and
+/// passed to the callback, which is expected to print it followed by a line
+/// ending. It allows routing console output through libraries drawing on the
+/// terminal, e.g. `ProgressBar::println` or `ProgressBar::suspend` of
+/// [indicatif], so that log lines do not corrupt active progress bars.
+///
+/// Styles are rendered according to the given [`StyleMode`] as
+/// [`StdStreamSink`] does, [`StyleMode::Auto`] detects whether stderr is a
+/// terminal since progress bars are usually drawn on stderr.
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::Arc;
+///
+/// use spdlog::{prelude::*, sink::ConsoleBridgeSink, terminal_style::StyleMode};
+///
+/// # struct ProgressBar;
+/// # impl ProgressBar {
+/// #     fn println(&self, _msg: impl AsRef<str>) {}
+/// # }
+/// # let progress_bar = Arc::new(ProgressBar);
+/// let sink = ConsoleBridgeSink::new(
+///     {
+///         let progress_bar = progress_bar.clone();
+///         move |line| progress_bar.println(line)
+///     },
+///     StyleMode::Auto,
+/// );
+///
+/// let logger: Logger = Logger::builder().sink(Arc::new(sink)).build();
+/// info!(logger: logger, "printed above the progress bar");
+/// ```
+///
+/// [indicatif]: https://crates.io/crates/indicatif
+/// [`StdStreamSink`]: crate::sink::StdStreamSink
+pub struct ConsoleBridgeSink {
+    level_filter: Atomic<LevelFilter>,
+    name: spin::RwLock<Option<Arc<str>>>,
+    formatter: spin::RwLock<Box<dyn Formatter>>,
+    writer: Box<ConsoleWriter>,
+    should_render_style: bool,
+    level_style_codes: LevelStyleCodes,
+}
+
+impl ConsoleBridgeSink {
+    /// Constructs a `ConsoleBridgeSink` with the callback printing lines.
+    pub fn new<F>(writer: F, style_mode: StyleMode) -> ConsoleBridgeSink
+    where
+        F: Fn(&str) + Send + Sync + 'static,
+    {
+        ConsoleBridgeSink {
+            level_filter: Atomic::new(LevelFilter::All),
+            name: spin::RwLock::new(None),
+            formatter: spin::RwLock::new(Box::new(FullFormatter::new())),
+            writer: Box::new(writer),
+            should_render_style: terminal_style::should_render_style(
+                style_mode,
+                atty::Stream::Stderr,
+            ),
+            level_style_codes: LevelStyleCodes::default(),
+        }
+    }
+
+    /// Sets the style of the specified log level.
+    pub fn set_style(&mut self, level: Level, style: Style) {
+        self.level_style_codes.set_code(level, style);
+    }
+
+    /// Sets the style mode.
+    pub fn set_style_mode(&mut self, style_mode: StyleMode) {
+        self.should_render_style =
+            terminal_style::should_render_style(style_mode, atty::Stream::Stderr);
+    }
+}
+
+impl Sink for ConsoleBridgeSink {
+    fn log(&self, record: &Record) -> Result<()> {
+        if !self.should_log(record.level()) {
+            return Ok(());
+        }
+
+        let name = self.name.read();
+        let ctx = FormatterContext::new("ConsoleBridgeSink")
+            .with_sink_name(name.as_deref())
+            .with_style_supported(self.should_render_style);
+        let mut string_buf = StringBuf::new();
+        let extra_info = profiling::measure(Stage::Format, || {
+            self.formatter.read().format(record, &mut string_buf, &ctx)
+        })?;
+
+        let mut line = String::with_capacity(string_buf.len());
+        if self.should_render_style {
+            terminal_style::write_styled(
+                record,
+                &string_buf,
+                &extra_info,
+                &self.level_style_codes,
+                |text| {
+                    line.push_str(text);
+                    Ok::<_, Infallible>(())
+                },
+            )
+            .unwrap_or_else(|never| match never {});
+        } else {
+            line.push_str(&string_buf);
+        }
+
+        (self.writer)(&line);
+        Ok(())
+    }
+
+    fn flush(&self) -> Result<()> {
+        Ok(())
+    }
+
+    fn level_filter(&self) -> LevelFilter {
+        self.level_filter.load(Ordering::Relaxed)
+    }
+
+    fn set_level_filter(&self, level_filter: LevelFilter) {
+        self.level_filter.store(level_filter, Ordering::Relaxed);
+    }
+
+    fn name(&self) -> Option<Arc<str>> {
+        self.name.read().clone()
+    }
+
+    fn set_name(&self, name: Option<String>) {
+        *self.name.write() = name.map(Arc::from);
+    }
+
+    fn swap_formatter(&self, mut formatter: Box<dyn Formatter>) -> Box<dyn Formatter> {
+        mem::swap(&mut *self.formatter.write(), &mut formatter);
+        formatter
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use super::*;
+    use crate::{prelude::*, test_utils::*};
+
+    #[test]
+    fn log() {
+        let lines = Arc::new(Mutex::new(vec![]));
+        let mut sink = ConsoleBridgeSink::new(
+            {
+                let lines = lines.clone();
+                move |line| lines.lock().unwrap().push(line.to_string())
+            },
+            StyleMode::Never,
+        );
+        sink.swap_formatter(Box::new(NoModFormatter::new()));
+
+        let logger = test_logger_builder().sink(Arc::new(sink)).build();
+        info!(logger: logger, "hello");
+        assert_eq!(*lines.lock().unwrap(), vec!["hello"]);
+
+        sink = ConsoleBridgeSink::new(
+            {
+                let lines = lines.clone();
+                move |line| lines.lock().unwrap().push(line.to_string())
+            },
+            StyleMode::Always,
+        );
+        let logger = test_logger_builder().sink(Arc::new(sink)).build();
+        warn!(logger: logger, "world");
+        let line = lines.lock().unwrap()[1].clone();
+        assert!(line.contains("\x1b[33m\x1b[1mwarn\x1b[m"));
+        assert!(line.ends_with("world"));
+    }
+}
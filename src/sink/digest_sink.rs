@@ -0,0 +1,427 @@
+//! Provides a sink summarizing records periodically.
+
+use std::{
+    sync::{atomic::Ordering, Arc, Mutex},
+    time::{Duration, SystemTime},
+};
+
+use atomic::Atomic;
+
+use crate::{
+    formatter::Formatter, periodic_worker::PeriodicWorker, sink::Sink, Error, InvalidArgumentError,
+    Level, LevelFilter, Record, Result,
+};
+
+type DigestCallback = dyn Fn(&Digest) + Send + Sync;
+
+/// A sink that aggregates records over a time window, and passes a summary of
+/// them to a callback at the end of each window.
+///
+/// Records are grouped by their level and message signature, which is the
+/// payload with digits replaced by `#`, so that records like `timeout after
+/// 30s` and `timeout after 60s` are counted together. The callback is not
+/// called for windows without records, and is called for the last window when
+/// the sink is dropped. It is useful for batched notifications (e.g. emails or
+/// webhooks) of errors, rather than one notification per error.
+///
+/// By default, the level filter is `MoreSevereEqual(Level::Error)`.
+///
+/// `DigestSink` does not format records, so [`Sink::swap_formatter`] on it
+/// does nothing and returns the given formatter directly.
+///
+/// # Examples
+///
+/// ```
+/// use std::{sync::Arc, time::Duration};
+///
+/// use spdlog::{prelude::*, sink::DigestSink};
+///
+/// # fn main() -> Result<(), spdlog::Error> {
+/// # fn send_email(_: String) {}
+/// let sink = DigestSink::builder(|digest| {
+///     let body = digest
+///         .entries()
+///         .iter()
+///         .map(|entry| format!("{}x [{}] {}", entry.count(), entry.level(), entry.signature()))
+///         .collect::<Vec<_>>()
+///         .join("\n");
+///     send_email(body);
+/// })
+/// .window(Duration::from_secs(10 * 60))
+/// .build()?;
+///
+/// let logger: Logger = Logger::builder().sink(Arc::new(sink)).build();
+/// error!(logger: logger, "connection to 10.0.0.1 lost");
+/// # Ok(()) }
+/// ```
+pub struct DigestSink {
+    level_filter: Atomic<LevelFilter>,
+    name: spin::RwLock<Option<Arc<str>>>,
+    state: Arc<DigestState>,
+    _worker: PeriodicWorker,
+}
+
+struct DigestState {
+    callback: Box<DigestCallback>,
+    max_signatures: usize,
+    window: Mutex<Digest>,
+}
+
+/// A summary of records in a time window, passed to the callback of
+/// [`DigestSink`].
+#[derive(Clone, Debug)]
+pub struct Digest {
+    start: SystemTime,
+    end: SystemTime,
+    entries: Vec<DigestEntry>,
+    other_count: usize,
+}
+
+impl Digest {
+    fn new(start: SystemTime) -> Digest {
+        Digest {
+            start,
+            end: start,
+            entries: vec![],
+            other_count: 0,
+        }
+    }
+
+    /// Gets the start time of the window.
+    pub fn start(&self) -> SystemTime {
+        self.start
+    }
+
+    /// Gets the end time of the window.
+    pub fn end(&self) -> SystemTime {
+        self.end
+    }
+
+    /// Gets the entries, in the order of their first occurrences.
+    pub fn entries(&self) -> &[DigestEntry] {
+        &self.entries
+    }
+
+    /// Gets the number of records that are not counted in the entries because
+    /// the maximum number of signatures is exceeded.
+    ///
+    /// See [`DigestSinkBuilder::max_signatures`].
+    pub fn other_count(&self) -> usize {
+        self.other_count
+    }
+
+    /// Gets the total number of records in the window.
+    pub fn total_count(&self) -> usize {
+        self.entries.iter().map(|entry| entry.count).sum::<usize>() + self.other_count
+    }
+
+    fn is_empty(&self) -> bool {
+        self.entries.is_empty() && self.other_count == 0
+    }
+
+    fn add(&mut self, record: &Record, max_signatures: usize) {
+        let signature = signature(record.payload());
+        let time = record.time();
+
+        let existing = self
+            .entries
+            .iter_mut()
+            .find(|entry| entry.level == record.level() && entry.signature == signature);
+        if let Some(entry) = existing {
+            entry.count += 1;
+            entry.first_time = entry.first_time.min(time);
+            entry.last_time = entry.last_time.max(time);
+        } else if self.entries.len() < max_signatures {
+            self.entries.push(DigestEntry {
+                level: record.level(),
+                signature,
+                example: record.payload().to_owned(),
+                count: 1,
+                first_time: time,
+                last_time: time,
+            });
+        } else {
+            self.other_count += 1;
+        }
+    }
+}
+
+/// Records with the same level and message signature in a [`Digest`].
+#[derive(Clone, Debug)]
+pub struct DigestEntry {
+    level: Level,
+    signature: String,
+    example: String,
+    count: usize,
+    first_time: SystemTime,
+    last_time: SystemTime,
+}
+
+impl DigestEntry {
+    /// Gets the level.
+    pub fn level(&self) -> Level {
+        self.level
+    }
+
+    /// Gets the message signature, i.e. the payload with digits replaced by
+    /// `#`.
+    pub fn signature(&self) -> &str {
+        &self.signature
+    }
+
+    /// Gets the payload of the first record.
+    pub fn example(&self) -> &str {
+        &self.example
+    }
+
+    /// Gets the number of records.
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Gets the time of the first record.
+    pub fn first_time(&self) -> SystemTime {
+        self.first_time
+    }
+
+    /// Gets the time of the last record.
+    pub fn last_time(&self) -> SystemTime {
+        self.last_time
+    }
+}
+
+// Replaces each run of ASCII digits with a `#`.
+fn signature(payload: &str) -> String {
+    let mut signature = String::with_capacity(payload.len());
+    let mut in_digits = false;
+    for ch in payload.chars() {
+        if ch.is_ascii_digit() {
+            if !in_digits {
+                signature.push('#');
+            }
+            in_digits = true;
+        } else {
+            signature.push(ch);
+            in_digits = false;
+        }
+    }
+    signature
+}
+
+impl DigestSink {
+    /// Constructs a [`DigestSinkBuilder`] with the callback receiving digests.
+    ///
+    /// The callback is called on a background thread, or on the thread
+    /// dropping the sink.
+    pub fn builder<F>(callback: F) -> DigestSinkBuilder
+    where
+        F: Fn(&Digest) + Send + Sync + 'static,
+    {
+        DigestSinkBuilder {
+            callback: Box::new(callback),
+            window: Duration::from_secs(5 * 60),
+            max_signatures: 100,
+        }
+    }
+
+    /// Ends the current window immediately, and passes its digest to the
+    /// callback if there are records in it.
+    pub fn send_digest(&self) {
+        self.state.send_digest();
+    }
+}
+
+impl DigestState {
+    fn send_digest(&self) {
+        let now = SystemTime::now();
+        let mut digest = std::mem::replace(&mut *self.window.lock().unwrap(), Digest::new(now));
+        if !digest.is_empty() {
+            digest.end = now;
+            (self.callback)(&digest);
+        }
+    }
+}
+
+impl Sink for DigestSink {
+    fn log(&self, record: &Record) -> Result<()> {
+        if !self.should_log(record.level()) {
+            return Ok(());
+        }
+
+        self.state
+            .window
+            .lock()
+            .unwrap()
+            .add(record, self.state.max_signatures);
+        Ok(())
+    }
+
+    fn flush(&self) -> Result<()> {
+        Ok(())
+    }
+
+    fn level_filter(&self) -> LevelFilter {
+        self.level_filter.load(Ordering::Relaxed)
+    }
+
+    fn set_level_filter(&self, level_filter: LevelFilter) {
+        self.level_filter.store(level_filter, Ordering::Relaxed);
+    }
+
+    fn name(&self) -> Option<Arc<str>> {
+        self.name.read().clone()
+    }
+
+    fn set_name(&self, name: Option<String>) {
+        *self.name.write() = name.map(Arc::from);
+    }
+
+    fn swap_formatter(&self, formatter: Box<dyn Formatter>) -> Box<dyn Formatter> {
+        formatter
+    }
+}
+
+impl Drop for DigestSink {
+    fn drop(&mut self) {
+        self.state.send_digest();
+    }
+}
+
+/// The builder of [`DigestSink`].
+pub struct DigestSinkBuilder {
+    callback: Box<DigestCallback>,
+    window: Duration,
+    max_signatures: usize,
+}
+
+impl DigestSinkBuilder {
+    /// Sets the length of time windows.
+    ///
+    /// The default is 5 minutes.
+    #[must_use]
+    pub fn window(mut self, window: Duration) -> Self {
+        self.window = window;
+        self
+    }
+
+    /// Sets the maximum number of distinct signatures in a window.
+    ///
+    /// Records with new signatures beyond the limit are only counted in
+    /// [`Digest::other_count`]. The default is 100.
+    #[must_use]
+    pub fn max_signatures(mut self, max_signatures: usize) -> Self {
+        self.max_signatures = max_signatures;
+        self
+    }
+
+    /// Builds a [`DigestSink`].
+    ///
+    /// # Errors
+    ///
+    /// If the window is zero, [`Error::InvalidArgument`] will be returned.
+    pub fn build(self) -> Result<DigestSink> {
+        if self.window.is_zero() {
+            return Err(Error::InvalidArgument(InvalidArgumentError::Interval(
+                "expect `window` to be non-zero".to_string(),
+            )));
+        }
+
+        let state = Arc::new(DigestState {
+            callback: self.callback,
+            max_signatures: self.max_signatures,
+            window: Mutex::new(Digest::new(SystemTime::now())),
+        });
+
+        let weak = Arc::downgrade(&state);
+        let callback = move || match weak.upgrade() {
+            Some(state) => {
+                state.send_digest();
+                true
+            }
+            None => false,
+        };
+
+        Ok(DigestSink {
+            level_filter: Atomic::new(LevelFilter::MoreSevereEqual(Level::Error)),
+            name: spin::RwLock::new(None),
+            state,
+            _worker: PeriodicWorker::new(callback, self.window),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{prelude::*, test_utils::*};
+
+    #[test]
+    fn zero_window() {
+        assert!(matches!(
+            DigestSink::builder(|_| {}).window(Duration::ZERO).build(),
+            Err(Error::InvalidArgument(InvalidArgumentError::Interval(_)))
+        ));
+    }
+
+    #[test]
+    fn digest() {
+        assert_eq!(
+            signature("timeout after 30s (#12)"),
+            "timeout after #s (##)"
+        );
+
+        let digests = Arc::new(Mutex::new(vec![]));
+        let sink = Arc::new(
+            DigestSink::builder({
+                let digests = digests.clone();
+                move |digest| digests.lock().unwrap().push(digest.clone())
+            })
+            .window(Duration::from_secs(3600))
+            .max_signatures(2)
+            .build()
+            .unwrap(),
+        );
+        let logger = test_logger_builder().sink(sink.clone()).build();
+
+        sink.send_digest();
+        assert!(digests.lock().unwrap().is_empty());
+
+        error!(logger: logger, "timeout after 30s");
+        warn!(logger: logger, "ignored");
+        error!(logger: logger, "timeout after 60s");
+        critical!(logger: logger, "disk full");
+        error!(logger: logger, "other");
+        sink.send_digest();
+
+        critical!(logger: logger, "disk full");
+        drop(logger);
+        drop(sink);
+
+        let digests = digests.lock().unwrap();
+        assert_eq!(digests.len(), 2);
+
+        let entries = digests[0]
+            .entries()
+            .iter()
+            .map(|entry| {
+                (
+                    entry.level(),
+                    entry.signature(),
+                    entry.example(),
+                    entry.count(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            entries,
+            vec![
+                (Level::Error, "timeout after #s", "timeout after 30s", 2),
+                (Level::Critical, "disk full", "disk full", 1)
+            ]
+        );
+        assert_eq!(digests[0].other_count(), 1);
+        assert_eq!(digests[0].total_count(), 4);
+        assert!(digests[0].entries()[0].first_time() <= digests[0].entries()[0].last_time());
+        assert_eq!(digests[1].total_count(), 1);
+    }
+}
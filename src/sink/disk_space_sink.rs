@@ -0,0 +1,286 @@
+//! Provides a sink guarding an inner sink against filling the filesystem.
+
+use std::{
+    io,
+    path::{Path, PathBuf},
+    sync::{atomic::Ordering, Arc},
+    time::{Duration, Instant},
+};
+
+use atomic::Atomic;
+
+use crate::{formatter::Formatter, sink::Sink, Level, LevelFilter, Record, Result};
+
+/// A sink that checks the available space of a filesystem periodically, and
+/// only passes severe records to an inner sink while the space is low.
+///
+/// It is intended to wrap sinks writing files (e.g. [`RotatingFileSink`]), so
+/// that verbose logs do not fill the filesystem, which is a classic cause of
+/// outages. The available space is checked when records are logged, at most
+/// once per check interval. While it is below the threshold, only records
+/// allowed by the low space level filter (by default, [`Level::Warn`] and
+/// more severe) are passed to the inner sink.
+///
+/// A callback can be set by [`DiskSpaceSink::on_low_space`] to react when the
+/// space becomes low, e.g. to remove old log files or to raise an alert.
+///
+/// The available space is queried by `statvfs` on Unix and
+/// `GetDiskFreeSpaceExW` on Windows, it is considered sufficient if the query
+/// fails or the platform is not supported.
+///
+/// Records are formatted by the inner sink, so `DiskSpaceSink` does not use a
+/// formatter itself. [`Sink::swap_formatter`] on it does nothing and returns
+/// the given formatter directly, set the formatter of the inner sink instead.
+///
+/// # Examples
+///
+/// ```
+/// use std::{sync::Arc, time::Duration};
+///
+/// use spdlog::{
+///     prelude::*,
+///     sink::{DiskSpaceSink, RotatingFileSink, RotationPolicy},
+/// };
+///
+/// # fn main() -> Result<(), spdlog::Error> {
+/// # let dir = std::env::temp_dir().join("spdlog-rs-doctest");
+/// let file_sink = Arc::new(RotatingFileSink::new(
+///     dir.join("app.log"),
+///     RotationPolicy::Daily { hour: 0, minute: 0 },
+///     7,
+///     false,
+/// )?);
+/// let sink = DiskSpaceSink::new(file_sink, &dir, 512 * 1024 * 1024)
+///     .check_interval(Duration::from_secs(10))
+///     .on_low_space(|available| {
+///         eprintln!("only {} bytes left, dropping verbose logs", available);
+///     });
+///
+/// let logger: Logger = Logger::builder().sink(Arc::new(sink)).build();
+/// # Ok(()) }
+/// ```
+///
+/// [`RotatingFileSink`]: crate::sink::RotatingFileSink
+pub struct DiskSpaceSink {
+    level_filter: Atomic<LevelFilter>,
+    name: spin::RwLock<Option<Arc<str>>>,
+    sink: Arc<dyn Sink>,
+    path: PathBuf,
+    min_available: u64,
+    check_interval: Duration,
+    low_space_level_filter: LevelFilter,
+    on_low_space: Option<Box<dyn Fn(u64) + Send + Sync>>,
+    // (the time of the last check, whether the space was low)
+    last: spin::Mutex<Option<(Instant, bool)>>,
+}
+
+impl DiskSpaceSink {
+    /// Constructs a `DiskSpaceSink`.
+    ///
+    /// The available space is checked on the filesystem containing `path`
+    /// (e.g. the directory of log files), and is low if it is less than
+    /// `min_available` bytes. By default, it is checked every 5 seconds.
+    pub fn new<P>(sink: Arc<dyn Sink>, path: P, min_available: u64) -> DiskSpaceSink
+    where
+        P: Into<PathBuf>,
+    {
+        DiskSpaceSink {
+            level_filter: Atomic::new(LevelFilter::All),
+            name: spin::RwLock::new(None),
+            sink,
+            path: path.into(),
+            min_available,
+            check_interval: Duration::from_secs(5),
+            low_space_level_filter: LevelFilter::MoreSevereEqual(Level::Warn),
+            on_low_space: None,
+            last: spin::Mutex::new(None),
+        }
+    }
+
+    /// Sets the minimum interval between checks of the available space.
+    #[must_use]
+    pub fn check_interval(mut self, interval: Duration) -> Self {
+        self.check_interval = interval;
+        self
+    }
+
+    /// Sets the level filter of records passed to the inner sink while the
+    /// space is low.
+    ///
+    /// The default is `LevelFilter::MoreSevereEqual(Level::Warn)`, use
+    /// `LevelFilter::Off` to stop logging to the inner sink entirely.
+    #[must_use]
+    pub fn low_space_level_filter(mut self, level_filter: LevelFilter) -> Self {
+        self.low_space_level_filter = level_filter;
+        self
+    }
+
+    /// Sets a callback called with the available space in bytes when the space
+    /// becomes low.
+    #[must_use]
+    pub fn on_low_space<F>(mut self, callback: F) -> Self
+    where
+        F: Fn(u64) + Send + Sync + 'static,
+    {
+        self.on_low_space = Some(Box::new(callback));
+        self
+    }
+
+    /// Gets the inner sink.
+    pub fn sink(&self) -> &Arc<dyn Sink> {
+        &self.sink
+    }
+
+    /// Returns `true` if the space was low at the last check.
+    pub fn is_low_space(&self) -> bool {
+        matches!(*self.last.lock(), Some((_, true)))
+    }
+
+    fn check_low_space(&self) -> bool {
+        let mut last = self.last.lock();
+        match *last {
+            Some((checked_at, low)) if checked_at.elapsed() < self.check_interval => low,
+            _ => {
+                let was_low = matches!(*last, Some((_, true)));
+                let available = available_space(&self.path).ok();
+                let low = available.is_some_and(|available| available < self.min_available);
+                *last = Some((Instant::now(), low));
+                drop(last);
+
+                if low && !was_low {
+                    if let (Some(callback), Some(available)) = (&self.on_low_space, available) {
+                        callback(available);
+                    }
+                }
+                low
+            }
+        }
+    }
+}
+
+impl Sink for DiskSpaceSink {
+    fn log(&self, record: &Record) -> Result<()> {
+        if !self.should_log(record.level()) {
+            return Ok(());
+        }
+        if self.check_low_space() && !self.low_space_level_filter.compare(record.level()) {
+            return Ok(());
+        }
+        self.sink.log(record)
+    }
+
+    fn flush(&self) -> Result<()> {
+        self.sink.flush()
+    }
+
+    fn shutdown(&self) -> Result<()> {
+        self.sink.shutdown()
+    }
+
+    fn level_filter(&self) -> LevelFilter {
+        self.level_filter.load(Ordering::Relaxed)
+    }
+
+    fn set_level_filter(&self, level_filter: LevelFilter) {
+        self.level_filter.store(level_filter, Ordering::Relaxed);
+    }
+
+    fn name(&self) -> Option<Arc<str>> {
+        self.name.read().clone()
+    }
+
+    fn set_name(&self, name: Option<String>) {
+        *self.name.write() = name.map(Arc::from);
+    }
+
+    fn swap_formatter(&self, formatter: Box<dyn Formatter>) -> Box<dyn Formatter> {
+        formatter
+    }
+}
+
+#[cfg(unix)]
+fn available_space(path: &Path) -> io::Result<u64> {
+    use std::{ffi::CString, mem, os::unix::ffi::OsStrExt};
+
+    let path = CString::new(path.as_os_str().as_bytes())?;
+    // SAFETY: `statvfs` is a plain C struct, and `statvfs` only writes to it.
+    let mut stat: libc::statvfs = unsafe { mem::zeroed() };
+    if unsafe { libc::statvfs(path.as_ptr(), &mut stat) } != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(stat.f_bavail as u64 * stat.f_frsize as u64)
+}
+
+#[cfg(windows)]
+fn available_space(path: &Path) -> io::Result<u64> {
+    use std::{iter::once, mem, os::windows::ffi::OsStrExt, ptr};
+
+    use winapi::um::{fileapi::GetDiskFreeSpaceExW, winnt::ULARGE_INTEGER};
+
+    let path = path
+        .as_os_str()
+        .encode_wide()
+        .chain(once(0))
+        .collect::<Vec<_>>();
+    // SAFETY: `ULARGE_INTEGER` is a plain C union, and all-zero is a valid value.
+    let mut available: ULARGE_INTEGER = unsafe { mem::zeroed() };
+    if unsafe {
+        GetDiskFreeSpaceExW(
+            path.as_ptr(),
+            &mut available,
+            ptr::null_mut(),
+            ptr::null_mut(),
+        )
+    } == 0
+    {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(unsafe { *available.QuadPart() })
+}
+
+#[cfg(not(any(unix, windows)))]
+fn available_space(_path: &Path) -> io::Result<u64> {
+    Err(io::ErrorKind::Unsupported.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::AtomicU64;
+
+    use super::*;
+    use crate::{prelude::*, test_utils::*};
+
+    #[cfg(any(unix, windows))]
+    #[test]
+    fn query_available_space() {
+        assert!(available_space(&std::env::temp_dir()).unwrap() > 0);
+    }
+
+    #[cfg(any(unix, windows))]
+    #[test]
+    fn low_space() {
+        let counter_sink = Arc::new(CounterSink::new());
+        let low_count = Arc::new(AtomicU64::new(0));
+        let sink = DiskSpaceSink::new(counter_sink.clone(), std::env::temp_dir(), u64::MAX)
+            .check_interval(Duration::ZERO)
+            .on_low_space({
+                let low_count = low_count.clone();
+                move |_available| {
+                    low_count.fetch_add(1, Ordering::Relaxed);
+                }
+            });
+        let logger = test_logger_builder().sink(Arc::new(sink)).build();
+
+        info!(logger: logger, "a");
+        warn!(logger: logger, "b");
+        error!(logger: logger, "c");
+        assert_eq!(counter_sink.payloads(), vec!["b", "c"]);
+        assert_eq!(low_count.load(Ordering::Relaxed), 1);
+
+        let counter_sink = Arc::new(CounterSink::new());
+        let sink = DiskSpaceSink::new(counter_sink.clone(), std::env::temp_dir(), 0);
+        sink.log(&Record::new(Level::Info, "a")).unwrap();
+        assert!(!sink.is_low_space());
+        assert_eq!(counter_sink.payloads(), vec!["a"]);
+    }
+}
@@ -0,0 +1,293 @@
+//! Provides a sink falling back to a secondary sink when the primary fails.
+
+use std::{
+    sync::{atomic::Ordering, Arc},
+    time::{Duration, Instant},
+};
+
+use atomic::Atomic;
+
+use crate::{formatter::Formatter, sink::Sink, Error, LevelFilter, Record, Result};
+
+/// A sink that writes records to a primary sink, and falls back to a secondary
+/// sink while the primary is failing.
+///
+/// When the primary sink fails to log a record, the record is logged to the
+/// secondary sink instead, and the sink fails over: following records go to
+/// the secondary sink directly. The primary sink is probed with a record
+/// periodically, and records go to it again once a probe succeeds. A record
+/// failing a probe is logged to the secondary sink as well, so no record is
+/// lost as long as the secondary sink works.
+///
+/// The error of the primary sink causing a failover is returned (along with
+/// the error of the secondary sink if it also fails), so that it reaches the
+/// error handler of the logger once. Errors of failed probes are not returned.
+///
+/// A typical secondary sink is a [`StdStreamSink`] writing to stderr, so that
+/// e.g. errors are still visible when the disk of the log file is full.
+///
+/// Records are formatted by the inner sinks, so `FallbackSink` does not use a
+/// formatter itself. [`Sink::swap_formatter`] on it does nothing and returns
+/// the given formatter directly, set the formatter of inner sinks instead.
+///
+/// # Examples
+///
+/// ```
+/// use std::{sync::Arc, time::Duration};
+///
+/// use spdlog::{
+///     prelude::*,
+///     sink::{FallbackSink, FileSink, StdStream, StdStreamSink},
+///     terminal_style::StyleMode,
+/// };
+///
+/// # fn main() -> Result<(), spdlog::Error> {
+/// # let path = std::env::temp_dir().join("spdlog-rs-doctest").join("fallback.log");
+/// let file_sink = Arc::new(FileSink::new(path, false)?);
+/// let stderr_sink = Arc::new(StdStreamSink::new(StdStream::Stderr, StyleMode::Auto));
+/// let sink = FallbackSink::new(file_sink, stderr_sink).probe_interval(Duration::from_secs(30));
+///
+/// let logger: Logger = Logger::builder().sink(Arc::new(sink)).build();
+/// error!(logger: logger, "written to stderr if the file cannot be written");
+/// # Ok(()) }
+/// ```
+///
+/// [`StdStreamSink`]: crate::sink::StdStreamSink
+pub struct FallbackSink {
+    level_filter: Atomic<LevelFilter>,
+    name: spin::RwLock<Option<Arc<str>>>,
+    primary: Arc<dyn Sink>,
+    secondary: Arc<dyn Sink>,
+    probe_interval: Duration,
+    // The time of the next probe of the primary sink, or `None` if it is not
+    // failing.
+    next_probe: spin::Mutex<Option<Instant>>,
+}
+
+impl FallbackSink {
+    /// Constructs a `FallbackSink`.
+    ///
+    /// By default, the primary sink is probed every 5 seconds while it is
+    /// failing.
+    pub fn new(primary: Arc<dyn Sink>, secondary: Arc<dyn Sink>) -> FallbackSink {
+        FallbackSink {
+            level_filter: Atomic::new(LevelFilter::All),
+            name: spin::RwLock::new(None),
+            primary,
+            secondary,
+            probe_interval: Duration::from_secs(5),
+            next_probe: spin::Mutex::new(None),
+        }
+    }
+
+    /// Sets the interval between probes of the primary sink while it is
+    /// failing.
+    #[must_use]
+    pub fn probe_interval(mut self, interval: Duration) -> Self {
+        self.probe_interval = interval;
+        self
+    }
+
+    /// Gets the primary sink.
+    pub fn primary(&self) -> &Arc<dyn Sink> {
+        &self.primary
+    }
+
+    /// Gets the secondary sink.
+    pub fn secondary(&self) -> &Arc<dyn Sink> {
+        &self.secondary
+    }
+
+    /// Returns `true` if the sink has failed over to the secondary sink.
+    pub fn is_failed_over(&self) -> bool {
+        self.next_probe.lock().is_some()
+    }
+
+    fn log_secondary(&self, record: &Record) -> Result<()> {
+        self.secondary
+            .log(record)
+            .map_err(|err| err.with_sink(&*self.secondary, None, None))
+    }
+
+    // The primary sink is skipped while failed over.
+    fn for_each_sink<F>(&self, f: F) -> Result<()>
+    where
+        F: Fn(&dyn Sink) -> Result<()>,
+    {
+        let primary_result = if self.is_failed_over() {
+            Ok(())
+        } else {
+            f(&*self.primary).map_err(|err| err.with_sink(&*self.primary, None, None))
+        };
+        let secondary_result =
+            f(&*self.secondary).map_err(|err| err.with_sink(&*self.secondary, None, None));
+
+        match (primary_result, secondary_result) {
+            (Ok(()), result) | (result, Ok(())) => result,
+            (Err(primary_err), Err(secondary_err)) => {
+                Err(Error::Multiple(vec![primary_err, secondary_err]))
+            }
+        }
+    }
+}
+
+impl Sink for FallbackSink {
+    fn log(&self, record: &Record) -> Result<()> {
+        if !self.should_log(record.level()) {
+            return Ok(());
+        }
+
+        let now = Instant::now();
+        // `None` if failed over and it is not yet time to probe.
+        let failed_over = {
+            let mut next_probe = self.next_probe.lock();
+            match *next_probe {
+                None => Some(false),
+                Some(time) if now >= time => {
+                    // Postpones the next probe, so that only one thread probes at a time.
+                    *next_probe = Some(now + self.probe_interval);
+                    Some(true)
+                }
+                Some(_) => None,
+            }
+        };
+        let Some(failed_over) = failed_over else {
+            return self.log_secondary(record);
+        };
+
+        match self.primary.log(record) {
+            Ok(()) => {
+                if failed_over {
+                    *self.next_probe.lock() = None;
+                }
+                Ok(())
+            }
+            Err(err) => {
+                *self.next_probe.lock() = Some(now + self.probe_interval);
+                let secondary_result = self.log_secondary(record);
+                if failed_over {
+                    return secondary_result;
+                }
+
+                let err = err.with_sink(&*self.primary, None, None);
+                match secondary_result {
+                    Ok(()) => Err(err),
+                    Err(secondary_err) => Err(Error::Multiple(vec![err, secondary_err])),
+                }
+            }
+        }
+    }
+
+    fn flush(&self) -> Result<()> {
+        self.for_each_sink(|sink| sink.flush())
+    }
+
+    fn shutdown(&self) -> Result<()> {
+        self.for_each_sink(|sink| sink.shutdown())
+    }
+
+    fn level_filter(&self) -> LevelFilter {
+        self.level_filter.load(Ordering::Relaxed)
+    }
+
+    fn set_level_filter(&self, level_filter: LevelFilter) {
+        self.level_filter.store(level_filter, Ordering::Relaxed);
+    }
+
+    fn name(&self) -> Option<Arc<str>> {
+        self.name.read().clone()
+    }
+
+    fn set_name(&self, name: Option<String>) {
+        *self.name.write() = name.map(Arc::from);
+    }
+
+    fn swap_formatter(&self, formatter: Box<dyn Formatter>) -> Box<dyn Formatter> {
+        formatter
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{io, sync::atomic::AtomicBool};
+
+    use super::*;
+    use crate::{prelude::*, test_utils::*};
+
+    // Fails while `failing` is set, otherwise logs to the inner counter sink.
+    struct SwitchSink {
+        failing: AtomicBool,
+        counter: CounterSink,
+    }
+
+    impl Sink for SwitchSink {
+        fn log(&self, record: &Record) -> Result<()> {
+            if self.failing.load(Ordering::Relaxed) {
+                return Err(Error::WriteRecord(io::ErrorKind::Other.into()));
+            }
+            self.counter.log(record)
+        }
+
+        fn flush(&self) -> Result<()> {
+            self.counter.flush()
+        }
+
+        fn level_filter(&self) -> LevelFilter {
+            LevelFilter::All
+        }
+
+        fn set_level_filter(&self, _level_filter: LevelFilter) {}
+
+        fn swap_formatter(&self, formatter: Box<dyn Formatter>) -> Box<dyn Formatter> {
+            formatter
+        }
+    }
+
+    #[test]
+    fn fallback() {
+        let primary = Arc::new(SwitchSink {
+            failing: AtomicBool::new(false),
+            counter: CounterSink::new(),
+        });
+        let secondary = Arc::new(CounterSink::new());
+        let sink = FallbackSink::new(primary.clone(), secondary.clone())
+            .probe_interval(Duration::from_secs(3600));
+        let log = |payload| sink.log(&Record::new(Level::Error, payload));
+
+        assert!(log("a").is_ok());
+        primary.failing.store(true, Ordering::Relaxed);
+        assert!(matches!(log("b"), Err(Error::Sink(err)) if err.sink() == "SwitchSink"));
+        assert!(sink.is_failed_over());
+        primary.failing.store(false, Ordering::Relaxed);
+        assert!(log("c").is_ok());
+        assert!(sink.flush().is_ok());
+        assert_eq!(primary.counter.payloads(), vec!["a"]);
+        assert_eq!(primary.counter.flush_count(), 0);
+        assert_eq!(secondary.payloads(), vec!["b", "c"]);
+        assert_eq!(secondary.flush_count(), 1);
+
+        // Probes the primary sink on the next record.
+        *sink.next_probe.lock() = Some(Instant::now());
+        assert!(log("d").is_ok());
+        assert!(!sink.is_failed_over());
+        assert_eq!(primary.counter.payloads(), vec!["a", "d"]);
+        assert_eq!(secondary.payloads(), vec!["b", "c"]);
+    }
+
+    #[test]
+    fn failed_probe() {
+        let primary = Arc::new(SwitchSink {
+            failing: AtomicBool::new(true),
+            counter: CounterSink::new(),
+        });
+        let secondary = Arc::new(CounterSink::new());
+        let sink =
+            FallbackSink::new(primary.clone(), secondary.clone()).probe_interval(Duration::ZERO);
+
+        assert!(sink.log(&Record::new(Level::Error, "a")).is_err());
+        assert!(sink.log(&Record::new(Level::Error, "b")).is_ok());
+        assert!(sink.is_failed_over());
+        assert_eq!(primary.counter.log_count(), 0);
+        assert_eq!(secondary.payloads(), vec!["a", "b"]);
+    }
+}
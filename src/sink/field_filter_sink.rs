@@ -0,0 +1,129 @@
+//! Provides a sink filtering records by fields.
+
+use std::sync::{atomic::Ordering, Arc};
+
+use atomic::Atomic;
+
+use crate::{formatter::Formatter, sink::Sink, FieldFilter, LevelFilter, Record, Result};
+
+/// A sink that only passes records matching a [`FieldFilter`] to an inner
+/// sink.
+///
+/// Records are formatted by the inner sink, so `FieldFilterSink` does not use
+/// a formatter itself. [`Sink::swap_formatter`] on it does nothing and returns
+/// the given formatter directly, set the formatter of the inner sink instead.
+///
+/// To filter all sinks of a logger, wrap them with
+/// [`LoggerBuilder::wrap_sinks`]. To combine field filters with other kinds of
+/// filters, use [`FilterSink`] instead.
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::Arc;
+///
+/// use spdlog::{
+///     prelude::*,
+///     sink::{FieldFilterSink, FileSink},
+///     FieldFilter,
+/// };
+///
+/// # fn main() -> Result<(), spdlog::Error> {
+/// # let path = std::env::temp_dir().join("spdlog-rs-doctest").join("acme.log");
+/// let acme_sink = Arc::new(FileSink::new(path, false)?);
+/// let sink = FieldFilterSink::new(FieldFilter::equals("tenant", "acme"), acme_sink);
+///
+/// let logger: Logger = Logger::builder().sink(Arc::new(sink)).build();
+/// info!(logger: logger, tenant = "acme"; "written to the acme sink");
+/// info!(logger: logger, tenant = "other"; "discarded");
+/// # Ok(()) }
+/// ```
+///
+/// [`LoggerBuilder::wrap_sinks`]: crate::LoggerBuilder::wrap_sinks
+/// [`FilterSink`]: crate::sink::FilterSink
+pub struct FieldFilterSink {
+    level_filter: Atomic<LevelFilter>,
+    name: spin::RwLock<Option<Arc<str>>>,
+    filter: FieldFilter,
+    sink: Arc<dyn Sink>,
+}
+
+impl FieldFilterSink {
+    /// Constructs a `FieldFilterSink`.
+    pub fn new(filter: FieldFilter, sink: Arc<dyn Sink>) -> FieldFilterSink {
+        FieldFilterSink {
+            level_filter: Atomic::new(LevelFilter::All),
+            name: spin::RwLock::new(None),
+            filter,
+            sink,
+        }
+    }
+
+    /// Gets the field filter.
+    pub fn filter(&self) -> &FieldFilter {
+        &self.filter
+    }
+}
+
+impl Sink for FieldFilterSink {
+    fn log(&self, record: &Record) -> Result<()> {
+        if !self.should_log(record.level()) || !self.filter.matches(record) {
+            return Ok(());
+        }
+        self.sink.log(record)
+    }
+
+    fn flush(&self) -> Result<()> {
+        self.sink.flush()
+    }
+
+    fn shutdown(&self) -> Result<()> {
+        self.sink.shutdown()
+    }
+
+    fn level_filter(&self) -> LevelFilter {
+        self.level_filter.load(Ordering::Relaxed)
+    }
+
+    fn set_level_filter(&self, level_filter: LevelFilter) {
+        self.level_filter.store(level_filter, Ordering::Relaxed);
+    }
+
+    fn name(&self) -> Option<Arc<str>> {
+        self.name.read().clone()
+    }
+
+    fn set_name(&self, name: Option<String>) {
+        *self.name.write() = name.map(Arc::from);
+    }
+
+    fn swap_formatter(&self, formatter: Box<dyn Formatter>) -> Box<dyn Formatter> {
+        formatter
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{prelude::*, test_utils::*};
+
+    #[test]
+    fn filter() {
+        let counter_sink = Arc::new(CounterSink::new());
+        let logger = test_logger_builder()
+            .sink(counter_sink.clone())
+            .wrap_sinks(|sink| {
+                Arc::new(FieldFilterSink::new(
+                    FieldFilter::equals("tenant", "acme"),
+                    sink,
+                ))
+            })
+            .sink(counter_sink.clone())
+            .build();
+
+        info!(logger: logger, tenant = "acme"; "a");
+        info!(logger: logger, tenant = "other"; "b");
+        info!(logger: logger, "c");
+        assert_eq!(counter_sink.payloads(), vec!["a", "a", "b", "c"]);
+    }
+}
@@ -4,16 +4,25 @@ use std::{
     fs::File,
     io::{BufWriter, Write},
     mem,
-    path::Path,
-    sync::atomic::Ordering,
+    path::{Path, PathBuf},
+    ptr,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex, Weak,
+    },
+    time::{Duration, Instant},
 };
 
 use atomic::Atomic;
+use once_cell::sync::Lazy;
 
 use crate::{
-    formatter::{Formatter, FullFormatter},
-    sink::Sink,
-    utils, Error, LevelFilter, Record, Result, StringBuf,
+    formatter::{Formatter, FormatterContext, FullFormatter},
+    periodic_worker::PeriodicWorker,
+    profiling::{self, Stage},
+    sink::{LineEnding, Sink},
+    utils::{self, PathClaim},
+    Error, InvalidArgumentError, Level, LevelFilter, Record, Result, StringBuf,
 };
 
 /// A sink with a file as the target.
@@ -25,8 +34,25 @@ use crate::{
 /// [./examples]: https://github.com/SpriteOvO/spdlog-rs/tree/main/examples
 pub struct FileSink {
     level_filter: Atomic<LevelFilter>,
+    name: spin::RwLock<Option<Arc<str>>>,
     formatter: spin::RwLock<Box<dyn Formatter>>,
-    file: spin::Mutex<BufWriter<File>>,
+    inner: Arc<spin::Mutex<FileSinkInner>>,
+    path: PathBuf,
+    // the path as the destination in `FormatterContext`
+    destination: String,
+    truncate: bool,
+    footer: Option<String>,
+    footer_written: AtomicBool,
+    line_ending: LineEnding,
+    reopen_requested: AtomicBool,
+    _path_claim: PathClaim,
+}
+
+struct FileSinkInner {
+    file: Option<BufWriter<File>>,
+    // the file is truncated only when it is opened for the first time
+    opened: bool,
+    last_write: Instant,
 }
 
 impl FileSink {
@@ -43,15 +69,177 @@ impl FileSink {
     where
         P: AsRef<Path>,
     {
-        let file = utils::open_file(path, truncate)?;
+        FileSink::builder(path.as_ref()).truncate(truncate).build()
+    }
 
-        let sink = FileSink {
-            level_filter: Atomic::new(LevelFilter::All),
-            formatter: spin::RwLock::new(Box::new(FullFormatter::new())),
-            file: spin::Mutex::new(BufWriter::new(file)),
-        };
+    /// Constructs a [`FileSinkBuilder`].
+    pub fn builder<P>(path: P) -> FileSinkBuilder
+    where
+        P: Into<PathBuf>,
+    {
+        FileSinkBuilder::new(path)
+    }
 
-        Ok(sink)
+    /// Requests to close the file and open the path again before writing the
+    /// next record.
+    ///
+    /// It is used to cooperate with external log rotation tools (e.g.
+    /// `logrotate` without `copytruncate`), which rename the file and then
+    /// notify the program, usually by a signal. After the file is renamed, the
+    /// sink keeps writing to the renamed file until it is reopened, then a new
+    /// file is created at the path.
+    ///
+    /// It only sets a flag and does not block or allocate, so it is safe to be
+    /// called in signal handlers. The file is actually reopened (without
+    /// truncating) on the next write.
+    pub fn reopen(&self) {
+        self.reopen_requested.store(true, Ordering::Relaxed);
+    }
+
+    fn open(&self, inner: &mut FileSinkInner) -> Result<()> {
+        let truncate = self.truncate && !inner.opened;
+        inner.file = Some(BufWriter::new(utils::open_file(&self.path, truncate)?));
+        inner.opened = true;
+        Ok(())
+    }
+
+    // if the file has not been opened yet (lazy) or has been closed (idle),
+    // opens it before writing.
+    fn write_all(&self, bytes: &[u8]) -> Result<()> {
+        let mut inner = self.inner.lock();
+        if self.reopen_requested.swap(false, Ordering::Relaxed) && inner.file.is_some() {
+            if let Err(err) = inner.flush() {
+                crate::default_error_handler("FileSink", err);
+            }
+            inner.file = None;
+        }
+        if inner.file.is_none() {
+            self.open(&mut inner)?;
+        }
+        inner.last_write = Instant::now();
+        inner
+            .file
+            .as_mut()
+            .unwrap()
+            .write_all(bytes)
+            .map_err(Error::WriteRecord)
+    }
+
+    fn formatter_context<'a>(&'a self, name: Option<&'a str>) -> FormatterContext<'a> {
+        FormatterContext::new("FileSink")
+            .with_sink_name(name)
+            .with_destination(&self.destination)
+    }
+
+    fn write_footer(&self) -> Result<()> {
+        // do not create a file that contains only the footer
+        if !self.inner.lock().opened {
+            return Ok(());
+        }
+        // written once, by `shutdown` or on drop
+        if self.footer_written.swap(true, Ordering::Relaxed) {
+            return Ok(());
+        }
+
+        if let Some(footer) = &self.footer {
+            let record = Record::new(Level::Info, footer.as_str());
+            let name = self.name.read();
+
+            let mut string_buf = StringBuf::new();
+            self.formatter.read().format(
+                &record,
+                &mut string_buf,
+                &self.formatter_context(name.as_deref()),
+            )?;
+            self.line_ending.apply(&mut string_buf);
+
+            self.write_all(string_buf.as_bytes())?;
+        }
+        Ok(())
+    }
+
+    fn flush_file(&self) -> Result<()> {
+        self.inner.lock().flush()
+    }
+}
+
+impl FileSinkInner {
+    fn flush(&mut self) -> Result<()> {
+        match self.file.as_mut() {
+            Some(file) => file.flush().map_err(Error::FlushBuffer),
+            None => Ok(()),
+        }
+    }
+
+    fn close_if_idle(&mut self, idle_timeout: Duration) {
+        if self.file.is_some() && self.last_write.elapsed() >= idle_timeout {
+            if let Err(err) = self.flush() {
+                crate::default_error_handler("FileSink", err);
+            }
+            self.file = None;
+        }
+    }
+}
+
+// A single background thread closes idle files of all sinks with an idle
+// timeout, checking them at the shortest idle timeout among them. The thread is
+// stopped when the last of them is dropped.
+struct IdleCloser {
+    sinks: Vec<(Weak<spin::Mutex<FileSinkInner>>, Duration)>,
+    worker: Option<(PeriodicWorker, Duration)>,
+}
+
+static IDLE_CLOSER: Lazy<Mutex<IdleCloser>> = Lazy::new(|| {
+    Mutex::new(IdleCloser {
+        sinks: vec![],
+        worker: None,
+    })
+});
+
+impl IdleCloser {
+    fn register(inner: &Arc<spin::Mutex<FileSinkInner>>, idle_timeout: Duration) {
+        let mut closer = IDLE_CLOSER.lock().unwrap();
+        closer.sinks.push((Arc::downgrade(inner), idle_timeout));
+        if matches!(closer.worker, Some((_, interval)) if interval <= idle_timeout) {
+            return;
+        }
+
+        let worker = PeriodicWorker::new(Self::close_idle, idle_timeout);
+        let old_worker = closer.worker.replace((worker, idle_timeout));
+        // The old worker is joined after unlocking, since it may be waiting for the
+        // lock.
+        drop(closer);
+        drop(old_worker);
+    }
+
+    fn unregister(inner: &Arc<spin::Mutex<FileSinkInner>>) {
+        let mut closer = IDLE_CLOSER.lock().unwrap();
+        closer
+            .sinks
+            .retain(|(registered, _)| !ptr::eq(registered.as_ptr(), Arc::as_ptr(inner)));
+        if !closer.sinks.is_empty() {
+            return;
+        }
+
+        let worker = closer.worker.take();
+        // Joined after unlocking, since the worker may be waiting for the lock.
+        drop(closer);
+        drop(worker);
+    }
+
+    fn close_idle() -> bool {
+        IDLE_CLOSER
+            .lock()
+            .unwrap()
+            .sinks
+            .retain(|(inner, idle_timeout)| match inner.upgrade() {
+                Some(inner) => {
+                    inner.lock().close_if_idle(*idle_timeout);
+                    true
+                }
+                None => false,
+            });
+        true
     }
 }
 
@@ -61,19 +249,27 @@ impl Sink for FileSink {
             return Ok(());
         }
 
+        let name = self.name.read();
         let mut string_buf = StringBuf::new();
-        self.formatter.read().format(record, &mut string_buf)?;
-
-        self.file
-            .lock()
-            .write_all(string_buf.as_bytes())
-            .map_err(Error::WriteRecord)?;
+        profiling::measure(Stage::Format, || {
+            self.formatter.read().format(
+                record,
+                &mut string_buf,
+                &self.formatter_context(name.as_deref()),
+            )
+        })?;
+        self.line_ending.apply(&mut string_buf);
 
-        Ok(())
+        self.write_all(string_buf.as_bytes())
     }
 
     fn flush(&self) -> Result<()> {
-        self.file.lock().flush().map_err(Error::FlushBuffer)
+        self.flush_file()
+    }
+
+    fn shutdown(&self) -> Result<()> {
+        self.write_footer()?;
+        self.flush_file()
     }
 
     fn level_filter(&self) -> LevelFilter {
@@ -84,19 +280,477 @@ impl Sink for FileSink {
         self.level_filter.store(level_filter, Ordering::Relaxed);
     }
 
+    fn name(&self) -> Option<Arc<str>> {
+        self.name.read().clone()
+    }
+
+    fn set_name(&self, name: Option<String>) {
+        *self.name.write() = name.map(Arc::from);
+    }
+
     fn swap_formatter(&self, mut formatter: Box<dyn Formatter>) -> Box<dyn Formatter> {
         mem::swap(&mut *self.formatter.write(), &mut formatter);
         formatter
     }
+
+    fn describe(&self) -> String {
+        format!("FileSink({})", self.path.display())
+    }
 }
 
 impl Drop for FileSink {
     fn drop(&mut self) {
-        if let Err(err) = self.file.lock().flush() {
-            // Sinks do not have an error handler, because it would increase complexity and
-            // the error is not common. So currently users cannot handle this error by
-            // themselves.
-            crate::default_error_handler("FileSink", Error::FlushBuffer(err));
+        // Sinks do not have an error handler, because it would increase complexity and
+        // the error is not common. So currently users cannot handle this error by
+        // themselves.
+
+        if let Err(err) = self.write_footer() {
+            crate::default_error_handler("FileSink", err);
+        }
+
+        if let Err(err) = self.flush_file() {
+            crate::default_error_handler("FileSink", err);
+        }
+
+        // Only sinks with an idle timeout are referenced by the idle closer.
+        if Arc::weak_count(&self.inner) > 0 {
+            IdleCloser::unregister(&self.inner);
         }
     }
 }
+
+/// The builder of [`FileSink`].
+///
+/// # Examples
+///
+/// ```
+/// use spdlog::sink::FileSink;
+///
+/// # fn main() -> Result<(), spdlog::Error> {
+/// # let path = std::env::temp_dir().join("spdlog-rs-doctest").join("file_sink_builder.log");
+/// let sink: FileSink = FileSink::builder(path)
+///     .truncate(true)
+///     .footer("log file closed")
+///     .build()?;
+/// # Ok(()) }
+/// ```
+#[derive(Clone, Debug)]
+pub struct FileSinkBuilder {
+    path: PathBuf,
+    truncate: bool,
+    lazy: bool,
+    idle_timeout: Option<Duration>,
+    footer: Option<String>,
+    line_ending: LineEnding,
+    name: Option<String>,
+}
+
+impl FileSinkBuilder {
+    /// Constructs a `FileSinkBuilder`.
+    ///
+    /// By default, the file is opened when building the sink, the existing
+    /// contents of the file will be kept, no footer will be written and the
+    /// line ending is [`LineEnding::native`].
+    pub fn new<P>(path: P) -> Self
+    where
+        P: Into<PathBuf>,
+    {
+        Self {
+            path: path.into(),
+            truncate: false,
+            lazy: false,
+            idle_timeout: None,
+            footer: None,
+            line_ending: LineEnding::native(),
+            name: None,
+        }
+    }
+
+    /// Specifies whether to discard the existing contents of the file.
+    #[must_use]
+    pub fn truncate(mut self, truncate: bool) -> Self {
+        self.truncate = truncate;
+        self
+    }
+
+    /// Specifies whether to defer opening the file until the first record is
+    /// written.
+    ///
+    /// If `true`, neither the file nor its parent directories are created
+    /// when building the sink, so programs that usually log nothing do not
+    /// leave empty log files behind. Errors that occur opening the file are
+    /// returned when writing the first record instead.
+    #[must_use]
+    pub fn lazy(mut self, lazy: bool) -> Self {
+        self.lazy = lazy;
+        self
+    }
+
+    /// Sets a duration after which the file is closed if nothing is written.
+    ///
+    /// The file is reopened (without truncating) on the next write. It helps
+    /// applications writing to many files to stay within the limit of open
+    /// file descriptors. A single background thread, shared by all file sinks
+    /// with an idle timeout, checks the idle durations periodically, so the
+    /// file may stay open for up to twice the duration.
+    ///
+    /// The duration must be non-zero, see [`FileSinkBuilder::build`].
+    #[must_use]
+    pub fn idle_timeout(mut self, idle_timeout: Duration) -> Self {
+        self.idle_timeout = Some(idle_timeout);
+        self
+    }
+
+    /// Sets a footer to be written when the sink is dropped.
+    ///
+    /// The footer is formatted as an `info` level record by the current
+    /// formatter of the sink, regardless of the level filter. It is written
+    /// only on a clean shutdown, so a log file that does not end with the
+    /// footer indicates that the process did not exit normally.
+    ///
+    /// The footer is written once, by [`Logger::shutdown`] (or
+    /// [`Sink::shutdown`]) or when the sink is dropped, whichever comes first.
+    /// Sinks held by the default logger are never dropped, since it lives for
+    /// the whole process, so call [`Logger::shutdown`] on it before exiting.
+    /// If the sink is [lazy] and no record has been written, the footer will
+    /// not be written either.
+    ///
+    /// [lazy]: FileSinkBuilder::lazy
+    ///
+    /// [`Logger::shutdown`]: crate::logger::Logger::shutdown
+    #[must_use]
+    pub fn footer<S>(mut self, footer: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.footer = Some(footer.into());
+        self
+    }
+
+    /// Sets the line ending written after each record.
+    #[must_use]
+    pub fn line_ending(mut self, line_ending: LineEnding) -> Self {
+        self.line_ending = line_ending;
+        self
+    }
+
+    /// Sets the name of the sink, see [`Sink::name`].
+    #[must_use]
+    pub fn name<S>(mut self, name: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Builds a [`FileSink`].
+    ///
+    /// # Errors
+    ///
+    /// If the idle timeout is zero, or another sink is writing to the path,
+    /// [`Error::InvalidArgument`] will be returned.
+    ///
+    /// If an error occurs opening the file, [`Error::CreateDirectory`] or
+    /// [`Error::OpenFile`] will be returned. They are never returned if the
+    /// sink is lazy.
+    pub fn build(self) -> Result<FileSink> {
+        if matches!(self.idle_timeout, Some(idle_timeout) if idle_timeout.is_zero()) {
+            return Err(Error::InvalidArgument(InvalidArgumentError::Interval(
+                "expect `idle_timeout` to be non-zero".to_string(),
+            )));
+        }
+        let path_claim = PathClaim::new(&self.path)?;
+
+        let inner = Arc::new(spin::Mutex::new(FileSinkInner {
+            file: None,
+            opened: false,
+            last_write: Instant::now(),
+        }));
+
+        if let Some(idle_timeout) = self.idle_timeout {
+            IdleCloser::register(&inner, idle_timeout);
+        }
+
+        let sink = FileSink {
+            level_filter: Atomic::new(LevelFilter::All),
+            name: spin::RwLock::new(self.name.map(Arc::from)),
+            formatter: spin::RwLock::new(Box::new(FullFormatter::new())),
+            inner,
+            destination: self.path.to_string_lossy().into_owned(),
+            path: self.path,
+            truncate: self.truncate,
+            footer: self.footer,
+            footer_written: AtomicBool::new(false),
+            line_ending: self.line_ending,
+            reopen_requested: AtomicBool::new(false),
+            _path_claim: path_claim,
+        };
+
+        if !self.lazy {
+            sink.open(&mut sink.inner.lock())?;
+        }
+
+        Ok(sink)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{formatter::FmtExtraInfo, prelude::*, sink::AsyncSink, test_utils::*};
+
+    use std::{fs, thread};
+
+    #[test]
+    fn footer() {
+        let path = TEST_LOGS_PATH.join("file_sink_footer.log");
+
+        {
+            let sink = FileSink::builder(&path)
+                .truncate(true)
+                .footer("clean shutdown")
+                .line_ending(LineEnding::None)
+                .build()
+                .unwrap();
+            sink.set_formatter(Box::new(NoModFormatter::new()));
+            let logger = test_logger_builder().sink(Arc::new(sink)).build();
+
+            info!(logger: logger, "hello ");
+            info!(logger: logger, "world ");
+        }
+
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "hello world clean shutdown"
+        );
+    }
+
+    #[test]
+    fn footer_on_shutdown() {
+        let path = TEST_LOGS_PATH.join("file_sink_footer_on_shutdown.log");
+
+        {
+            let sink = FileSink::builder(&path)
+                .truncate(true)
+                .footer("clean shutdown")
+                .line_ending(LineEnding::None)
+                .build()
+                .unwrap();
+            sink.set_formatter(Box::new(NoModFormatter::new()));
+            let async_sink = AsyncSink::builder().sink(Arc::new(sink)).build().unwrap();
+            let logger = test_logger_builder().sink(Arc::new(async_sink)).build();
+
+            info!(logger: logger, "hello ");
+            logger.shutdown();
+            // Written while the sink is still alive, and only once.
+            assert_eq!(fs::read_to_string(&path).unwrap(), "hello clean shutdown");
+            logger.shutdown();
+        }
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "hello clean shutdown");
+    }
+
+    #[test]
+    fn formatter_context() {
+        struct ContextFormatter;
+
+        impl Formatter for ContextFormatter {
+            fn format(
+                &self,
+                _record: &Record,
+                dest: &mut StringBuf,
+                ctx: &FormatterContext,
+            ) -> Result<FmtExtraInfo> {
+                dest.push_str(ctx.sink_type());
+                dest.push_str(ctx.sink_name().unwrap());
+                dest.push_str(ctx.destination().unwrap());
+                dest.push_str(if ctx.is_style_supported() { "+" } else { "-" });
+                Ok(FmtExtraInfo::new())
+            }
+        }
+
+        let path = TEST_LOGS_PATH.join("file_sink_formatter_context.log");
+        {
+            let sink = FileSink::builder(&path)
+                .truncate(true)
+                .line_ending(LineEnding::None)
+                .name("app")
+                .build()
+                .unwrap();
+            sink.set_formatter(Box::new(ContextFormatter));
+            let logger = test_logger_builder().sink(Arc::new(sink)).build();
+            assert_eq!(&*logger.sink_by_name("app").unwrap().name().unwrap(), "app");
+            assert!(logger.sink_by_name("audit").is_none());
+            info!(logger: logger, "hello");
+        }
+
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            format!("FileSinkapp{}-", path.display())
+        );
+    }
+
+    #[test]
+    fn line_ending() {
+        let path = TEST_LOGS_PATH.join("file_sink_line_ending.log");
+
+        {
+            let sink = FileSink::builder(&path)
+                .truncate(true)
+                .line_ending(LineEnding::CrLf)
+                .build()
+                .unwrap();
+            let logger = test_logger_builder().sink(Arc::new(sink)).build();
+
+            info!(logger: logger, "hello");
+            info!(logger: logger, "world");
+        }
+
+        let contents = fs::read_to_string(&path).unwrap();
+        assert!(contents.ends_with("world\r\n"));
+        assert_eq!(contents.matches("\r\n").count(), 2);
+        assert_eq!(contents.matches('\n').count(), 2);
+    }
+
+    #[test]
+    fn lazy() {
+        let dir = TEST_LOGS_PATH.join("file_sink_lazy");
+        let path = dir.join("lazy.log");
+        if dir.exists() {
+            fs::remove_dir_all(&dir).unwrap();
+        }
+
+        let sink = FileSink::builder(&path)
+            .lazy(true)
+            .footer("footer")
+            .line_ending(LineEnding::None)
+            .build()
+            .unwrap();
+        sink.set_formatter(Box::new(NoModFormatter::new()));
+        let sink = Arc::new(sink);
+        let logger = test_logger_builder().sink(sink.clone()).build();
+
+        logger.flush();
+        assert!(!dir.exists());
+
+        info!(logger: logger, "hello");
+        assert!(path.exists());
+
+        drop((logger, sink));
+        assert_eq!(fs::read_to_string(&path).unwrap(), "hellofooter");
+    }
+
+    #[cfg(not(windows))] // opened files cannot be renamed on Windows
+    #[test]
+    fn reopen() {
+        let path = TEST_LOGS_PATH.join("file_sink_reopen.log");
+        let rotated_path = TEST_LOGS_PATH.join("file_sink_reopen.log.1");
+
+        let sink = FileSink::builder(&path)
+            .truncate(true)
+            .line_ending(LineEnding::None)
+            .build()
+            .unwrap();
+        sink.set_formatter(Box::new(NoModFormatter::new()));
+        let sink = Arc::new(sink);
+        let logger = test_logger_builder().sink(sink.clone()).build();
+
+        info!(logger: logger, "hello ");
+        fs::rename(&path, &rotated_path).unwrap();
+        info!(logger: logger, "world ");
+        logger.flush();
+        assert!(!path.exists());
+
+        sink.reopen();
+        info!(logger: logger, "reopened");
+        logger.flush();
+
+        assert_eq!(fs::read_to_string(&rotated_path).unwrap(), "hello world ");
+        assert_eq!(fs::read_to_string(&path).unwrap(), "reopened");
+    }
+
+    #[test]
+    fn invalid_arguments() {
+        let path = TEST_LOGS_PATH.join("file_sink_invalid_arguments.log");
+        assert!(matches!(
+            FileSink::builder(&path)
+                .idle_timeout(Duration::ZERO)
+                .build(),
+            Err(Error::InvalidArgument(InvalidArgumentError::Interval(_)))
+        ));
+
+        let sink = FileSink::new(&path, true).unwrap();
+        assert!(matches!(
+            FileSink::new(&path, false),
+            Err(Error::InvalidArgument(InvalidArgumentError::Path(_)))
+        ));
+        drop(sink);
+        FileSink::new(&path, false).unwrap();
+    }
+
+    #[test]
+    fn idle_timeout() {
+        let path = TEST_LOGS_PATH.join("file_sink_idle_timeout.log");
+
+        let sink = FileSink::builder(&path)
+            .truncate(true)
+            .idle_timeout(Duration::from_millis(100))
+            .line_ending(LineEnding::None)
+            .build()
+            .unwrap();
+        sink.set_formatter(Box::new(NoModFormatter::new()));
+        let sink = Arc::new(sink);
+        let logger = test_logger_builder().sink(sink.clone()).build();
+
+        info!(logger: logger, "hello ");
+        assert!(sink.inner.lock().file.is_some());
+
+        thread::sleep(Duration::from_millis(300));
+        assert!(sink.inner.lock().file.is_none());
+        assert_eq!(fs::read_to_string(&path).unwrap(), "hello ");
+
+        // reopened without truncating
+        info!(logger: logger, "world");
+        assert!(sink.inner.lock().file.is_some());
+
+        drop((logger, sink));
+        assert_eq!(fs::read_to_string(&path).unwrap(), "hello world");
+    }
+
+    #[test]
+    fn shared_idle_closer() {
+        let sinks = [200, 100].map(|millis| {
+            let path = TEST_LOGS_PATH.join(format!("file_sink_shared_idle_closer_{millis}.log"));
+            let sink = FileSink::builder(path)
+                .truncate(true)
+                .idle_timeout(Duration::from_millis(millis))
+                .build()
+                .unwrap();
+            sink.log(&Record::new(Level::Info, "hello")).unwrap();
+            sink
+        });
+        assert!(sinks.iter().all(|sink| sink.inner.lock().file.is_some()));
+
+        // The worker has been restarted at the shorter idle timeout.
+        let interval = IDLE_CLOSER.lock().unwrap().worker.as_ref().unwrap().1;
+        assert!(interval <= Duration::from_millis(100));
+
+        thread::sleep(Duration::from_millis(500));
+        assert!(sinks.iter().all(|sink| sink.inner.lock().file.is_none()));
+
+        // Other tests may register sinks concurrently, but the worker only runs
+        // while some sink is registered.
+        let inners = sinks
+            .iter()
+            .map(|sink| Arc::downgrade(&sink.inner))
+            .collect::<Vec<_>>();
+        drop(sinks);
+        let closer = IDLE_CLOSER.lock().unwrap();
+        assert!(closer
+            .sinks
+            .iter()
+            .all(|(inner, _)| inners.iter().all(|dropped| !dropped.ptr_eq(inner))));
+        assert_eq!(closer.sinks.is_empty(), closer.worker.is_none());
+    }
+}
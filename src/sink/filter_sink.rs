@@ -0,0 +1,133 @@
+//! Provides a sink filtering records by a filter.
+
+use std::sync::{atomic::Ordering, Arc};
+
+use atomic::Atomic;
+
+use crate::{
+    filter::{self, Filter},
+    formatter::Formatter,
+    sink::Sink,
+    LevelFilter, Record, Result,
+};
+
+/// A sink that only passes records allowed by [`Filter`]s to an inner sink.
+///
+/// The filters are consulted in the order they are added, see [`Filter`] for
+/// how their decisions are combined. The level filter of this sink applies
+/// before them.
+///
+/// Records are formatted by the inner sink, so `FilterSink` does not use a
+/// formatter itself. [`Sink::swap_formatter`] on it does nothing and returns
+/// the given formatter directly, set the formatter of the inner sink instead.
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::Arc;
+///
+/// use spdlog::{
+///     filter::Decision,
+///     prelude::*,
+///     sink::{FileSink, FilterSink},
+///     Record,
+/// };
+///
+/// # fn main() -> Result<(), spdlog::Error> {
+/// # let path = std::env::temp_dir().join("spdlog-rs-doctest").join("slow.log");
+/// let file_sink = Arc::new(FileSink::new(path, false)?);
+/// let sink = FilterSink::new(file_sink).filter(|record: &Record| {
+///     Decision::from(record.payload().starts_with("slow query"))
+/// });
+///
+/// let logger: Logger = Logger::builder().sink(Arc::new(sink)).build();
+/// warn!(logger: logger, "slow query took 3s");
+/// # Ok(()) }
+/// ```
+pub struct FilterSink {
+    level_filter: Atomic<LevelFilter>,
+    name: spin::RwLock<Option<Arc<str>>>,
+    filters: Vec<Arc<dyn Filter>>,
+    sink: Arc<dyn Sink>,
+}
+
+impl FilterSink {
+    /// Constructs a `FilterSink` without filters.
+    pub fn new(sink: Arc<dyn Sink>) -> FilterSink {
+        FilterSink {
+            level_filter: Atomic::new(LevelFilter::All),
+            name: spin::RwLock::new(None),
+            filters: vec![],
+            sink,
+        }
+    }
+
+    /// Adds a [`Filter`].
+    #[must_use]
+    pub fn filter<F>(mut self, filter: F) -> Self
+    where
+        F: Filter + 'static,
+    {
+        self.filters.push(Arc::new(filter));
+        self
+    }
+}
+
+impl Sink for FilterSink {
+    fn log(&self, record: &Record) -> Result<()> {
+        if !self.should_log(record.level()) || !filter::apply(&self.filters, record) {
+            return Ok(());
+        }
+        self.sink.log(record)
+    }
+
+    fn flush(&self) -> Result<()> {
+        self.sink.flush()
+    }
+
+    fn shutdown(&self) -> Result<()> {
+        self.sink.shutdown()
+    }
+
+    fn level_filter(&self) -> LevelFilter {
+        self.level_filter.load(Ordering::Relaxed)
+    }
+
+    fn set_level_filter(&self, level_filter: LevelFilter) {
+        self.level_filter.store(level_filter, Ordering::Relaxed);
+    }
+
+    fn name(&self) -> Option<Arc<str>> {
+        self.name.read().clone()
+    }
+
+    fn set_name(&self, name: Option<String>) {
+        *self.name.write() = name.map(Arc::from);
+    }
+
+    fn swap_formatter(&self, formatter: Box<dyn Formatter>) -> Box<dyn Formatter> {
+        formatter
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{filter::Decision, prelude::*, test_utils::*, FieldFilter};
+
+    #[test]
+    fn filter() {
+        let counter_sink = Arc::new(CounterSink::new());
+        let sink = FilterSink::new(counter_sink.clone())
+            .filter(LevelFilter::MoreSevereEqual(Level::Warn))
+            .filter(FieldFilter::present("tenant"))
+            .filter(|record: &Record| Decision::from(record.payload() != "c"));
+        let logger = test_logger_builder().sink(Arc::new(sink)).build();
+
+        warn!(logger: logger, tenant = "acme"; "a");
+        info!(logger: logger, tenant = "acme"; "b");
+        warn!(logger: logger, tenant = "acme"; "c");
+        warn!(logger: logger, "d");
+        assert_eq!(counter_sink.payloads(), vec!["a"]);
+    }
+}
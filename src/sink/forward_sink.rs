@@ -0,0 +1,151 @@
+//! Provides a sink forwarding records to another logger.
+
+use std::sync::{atomic::Ordering, Arc};
+
+use atomic::Atomic;
+
+use crate::{formatter::Formatter, sink::Sink, LevelFilter, Logger, Record, Result};
+
+/// A sink that forwards records to another logger.
+///
+/// Records are forwarded by [`Logger::forward`], so they keep the logger
+/// name, time and source location of the originating logger, and get the
+/// fields of the target logger in addition to their own. It allows a child
+/// logger (e.g. of a library) to pipe its records into the root logger of the
+/// application, while still writing them to its own sinks if needed.
+///
+/// Records are formatted by the sinks of the target logger, so
+/// `ForwardSink` does not use a formatter itself. [`Sink::swap_formatter`] on
+/// it does nothing and returns the given formatter directly.
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::Arc;
+///
+/// use spdlog::{prelude::*, sink::ForwardSink};
+///
+/// let root_logger: Arc<Logger> = spdlog::default_logger();
+/// let library_logger: Logger = Logger::builder()
+///     .name("library")
+///     .field("component", "storage")
+///     .sink(Arc::new(ForwardSink::new(root_logger)))
+///     .build();
+///
+/// info!(logger: library_logger, "written by the sinks of the root logger");
+/// ```
+pub struct ForwardSink {
+    level_filter: Atomic<LevelFilter>,
+    name: spin::RwLock<Option<Arc<str>>>,
+    logger: Arc<Logger>,
+}
+
+impl ForwardSink {
+    /// Constructs a `ForwardSink` forwarding records to the given logger.
+    pub fn new(logger: Arc<Logger>) -> ForwardSink {
+        ForwardSink {
+            level_filter: Atomic::new(LevelFilter::All),
+            name: spin::RwLock::new(None),
+            logger,
+        }
+    }
+
+    /// Gets the logger to which records are forwarded.
+    pub fn logger(&self) -> &Arc<Logger> {
+        &self.logger
+    }
+}
+
+impl Sink for ForwardSink {
+    fn log(&self, record: &Record) -> Result<()> {
+        if self.should_log(record.level()) {
+            self.logger.forward(record);
+        }
+        Ok(())
+    }
+
+    fn flush(&self) -> Result<()> {
+        self.logger.flush();
+        Ok(())
+    }
+
+    fn level_filter(&self) -> LevelFilter {
+        self.level_filter.load(Ordering::Relaxed)
+    }
+
+    fn set_level_filter(&self, level_filter: LevelFilter) {
+        self.level_filter.store(level_filter, Ordering::Relaxed);
+    }
+
+    fn name(&self) -> Option<Arc<str>> {
+        self.name.read().clone()
+    }
+
+    fn set_name(&self, name: Option<String>) {
+        *self.name.write() = name.map(Arc::from);
+    }
+
+    fn swap_formatter(&self, formatter: Box<dyn Formatter>) -> Box<dyn Formatter> {
+        formatter
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{prelude::*, test_utils::*};
+
+    #[test]
+    fn forward() {
+        let root_sink = Arc::new(CounterSink::new());
+        let root_logger = Arc::new(
+            test_logger_builder()
+                .name("root")
+                .field("service", "billing")
+                .sink(root_sink.clone())
+                .build(),
+        );
+        let library_sink = Arc::new(CounterSink::new());
+        let library_logger = test_logger_builder()
+            .name("library")
+            .field("component", "storage")
+            .sink(library_sink.clone())
+            .sink(Arc::new(ForwardSink::new(root_logger.clone())))
+            .build();
+
+        warn!(logger: library_logger, key = 1; "forwarded");
+        library_logger.flush();
+
+        assert_eq!(library_sink.log_count(), 1);
+        assert_eq!(root_sink.payloads(), vec!["forwarded"]);
+        assert_eq!(root_sink.flush_count(), 1);
+
+        let library_record = &library_sink.records()[0];
+        let root_record = &root_sink.records()[0];
+        assert_eq!(root_record.logger_name(), Some("library"));
+        assert_eq!(root_record.time(), library_record.time());
+        assert_eq!(root_record.sequence(), library_record.sequence());
+        assert_eq!(
+            root_record
+                .fields()
+                .iter()
+                .map(|field| field.key())
+                .collect::<Vec<_>>(),
+            vec!["service", "component", "key"]
+        );
+    }
+
+    #[test]
+    fn cycle() {
+        let sink = Arc::new(CounterSink::new());
+        let logger = Arc::new(test_logger_builder().sink(sink.clone()).build());
+        let sinks: Vec<Arc<dyn Sink>> =
+            vec![sink.clone(), Arc::new(ForwardSink::new(logger.clone()))];
+        logger.set_sinks(sinks);
+
+        info!(logger: logger, "cycle");
+        assert_eq!(sink.log_count(), 9);
+
+        logger.set_sinks(vec![]);
+    }
+}
@@ -0,0 +1,199 @@
+//! Provides a sink forwarding records to a group of sinks.
+
+use std::sync::{atomic::Ordering, Arc};
+
+use atomic::Atomic;
+
+use crate::{
+    formatter::Formatter,
+    sink::{Sink, Sinks},
+    Error, LevelFilter, Record, Result,
+};
+
+/// A sink that forwards records to a group of inner sinks.
+///
+/// It is the counterpart of `dist_sink` of C++ spdlog. Unlike the sinks of a
+/// [`Logger`], a group is a single sink, so it can be nested in wrapper sinks
+/// (e.g. [`AsyncSink`]) to wrap multiple sinks at once.
+///
+/// Failures of inner sinks are isolated, a failing inner sink does not prevent
+/// the others from receiving the record. If one inner sink fails, its error is
+/// returned. If more than one inner sink fails, their errors are returned in
+/// an [`Error::Multiple`], and eventually passed to the error handler of the
+/// logger.
+///
+/// Records are formatted by the inner sinks, so `GroupSink` does not use a
+/// formatter itself. [`Sink::swap_formatter`] on it does nothing and returns
+/// the given formatter directly, set the formatter of inner sinks instead.
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::Arc;
+///
+/// use spdlog::{
+///     prelude::*,
+///     sink::{AsyncSink, FileSink, GroupSink, Sink},
+/// };
+///
+/// # fn main() -> Result<(), spdlog::Error> {
+/// # let dir = std::env::temp_dir().join("spdlog-rs-doctest");
+/// let sinks: [Arc<dyn Sink>; 2] = [
+///     Arc::new(FileSink::new(dir.join("group_1.log"), false)?),
+///     Arc::new(FileSink::new(dir.join("group_2.log"), false)?),
+/// ];
+/// let group_sink = Arc::new(GroupSink::new(sinks));
+/// let async_sink = AsyncSink::builder().sink(group_sink).build()?;
+///
+/// let logger: Logger = Logger::builder().sink(Arc::new(async_sink)).build();
+/// info!(logger: logger, "written to both files on a background thread");
+/// # Ok(()) }
+/// ```
+///
+/// [`Logger`]: crate::Logger
+/// [`AsyncSink`]: crate::sink::AsyncSink
+pub struct GroupSink {
+    level_filter: Atomic<LevelFilter>,
+    name: spin::RwLock<Option<Arc<str>>>,
+    sinks: Sinks,
+}
+
+impl GroupSink {
+    /// Constructs a `GroupSink`.
+    pub fn new<I>(sinks: I) -> GroupSink
+    where
+        I: IntoIterator<Item = Arc<dyn Sink>>,
+    {
+        GroupSink {
+            level_filter: Atomic::new(LevelFilter::All),
+            name: spin::RwLock::new(None),
+            sinks: sinks.into_iter().collect(),
+        }
+    }
+
+    /// Gets a reference to the inner sinks.
+    pub fn sinks(&self) -> &Sinks {
+        &self.sinks
+    }
+
+    fn for_each_sink<F>(&self, f: F) -> Result<()>
+    where
+        F: Fn(&dyn Sink) -> Result<()>,
+    {
+        let mut errors = self
+            .sinks
+            .iter()
+            .filter_map(|sink| {
+                f(&**sink)
+                    .err()
+                    .map(|err| err.with_sink(&**sink, None, None))
+            })
+            .collect::<Vec<_>>();
+
+        match errors.len() {
+            0 => Ok(()),
+            1 => Err(errors.pop().unwrap()),
+            _ => Err(Error::Multiple(errors)),
+        }
+    }
+}
+
+impl Sink for GroupSink {
+    fn log(&self, record: &Record) -> Result<()> {
+        if !self.should_log(record.level()) {
+            return Ok(());
+        }
+        self.for_each_sink(|sink| sink.log(record))
+    }
+
+    fn flush(&self) -> Result<()> {
+        self.for_each_sink(|sink| sink.flush())
+    }
+
+    fn shutdown(&self) -> Result<()> {
+        self.for_each_sink(|sink| sink.shutdown())
+    }
+
+    fn level_filter(&self) -> LevelFilter {
+        self.level_filter.load(Ordering::Relaxed)
+    }
+
+    fn set_level_filter(&self, level_filter: LevelFilter) {
+        self.level_filter.store(level_filter, Ordering::Relaxed);
+    }
+
+    fn name(&self) -> Option<Arc<str>> {
+        self.name.read().clone()
+    }
+
+    fn set_name(&self, name: Option<String>) {
+        *self.name.write() = name.map(Arc::from);
+    }
+
+    fn swap_formatter(&self, formatter: Box<dyn Formatter>) -> Box<dyn Formatter> {
+        formatter
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fmt, io};
+
+    use super::*;
+    use crate::{prelude::*, test_utils::*};
+
+    struct FailingSink;
+
+    impl Sink for FailingSink {
+        fn log(&self, _record: &Record) -> Result<()> {
+            Err(Error::FormatRecord(fmt::Error))
+        }
+
+        fn flush(&self) -> Result<()> {
+            Err(Error::FlushBuffer(io::ErrorKind::Other.into()))
+        }
+
+        fn level_filter(&self) -> LevelFilter {
+            LevelFilter::All
+        }
+
+        fn set_level_filter(&self, _level_filter: LevelFilter) {}
+
+        fn swap_formatter(&self, formatter: Box<dyn Formatter>) -> Box<dyn Formatter> {
+            formatter
+        }
+    }
+
+    #[test]
+    fn isolate_errors() {
+        let counter_sink = Arc::new(CounterSink::new());
+        let sink = GroupSink::new([
+            Arc::new(FailingSink) as Arc<dyn Sink>,
+            counter_sink.clone(),
+            Arc::new(FailingSink),
+        ]);
+
+        let record = Record::new(Level::Info, "hello");
+        assert!(matches!(
+            sink.log(&record),
+            Err(Error::Multiple(errors)) if errors.len() == 2
+        ));
+        assert!(matches!(sink.flush(), Err(Error::Multiple(_))));
+        assert_eq!(counter_sink.payloads(), vec!["hello"]);
+        assert_eq!(counter_sink.flush_count(), 1);
+
+        let sink = GroupSink::new([counter_sink.clone() as Arc<dyn Sink>, Arc::new(FailingSink)]);
+        match sink.log(&record) {
+            Err(Error::Sink(err)) => {
+                assert_eq!(err.sink(), "FailingSink");
+                assert!(matches!(err.error(), Error::FormatRecord(_)));
+            }
+            _ => panic!("expect a sink error"),
+        }
+        assert_eq!(counter_sink.log_count(), 2);
+
+        sink.set_level_filter(LevelFilter::Off);
+        assert!(sink.log(&record).is_ok());
+        assert_eq!(counter_sink.log_count(), 2);
+    }
+}
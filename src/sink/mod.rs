@@ -1,20 +1,54 @@
 //! Provides sinks to flexibly output log messages to specified targets.
 
+mod async_sink;
+mod async_target_sink;
+mod buffer_until_configured_sink;
+mod console_bridge_sink;
+mod digest_sink;
+mod disk_space_sink;
+mod fallback_sink;
+mod field_filter_sink;
 mod file_sink;
+mod filter_sink;
+mod forward_sink;
+mod group_sink;
+#[cfg(feature = "opentelemetry")]
+mod otel_log_sink;
+mod retry_sink;
 mod rotating_file_sink;
+mod routing_sink;
+mod session_file_sink;
 mod std_stream_sink;
+mod weak_sink;
 #[cfg(windows)]
 mod win_debug_sink;
 
+pub use async_sink::*;
+pub use async_target_sink::*;
+pub use buffer_until_configured_sink::*;
+pub use console_bridge_sink::*;
+pub use digest_sink::*;
+pub use disk_space_sink::*;
+pub use fallback_sink::*;
+pub use field_filter_sink::*;
 pub use file_sink::*;
+pub use filter_sink::*;
+pub use forward_sink::*;
+pub use group_sink::*;
+#[cfg(feature = "opentelemetry")]
+pub use otel_log_sink::*;
+pub use retry_sink::*;
 pub use rotating_file_sink::*;
+pub use routing_sink::*;
+pub use session_file_sink::*;
 pub use std_stream_sink::*;
+pub use weak_sink::*;
 #[cfg(windows)]
 pub use win_debug_sink::*;
 
-use std::sync::Arc;
+use std::{any::Any, result::Result as StdResult, sync::Arc};
 
-use crate::{formatter::Formatter, Level, LevelFilter, Record, Result};
+use crate::{formatter::Formatter, Level, LevelFilter, Record, Result, StringBuf, EOL};
 
 /// A trait for sinks.
 ///
@@ -26,8 +60,12 @@ use crate::{formatter::Formatter, Level, LevelFilter, Record, Result};
 ///
 /// A sink has its own level filter that is not shared with the logger.
 ///
+/// A `dyn Sink` can be downcast to its concrete type, see
+/// [`downcast_ref`](#method.downcast_ref) and
+/// [`downcast_arc`](#method.downcast_arc).
+///
 /// [`Logger`]: crate::logger::Logger
-pub trait Sink: Sync + Send {
+pub trait Sink: Sync + Send + AsAny {
     /// Determines if a log message with the specified level would be logged.
     fn should_log(&self, level: Level) -> bool {
         self.level_filter().compare(level)
@@ -42,6 +80,18 @@ pub trait Sink: Sync + Send {
     /// Flushes any buffered records.
     fn flush(&self) -> Result<()>;
 
+    /// Flushes any buffered records and finalizes the output for a clean
+    /// shutdown, e.g. writes the footer of a [`FileSink`].
+    ///
+    /// It is called by [`Logger::shutdown`]. Sinks wrapping other sinks
+    /// forward it to them. The default implementation only flushes the sink.
+    ///
+    /// [`FileSink`]: crate::sink::FileSink
+    /// [`Logger::shutdown`]: crate::Logger::shutdown
+    fn shutdown(&self) -> Result<()> {
+        self.flush()
+    }
+
     /// Gets the log level filter.
     fn level_filter(&self) -> LevelFilter;
 
@@ -55,7 +105,234 @@ pub trait Sink: Sync + Send {
     fn set_formatter(&self, formatter: Box<dyn Formatter>) {
         self.swap_formatter(formatter);
     }
+
+    /// Gets the name of the sink, set by [`Sink::set_name`] or by the builder
+    /// of the sink.
+    ///
+    /// Names identify sinks, e.g. multiple [`FileSink`]s of a logger, in
+    /// errors (see [`SinkError::sink_name`]), in [`FormatterContext`] and by
+    /// [`Logger::sink_by_name`].
+    ///
+    /// The name is shared as an `Arc<str>`, so getting it does not allocate.
+    /// The default implementation returns `None`, sinks supporting names
+    /// override both this method and [`Sink::set_name`].
+    ///
+    /// [`SinkError::sink_name`]: crate::SinkError::sink_name
+    /// [`FormatterContext`]: crate::formatter::FormatterContext
+    /// [`Logger::sink_by_name`]: crate::Logger::sink_by_name
+    fn name(&self) -> Option<Arc<str>> {
+        None
+    }
+
+    /// Sets the name of the sink, or removes it if `None` is given.
+    ///
+    /// The default implementation ignores the name.
+    fn set_name(&self, _name: Option<String>) {}
+
+    /// Describes the sink for diagnostics, e.g. in [`Error::Sink`].
+    ///
+    /// The default implementation returns the type name of the sink without
+    /// the module path. Sinks writing to files append the path, e.g.
+    /// `FileSink(/var/log/app.log)`.
+    ///
+    /// [`Error::Sink`]: crate::Error::Sink
+    fn describe(&self) -> String {
+        type_name_of::<Self>()
+    }
+}
+
+// Returns the type name without module paths, e.g. `OtelLogSink<..>` for
+// `spdlog::sink::otel_log_sink::OtelLogSink<..>`.
+fn type_name_of<T: ?Sized>() -> String {
+    let type_name = std::any::type_name::<T>();
+    let (path, generics) = type_name.split_at(type_name.find('<').unwrap_or(type_name.len()));
+    let name = path.rsplit("::").next().unwrap_or(path);
+    if generics.is_empty() {
+        name.to_owned()
+    } else {
+        format!("{}<..>", name)
+    }
+}
+
+impl dyn Sink {
+    /// Returns `true` if the sink is of type `T`.
+    pub fn is<T: Sink + 'static>(&self) -> bool {
+        self.as_any().is::<T>()
+    }
+
+    /// Returns a reference to the sink as type `T`, or `None` if it is not of
+    /// that type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use spdlog::sink::{FileSink, Sink};
+    ///
+    /// # fn main() -> Result<(), spdlog::Error> {
+    /// # let path = std::env::temp_dir().join("spdlog-rs-doctest").join("downcast.log");
+    /// # let logger = spdlog::Logger::builder()
+    /// #     .sink(std::sync::Arc::new(FileSink::new(path, false)?))
+    /// #     .build();
+    /// for sink in logger.sinks().iter() {
+    ///     if let Some(file_sink) = sink.downcast_ref::<FileSink>() {
+    ///         file_sink.flush()?;
+    ///     }
+    /// }
+    /// # Ok(()) }
+    /// ```
+    pub fn downcast_ref<T: Sink + 'static>(&self) -> Option<&T> {
+        self.as_any().downcast_ref::<T>()
+    }
+
+    /// Converts the sink into `Arc<T>`, or returns it back if it is not of
+    /// type `T`.
+    pub fn downcast_arc<T: Sink + 'static>(self: Arc<Self>) -> StdResult<Arc<T>, Arc<dyn Sink>> {
+        if self.is::<T>() {
+            Ok(self.into_any_arc().downcast::<T>().unwrap())
+        } else {
+            Err(self)
+        }
+    }
+}
+
+// Used for downcasting `dyn Sink`, it is implemented for all types.
+#[doc(hidden)]
+pub trait AsAny {
+    fn as_any(&self) -> &dyn Any;
+
+    fn into_any_arc(self: Arc<Self>) -> Arc<dyn Any + Send + Sync>;
+}
+
+impl<T: Any + Send + Sync> AsAny for T {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn into_any_arc(self: Arc<Self>) -> Arc<dyn Any + Send + Sync> {
+        self
+    }
 }
 
 /// A container for [`Sink`]s.
 pub type Sinks = Vec<Arc<dyn Sink>>;
+
+/// Policies for buffered sinks when their buffer is full.
+///
+/// Sinks that buffer records before writing them to the target (e.g.
+/// [`AsyncSink`]) accept this enum to configure their behavior when records
+/// are produced faster than they can be written.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum OverflowPolicy {
+    /// Blocks the logging thread until there is space in the buffer.
+    Block,
+    /// Discards the incoming record.
+    DropIncoming,
+    /// Discards the oldest buffered record to make space for the incoming one.
+    ///
+    /// If the buffer holds no records but only pending operations (e.g.
+    /// flushes), which are never discarded, the incoming record is discarded
+    /// instead, as with [`OverflowPolicy::DropIncoming`].
+    DropOldest,
+}
+
+/// Line endings written after each record by sinks writing text.
+///
+/// Line breaks inside a record (e.g. lines of backtraces) are written by
+/// formatters as the line ending of the target platform of the compilation
+/// (`\r\n` on Windows, `\n` elsewhere). Sinks configured with another line
+/// ending also replace them, e.g. to write `\n` to files consumed by Unix
+/// tools from a program compiled for Windows.
+///
+/// The default is [`LineEnding::native`].
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum LineEnding {
+    /// `\n`.
+    Lf,
+    /// `\r\n`.
+    CrLf,
+    /// No line ending, records are written back to back.
+    ///
+    /// Line breaks inside a record (e.g. lines of backtraces) are kept as the
+    /// line ending of the target platform.
+    None,
+}
+
+impl LineEnding {
+    /// Gets the line ending of the target platform of the compilation.
+    pub const fn native() -> Self {
+        if cfg!(windows) {
+            Self::CrLf
+        } else {
+            Self::Lf
+        }
+    }
+
+    /// Gets the line ending as a string.
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Self::Lf => "\n",
+            Self::CrLf => "\r\n",
+            Self::None => "",
+        }
+    }
+
+    // Appends this line ending to a formatted record, and replaces the line
+    // breaks inside the record with it.
+    pub(crate) fn apply(self, string_buf: &mut StringBuf) {
+        if self != Self::native() && self != Self::None && string_buf.contains(EOL) {
+            *string_buf = StringBuf::from(string_buf.replace(EOL, self.as_str()));
+        }
+        string_buf.push_str(self.as_str());
+    }
+}
+
+impl Default for LineEnding {
+    fn default() -> Self {
+        Self::native()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+
+    #[test]
+    fn downcast() {
+        let sink: Arc<dyn Sink> = Arc::new(CounterSink::new());
+
+        assert!(sink.is::<CounterSink>());
+        assert!(!sink.is::<FileSink>());
+        assert!(sink.downcast_ref::<CounterSink>().is_some());
+        assert!(sink.downcast_ref::<FileSink>().is_none());
+
+        let sink = sink.downcast_arc::<FileSink>().err().unwrap();
+        let sink: Arc<CounterSink> = sink.downcast_arc::<CounterSink>().ok().unwrap();
+        assert_eq!(sink.log_count(), 0);
+    }
+
+    #[test]
+    fn line_ending() {
+        let apply = |line_ending: LineEnding, text: String| {
+            let mut string_buf = StringBuf::from(text);
+            line_ending.apply(&mut string_buf);
+            string_buf.to_string()
+        };
+
+        let text = format!("record{}    backtrace", EOL);
+        assert_eq!(
+            apply(LineEnding::native(), text.clone()),
+            format!("{}{}", text, EOL)
+        );
+        assert_eq!(
+            apply(LineEnding::Lf, text.clone()),
+            "record\n    backtrace\n"
+        );
+        assert_eq!(
+            apply(LineEnding::CrLf, text.clone()),
+            "record\r\n    backtrace\r\n"
+        );
+        assert_eq!(apply(LineEnding::None, text.clone()), text);
+        assert_eq!(apply(LineEnding::Lf, "record".into()), "record\n");
+    }
+}
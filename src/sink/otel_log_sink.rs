@@ -0,0 +1,420 @@
+//! Provides a sink emitting records to OpenTelemetry.
+
+use std::{
+    collections::HashMap,
+    sync::{atomic::Ordering, Arc},
+    time::SystemTime,
+};
+
+use atomic::Atomic;
+use opentelemetry::{
+    logs::{AnyValue, LogRecord, Logger as OtelLogger, LoggerProvider, Severity},
+    Key,
+};
+
+use crate::{formatter::Formatter, sink::Sink, Field, Level, LevelFilter, Record, Result, Value};
+
+/// A sink that emits records to an OpenTelemetry [`LoggerProvider`], e.g. the
+/// `LoggerProvider` of `opentelemetry_sdk` with an OTLP exporter.
+///
+/// This sink is available only if crate feature `opentelemetry` is enabled.
+///
+/// Records are mapped to OpenTelemetry log records as follows:
+///
+/// | Record                  | OpenTelemetry log record                           |
+/// |-------------------------|----------------------------------------------------|
+/// | time                    | timestamp                                          |
+/// | level                   | severity number and severity text                  |
+/// | payload                 | body                                               |
+/// | fields                  | attributes                                         |
+/// | source location         | `code.namespace`, `code.filepath` and `code.lineno` attributes |
+/// | logger name             | name of the instrumentation scope                  |
+///
+/// The [`Level::Critical`] level is mapped to [`Severity::Fatal`], other
+/// levels are mapped to the severities with the same names. Field values keep
+/// their types, unsigned integers out of the range of `i64` are converted to
+/// strings, and durations are converted to numbers of seconds. Records
+/// without a logger name are emitted in the default scope, see
+/// [`OtelLogSinkBuilder::default_scope`].
+///
+/// `OtelLogSink` does not format records, so [`Sink::swap_formatter`] on it
+/// does nothing and returns the given formatter directly. Exporting and
+/// batching are done by the provider, [`Sink::flush`] on it does nothing, call
+/// the `force_flush` method of the provider instead.
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::Arc;
+///
+/// use opentelemetry::logs::NoopLoggerProvider;
+/// use spdlog::{prelude::*, sink::OtelLogSink};
+///
+/// # let provider = NoopLoggerProvider::new();
+/// // `provider` is usually an `opentelemetry_sdk::logs::SdkLoggerProvider`
+/// // with an OTLP exporter.
+/// let sink = OtelLogSink::builder(provider).default_scope("my-app").build();
+///
+/// let logger: Logger = Logger::builder()
+///     .name("http")
+///     .sink(Arc::new(sink))
+///     .build();
+/// info!(logger: logger, status = 200; "request served");
+/// ```
+pub struct OtelLogSink<P>
+where
+    P: LoggerProvider,
+{
+    level_filter: Atomic<LevelFilter>,
+    name: spin::RwLock<Option<Arc<str>>>,
+    provider: P,
+    default_logger: P::Logger,
+    loggers: spin::RwLock<HashMap<String, Arc<P::Logger>>>,
+}
+
+impl<P> OtelLogSink<P>
+where
+    P: LoggerProvider + Send + Sync + 'static,
+    P::Logger: Send + Sync + 'static,
+{
+    /// Constructs an `OtelLogSink` with the default options.
+    pub fn new(provider: P) -> OtelLogSink<P> {
+        OtelLogSink::builder(provider).build()
+    }
+
+    /// Constructs an [`OtelLogSinkBuilder`].
+    pub fn builder(provider: P) -> OtelLogSinkBuilder<P> {
+        OtelLogSinkBuilder {
+            provider,
+            default_scope: "spdlog-rs".to_string(),
+        }
+    }
+
+    /// Gets a reference to the logger provider.
+    pub fn provider(&self) -> &P {
+        &self.provider
+    }
+
+    fn emit(&self, logger: &P::Logger, record: &Record) {
+        let mut otel_record = logger.create_log_record();
+
+        otel_record.set_timestamp(record.time());
+        otel_record.set_observed_timestamp(SystemTime::now());
+        otel_record.set_severity_number(severity(record.level()));
+        otel_record.set_severity_text(record.level().as_str());
+        otel_record.set_body(AnyValue::from(record.payload().to_string()));
+
+        otel_record.add_attributes(
+            record
+                .fields()
+                .iter()
+                .map(|field| (Key::from(field.key().to_string()), any_value(field.value()))),
+        );
+
+        if let Some(srcloc) = record.source_location() {
+            otel_record.add_attribute("code.namespace", srcloc.module_path());
+            if srcloc.is_hashed() {
+                otel_record.add_attribute("code.filepath", format!("#{:016x}", srcloc.id()));
+            } else {
+                otel_record.add_attribute("code.filepath", srcloc.file());
+                otel_record.add_attribute("code.lineno", srcloc.line());
+            }
+        }
+
+        logger.emit(otel_record);
+    }
+}
+
+impl<P> Sink for OtelLogSink<P>
+where
+    P: LoggerProvider + Send + Sync + 'static,
+    P::Logger: Send + Sync + 'static,
+{
+    fn log(&self, record: &Record) -> Result<()> {
+        if !self.should_log(record.level()) {
+            return Ok(());
+        }
+
+        let name = match record.logger_name() {
+            Some(name) => name,
+            None => {
+                self.emit(&self.default_logger, record);
+                return Ok(());
+            }
+        };
+
+        let logger = self.loggers.read().get(name).cloned();
+        let logger = match logger {
+            Some(logger) => logger,
+            None => self
+                .loggers
+                .write()
+                .entry(name.to_string())
+                .or_insert_with(|| Arc::new(self.provider.logger(name.to_string())))
+                .clone(),
+        };
+        self.emit(&logger, record);
+        Ok(())
+    }
+
+    fn flush(&self) -> Result<()> {
+        Ok(())
+    }
+
+    fn level_filter(&self) -> LevelFilter {
+        self.level_filter.load(Ordering::Relaxed)
+    }
+
+    fn set_level_filter(&self, level_filter: LevelFilter) {
+        self.level_filter.store(level_filter, Ordering::Relaxed);
+    }
+
+    fn name(&self) -> Option<Arc<str>> {
+        self.name.read().clone()
+    }
+
+    fn set_name(&self, name: Option<String>) {
+        *self.name.write() = name.map(Arc::from);
+    }
+
+    fn swap_formatter(&self, formatter: Box<dyn Formatter>) -> Box<dyn Formatter> {
+        formatter
+    }
+}
+
+/// The builder of [`OtelLogSink`].
+pub struct OtelLogSinkBuilder<P> {
+    provider: P,
+    default_scope: String,
+}
+
+impl<P> OtelLogSinkBuilder<P>
+where
+    P: LoggerProvider + Send + Sync + 'static,
+    P::Logger: Send + Sync + 'static,
+{
+    /// Sets the name of the instrumentation scope for records without a
+    /// logger name.
+    ///
+    /// The default is `spdlog-rs`.
+    #[must_use]
+    pub fn default_scope<S>(mut self, default_scope: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.default_scope = default_scope.into();
+        self
+    }
+
+    /// Builds an [`OtelLogSink`].
+    pub fn build(self) -> OtelLogSink<P> {
+        OtelLogSink {
+            level_filter: Atomic::new(LevelFilter::All),
+            name: spin::RwLock::new(None),
+            default_logger: self.provider.logger(self.default_scope),
+            provider: self.provider,
+            loggers: spin::RwLock::new(HashMap::new()),
+        }
+    }
+}
+
+fn severity(level: Level) -> Severity {
+    match level {
+        Level::Critical => Severity::Fatal,
+        Level::Error => Severity::Error,
+        Level::Warn => Severity::Warn,
+        Level::Info => Severity::Info,
+        Level::Debug => Severity::Debug,
+        Level::Trace => Severity::Trace,
+    }
+}
+
+fn any_value(value: &Value) -> AnyValue {
+    match value {
+        Value::Str(value) => AnyValue::from(value.to_string()),
+        Value::I64(value) => AnyValue::Int(*value),
+        Value::U64(value) => {
+            i64::try_from(*value).map_or_else(|_| AnyValue::from(value.to_string()), AnyValue::Int)
+        }
+        Value::F64(value) => AnyValue::Double(*value),
+        Value::Bool(value) => AnyValue::Boolean(*value),
+        Value::Duration(value) => AnyValue::Double(value.as_secs_f64()),
+        Value::Map(fields) => fields
+            .iter()
+            .map(|field: &Field| (Key::from(field.key().to_string()), any_value(field.value())))
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{borrow::Cow, sync::Mutex, time::Duration};
+
+    use opentelemetry::InstrumentationScope;
+
+    use super::*;
+    use crate::{prelude::*, test_utils::*};
+
+    #[derive(Default)]
+    struct TestLogRecord {
+        timestamp: Option<SystemTime>,
+        severity_number: Option<Severity>,
+        severity_text: Option<&'static str>,
+        body: Option<AnyValue>,
+        attributes: Vec<(Key, AnyValue)>,
+    }
+
+    impl LogRecord for TestLogRecord {
+        fn set_event_name(&mut self, _name: &'static str) {}
+
+        fn set_target<T>(&mut self, _target: T)
+        where
+            T: Into<Cow<'static, str>>,
+        {
+        }
+
+        fn set_timestamp(&mut self, timestamp: SystemTime) {
+            self.timestamp = Some(timestamp);
+        }
+
+        fn set_observed_timestamp(&mut self, _timestamp: SystemTime) {}
+
+        fn set_severity_text(&mut self, text: &'static str) {
+            self.severity_text = Some(text);
+        }
+
+        fn set_severity_number(&mut self, number: Severity) {
+            self.severity_number = Some(number);
+        }
+
+        fn set_body(&mut self, body: AnyValue) {
+            self.body = Some(body);
+        }
+
+        fn add_attributes<I, K, V>(&mut self, attributes: I)
+        where
+            I: IntoIterator<Item = (K, V)>,
+            K: Into<Key>,
+            V: Into<AnyValue>,
+        {
+            for (key, value) in attributes {
+                self.add_attribute(key, value);
+            }
+        }
+
+        fn add_attribute<K, V>(&mut self, key: K, value: V)
+        where
+            K: Into<Key>,
+            V: Into<AnyValue>,
+        {
+            self.attributes.push((key.into(), value.into()));
+        }
+    }
+
+    type Emitted = Arc<Mutex<Vec<(String, TestLogRecord)>>>;
+
+    #[derive(Default)]
+    struct TestProvider {
+        emitted: Emitted,
+    }
+
+    struct TestLogger {
+        scope: String,
+        emitted: Emitted,
+    }
+
+    impl OtelLogger for TestLogger {
+        type LogRecord = TestLogRecord;
+
+        fn create_log_record(&self) -> TestLogRecord {
+            TestLogRecord::default()
+        }
+
+        fn emit(&self, record: TestLogRecord) {
+            self.emitted
+                .lock()
+                .unwrap()
+                .push((self.scope.clone(), record));
+        }
+
+        fn event_enabled(&self, _level: Severity, _target: &str, _name: Option<&str>) -> bool {
+            true
+        }
+    }
+
+    impl LoggerProvider for TestProvider {
+        type Logger = TestLogger;
+
+        fn logger_with_scope(&self, scope: InstrumentationScope) -> TestLogger {
+            TestLogger {
+                scope: scope.name().to_string(),
+                emitted: self.emitted.clone(),
+            }
+        }
+    }
+
+    #[test]
+    fn emit() {
+        let sink = Arc::new(
+            OtelLogSink::builder(TestProvider::default())
+                .default_scope("app")
+                .build(),
+        );
+        let logger = test_logger_builder().sink(sink.clone()).build();
+        let named_logger = test_logger_builder()
+            .name("http")
+            .sink(sink.clone())
+            .build();
+
+        info!(logger: logger, "hello");
+        critical!(
+            logger: named_logger,
+            status = 500u16,
+            big = u64::MAX,
+            elapsed = Duration::from_millis(1500),
+            client = vec![Field::new("ip", "127.0.0.1")];
+            "failed"
+        );
+
+        let emitted = sink.provider().emitted.lock().unwrap();
+        assert_eq!(emitted.len(), 2);
+
+        let (scope, record) = &emitted[0];
+        assert_eq!(scope, "app");
+        assert_eq!(record.severity_number, Some(Severity::Info));
+        assert_eq!(record.severity_text, Some("info"));
+        assert_eq!(record.body, Some(AnyValue::from("hello")));
+        assert!(record.timestamp.is_some());
+
+        let (scope, record) = &emitted[1];
+        assert_eq!(scope, "http");
+        assert_eq!(record.severity_number, Some(Severity::Fatal));
+        let attributes = record
+            .attributes
+            .iter()
+            .filter(|(key, _)| !key.as_str().starts_with("code."))
+            .cloned()
+            .collect::<Vec<_>>();
+        assert_eq!(
+            attributes,
+            vec![
+                (Key::from("status"), AnyValue::Int(500)),
+                (Key::from("big"), AnyValue::from(u64::MAX.to_string())),
+                (Key::from("elapsed"), AnyValue::Double(1.5)),
+                (
+                    Key::from("client"),
+                    [(Key::from("ip"), AnyValue::from("127.0.0.1"))]
+                        .into_iter()
+                        .collect()
+                ),
+            ]
+        );
+        assert_eq!(
+            record
+                .attributes
+                .iter()
+                .any(|(key, _)| key.as_str() == "code.lineno"),
+            cfg!(feature = "source-location") && !cfg!(feature = "source-location-hashed")
+        );
+    }
+}
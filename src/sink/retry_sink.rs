@@ -0,0 +1,328 @@
+//! Provides a sink retrying transient errors of an inner sink.
+
+use std::{
+    io,
+    sync::{atomic::Ordering, Arc},
+    thread,
+    time::Duration,
+};
+
+use atomic::Atomic;
+
+use crate::{formatter::Formatter, sink::Sink, Error, LevelFilter, Record, Result};
+
+/// A sink that retries logging and flushing on an inner sink when they fail
+/// with transient errors.
+///
+/// An operation is retried up to a maximum number of times, with an
+/// exponential backoff between attempts. If it still fails, the last error is
+/// returned, so it reaches the error handler of the logger only after the
+/// retries are exhausted. Non-transient errors are returned immediately.
+///
+/// By default, the following I/O errors of writing records and flushing
+/// buffers are considered transient:
+///
+///  - [`io::ErrorKind::Interrupted`] (`EINTR`)
+///  - [`io::ErrorKind::WouldBlock`] (`EAGAIN`)
+///  - [`io::ErrorKind::TimedOut`]
+///  - `ENOSPC` on Unix, as space may be freed in the meantime
+///
+/// Errors of sinks combining other sinks, such as [`GroupSink`] and
+/// [`FallbackSink`], are never transient by default, since they are returned
+/// only for the inner sinks that failed, and retrying would write the record
+/// again to the ones that succeeded. Wrap the inner sinks in `RetrySink`s
+/// instead.
+///
+/// Use [`RetrySink::retry_if`] to classify errors differently.
+///
+/// Backoff blocks the logging thread, consider wrapping a `RetrySink` in an
+/// [`AsyncSink`] if the target may stall. A record may be written more than
+/// once if the inner sink fails after writing part of it, e.g. on a short
+/// write.
+///
+/// Records are formatted by the inner sink, so `RetrySink` does not use a
+/// formatter itself. [`Sink::swap_formatter`] on it does nothing and returns
+/// the given formatter directly, set the formatter of the inner sink instead.
+///
+/// # Examples
+///
+/// ```
+/// use std::{sync::Arc, time::Duration};
+///
+/// use spdlog::{
+///     prelude::*,
+///     sink::{FileSink, RetrySink},
+/// };
+///
+/// # fn main() -> Result<(), spdlog::Error> {
+/// # let path = std::env::temp_dir().join("spdlog-rs-doctest").join("nfs.log");
+/// let file_sink = Arc::new(FileSink::new(path, false)?);
+/// let sink = RetrySink::new(file_sink)
+///     .max_retries(5)
+///     .backoff(Duration::from_millis(20), Duration::from_secs(1));
+///
+/// let logger: Logger = Logger::builder().sink(Arc::new(sink)).build();
+/// info!(logger: logger, "written even if the first attempt is interrupted");
+/// # Ok(()) }
+/// ```
+///
+/// [`AsyncSink`]: crate::sink::AsyncSink
+/// [`GroupSink`]: crate::sink::GroupSink
+/// [`FallbackSink`]: crate::sink::FallbackSink
+pub struct RetrySink {
+    level_filter: Atomic<LevelFilter>,
+    name: spin::RwLock<Option<Arc<str>>>,
+    sink: Arc<dyn Sink>,
+    max_retries: u32,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    retry_if: Box<dyn Fn(&Error) -> bool + Send + Sync>,
+}
+
+impl RetrySink {
+    /// Constructs a `RetrySink` wrapping the given sink.
+    ///
+    /// By default, operations are retried up to 3 times, with a backoff
+    /// starting from 10 milliseconds and doubling up to 1 second.
+    pub fn new(sink: Arc<dyn Sink>) -> RetrySink {
+        RetrySink {
+            level_filter: Atomic::new(LevelFilter::All),
+            name: spin::RwLock::new(None),
+            sink,
+            max_retries: 3,
+            initial_backoff: Duration::from_millis(10),
+            max_backoff: Duration::from_secs(1),
+            retry_if: Box::new(is_transient),
+        }
+    }
+
+    /// Sets the maximum number of retries after the first attempt.
+    #[must_use]
+    pub fn max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// Sets the backoff before the first retry, and the maximum backoff. The
+    /// backoff doubles after each retry until it reaches the maximum.
+    #[must_use]
+    pub fn backoff(mut self, initial: Duration, max: Duration) -> Self {
+        self.initial_backoff = initial;
+        self.max_backoff = max;
+        self
+    }
+
+    /// Sets the predicate deciding whether an error is transient and the
+    /// operation should be retried, replacing the default one.
+    #[must_use]
+    pub fn retry_if<F>(mut self, predicate: F) -> Self
+    where
+        F: Fn(&Error) -> bool + Send + Sync + 'static,
+    {
+        self.retry_if = Box::new(predicate);
+        self
+    }
+
+    /// Gets the inner sink.
+    pub fn sink(&self) -> &Arc<dyn Sink> {
+        &self.sink
+    }
+
+    fn retry(&self, op: impl Fn() -> Result<()>) -> Result<()> {
+        let mut backoff = self.initial_backoff;
+        let mut retries = 0;
+        loop {
+            match op() {
+                Err(err) if retries < self.max_retries && (self.retry_if)(&err) => {
+                    thread::sleep(backoff);
+                    backoff = backoff.saturating_mul(2).min(self.max_backoff);
+                    retries += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+impl Sink for RetrySink {
+    fn log(&self, record: &Record) -> Result<()> {
+        if !self.should_log(record.level()) {
+            return Ok(());
+        }
+        self.retry(|| self.sink.log(record))
+    }
+
+    fn flush(&self) -> Result<()> {
+        self.retry(|| self.sink.flush())
+    }
+
+    fn shutdown(&self) -> Result<()> {
+        self.retry(|| self.sink.shutdown())
+    }
+
+    fn level_filter(&self) -> LevelFilter {
+        self.level_filter.load(Ordering::Relaxed)
+    }
+
+    fn set_level_filter(&self, level_filter: LevelFilter) {
+        self.level_filter.store(level_filter, Ordering::Relaxed);
+    }
+
+    fn name(&self) -> Option<Arc<str>> {
+        self.name.read().clone()
+    }
+
+    fn set_name(&self, name: Option<String>) {
+        *self.name.write() = name.map(Arc::from);
+    }
+
+    fn swap_formatter(&self, formatter: Box<dyn Formatter>) -> Box<dyn Formatter> {
+        formatter
+    }
+
+    fn describe(&self) -> String {
+        format!("RetrySink({})", self.sink.describe())
+    }
+}
+
+// The default predicate of `RetrySink`. `Error::Sink` and `Error::Multiple`
+// come from sinks combining other sinks and only cover the failed ones, so
+// retrying them would write again to the ones that succeeded.
+fn is_transient(err: &Error) -> bool {
+    match err {
+        Error::WriteRecord(err) | Error::FlushBuffer(err) => is_transient_io(err),
+        _ => false,
+    }
+}
+
+fn is_transient_io(err: &io::Error) -> bool {
+    #[cfg(unix)]
+    if err.raw_os_error() == Some(libc::ENOSPC) {
+        return true;
+    }
+    matches!(
+        err.kind(),
+        io::ErrorKind::Interrupted | io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::AtomicUsize;
+
+    use super::*;
+    use crate::{prelude::*, sink::GroupSink, test_utils::*};
+
+    // Fails with the given error kind a number of times, then logs to the
+    // inner counter sink.
+    struct FlakySink {
+        kind: io::ErrorKind,
+        failures: AtomicUsize,
+        attempts: AtomicUsize,
+        counter: CounterSink,
+    }
+
+    impl FlakySink {
+        fn new(kind: io::ErrorKind, failures: usize) -> FlakySink {
+            FlakySink {
+                kind,
+                failures: AtomicUsize::new(failures),
+                attempts: AtomicUsize::new(0),
+                counter: CounterSink::new(),
+            }
+        }
+    }
+
+    impl Sink for FlakySink {
+        fn log(&self, record: &Record) -> Result<()> {
+            self.attempts.fetch_add(1, Ordering::Relaxed);
+            let failed = self
+                .failures
+                .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |n| n.checked_sub(1))
+                .is_ok();
+            if failed {
+                return Err(Error::WriteRecord(self.kind.into()));
+            }
+            self.counter.log(record)
+        }
+
+        fn flush(&self) -> Result<()> {
+            Ok(())
+        }
+
+        fn level_filter(&self) -> LevelFilter {
+            LevelFilter::All
+        }
+
+        fn set_level_filter(&self, _level_filter: LevelFilter) {}
+
+        fn swap_formatter(&self, formatter: Box<dyn Formatter>) -> Box<dyn Formatter> {
+            formatter
+        }
+    }
+
+    fn log_to(flaky_sink: &Arc<FlakySink>) -> Result<()> {
+        let sink = RetrySink::new(flaky_sink.clone())
+            .max_retries(2)
+            .backoff(Duration::ZERO, Duration::ZERO);
+        sink.log(&Record::new(Level::Info, "hello"))
+    }
+
+    #[test]
+    fn retry() {
+        let flaky_sink = Arc::new(FlakySink::new(io::ErrorKind::Interrupted, 2));
+        assert!(log_to(&flaky_sink).is_ok());
+        assert_eq!(flaky_sink.attempts.load(Ordering::Relaxed), 3);
+        assert_eq!(flaky_sink.counter.payloads(), vec!["hello"]);
+
+        let flaky_sink = Arc::new(FlakySink::new(io::ErrorKind::WouldBlock, 3));
+        assert!(matches!(
+            log_to(&flaky_sink),
+            Err(Error::WriteRecord(err)) if err.kind() == io::ErrorKind::WouldBlock
+        ));
+        assert_eq!(flaky_sink.attempts.load(Ordering::Relaxed), 3);
+        assert_eq!(flaky_sink.counter.log_count(), 0);
+
+        let flaky_sink = Arc::new(FlakySink::new(io::ErrorKind::PermissionDenied, 1));
+        assert!(log_to(&flaky_sink).is_err());
+        assert_eq!(flaky_sink.attempts.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn transient() {
+        let io_err = |kind: io::ErrorKind| Error::WriteRecord(kind.into());
+        assert!(is_transient(&io_err(io::ErrorKind::TimedOut)));
+        assert!(!is_transient(&io_err(io::ErrorKind::NotFound)));
+        assert!(!is_transient(&Error::OpenFile(
+            io::ErrorKind::Interrupted.into()
+        )));
+        #[cfg(unix)]
+        assert!(is_transient(&Error::FlushBuffer(
+            io::Error::from_raw_os_error(libc::ENOSPC)
+        )));
+        assert!(!is_transient(&Error::Multiple(vec![
+            io_err(io::ErrorKind::Interrupted),
+            io_err(io::ErrorKind::WouldBlock),
+        ])));
+    }
+
+    #[test]
+    fn group_partially_failed() {
+        let flaky_sink = Arc::new(FlakySink::new(io::ErrorKind::Interrupted, 1));
+        let counter_sink = Arc::new(CounterSink::new());
+        let group: Arc<dyn Sink> = Arc::new(GroupSink::new(vec![
+            flaky_sink.clone() as Arc<dyn Sink>,
+            counter_sink.clone(),
+        ]));
+        let sink = RetrySink::new(group)
+            .max_retries(2)
+            .backoff(Duration::ZERO, Duration::ZERO);
+
+        assert!(matches!(
+            sink.log(&Record::new(Level::Info, "hello")),
+            Err(Error::Sink(_))
+        ));
+        assert_eq!(flaky_sink.attempts.load(Ordering::Relaxed), 1);
+        assert_eq!(counter_sink.payloads(), vec!["hello"]);
+    }
+}
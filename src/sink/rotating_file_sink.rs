@@ -3,31 +3,35 @@
 use std::{
     collections::LinkedList,
     ffi::OsString,
-    fs::{self, File},
+    fs::{self, File, OpenOptions},
     hash::Hash,
-    io::{BufWriter, Write},
+    io::{self, BufWriter, Write},
     mem,
     path::{Path, PathBuf},
-    sync::atomic::Ordering,
+    sync::{atomic::Ordering, Arc},
+    thread,
     time::{Duration, SystemTime},
 };
 
 use atomic::Atomic;
-use chrono::prelude::*;
 use spin::MutexGuard;
 
 use crate::{
-    formatter::{Formatter, FullFormatter},
-    sink::Sink,
-    utils, Error, LevelFilter, Record, Result, StringBuf,
+    formatter::{Formatter, FormatterContext, FullFormatter},
+    profiling::{self, Stage},
+    self_log,
+    sink::{LineEnding, Sink},
+    utils::{self, PathClaim},
+    Error, Field, InvalidArgumentError, Level, LevelFilter, Record, RecordFlags, Result,
+    RotateFileError, StringBuf, TimeZone,
 };
 
 /// Rotation policies for [`RotatingFileSink`].
 ///
-/// # Panics
+/// # Errors
 ///
 /// Note that some parameters have range requirements, functions that receive it
-/// will panic if the requirements are not met.
+/// will return [`Error::InvalidArgument`] if the requirements are not met.
 #[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
 pub enum RotationPolicy {
     /// Rotates when the log file reaches the given max file size.
@@ -36,6 +40,10 @@ pub enum RotationPolicy {
         u64,
     ),
     /// Rotates daily at the given time point.
+    ///
+    /// The time point is in the time zone specified by
+    /// [`RotatingFileSinkBuilder::time_zone`], which is the local time zone by
+    /// default.
     Daily {
         /// Hour of the time point. Range: [0, 23].
         hour: u32,
@@ -43,12 +51,17 @@ pub enum RotationPolicy {
         minute: u32,
     },
     /// Rotates hourly.
+    ///
+    /// Hours are counted in the time zone specified by
+    /// [`RotatingFileSinkBuilder::time_zone`], which is the local time zone by
+    /// default.
     Hourly,
 }
 
 trait Rotator {
+    // Returns `true` if the file was rotated before writing the record.
     #[allow(clippy::ptr_arg)]
-    fn log(&self, record: &Record, string_buf: &StringBuf) -> Result<()>;
+    fn log(&self, record: &Record, string_buf: &StringBuf) -> Result<bool>;
     fn flush(&self) -> Result<()>;
     fn drop_flush(&mut self) -> Result<()> {
         self.flush()
@@ -70,11 +83,14 @@ struct RotatorFileSize {
 struct RotatorFileSizeInner {
     file: Option<BufWriter<File>>,
     current_size: u64,
+    rotation_count: u64,
 }
 
 struct RotatorTimePoint {
     base_path: PathBuf,
     time_point: TimePoint,
+    time_zone: TimeZone,
+    date_directories: bool,
     max_files: usize,
     inner: spin::Mutex<RotatorTimePointInner>,
 }
@@ -87,12 +103,29 @@ enum TimePoint {
 
 struct RotatorTimePointInner {
     file: BufWriter<File>,
+    file_path: PathBuf,
     rotation_time_point: SystemTime,
+    rotation_count: u64,
     file_paths: Option<LinkedList<PathBuf>>,
 }
 
 /// A sink with a file as the target, rotating according to the rotation policy.
 ///
+/// For the [`RotationPolicy::Daily`] and [`RotationPolicy::Hourly`] rotation
+/// policies, rotations are driven by the [time of records] rather than the
+/// current time, so replayed records with historical times do not trigger
+/// rotations. Records with a time earlier than the current file are written
+/// to the current file.
+///
+/// For the [`RotationPolicy::FileSize`] rotation policy, files are moved by
+/// renaming, which atomically replaces the older file. On Windows, files
+/// opened by other processes (e.g. tailing tools or antiviruses) cannot be
+/// renamed or removed, so these operations are retried a few times with
+/// backoff. If a file is still in use, it is copied and truncated instead, and
+/// [`Error::RotateFile`] is returned if that fails too.
+///
+/// [time of records]: crate::Record::time
+///
 /// # Examples
 ///
 /// See [./examples] directory.
@@ -100,8 +133,13 @@ struct RotatorTimePointInner {
 /// [./examples]: https://github.com/SpriteOvO/spdlog-rs/tree/main/examples
 pub struct RotatingFileSink {
     level_filter: Atomic<LevelFilter>,
+    name: spin::RwLock<Option<Arc<str>>>,
     formatter: spin::RwLock<Box<dyn Formatter>>,
     rotator: RotatorKind,
+    // the base path as the destination in `FormatterContext`
+    destination: String,
+    line_ending: LineEnding,
+    _path_claim: PathClaim,
 }
 
 impl RotatingFileSink {
@@ -117,15 +155,16 @@ impl RotatingFileSink {
     /// contents of the existing file if the parameter is `true`, since the file
     /// name is a time point and not an index.
     ///
+    /// For more options, use [`RotatingFileSink::builder`] instead.
+    ///
     /// # Errors
     ///
+    /// If the parameter `rotation_policy` is invalid,
+    /// [`Error::InvalidArgument`] will be returned. See the documentation of
+    /// [`RotationPolicy`] for requirements.
+    ///
     /// If an error occurs opening the file, [`Error::CreateDirectory`] or
     /// [`Error::OpenFile`] will be returned.
-    ///
-    /// # Panics
-    ///
-    /// Panics if the parameter `rotation_policy` is invalid. See the
-    /// documentation of [`RotationPolicy`] for requirements.
     pub fn new<P>(
         base_path: P,
         rotation_policy: RotationPolicy,
@@ -135,50 +174,343 @@ impl RotatingFileSink {
     where
         P: Into<PathBuf>,
     {
-        rotation_policy.validate();
+        RotatingFileSink::builder(base_path, rotation_policy)
+            .max_files(max_files)
+            .rotate_on_open(rotate_on_open)
+            .build()
+    }
 
-        let base_path = base_path.into();
+    /// Constructs a [`RotatingFileSinkBuilder`].
+    pub fn builder<P>(base_path: P, rotation_policy: RotationPolicy) -> RotatingFileSinkBuilder
+    where
+        P: Into<PathBuf>,
+    {
+        RotatingFileSinkBuilder::new(base_path, rotation_policy)
+    }
 
-        let rotator = match rotation_policy {
-            RotationPolicy::FileSize(max_size) => RotatorKind::FileSize(RotatorFileSize::new(
+    /// Gets the path of the file currently being written.
+    ///
+    /// For the [`RotationPolicy::FileSize`] rotation policy, it is always the
+    /// base path. For the [`RotationPolicy::Daily`] and
+    /// [`RotationPolicy::Hourly`] rotation policies, it is the base path with
+    /// the time point of the current file appended, or placed in the date
+    /// directories if [`RotatingFileSinkBuilder::date_directories`] is
+    /// enabled.
+    pub fn current_path(&self) -> PathBuf {
+        match &self.rotator {
+            RotatorKind::FileSize(rotator) => rotator.base_path.clone(),
+            RotatorKind::TimePoint(rotator) => rotator.inner.lock().file_path.clone(),
+        }
+    }
+
+    /// Gets the number of rotations performed since the sink was built.
+    ///
+    /// For the [`RotationPolicy::FileSize`] rotation policy, the rotation
+    /// performed because of [`RotatingFileSinkBuilder::rotate_on_open`] is
+    /// counted.
+    pub fn rotation_count(&self) -> u64 {
+        match &self.rotator {
+            RotatorKind::FileSize(rotator) => rotator.inner.lock().rotation_count,
+            RotatorKind::TimePoint(rotator) => rotator.inner.lock().rotation_count,
+        }
+    }
+
+    /// Gets the paths of the existing log files managed by the sink, from the
+    /// newest to the oldest.
+    ///
+    /// The first path is always the [current path]. For the
+    /// [`RotationPolicy::Daily`] and [`RotationPolicy::Hourly`] rotation
+    /// policies without a limit on the number of files, previous files are
+    /// looked up backwards from the current time point until a time point
+    /// without a file.
+    ///
+    /// [current path]: RotatingFileSink::current_path
+    pub fn files(&self) -> Vec<PathBuf> {
+        match &self.rotator {
+            RotatorKind::FileSize(rotator) => rotator.files(),
+            RotatorKind::TimePoint(rotator) => rotator.files(),
+        }
+    }
+
+    /// Computes the path of the file with the given index for the
+    /// [`RotationPolicy::FileSize`] rotation policy.
+    ///
+    /// Index `0` is the file currently being written, which is the base path
+    /// itself. Greater indexes are older files, the index is appended to the
+    /// file stem of the base path.
+    ///
+    /// This is a pure function, the file system is not accessed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::path::Path;
+    ///
+    /// use spdlog::sink::RotatingFileSink;
+    ///
+    /// let path = RotatingFileSink::file_path_of_index("logs/app.log", 2);
+    /// assert_eq!(path, Path::new("logs/app_2.log"));
+    /// ```
+    pub fn file_path_of_index<P>(base_path: P, index: usize) -> PathBuf
+    where
+        P: AsRef<Path>,
+    {
+        RotatorFileSize::calc_file_path(base_path, index)
+    }
+
+    /// Computes the path of the file containing records at the given time for
+    /// the [`RotationPolicy::Daily`] and [`RotationPolicy::Hourly`] rotation
+    /// policies.
+    ///
+    /// The date (and the hour for [`RotationPolicy::Hourly`]) of the time in
+    /// the given time zone is appended to the file stem of the base path. For
+    /// the [`RotationPolicy::FileSize`] rotation policy, file names do not
+    /// depend on time, the base path is returned.
+    ///
+    /// It does not take [`RotatingFileSinkBuilder::date_directories`] into
+    /// account.
+    ///
+    /// This is a pure function, the file system is not accessed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::{
+    ///     path::Path,
+    ///     time::{Duration, SystemTime},
+    /// };
+    ///
+    /// use spdlog::{
+    ///     sink::{RotatingFileSink, RotationPolicy},
+    ///     TimeZone,
+    /// };
+    ///
+    /// // 2012-03-04 05:06:07 UTC
+    /// let time = SystemTime::UNIX_EPOCH + Duration::from_secs(1330837567);
+    /// let path = RotatingFileSink::file_path_of_time(
+    ///     "logs/app.log",
+    ///     RotationPolicy::Hourly,
+    ///     TimeZone::Utc,
+    ///     time,
+    /// );
+    /// assert_eq!(path, Path::new("logs/app_2012-03-04_05.log"));
+    /// ```
+    pub fn file_path_of_time<P>(
+        base_path: P,
+        rotation_policy: RotationPolicy,
+        time_zone: TimeZone,
+        time: SystemTime,
+    ) -> PathBuf
+    where
+        P: AsRef<Path>,
+    {
+        match rotation_policy {
+            RotationPolicy::FileSize(_) => base_path.as_ref().to_owned(),
+            RotationPolicy::Daily { hour, minute } => RotatorTimePoint::calc_file_path(
                 base_path,
+                TimePoint::Daily { hour, minute },
+                time_zone,
+                false,
+                time,
+            ),
+            RotationPolicy::Hourly => RotatorTimePoint::calc_file_path(
+                base_path,
+                TimePoint::Hourly,
+                time_zone,
+                false,
+                time,
+            ),
+        }
+    }
+
+    #[cfg(test)]
+    fn _current_size(&self) -> u64 {
+        if let RotatorKind::FileSize(rotator) = &self.rotator {
+            rotator.inner.lock().current_size
+        } else {
+            panic!();
+        }
+    }
+}
+
+/// The builder of [`RotatingFileSink`].
+///
+/// # Examples
+///
+/// ```
+/// use spdlog::{
+///     sink::{RotatingFileSink, RotationPolicy},
+///     TimeZone,
+/// };
+///
+/// # fn main() -> Result<(), spdlog::Error> {
+/// # let path = std::env::temp_dir().join("spdlog-rs-doctest").join("rotating_builder.log");
+/// // Rotates at midnight UTC, wherever the process is running.
+/// let sink: RotatingFileSink =
+///     RotatingFileSink::builder(path, RotationPolicy::Daily { hour: 0, minute: 0 })
+///         .max_files(7)
+///         .time_zone(TimeZone::Utc)
+///         .build()?;
+/// # Ok(()) }
+/// ```
+#[derive(Clone, Debug)]
+pub struct RotatingFileSinkBuilder {
+    base_path: PathBuf,
+    rotation_policy: RotationPolicy,
+    max_files: usize,
+    rotate_on_open: bool,
+    time_zone: TimeZone,
+    date_directories: bool,
+    line_ending: LineEnding,
+    name: Option<String>,
+}
+
+impl RotatingFileSinkBuilder {
+    /// Constructs a `RotatingFileSinkBuilder`.
+    ///
+    /// By default, there is no limit on the number of files, files are not
+    /// rotated on open, the local time zone is used, and the line ending is
+    /// [`LineEnding::native`].
+    pub fn new<P>(base_path: P, rotation_policy: RotationPolicy) -> Self
+    where
+        P: Into<PathBuf>,
+    {
+        Self {
+            base_path: base_path.into(),
+            rotation_policy,
+            max_files: 0,
+            rotate_on_open: false,
+            time_zone: TimeZone::Local,
+            date_directories: false,
+            line_ending: LineEnding::native(),
+            name: None,
+        }
+    }
+
+    /// Sets the maximum number of files.
+    ///
+    /// If the number of existing files reaches this parameter, the oldest file
+    /// will be deleted on the next rotation. Pass `0` for no limit.
+    #[must_use]
+    pub fn max_files(mut self, max_files: usize) -> Self {
+        self.max_files = max_files;
+        self
+    }
+
+    /// Specifies whether to rotate files once when building the sink.
+    ///
+    /// For the [`RotationPolicy::Daily`] and [`RotationPolicy::Hourly`]
+    /// rotation policies, it may truncate the contents of the existing file if
+    /// the parameter is `true`, since the file name is a time point and not an
+    /// index.
+    #[must_use]
+    pub fn rotate_on_open(mut self, rotate_on_open: bool) -> Self {
+        self.rotate_on_open = rotate_on_open;
+        self
+    }
+
+    /// Sets the time zone in which the rotation time points of
+    /// [`RotationPolicy::Daily`] and [`RotationPolicy::Hourly`] are computed.
+    ///
+    /// The time in the file names is also presented in this time zone. It has
+    /// no effect on [`RotationPolicy::FileSize`].
+    #[must_use]
+    pub fn time_zone(mut self, time_zone: TimeZone) -> Self {
+        self.time_zone = time_zone;
+        self
+    }
+
+    /// Specifies whether to place files in date directories.
+    ///
+    /// For the [`RotationPolicy::Daily`] and [`RotationPolicy::Hourly`]
+    /// rotation policies, files are placed in `YYYY/MM/DD` directories next
+    /// to the base path instead of having the date appended to their names,
+    /// e.g. `logs/2024/06/02/app.log` for the base path `logs/app.log`, or
+    /// `logs/2024/06/02/app_13.log` with the hour appended for
+    /// [`RotationPolicy::Hourly`]. Directories are created as needed, and
+    /// removed when they become empty after the files in them are deleted
+    /// because of [`max_files`].
+    ///
+    /// It helps large deployments to avoid thousands of files in one
+    /// directory. It has no effect on [`RotationPolicy::FileSize`].
+    ///
+    /// [`max_files`]: RotatingFileSinkBuilder::max_files
+    #[must_use]
+    pub fn date_directories(mut self, date_directories: bool) -> Self {
+        self.date_directories = date_directories;
+        self
+    }
+
+    /// Sets the line ending written after each record.
+    #[must_use]
+    pub fn line_ending(mut self, line_ending: LineEnding) -> Self {
+        self.line_ending = line_ending;
+        self
+    }
+
+    /// Sets the name of the sink, see [`Sink::name`].
+    #[must_use]
+    pub fn name<S>(mut self, name: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Builds a [`RotatingFileSink`].
+    ///
+    /// # Errors
+    ///
+    /// If the rotation policy is invalid, [`Error::InvalidArgument`] will be
+    /// returned. See the documentation of [`RotationPolicy`] for requirements.
+    /// It is also returned if another sink is writing to the base path.
+    ///
+    /// If an error occurs opening the file, [`Error::CreateDirectory`] or
+    /// [`Error::OpenFile`] will be returned.
+    pub fn build(self) -> Result<RotatingFileSink> {
+        self.rotation_policy.validate()?;
+        let path_claim = PathClaim::new(&self.base_path)?;
+
+        let rotator = match self.rotation_policy {
+            RotationPolicy::FileSize(max_size) => RotatorKind::FileSize(RotatorFileSize::new(
+                self.base_path,
                 max_size,
-                max_files,
-                rotate_on_open,
+                self.max_files,
+                self.rotate_on_open,
             )?),
             RotationPolicy::Daily { hour, minute } => {
                 RotatorKind::TimePoint(RotatorTimePoint::new(
-                    base_path,
+                    self.base_path,
                     TimePoint::Daily { hour, minute },
-                    max_files,
-                    rotate_on_open,
+                    self.time_zone,
+                    self.date_directories,
+                    self.max_files,
+                    self.rotate_on_open,
                 )?)
             }
             RotationPolicy::Hourly => RotatorKind::TimePoint(RotatorTimePoint::new(
-                base_path,
+                self.base_path,
                 TimePoint::Hourly,
-                max_files,
-                rotate_on_open,
+                self.time_zone,
+                self.date_directories,
+                self.max_files,
+                self.rotate_on_open,
             )?),
         };
 
-        let res = Self {
+        let res = RotatingFileSink {
             level_filter: Atomic::new(LevelFilter::All),
+            name: spin::RwLock::new(self.name.map(Arc::from)),
             formatter: spin::RwLock::new(Box::new(FullFormatter::new())),
+            destination: rotator.base_path().to_string_lossy().into_owned(),
             rotator,
+            line_ending: self.line_ending,
+            _path_claim: path_claim,
         };
 
         Ok(res)
     }
-
-    #[cfg(test)]
-    fn _current_size(&self) -> u64 {
-        if let RotatorKind::FileSize(rotator) = &self.rotator {
-            rotator.inner.lock().current_size
-        } else {
-            panic!();
-        }
-    }
 }
 
 impl Sink for RotatingFileSink {
@@ -188,8 +520,30 @@ impl Sink for RotatingFileSink {
         }
 
         let mut string_buf = StringBuf::new();
-        self.formatter.read().format(record, &mut string_buf)?;
-        self.rotator.log(record, &string_buf)
+        {
+            let name = self.name.read();
+            let ctx = FormatterContext::new("RotatingFileSink")
+                .with_sink_name(name.as_deref())
+                .with_destination(&self.destination);
+            profiling::measure(Stage::Format, || {
+                self.formatter.read().format(record, &mut string_buf, &ctx)
+            })?;
+        }
+        self.line_ending.apply(&mut string_buf);
+
+        // Reported after the locks are released, the self logger may write to this
+        // sink.
+        if self.rotator.log(record, &string_buf)? {
+            let path = self.current_path();
+            self_log::event(
+                Level::Info,
+                "RotatingFileSink",
+                "log file rotated",
+                &[Field::new("path", path.display().to_string())],
+                RecordFlags::empty(),
+            );
+        }
+        Ok(())
     }
 
     fn flush(&self) -> Result<()> {
@@ -204,10 +558,22 @@ impl Sink for RotatingFileSink {
         self.level_filter.store(level_filter, Ordering::Relaxed);
     }
 
+    fn name(&self) -> Option<Arc<str>> {
+        self.name.read().clone()
+    }
+
+    fn set_name(&self, name: Option<String>) {
+        *self.name.write() = name.map(Arc::from);
+    }
+
     fn swap_formatter(&self, mut formatter: Box<dyn Formatter>) -> Box<dyn Formatter> {
         mem::swap(&mut *self.formatter.write(), &mut formatter);
         formatter
     }
+
+    fn describe(&self) -> String {
+        format!("RotatingFileSink({})", self.current_path().display())
+    }
 }
 
 impl Drop for RotatingFileSink {
@@ -221,34 +587,101 @@ impl Drop for RotatingFileSink {
     }
 }
 
+// The number of attempts and the delay before the first retry for operations
+// on files in use by other processes, the delay is doubled for each retry.
+const IN_USE_ATTEMPTS: u32 = 5;
+const IN_USE_RETRY_DELAY: Duration = Duration::from_millis(10);
+
+// Whether the error is probably caused by another process having the file
+// opened (e.g. a tailing tool or an antivirus), which is transient. It only
+// happens on Windows, where files are opened without `FILE_SHARE_DELETE` by
+// most programs. ERROR_ACCESS_DENIED is not included, since it is usually
+// caused by permissions or read-only files, which retrying does not fix.
+fn is_in_use(err: &io::Error) -> bool {
+    // ERROR_SHARING_VIOLATION and ERROR_LOCK_VIOLATION
+    cfg!(windows) && matches!(err.raw_os_error(), Some(32 | 33))
+}
+
+// Retries the operation with backoff while the file is in use, and returns the
+// result of the last attempt with the number of attempts.
+fn retry_in_use<T>(op: impl FnMut() -> io::Result<T>) -> (io::Result<T>, u32) {
+    retry_with(op, is_in_use, IN_USE_RETRY_DELAY)
+}
+
+fn retry_with<T>(
+    mut op: impl FnMut() -> io::Result<T>,
+    is_transient: impl Fn(&io::Error) -> bool,
+    mut delay: Duration,
+) -> (io::Result<T>, u32) {
+    let mut attempts = 1;
+    loop {
+        match op() {
+            Err(err) if is_transient(&err) && attempts < IN_USE_ATTEMPTS => {
+                thread::sleep(delay);
+                delay *= 2;
+                attempts += 1;
+            }
+            res => return (res, attempts),
+        }
+    }
+}
+
+// Moves the file by renaming, which atomically replaces the destination. If the
+// file stays in use, falls back to copying and truncating it, which works if
+// the other process allows writing.
+fn move_file(from: &Path, to: &Path) -> Result<()> {
+    let (res, attempts) = retry_in_use(|| fs::rename(from, to));
+    let error = match res {
+        Ok(()) => return Ok(()),
+        Err(err) if !is_in_use(&err) => return Err(Error::RenameFile(err)),
+        Err(err) => err,
+    };
+
+    let fallback = || -> io::Result<()> {
+        fs::copy(from, to)?;
+        OpenOptions::new().write(true).open(from)?.set_len(0)
+    };
+    fallback().map_err(|fallback_error| {
+        Error::RotateFile(Box::new(RotateFileError {
+            from: from.to_owned(),
+            to: to.to_owned(),
+            attempts,
+            error,
+            fallback_error: Some(fallback_error),
+        }))
+    })
+}
+
 impl RotationPolicy {
-    fn validate(&self) {
+    fn validate(&self) -> Result<()> {
+        let reason = match self {
+            Self::FileSize(max_size) if *max_size == 0 => format!(
+                "(FileSize) expect `max_size` to be (0, u64::MAX] but {}",
+                *max_size
+            ),
+            Self::Daily { hour, minute } if *hour > 23 || *minute > 59 => format!(
+                "(Daily) expect (`hour`, `minute`) to be ([0, 23], [0, 59]) but ({}, {})",
+                *hour, *minute
+            ),
+            _ => return Ok(()),
+        };
+        Err(Error::InvalidArgument(
+            InvalidArgumentError::RotationPolicy(reason),
+        ))
+    }
+}
+
+impl RotatorKind {
+    fn base_path(&self) -> &Path {
         match self {
-            Self::FileSize(max_size) => {
-                if *max_size == 0 {
-                    panic!(
-                        "invalid rotation policy. (FileSize) \
-                         expect `max_size` to be (0, u64::MAX] but {}",
-                        *max_size
-                    );
-                }
-            }
-            Self::Daily { hour, minute } => {
-                if *hour > 23 || *minute > 59 {
-                    panic!(
-                        "invalid rotation policy. (Daily) \
-                         expect (`hour`, `minute`) to be ([0, 23], [0, 59]) but ({}, {})",
-                        *hour, *minute
-                    );
-                }
-            }
-            Self::Hourly => {}
+            Self::FileSize(rotator) => &rotator.base_path,
+            Self::TimePoint(rotator) => &rotator.base_path,
         }
     }
 }
 
 impl Rotator for RotatorKind {
-    fn log(&self, record: &Record, string_buf: &StringBuf) -> Result<()> {
+    fn log(&self, record: &Record, string_buf: &StringBuf) -> Result<bool> {
         match self {
             Self::FileSize(rotator) => rotator.log(record, string_buf),
             Self::TimePoint(rotator) => rotator.log(record, string_buf),
@@ -309,16 +742,13 @@ impl RotatorFileSize {
                 }
 
                 let dst = Self::calc_file_path(&self.base_path, i);
-                if dst.exists() {
-                    fs::remove_file(&dst).map_err(Error::RemoveFile)?;
-                }
-
-                fs::rename(src, dst).map_err(Error::RenameFile)?;
+                move_file(&src, &dst)?;
             }
             Ok(())
         };
 
         opened_file.file = None;
+        opened_file.rotation_count += 1;
 
         let res = inner();
         if res.is_err() {
@@ -356,6 +786,16 @@ impl RotatorFileSize {
         path
     }
 
+    fn files(&self) -> Vec<PathBuf> {
+        let mut files = vec![self.base_path.clone()];
+        files.extend(
+            (1..self.max_files)
+                .map(|index| Self::calc_file_path(&self.base_path, index))
+                .filter(|path| path.exists()),
+        );
+        files
+    }
+
     // if `self.inner.file` is `None`, try to reopen the file.
     fn lock_inner(&self) -> Result<spin::MutexGuard<RotatorFileSizeInner>> {
         let mut inner = self.inner.lock();
@@ -367,11 +807,12 @@ impl RotatorFileSize {
 }
 
 impl Rotator for RotatorFileSize {
-    fn log(&self, _record: &Record, string_buf: &StringBuf) -> Result<()> {
+    fn log(&self, _record: &Record, string_buf: &StringBuf) -> Result<bool> {
         let mut inner = self.lock_inner()?;
 
         inner.current_size += string_buf.len() as u64;
-        if inner.current_size > self.max_size {
+        let should_rotate = inner.current_size > self.max_size;
+        if should_rotate {
             self.rotate(&mut inner)?;
             inner.current_size = string_buf.len() as u64;
         }
@@ -381,7 +822,9 @@ impl Rotator for RotatorFileSize {
             .as_mut()
             .unwrap()
             .write_all(string_buf.as_bytes())
-            .map_err(Error::WriteRecord)
+            .map_err(Error::WriteRecord)?;
+
+        Ok(should_rotate)
     }
 
     fn flush(&self) -> Result<()> {
@@ -408,6 +851,7 @@ impl RotatorFileSizeInner {
         Self {
             file: Some(BufWriter::new(file)),
             current_size,
+            rotation_count: 0,
         }
     }
 }
@@ -416,22 +860,34 @@ impl RotatorTimePoint {
     fn new(
         base_path: PathBuf,
         time_point: TimePoint,
+        time_zone: TimeZone,
+        date_directories: bool,
         max_files: usize,
         truncate: bool,
     ) -> Result<Self> {
         let now = SystemTime::now();
-        let file_path = Self::calc_file_path(base_path.as_path(), time_point, now);
-        let file = utils::open_file(file_path, truncate)?;
+        let file_path = Self::calc_file_path(
+            base_path.as_path(),
+            time_point,
+            time_zone,
+            date_directories,
+            now,
+        );
+        let file = utils::open_file(&file_path, truncate)?;
 
         let inner = RotatorTimePointInner {
             file: BufWriter::new(file),
-            rotation_time_point: Self::next_rotation_time_point(time_point, now),
+            file_path,
+            rotation_time_point: Self::next_rotation_time_point(time_point, time_zone, now),
+            rotation_count: 0,
             file_paths: None,
         };
 
         let mut res = Self {
             base_path,
             time_point,
+            time_zone,
+            date_directories,
             max_files,
             inner: spin::Mutex::new(inner),
         };
@@ -446,7 +902,7 @@ impl RotatorTimePoint {
             let mut file_paths = LinkedList::new();
 
             for _ in 0..max_files {
-                let file_path = Self::calc_file_path(&self.base_path, self.time_point, now);
+                let file_path = self.file_path(now);
 
                 if !file_path.exists() {
                     break;
@@ -460,41 +916,48 @@ impl RotatorTimePoint {
         }
     }
 
-    // a little expensive, should only be called when rotation is needed or in
-    // constructor.
-    fn next_rotation_time_point(time_point: TimePoint, now: SystemTime) -> SystemTime {
-        let now: DateTime<Utc> = now.into();
-        let mut rotation_time: DateTime<Utc> = now;
+    fn files(&self) -> Vec<PathBuf> {
+        let inner = self.inner.lock();
 
-        match time_point {
-            TimePoint::Daily { hour, minute } => {
-                rotation_time = rotation_time
-                    .with_hour(hour)
-                    .unwrap()
-                    .with_minute(minute)
-                    .unwrap()
-                    .with_second(0)
-                    .unwrap()
-                    .with_nanosecond(0)
-                    .unwrap()
-            }
-            TimePoint::Hourly => {
-                rotation_time = rotation_time
-                    .with_minute(0)
-                    .unwrap()
-                    .with_second(0)
-                    .unwrap()
-                    .with_nanosecond(0)
-                    .unwrap()
+        if let Some(file_paths) = &inner.file_paths {
+            return file_paths.iter().rev().cloned().collect();
+        }
+
+        let mut files = vec![inner.file_path.clone()];
+        let mut time = inner.rotation_time_point;
+        // skips the time point of the current file
+        time = time.checked_sub(self.time_point.delta_std()).unwrap();
+        while let Some(previous) = time.checked_sub(self.time_point.delta_std()) {
+            time = previous;
+            let file_path = self.file_path(time);
+            if !file_path.exists() {
+                break;
             }
+            files.push(file_path);
+        }
+        files
+    }
+
+    // a little expensive, should only be called when rotation is needed or in
+    // constructor.
+    fn next_rotation_time_point(
+        time_point: TimePoint,
+        time_zone: TimeZone,
+        now: SystemTime,
+    ) -> SystemTime {
+        let wall_now = time_zone.wall_time(now);
+
+        let mut rotation_time = match time_point {
+            TimePoint::Daily { hour, minute } => wall_now.with_hms(hour, minute, 0),
+            TimePoint::Hourly => wall_now.with_hms(wall_now.hour, 0, 0),
         };
 
-        if rotation_time < now {
-            rotation_time = rotation_time
-                .checked_add_signed(time_point.delta_chrono())
-                .unwrap();
+        if rotation_time < wall_now {
+            // Adds the delta to the wall-clock time, so that daily rotation time points
+            // stay at the same time of day across daylight saving time transitions.
+            rotation_time = rotation_time.add_secs(time_point.delta_std().as_secs() as i64);
         }
-        rotation_time.into()
+        time_zone.system_time(rotation_time)
     }
 
     fn push_new_remove_old(
@@ -507,7 +970,12 @@ impl RotatorTimePoint {
         while file_paths.len() >= self.max_files {
             let old = file_paths.pop_front().unwrap();
             if old.exists() {
-                fs::remove_file(old).map_err(Error::RemoveFile)?;
+                retry_in_use(|| fs::remove_file(&old))
+                    .0
+                    .map_err(Error::RemoveFile)?;
+            }
+            if self.date_directories {
+                Self::remove_empty_date_directories(&old);
             }
         }
         file_paths.push_back(new);
@@ -515,13 +983,35 @@ impl RotatorTimePoint {
         Ok(())
     }
 
+    // Removes the day, month and year directories of a deleted file, as long as
+    // they are empty.
+    fn remove_empty_date_directories(file_path: &Path) {
+        for dir in file_path.ancestors().skip(1).take(3) {
+            if fs::remove_dir(dir).is_err() {
+                break;
+            }
+        }
+    }
+
+    fn file_path(&self, system_time: SystemTime) -> PathBuf {
+        Self::calc_file_path(
+            &self.base_path,
+            self.time_point,
+            self.time_zone,
+            self.date_directories,
+            system_time,
+        )
+    }
+
     fn calc_file_path(
         base_path: impl AsRef<Path>,
         time_point: TimePoint,
+        time_zone: TimeZone,
+        date_directories: bool,
         system_time: SystemTime,
     ) -> PathBuf {
         let base_path = base_path.as_ref();
-        let local_time: DateTime<Local> = system_time.into();
+        let local_time = time_zone.wall_time(system_time);
 
         let mut file_name = base_path
             .file_stem()
@@ -530,24 +1020,38 @@ impl RotatorTimePoint {
 
         let externsion = base_path.extension();
 
+        if date_directories {
+            if let TimePoint::Hourly = time_point {
+                // append h
+                file_name.push(format!("_{:02}", local_time.hour));
+            }
+
+            // place in y/m/d
+            let mut path = base_path.to_owned();
+            path.pop();
+            path.push(local_time.year.to_string());
+            path.push(format!("{:02}", local_time.month));
+            path.push(format!("{:02}", local_time.day));
+            path.push(file_name);
+            if let Some(externsion) = externsion {
+                path.set_extension(externsion);
+            }
+            return path;
+        }
+
         match time_point {
             TimePoint::Daily { .. } => {
                 // append y-m-d
                 file_name.push(format!(
                     "_{}-{:02}-{:02}",
-                    local_time.year(),
-                    local_time.month(),
-                    local_time.day()
+                    local_time.year, local_time.month, local_time.day
                 ));
             }
             TimePoint::Hourly => {
                 // append y-m-d_h
                 file_name.push(format!(
                     "_{}-{:02}-{:02}_{:02}",
-                    local_time.year(),
-                    local_time.month(),
-                    local_time.day(),
-                    local_time.hour()
+                    local_time.year, local_time.month, local_time.day, local_time.hour
                 ));
             }
         }
@@ -563,22 +1067,19 @@ impl RotatorTimePoint {
 }
 
 impl Rotator for RotatorTimePoint {
-    fn log(&self, record: &Record, string_buf: &StringBuf) -> Result<()> {
+    fn log(&self, record: &Record, string_buf: &StringBuf) -> Result<bool> {
         let mut inner = self.inner.lock();
 
-        let mut file_path = None;
         let record_time = record.time();
         let should_rotate = record_time >= inner.rotation_time_point;
 
         if should_rotate {
-            file_path = Some(Self::calc_file_path(
-                &self.base_path,
-                self.time_point,
-                record_time,
-            ));
-            inner.file = BufWriter::new(utils::open_file(file_path.as_ref().unwrap(), true)?);
+            let file_path = self.file_path(record_time);
+            inner.file = BufWriter::new(utils::open_file(&file_path, true)?);
+            inner.file_path = file_path;
+            inner.rotation_count += 1;
             inner.rotation_time_point =
-                Self::next_rotation_time_point(self.time_point, record_time);
+                Self::next_rotation_time_point(self.time_point, self.time_zone, record_time);
         }
 
         inner
@@ -587,10 +1088,11 @@ impl Rotator for RotatorTimePoint {
             .map_err(Error::WriteRecord)?;
 
         if should_rotate && inner.file_paths.is_some() {
-            self.push_new_remove_old(file_path.unwrap(), &mut inner)?;
+            let file_path = inner.file_path.clone();
+            self.push_new_remove_old(file_path, &mut inner)?;
         }
 
-        Ok(())
+        Ok(should_rotate)
     }
 
     fn flush(&self) -> Result<()> {
@@ -608,13 +1110,6 @@ impl TimePoint {
             Self::Hourly { .. } => HOUR_1,
         }
     }
-
-    fn delta_chrono(&self) -> chrono::Duration {
-        match self {
-            Self::Daily { .. } => chrono::Duration::days(1),
-            Self::Hourly { .. } => chrono::Duration::hours(1),
-        }
-    }
 }
 
 #[cfg(test)]
@@ -625,6 +1120,7 @@ mod tests {
 
     use std::sync::Arc;
 
+    use chrono::{DateTime, Local, NaiveDate, TimeZone as _, Timelike, Utc};
     use once_cell::sync::Lazy;
 
     static BASE_LOGS_PATH: Lazy<PathBuf> = Lazy::new(|| {
@@ -633,6 +1129,90 @@ mod tests {
         path
     });
 
+    #[test]
+    fn invalid_policy() {
+        for policy in [
+            RotationPolicy::FileSize(0),
+            RotationPolicy::Daily {
+                hour: 24,
+                minute: 0,
+            },
+            RotationPolicy::Daily {
+                hour: 0,
+                minute: 60,
+            },
+        ] {
+            let result =
+                RotatingFileSink::new(BASE_LOGS_PATH.join("invalid.log"), policy, 0, false);
+            assert!(matches!(
+                result,
+                Err(Error::InvalidArgument(
+                    InvalidArgumentError::RotationPolicy(_)
+                ))
+            ));
+        }
+    }
+
+    #[test]
+    fn retry_while_in_use() {
+        let in_use = || io::Error::new(io::ErrorKind::PermissionDenied, "in use");
+        let is_transient = |err: &io::Error| err.kind() == io::ErrorKind::PermissionDenied;
+
+        let mut calls = 0;
+        let (res, attempts) = retry_with(
+            || {
+                calls += 1;
+                if calls < 3 {
+                    Err(in_use())
+                } else {
+                    Ok(calls)
+                }
+            },
+            is_transient,
+            Duration::ZERO,
+        );
+        assert_eq!((res.unwrap(), attempts), (3, 3));
+
+        let (res, attempts) = retry_with(|| Err::<(), _>(in_use()), is_transient, Duration::ZERO);
+        assert!(res.is_err());
+        assert_eq!(attempts, IN_USE_ATTEMPTS);
+
+        let (res, attempts) = retry_with(
+            || Err::<(), _>(io::Error::other("fatal")),
+            is_transient,
+            Duration::ZERO,
+        );
+        assert!(res.is_err());
+        assert_eq!(attempts, 1);
+
+        let err = Error::RotateFile(Box::new(RotateFileError {
+            from: PathBuf::from("app.log"),
+            to: PathBuf::from("app_1.log"),
+            attempts: IN_USE_ATTEMPTS,
+            error: in_use(),
+            fallback_error: Some(io::Error::other("denied")),
+        }));
+        assert_eq!(
+            err.to_string(),
+            "rotate file error: cannot move app.log to app_1.log after 5 attempts: in use; copy fallback: denied"
+        );
+
+        assert!(!is_in_use(&io::Error::from_raw_os_error(5)));
+        assert_eq!(is_in_use(&io::Error::from_raw_os_error(32)), cfg!(windows));
+    }
+
+    #[test]
+    fn move_file_replaces() {
+        let from = BASE_LOGS_PATH.join("move_file_from.log");
+        let to = BASE_LOGS_PATH.join("move_file_to.log");
+        fs::write(&from, "new").unwrap();
+        fs::write(&to, "old").unwrap();
+
+        move_file(&from, &to).unwrap();
+        assert!(!from.exists());
+        assert_eq!(fs::read_to_string(&to).unwrap(), "new");
+    }
+
     mod policy_file_size {
         use super::*;
 
@@ -689,12 +1269,14 @@ mod tests {
                 }
 
                 let formatter = Box::new(NoModFormatter::new());
-                let sink = RotatingFileSink::new(
+                let sink = RotatingFileSink::builder(
                     LOGS_PATH.join(&base_path),
                     RotationPolicy::FileSize(16),
-                    3,
-                    rotate_on_open,
                 )
+                .max_files(3)
+                .rotate_on_open(rotate_on_open)
+                .line_ending(LineEnding::None)
+                .build()
                 .unwrap();
                 sink.set_formatter(formatter);
                 let sink = Arc::new(sink);
@@ -747,6 +1329,10 @@ mod tests {
                 info!(logger: logger, "{}", STR_4);
                 assert_eq!(files_exists_4(), (true, true, false, false));
                 assert_eq!(sink._current_size(), 4);
+
+                assert_eq!(sink.current_path(), base_path);
+                assert_eq!(sink.rotation_count(), 1);
+                assert_eq!(sink.files(), vec![index_to_path(0), index_to_path(1)]);
             }
             assert_eq!(
                 read_file_4(),
@@ -866,6 +1452,8 @@ mod tests {
                 RotatorTimePoint::calc_file_path(
                     base_path,
                     TimePoint::Daily { hour: 8, minute: 9 },
+                    TimeZone::Local,
+                    false,
                     system_time,
                 )
                 .to_str()
@@ -874,10 +1462,16 @@ mod tests {
             };
 
             let calc_hourly = |base_path| {
-                RotatorTimePoint::calc_file_path(base_path, TimePoint::Hourly, system_time)
-                    .to_str()
-                    .unwrap()
-                    .to_string()
+                RotatorTimePoint::calc_file_path(
+                    base_path,
+                    TimePoint::Hourly,
+                    TimeZone::Local,
+                    false,
+                    system_time,
+                )
+                .to_str()
+                .unwrap()
+                .to_string()
             };
 
             #[cfg(not(windows))]
@@ -902,6 +1496,132 @@ mod tests {
             run();
         }
 
+        fn utc_time(year: i32, month: u32, day: u32, hour: u32, min: u32, sec: u32) -> SystemTime {
+            let naive = NaiveDate::from_ymd_opt(year, month, day)
+                .unwrap()
+                .and_hms_opt(hour, min, sec)
+                .unwrap();
+            Utc.from_utc_datetime(&naive).into()
+        }
+
+        #[test]
+        fn calc_file_path_utc() {
+            let system_time = utc_time(2012, 3, 4, 23, 6, 7);
+
+            let calc = |time_point, date_directories| {
+                RotatorTimePoint::calc_file_path(
+                    Path::new("logs").join("test.log"),
+                    time_point,
+                    TimeZone::Utc,
+                    date_directories,
+                    system_time,
+                )
+            };
+            let daily = TimePoint::Daily { hour: 8, minute: 9 };
+
+            assert_eq!(
+                calc(daily, false),
+                Path::new("logs").join("test_2012-03-04.log")
+            );
+            assert_eq!(
+                calc(TimePoint::Hourly, false),
+                Path::new("logs").join("test_2012-03-04_23.log")
+            );
+            assert_eq!(
+                calc(daily, true),
+                Path::new("logs")
+                    .join("2012")
+                    .join("03")
+                    .join("04")
+                    .join("test.log")
+            );
+            assert_eq!(
+                calc(TimePoint::Hourly, true),
+                Path::new("logs")
+                    .join("2012")
+                    .join("03")
+                    .join("04")
+                    .join("test_23.log")
+            );
+        }
+
+        #[test]
+        fn date_directories() {
+            let logs_path = BASE_LOGS_PATH.join("policy_time_point_date_directories");
+            if logs_path.exists() {
+                fs::remove_dir_all(&logs_path).unwrap();
+            }
+
+            const DAY_1: Duration = Duration::from_secs(60 * 60 * 24);
+
+            let sink = Arc::new(
+                RotatingFileSink::builder(
+                    logs_path.join("app.log"),
+                    RotationPolicy::Daily { hour: 0, minute: 0 },
+                )
+                .time_zone(TimeZone::Utc)
+                .date_directories(true)
+                .max_files(2)
+                .build()
+                .unwrap(),
+            );
+            let logger = test_logger_builder().sink(sink.clone()).build();
+
+            let mut record = Record::new(Level::Info, "test log message");
+            let first_path = sink.current_path();
+            assert_eq!(first_path.file_name().unwrap(), "app.log");
+            assert_eq!(first_path.ancestors().nth(4).unwrap(), logs_path);
+            assert!(first_path.exists());
+
+            // Moves to a year far in the future, so that it does not share the
+            // year directory with the first file.
+            record.set_time(record.time() + DAY_1 * 366 * 2);
+            logger.log(&record);
+            let second_path = sink.current_path();
+            assert!(second_path.exists());
+            assert_ne!(
+                first_path.ancestors().nth(3),
+                second_path.ancestors().nth(3)
+            );
+
+            // The first file is removed by `max_files`, and its empty
+            // directories too.
+            record.set_time(record.time() + DAY_1);
+            logger.log(&record);
+            assert!(!first_path.exists());
+            assert!(!first_path.ancestors().nth(3).unwrap().exists());
+            assert!(second_path.exists());
+            assert_eq!(sink.files().len(), 2);
+        }
+
+        #[test]
+        fn next_rotation_time_point() {
+            let now = utc_time(2012, 3, 4, 5, 6, 7);
+
+            let next = |time_point, time_zone| {
+                RotatorTimePoint::next_rotation_time_point(time_point, time_zone, now)
+            };
+
+            assert_eq!(
+                next(TimePoint::Daily { hour: 8, minute: 9 }, TimeZone::Utc),
+                utc_time(2012, 3, 4, 8, 9, 0)
+            );
+            assert_eq!(
+                next(TimePoint::Daily { hour: 1, minute: 2 }, TimeZone::Utc),
+                utc_time(2012, 3, 5, 1, 2, 0)
+            );
+            assert_eq!(
+                next(TimePoint::Hourly, TimeZone::Utc),
+                utc_time(2012, 3, 4, 6, 0, 0)
+            );
+
+            let local_now: DateTime<Local> = now.into();
+            let local_next: DateTime<Local> =
+                next(TimePoint::Daily { hour: 8, minute: 9 }, TimeZone::Local).into();
+            assert_eq!((local_next.hour(), local_next.minute()), (8, 9));
+            assert!(local_next > local_now);
+        }
+
         #[test]
         fn rotate() {
             let build = |rotate_on_open| {
@@ -991,5 +1711,61 @@ mod tests {
                 assert_eq!(exist_daily_files(), 2);
             }
         }
+
+        #[test]
+        fn accessors() {
+            let logs_path = BASE_LOGS_PATH.join("policy_time_point_accessors");
+            if logs_path.exists() {
+                fs::remove_dir_all(&logs_path).unwrap();
+            }
+
+            const HOUR_1: Duration = Duration::from_secs(60 * 60);
+
+            for max_files in [0, 2] {
+                let base_path = logs_path.join(format!("max_files_{}.log", max_files));
+                let sink = Arc::new(
+                    RotatingFileSink::builder(&base_path, RotationPolicy::Hourly)
+                        .max_files(max_files)
+                        .build()
+                        .unwrap(),
+                );
+                let logger = test_logger_builder().sink(sink.clone()).build();
+
+                let mut record = Record::new(Level::Info, "test log message");
+                let calc = |time| {
+                    RotatingFileSink::file_path_of_time(
+                        &base_path,
+                        RotationPolicy::Hourly,
+                        TimeZone::Local,
+                        time,
+                    )
+                };
+                let first_path = calc(record.time());
+
+                assert_eq!(sink.current_path(), first_path);
+                assert_eq!(sink.rotation_count(), 0);
+                assert_eq!(sink.files(), vec![first_path.clone()]);
+
+                record.set_time(record.time() + HOUR_1);
+                logger.log(&record);
+                let second_path = calc(record.time());
+
+                assert_eq!(sink.current_path(), second_path);
+                assert_eq!(sink.rotation_count(), 1);
+                assert_eq!(sink.files(), vec![second_path.clone(), first_path.clone()]);
+
+                record.set_time(record.time() + HOUR_1);
+                logger.log(&record);
+                let third_path = calc(record.time());
+
+                assert_eq!(sink.current_path(), third_path);
+                assert_eq!(sink.rotation_count(), 2);
+                if max_files == 0 {
+                    assert_eq!(sink.files(), vec![third_path, second_path, first_path]);
+                } else {
+                    assert_eq!(sink.files(), vec![third_path, second_path]);
+                }
+            }
+        }
     }
 }
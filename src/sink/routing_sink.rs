@@ -0,0 +1,291 @@
+//! Provides a sink routing records to dynamically created sinks.
+
+use std::{
+    borrow::Cow,
+    sync::{atomic::Ordering, Arc, Mutex},
+};
+
+use atomic::Atomic;
+
+use crate::{formatter::Formatter, sink::Sink, LevelFilter, Record, Result};
+
+type SinkFactory = dyn Fn(&str) -> Result<Arc<dyn Sink>> + Send + Sync;
+
+/// A sink that routes each record to one of many inner sinks, by a key of the
+/// record.
+///
+/// Inner sinks are created on demand by a factory function that receives the
+/// key, e.g. to write records of each tenant to `logs/{tenant}.log`. Only a
+/// limited number of inner sinks are kept open, the least recently used one
+/// is dropped when the limit is exceeded, and will be created again when a
+/// record with its key arrives.
+///
+/// Records are formatted by the inner sinks, so `RoutingSink` does not use a
+/// formatter itself. [`Sink::swap_formatter`] on it does nothing and returns
+/// the given formatter directly, set the formatter of inner sinks in the
+/// factory function instead.
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::Arc;
+///
+/// use spdlog::{
+///     prelude::*,
+///     sink::{FileSink, RoutingKey, RoutingSink, Sink},
+/// };
+///
+/// # let dir = std::env::temp_dir().join("spdlog-rs-doctest");
+/// let sink = RoutingSink::builder(RoutingKey::Field("tenant".into()), move |tenant| {
+///     let sink: Arc<dyn Sink> = Arc::new(FileSink::new(dir.join(format!("{}.log", tenant)), false)?);
+///     Ok(sink)
+/// })
+/// .max_open_sinks(16)
+/// .build();
+///
+/// let logger: Logger = Logger::builder().sink(Arc::new(sink)).build();
+/// ```
+pub struct RoutingSink {
+    level_filter: Atomic<LevelFilter>,
+    name: spin::RwLock<Option<Arc<str>>>,
+    key: RoutingKey,
+    factory: Box<SinkFactory>,
+    fallback: Option<Arc<dyn Sink>>,
+    max_open_sinks: usize,
+    // sorted from the least recently used to the most recently used
+    open_sinks: Mutex<Vec<(String, Arc<dyn Sink>)>>,
+}
+
+/// Keys of records used by [`RoutingSink`] to route them.
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub enum RoutingKey {
+    /// The name of the logger.
+    LoggerName,
+    /// The value of the field with the given key, values that are not strings
+    /// are converted by their [`Display`] implementation.
+    ///
+    /// [`Display`]: std::fmt::Display
+    Field(String),
+}
+
+impl RoutingSink {
+    /// Constructs a [`RoutingSinkBuilder`].
+    ///
+    /// The parameter `factory` is called with the key to create the inner sink
+    /// for it. Errors returned by it are returned from [`Sink::log`].
+    pub fn builder<F>(key: RoutingKey, factory: F) -> RoutingSinkBuilder
+    where
+        F: Fn(&str) -> Result<Arc<dyn Sink>> + Send + Sync + 'static,
+    {
+        RoutingSinkBuilder {
+            key,
+            factory: Box::new(factory),
+            fallback: None,
+            max_open_sinks: 64,
+        }
+    }
+
+    /// Gets the number of currently open inner sinks.
+    pub fn open_sinks_count(&self) -> usize {
+        self.open_sinks.lock().unwrap().len()
+    }
+
+    fn route_key<'a>(&self, record: &'a Record) -> Option<Cow<'a, str>> {
+        match &self.key {
+            RoutingKey::LoggerName => record.logger_name().map(Cow::Borrowed),
+            RoutingKey::Field(key) => {
+                record
+                    .fields()
+                    .iter()
+                    .find(|field| field.key() == key)
+                    .map(|field| match field.value().as_str() {
+                        Some(value) => Cow::Borrowed(value),
+                        None => Cow::Owned(field.value().to_string()),
+                    })
+            }
+        }
+    }
+
+    fn sink(&self, key: &str) -> Result<Arc<dyn Sink>> {
+        let mut open_sinks = self.open_sinks.lock().unwrap();
+
+        if let Some(index) = open_sinks.iter().position(|(k, _)| k == key) {
+            let entry = open_sinks.remove(index);
+            let sink = entry.1.clone();
+            open_sinks.push(entry);
+            return Ok(sink);
+        }
+
+        let sink = (self.factory)(key)?;
+        if self.max_open_sinks != 0 && open_sinks.len() >= self.max_open_sinks {
+            open_sinks.remove(0);
+        }
+        open_sinks.push((key.to_owned(), sink.clone()));
+        Ok(sink)
+    }
+
+    fn for_each_sink<F>(&self, f: F) -> Result<()>
+    where
+        F: Fn(&dyn Sink) -> Result<()>,
+    {
+        let open_sinks = self
+            .open_sinks
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(_, sink)| sink.clone())
+            .collect::<Vec<_>>();
+
+        let mut result = Ok(());
+        for sink in open_sinks.iter().chain(self.fallback.iter()) {
+            if let Err(err) = f(&**sink) {
+                result = Err(err);
+            }
+        }
+        result
+    }
+}
+
+impl Sink for RoutingSink {
+    fn log(&self, record: &Record) -> Result<()> {
+        if !self.should_log(record.level()) {
+            return Ok(());
+        }
+
+        match self.route_key(record) {
+            Some(key) => self.sink(&key)?.log(record),
+            None => match &self.fallback {
+                Some(fallback) => fallback.log(record),
+                None => Ok(()),
+            },
+        }
+    }
+
+    fn flush(&self) -> Result<()> {
+        self.for_each_sink(|sink| sink.flush())
+    }
+
+    fn shutdown(&self) -> Result<()> {
+        self.for_each_sink(|sink| sink.shutdown())
+    }
+
+    fn level_filter(&self) -> LevelFilter {
+        self.level_filter.load(Ordering::Relaxed)
+    }
+
+    fn set_level_filter(&self, level_filter: LevelFilter) {
+        self.level_filter.store(level_filter, Ordering::Relaxed);
+    }
+
+    fn name(&self) -> Option<Arc<str>> {
+        self.name.read().clone()
+    }
+
+    fn set_name(&self, name: Option<String>) {
+        *self.name.write() = name.map(Arc::from);
+    }
+
+    fn swap_formatter(&self, formatter: Box<dyn Formatter>) -> Box<dyn Formatter> {
+        formatter
+    }
+}
+
+/// The builder of [`RoutingSink`].
+pub struct RoutingSinkBuilder {
+    key: RoutingKey,
+    factory: Box<SinkFactory>,
+    fallback: Option<Arc<dyn Sink>>,
+    max_open_sinks: usize,
+}
+
+impl RoutingSinkBuilder {
+    /// Sets a sink for records that do not have the key.
+    ///
+    /// By default, such records are discarded.
+    #[must_use]
+    pub fn fallback(mut self, sink: Arc<dyn Sink>) -> Self {
+        self.fallback = Some(sink);
+        self
+    }
+
+    /// Sets the maximum number of open inner sinks.
+    ///
+    /// The default is 64. Pass `0` for no limit.
+    #[must_use]
+    pub fn max_open_sinks(mut self, max_open_sinks: usize) -> Self {
+        self.max_open_sinks = max_open_sinks;
+        self
+    }
+
+    /// Builds a [`RoutingSink`].
+    pub fn build(self) -> RoutingSink {
+        RoutingSink {
+            level_filter: Atomic::new(LevelFilter::All),
+            name: spin::RwLock::new(None),
+            key: self.key,
+            factory: self.factory,
+            fallback: self.fallback,
+            max_open_sinks: self.max_open_sinks,
+            open_sinks: Mutex::new(vec![]),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{prelude::*, test_utils::*};
+
+    use std::collections::HashMap;
+
+    #[test]
+    fn route() {
+        let created = Arc::new(Mutex::new(HashMap::<String, Vec<Arc<CounterSink>>>::new()));
+        let fallback = Arc::new(CounterSink::new());
+
+        let sink = {
+            let created = created.clone();
+            RoutingSink::builder(RoutingKey::Field("tenant".into()), move |tenant| {
+                let sink = Arc::new(CounterSink::new());
+                created
+                    .lock()
+                    .unwrap()
+                    .entry(tenant.to_owned())
+                    .or_default()
+                    .push(sink.clone());
+                Ok(sink)
+            })
+            .fallback(fallback.clone())
+            .max_open_sinks(2)
+            .build()
+        };
+        let logger = test_logger_builder().sink(Arc::new(sink)).build();
+
+        let log = |tenant: Option<&str>, payload: &str| {
+            let mut builder = Record::builder(Level::Info, payload.to_owned());
+            if let Some(tenant) = tenant {
+                builder = builder.field("tenant", tenant);
+            }
+            logger.log(&builder.build());
+        };
+
+        log(Some("a"), "1");
+        log(Some("b"), "2");
+        log(Some("a"), "3");
+        log(None, "4");
+        log(Some("c"), "5"); // evicts "b"
+        log(Some("b"), "6"); // evicts "a", creates "b" again
+
+        let created = created.lock().unwrap();
+        let payloads = |tenant: &str| {
+            created[tenant]
+                .iter()
+                .map(|sink| sink.payloads())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(payloads("a"), vec![vec!["1", "3"]]);
+        assert_eq!(payloads("b"), vec![vec!["2"], vec!["6"]]);
+        assert_eq!(payloads("c"), vec![vec!["5"]]);
+        assert_eq!(fallback.payloads(), vec!["4"]);
+    }
+}
@@ -0,0 +1,393 @@
+//! Provides a sink that creates a new file per session.
+
+use std::{
+    ffi::OsString,
+    fs,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::SystemTime,
+};
+
+use crate::{
+    formatter::Formatter,
+    sink::{FileSink, LineEnding, Sink},
+    Error, LevelFilter, Record, Result, TimeZone,
+};
+
+/// A sink with a new file as the target for each session (i.e. each run of
+/// the program).
+///
+/// The file name is the base path with a suffix appended, which is determined
+/// by [`SessionNaming`]. For example, with the base path `logs/log.txt`, the
+/// file of a session may be `logs/log_2024-05-01_12-00-00.txt` or
+/// `logs/log_3.txt`.
+///
+/// Optionally, files of old sessions can be removed so that only the last N
+/// sessions are kept, see [`SessionFileSinkBuilder::max_sessions`].
+///
+/// # Examples
+///
+/// ```
+/// use spdlog::sink::{SessionFileSink, SessionNaming};
+///
+/// # fn main() -> Result<(), spdlog::Error> {
+/// # let path = std::env::temp_dir().join("spdlog-rs-doctest").join("session.log");
+/// let sink: SessionFileSink = SessionFileSink::builder(path)
+///     .naming(SessionNaming::Sequence)
+///     .max_sessions(10)
+///     .build()?;
+/// # Ok(()) }
+/// ```
+pub struct SessionFileSink {
+    file_sink: FileSink,
+    path: PathBuf,
+}
+
+/// Naming schemes of session files for [`SessionFileSink`].
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum SessionNaming {
+    /// Appends the time when the session started, e.g.
+    /// `log_2024-05-01_12-00-00.txt`.
+    ///
+    /// If two sessions start in the same second, they share the same file.
+    Time(TimeZone),
+    /// Appends a sequence number that is one greater than the largest existing
+    /// one, starting from 1, e.g. `log_3.txt`.
+    Sequence,
+}
+
+impl SessionFileSink {
+    /// Constructs a [`SessionFileSinkBuilder`].
+    pub fn builder<P>(base_path: P) -> SessionFileSinkBuilder
+    where
+        P: Into<PathBuf>,
+    {
+        SessionFileSinkBuilder::new(base_path)
+    }
+
+    /// Gets the path of the file of the current session.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Sink for SessionFileSink {
+    fn log(&self, record: &Record) -> Result<()> {
+        self.file_sink.log(record)
+    }
+
+    fn flush(&self) -> Result<()> {
+        self.file_sink.flush()
+    }
+
+    fn level_filter(&self) -> LevelFilter {
+        self.file_sink.level_filter()
+    }
+
+    fn set_level_filter(&self, level_filter: LevelFilter) {
+        self.file_sink.set_level_filter(level_filter)
+    }
+
+    fn name(&self) -> Option<Arc<str>> {
+        self.file_sink.name()
+    }
+
+    fn set_name(&self, name: Option<String>) {
+        self.file_sink.set_name(name)
+    }
+
+    fn swap_formatter(&self, formatter: Box<dyn Formatter>) -> Box<dyn Formatter> {
+        self.file_sink.swap_formatter(formatter)
+    }
+
+    fn describe(&self) -> String {
+        format!("SessionFileSink({})", self.path.display())
+    }
+}
+
+/// The builder of [`SessionFileSink`].
+#[derive(Clone, Debug)]
+pub struct SessionFileSinkBuilder {
+    base_path: PathBuf,
+    naming: SessionNaming,
+    max_sessions: usize,
+    line_ending: LineEnding,
+    name: Option<String>,
+}
+
+impl SessionFileSinkBuilder {
+    /// Constructs a `SessionFileSinkBuilder`.
+    ///
+    /// By default, files are named by [`SessionNaming::Time`] in the local
+    /// time zone, files of old sessions are kept and the line ending is
+    /// [`LineEnding::native`].
+    pub fn new<P>(base_path: P) -> Self
+    where
+        P: Into<PathBuf>,
+    {
+        Self {
+            base_path: base_path.into(),
+            naming: SessionNaming::Time(TimeZone::Local),
+            max_sessions: 0,
+            line_ending: LineEnding::native(),
+            name: None,
+        }
+    }
+
+    /// Sets the naming scheme of session files.
+    #[must_use]
+    pub fn naming(mut self, naming: SessionNaming) -> Self {
+        self.naming = naming;
+        self
+    }
+
+    /// Sets the maximum number of session files to keep, including the current
+    /// one.
+    ///
+    /// When building the sink, if the number of session files exceeds it, the
+    /// oldest files will be removed. Pass `0` for no limit.
+    ///
+    /// Only files matching the naming scheme are counted.
+    #[must_use]
+    pub fn max_sessions(mut self, max_sessions: usize) -> Self {
+        self.max_sessions = max_sessions;
+        self
+    }
+
+    /// Sets the line ending written after each record.
+    #[must_use]
+    pub fn line_ending(mut self, line_ending: LineEnding) -> Self {
+        self.line_ending = line_ending;
+        self
+    }
+
+    /// Sets the name of the sink, see [`Sink::name`].
+    #[must_use]
+    pub fn name<S>(mut self, name: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Builds a [`SessionFileSink`].
+    ///
+    /// # Errors
+    ///
+    /// If an error occurs opening the file, [`Error::CreateDirectory`] or
+    /// [`Error::OpenFile`] will be returned. If an error occurs listing or
+    /// removing files of old sessions, [`Error::ReadDirectory`] or
+    /// [`Error::RemoveFile`] will be returned.
+    pub fn build(self) -> Result<SessionFileSink> {
+        let mut sessions = self.existing_sessions()?;
+
+        let suffix = match self.naming {
+            SessionNaming::Time(time_zone) => {
+                let time = time_zone.wall_time(SystemTime::now());
+                SessionSuffix::Time(format!(
+                    "{}-{:02}-{:02}_{:02}-{:02}-{:02}",
+                    time.year, time.month, time.day, time.hour, time.minute, time.second
+                ))
+            }
+            SessionNaming::Sequence => SessionSuffix::Sequence(
+                sessions
+                    .iter()
+                    .filter_map(|(suffix, _)| match suffix {
+                        SessionSuffix::Sequence(seq) => Some(*seq),
+                        SessionSuffix::Time(_) => None,
+                    })
+                    .max()
+                    .unwrap_or(0)
+                    + 1,
+            ),
+        };
+        let path = self.session_path(&suffix);
+
+        let file_sink = FileSink::builder(&path)
+            .line_ending(self.line_ending)
+            .build()?;
+        file_sink.set_name(self.name);
+
+        if self.max_sessions > 0 {
+            sessions.retain(|(_, session_path)| *session_path != path);
+            sessions.sort();
+
+            let remove_count = (sessions.len() + 1).saturating_sub(self.max_sessions);
+            for (_, session_path) in sessions.into_iter().take(remove_count) {
+                fs::remove_file(session_path).map_err(Error::RemoveFile)?;
+            }
+        }
+
+        Ok(SessionFileSink { file_sink, path })
+    }
+
+    fn session_path(&self, suffix: &SessionSuffix) -> PathBuf {
+        let mut file_name = self
+            .base_path
+            .file_stem()
+            .map(|s| s.to_owned())
+            .unwrap_or_else(|| OsString::from(""));
+
+        match suffix {
+            SessionSuffix::Time(time) => file_name.push(format!("_{}", time)),
+            SessionSuffix::Sequence(seq) => file_name.push(format!("_{}", seq)),
+        }
+
+        let mut path = self.base_path.clone();
+        path.set_file_name(file_name);
+        if let Some(extension) = self.base_path.extension() {
+            path.set_extension(extension);
+        }
+        path
+    }
+
+    fn existing_sessions(&self) -> Result<Vec<(SessionSuffix, PathBuf)>> {
+        let dir = match self.base_path.parent() {
+            Some(parent) if parent.as_os_str().is_empty() => Path::new("."),
+            Some(parent) => parent,
+            None => return Ok(vec![]),
+        };
+        if !dir.exists() {
+            return Ok(vec![]);
+        }
+
+        let prefix = format!(
+            "{}_",
+            self.base_path
+                .file_stem()
+                .map(|s| s.to_string_lossy())
+                .unwrap_or_default()
+        );
+        let extension = self
+            .base_path
+            .extension()
+            .map(|ext| format!(".{}", ext.to_string_lossy()))
+            .unwrap_or_default();
+
+        let mut sessions = vec![];
+        for entry in fs::read_dir(dir).map_err(Error::ReadDirectory)? {
+            let entry = entry.map_err(Error::ReadDirectory)?;
+            let file_name = entry.file_name();
+            let file_name = match file_name.to_str() {
+                Some(file_name) => file_name,
+                None => continue,
+            };
+
+            let suffix = match file_name
+                .strip_prefix(&prefix)
+                .and_then(|s| s.strip_suffix(&extension))
+            {
+                Some(suffix) => suffix,
+                None => continue,
+            };
+
+            let suffix = match self.naming {
+                SessionNaming::Time(_) if is_session_time(suffix) => {
+                    SessionSuffix::Time(suffix.to_owned())
+                }
+                SessionNaming::Time(_) => continue,
+                SessionNaming::Sequence => match suffix.parse() {
+                    Ok(seq) => SessionSuffix::Sequence(seq),
+                    Err(_) => continue,
+                },
+            };
+            sessions.push((suffix, entry.path()));
+        }
+        Ok(sessions)
+    }
+}
+
+// Checks whether the suffix looks like `2024-05-01_12-00-00`.
+fn is_session_time(suffix: &str) -> bool {
+    const PATTERN: &[u8] = b"dddd-dd-dd_dd-dd-dd";
+
+    suffix.len() == PATTERN.len()
+        && suffix
+            .bytes()
+            .zip(PATTERN)
+            .all(|(byte, &expected)| match expected {
+                b'd' => byte.is_ascii_digit(),
+                _ => byte == expected,
+            })
+}
+
+// Sorted from the oldest to the newest.
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Debug)]
+enum SessionSuffix {
+    Time(String),
+    Sequence(u64),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+
+    fn session_files(dir: &Path) -> Vec<String> {
+        let mut files = fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .collect::<Vec<_>>();
+        files.sort();
+        files
+    }
+
+    #[test]
+    fn sequence() {
+        let dir = TEST_LOGS_PATH.join("session_file_sink_sequence");
+        if dir.exists() {
+            fs::remove_dir_all(&dir).unwrap();
+        }
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("other.log"), "").unwrap();
+        fs::write(dir.join("session_x.log"), "").unwrap();
+
+        let build = || {
+            SessionFileSink::builder(dir.join("session.log"))
+                .naming(SessionNaming::Sequence)
+                .max_sessions(3)
+                .build()
+                .unwrap()
+        };
+
+        for seq in 1..=5 {
+            let sink = build();
+            assert_eq!(sink.path(), dir.join(format!("session_{}.log", seq)));
+        }
+
+        assert_eq!(
+            session_files(&dir),
+            vec![
+                "other.log",
+                "session_3.log",
+                "session_4.log",
+                "session_5.log",
+                "session_x.log"
+            ]
+        );
+    }
+
+    #[test]
+    fn time() {
+        let dir = TEST_LOGS_PATH.join("session_file_sink_time");
+        if dir.exists() {
+            fs::remove_dir_all(&dir).unwrap();
+        }
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("session_2000-01-01_00-00-00.log"), "").unwrap();
+        fs::write(dir.join("session_2000-01-02_00-00-00.log"), "").unwrap();
+
+        let sink = SessionFileSink::builder(dir.join("session.log"))
+            .naming(SessionNaming::Time(TimeZone::Utc))
+            .max_sessions(2)
+            .build()
+            .unwrap();
+
+        let file_name = sink.path().file_name().unwrap().to_str().unwrap();
+        assert_eq!(
+            session_files(&dir),
+            vec!["session_2000-01-02_00-00-00.log", file_name]
+        );
+    }
+}
@@ -3,17 +3,17 @@
 use std::{
     io::{self, Write},
     mem,
-    sync::atomic::Ordering,
+    sync::{atomic::Ordering, Arc},
 };
 
 use atomic::Atomic;
-use if_chain::if_chain;
 
 use crate::{
-    formatter::{Formatter, FullFormatter},
+    formatter::{FmtExtraInfo, Formatter, FormatterContext, FullFormatter},
+    profiling::{self, Stage},
     sink::Sink,
-    terminal_style::{LevelStyleCodes, Style, StyleMode},
-    Error, Level, LevelFilter, Record, Result, StringBuf,
+    terminal_style::{self, LevelStyleCodes, Style, StyleMode},
+    Error, Level, LevelFilter, Record, Result, StringBuf, EOL,
 };
 
 /// An enum representing the available standard streams.
@@ -77,13 +77,21 @@ impl_write_for_dest!(StdStreamDest<io::StdoutLock<'_>, io::StderrLock<'_>>);
 /// It writes styled text or plain text according to the given [`StyleMode`].
 ///
 /// Note that this sink always flushes the buffer once with each logging.
+///
+/// The output can be captured for testing by [`test::capture`].
+///
+/// [`test::capture`]: crate::test::capture
 pub struct StdStreamSink {
     level_filter: Atomic<LevelFilter>,
+    name: spin::RwLock<Option<Arc<str>>>,
     formatter: spin::RwLock<Box<dyn Formatter>>,
     dest: StdStreamDest<io::Stdout, io::Stderr>,
     atty_stream: atty::Stream,
     should_render_style: bool,
     level_style_codes: LevelStyleCodes,
+    priority_prefix: bool,
+    #[cfg(windows)]
+    wide_console: bool,
 }
 
 impl StdStreamSink {
@@ -96,11 +104,15 @@ impl StdStreamSink {
 
         StdStreamSink {
             level_filter: Atomic::new(LevelFilter::All),
+            name: spin::RwLock::new(None),
             formatter: spin::RwLock::new(Box::new(FullFormatter::new())),
             dest: StdStreamDest::new(std_stream),
             atty_stream,
-            should_render_style: Self::should_render_style(style_mode, atty_stream),
+            should_render_style: terminal_style::should_render_style(style_mode, atty_stream),
             level_style_codes: LevelStyleCodes::default(),
+            priority_prefix: false,
+            #[cfg(windows)]
+            wide_console: false,
         }
     }
 
@@ -111,15 +123,67 @@ impl StdStreamSink {
 
     /// Sets the style mode.
     pub fn set_style_mode(&mut self, style_mode: StyleMode) {
-        self.should_render_style = Self::should_render_style(style_mode, self.atty_stream);
+        self.should_render_style =
+            terminal_style::should_render_style(style_mode, self.atty_stream);
+    }
+
+    /// Sets whether to prefix each line with a `<N>` priority tag.
+    ///
+    /// The tags are the priority prefixes understood by systemd (see
+    /// `sd-daemon(3)`), so that a service logging to a std stream is captured
+    /// by journald with correct severities. Levels are mapped as:
+    ///
+    /// | Level    | Priority      |
+    /// |----------|---------------|
+    /// | critical | `<2>` crit    |
+    /// | error    | `<3>` err     |
+    /// | warn     | `<4>` warning |
+    /// | info     | `<6>` info    |
+    /// | debug    | `<7>` debug   |
+    /// | trace    | `<7>` debug   |
+    pub fn set_priority_prefix(&mut self, priority_prefix: bool) {
+        self.priority_prefix = priority_prefix;
     }
 
-    fn should_render_style(style_mode: StyleMode, atty_stream: atty::Stream) -> bool {
-        match style_mode {
-            StyleMode::Always => true,
-            StyleMode::Auto => atty::is(atty_stream) && enable_ansi_escape_sequences(),
-            StyleMode::Never => false,
+    /// Sets whether to write via the wide-char console API when the stream is
+    /// attached to a console.
+    ///
+    /// If `true`, text is written by `WriteConsoleW` as UTF-16, so non-ASCII
+    /// characters are displayed correctly regardless of the console code page.
+    /// Redirected streams (e.g. pipes and files) are still written as UTF-8
+    /// bytes.
+    #[cfg(windows)]
+    pub fn set_wide_console(&mut self, wide_console: bool) {
+        self.wide_console = wide_console;
+    }
+
+    fn write_formatted(
+        &self,
+        dest: impl Write,
+        record: &Record,
+        string_buf: &StringBuf,
+        extra_info: &FmtExtraInfo,
+    ) -> io::Result<()> {
+        let mut dest = PriorityPrefixWriter {
+            inner: dest,
+            prefix: self
+                .priority_prefix
+                .then(|| priority_prefix(record.level())),
+            at_line_start: true,
+        };
+
+        if self.should_render_style {
+            terminal_style::write_styled(
+                record,
+                string_buf,
+                extra_info,
+                &self.level_style_codes,
+                |text| dest.write_all(text.as_bytes()),
+            )?;
+        } else {
+            dest.write_all(string_buf.as_bytes())?;
         }
+        dest.write_all(EOL.as_bytes())
     }
 }
 
@@ -129,31 +193,42 @@ impl Sink for StdStreamSink {
             return Ok(());
         }
 
+        let destination = match self.atty_stream {
+            atty::Stream::Stdout => "stdout",
+            _ => "stderr",
+        };
+        let name = self.name.read();
+        let ctx = FormatterContext::new("StdStreamSink")
+            .with_sink_name(name.as_deref())
+            .with_destination(destination)
+            .with_style_supported(self.should_render_style);
         let mut string_buf = StringBuf::new();
 
-        let extra_info = self.formatter.read().format(record, &mut string_buf)?;
+        let extra_info = profiling::measure(Stage::Format, || {
+            self.formatter.read().format(record, &mut string_buf, &ctx)
+        })?;
+
+        if let Some(res) = crate::test::write_captured_output(|buf| {
+            self.write_formatted(buf, record, &string_buf, &extra_info)
+        }) {
+            return res.map_err(Error::WriteRecord);
+        }
 
         let mut dest = self.dest.lock();
 
-        (|| {
-            if_chain! {
-                if self.should_render_style;
-                if let Some(style_range) = extra_info.style_range();
-                then {
-                    let style_code = self.level_style_codes.code(record.level());
-
-                    dest.write_all(string_buf[..style_range.start].as_bytes())?;
-                    dest.write_all(style_code.start.as_bytes())?;
-                    dest.write_all(string_buf[style_range.start..style_range.end].as_bytes())?;
-                    dest.write_all(style_code.end.as_bytes())?;
-                    dest.write_all(string_buf[style_range.end..].as_bytes())?;
-                } else {
-                    dest.write_all(string_buf.as_bytes())?;
-                }
+        #[cfg(windows)]
+        if self.wide_console {
+            if let Some(console) = wide_console::WideConsoleWriter::new(self.atty_stream) {
+                // keep the order with contents buffered by `std::io::Stdout`
+                dest.flush().map_err(Error::FlushBuffer)?;
+                return self
+                    .write_formatted(console, record, &string_buf, &extra_info)
+                    .map_err(Error::WriteRecord);
             }
-            Ok(())
-        })()
-        .map_err(Error::WriteRecord)?;
+        }
+
+        self.write_formatted(&mut dest, record, &string_buf, &extra_info)
+            .map_err(Error::WriteRecord)?;
 
         // stderr is not buffered, so we don't need to flush it.
         // https://doc.rust-lang.org/std/io/fn.stderr.html
@@ -176,18 +251,176 @@ impl Sink for StdStreamSink {
         self.level_filter.store(level_filter, Ordering::Relaxed);
     }
 
+    fn name(&self) -> Option<Arc<str>> {
+        self.name.read().clone()
+    }
+
+    fn set_name(&self, name: Option<String>) {
+        *self.name.write() = name.map(Arc::from);
+    }
+
     fn swap_formatter(&self, mut formatter: Box<dyn Formatter>) -> Box<dyn Formatter> {
         mem::swap(&mut *self.formatter.write(), &mut formatter);
         formatter
     }
 }
 
+fn priority_prefix(level: Level) -> &'static str {
+    match level {
+        Level::Critical => "<2>",
+        Level::Error => "<3>",
+        Level::Warn => "<4>",
+        Level::Info => "<6>",
+        Level::Debug | Level::Trace => "<7>",
+    }
+}
+
+// Writes the prefix (if any) at the start of each line.
+struct PriorityPrefixWriter<W> {
+    inner: W,
+    prefix: Option<&'static str>,
+    at_line_start: bool,
+}
+
+impl<W: Write> Write for PriorityPrefixWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let prefix = match self.prefix {
+            Some(prefix) => prefix,
+            None => return self.inner.write(buf),
+        };
+
+        for line in buf.split_inclusive(|&byte| byte == b'\n') {
+            if self.at_line_start {
+                self.inner.write_all(prefix.as_bytes())?;
+            }
+            self.inner.write_all(line)?;
+            self.at_line_start = line.ends_with(b"\n");
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
 #[cfg(windows)]
-fn enable_ansi_escape_sequences() -> bool {
-    crossterm::ansi_support::supports_ansi()
+mod wide_console {
+    use std::{io, ptr};
+
+    use winapi::{
+        shared::minwindef::DWORD,
+        um::{
+            consoleapi::{GetConsoleMode, WriteConsoleW},
+            processenv::GetStdHandle,
+            winbase::{STD_ERROR_HANDLE, STD_OUTPUT_HANDLE},
+            winnt::HANDLE,
+        },
+    };
+
+    // Writes UTF-8 bytes to a console as UTF-16.
+    pub(super) struct WideConsoleWriter {
+        handle: HANDLE,
+    }
+
+    impl WideConsoleWriter {
+        // Returns `None` if the stream is not attached to a console.
+        pub(super) fn new(atty_stream: atty::Stream) -> Option<Self> {
+            let std_handle = match atty_stream {
+                atty::Stream::Stdout => STD_OUTPUT_HANDLE,
+                atty::Stream::Stderr => STD_ERROR_HANDLE,
+                atty::Stream::Stdin => return None,
+            };
+
+            let handle = unsafe { GetStdHandle(std_handle) };
+            let mut mode: DWORD = 0;
+            if handle.is_null() || unsafe { GetConsoleMode(handle, &mut mode) } == 0 {
+                return None;
+            }
+            Some(Self { handle })
+        }
+    }
+
+    impl io::Write for WideConsoleWriter {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            let wide: Vec<u16> = String::from_utf8_lossy(buf).encode_utf16().collect();
+
+            let mut remaining = &wide[..];
+            while !remaining.is_empty() {
+                let mut written: DWORD = 0;
+                let res = unsafe {
+                    WriteConsoleW(
+                        self.handle,
+                        remaining.as_ptr().cast(),
+                        remaining.len() as DWORD,
+                        &mut written,
+                        ptr::null_mut(),
+                    )
+                };
+                if res == 0 {
+                    return Err(io::Error::last_os_error());
+                }
+                remaining = &remaining[written as usize..];
+            }
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
 }
 
-#[cfg(not(windows))]
-fn enable_ansi_escape_sequences() -> bool {
-    true
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::terminal_style::Color;
+
+    #[test]
+    fn priority_prefix_writer() {
+        let mut writer = PriorityPrefixWriter {
+            inner: vec![],
+            prefix: Some(priority_prefix(Level::Warn)),
+            at_line_start: true,
+        };
+        writer.write_all(b"[warn] ").unwrap();
+        writer.write_all(b"line 1\n").unwrap();
+        writer.write_all(b"line 2\nline 3\n").unwrap();
+        assert_eq!(
+            String::from_utf8(writer.inner).unwrap(),
+            "<4>[warn] line 1\n<4>line 2\n<4>line 3\n"
+        );
+
+        let mut writer = PriorityPrefixWriter {
+            inner: vec![],
+            prefix: None,
+            at_line_start: true,
+        };
+        writer.write_all(b"line 1\nline 2\n").unwrap();
+        assert_eq!(String::from_utf8(writer.inner).unwrap(), "line 1\nline 2\n");
+    }
+
+    #[test]
+    fn record_style() {
+        let sink = StdStreamSink::new(StdStream::Stdout, StyleMode::Always);
+        let style = Style::builder().color(Color::Green).build();
+        let string_buf = StringBuf::from("[info] done");
+        let extra_info = FmtExtraInfo::builder().style_range(1..5).build();
+
+        let write = |record: &Record| {
+            let mut dest = vec![];
+            sink.write_formatted(&mut dest, record, &string_buf, &extra_info)
+                .unwrap();
+            String::from_utf8(dest).unwrap()
+        };
+
+        assert_eq!(
+            write(&Record::builder(Level::Info, "done").style(style).build()),
+            format!("\x1b[32m[info] done\x1b[m{}", EOL)
+        );
+        assert_eq!(
+            write(&Record::new(Level::Info, "done")),
+            format!("[\x1b[32minfo\x1b[m] done{}", EOL)
+        );
+    }
 }
@@ -0,0 +1,145 @@
+//! Provides a sink holding a weak reference to an inner sink.
+
+use std::sync::{atomic::Ordering, Arc, Weak};
+
+use atomic::Atomic;
+
+use crate::{formatter::Formatter, sink::Sink, LevelFilter, Record, Result};
+
+/// A sink that holds a weak reference to an inner sink, and skips records
+/// once the inner sink has been dropped.
+///
+/// It breaks reference cycles between loggers and sinks owned by other
+/// objects, e.g. a log window of a GUI application that owns the sink
+/// displaying records. When the window is closed and drops its sink, loggers
+/// referencing it stop writing to it, without having to remove it from every
+/// logger.
+///
+/// Records are formatted by the inner sink, so `WeakSink` does not use a
+/// formatter itself. [`Sink::swap_formatter`] on it does nothing and returns
+/// the given formatter directly, set the formatter of the inner sink instead.
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::Arc;
+///
+/// use spdlog::{
+///     prelude::*,
+///     sink::{Sink, StdStream, StdStreamSink},
+///     terminal_style::StyleMode,
+/// };
+///
+/// let window_sink: Arc<dyn Sink> =
+///     Arc::new(StdStreamSink::new(StdStream::Stdout, StyleMode::Auto));
+/// let logger: Logger = Logger::builder()
+///     .weak_sink(Arc::downgrade(&window_sink))
+///     .build();
+///
+/// info!(logger: logger, "displayed in the window");
+/// drop(window_sink);
+/// info!(logger: logger, "skipped");
+/// ```
+pub struct WeakSink {
+    level_filter: Atomic<LevelFilter>,
+    name: spin::RwLock<Option<Arc<str>>>,
+    sink: Weak<dyn Sink>,
+}
+
+impl WeakSink {
+    /// Constructs a `WeakSink`.
+    pub fn new(sink: Weak<dyn Sink>) -> WeakSink {
+        WeakSink {
+            level_filter: Atomic::new(LevelFilter::All),
+            name: spin::RwLock::new(None),
+            sink,
+        }
+    }
+
+    /// Gets the inner sink, or `None` if it has been dropped.
+    pub fn upgrade(&self) -> Option<Arc<dyn Sink>> {
+        self.sink.upgrade()
+    }
+
+    /// Returns `true` if the inner sink has been dropped.
+    pub fn is_dangling(&self) -> bool {
+        self.sink.strong_count() == 0
+    }
+}
+
+impl Sink for WeakSink {
+    fn log(&self, record: &Record) -> Result<()> {
+        if !self.should_log(record.level()) {
+            return Ok(());
+        }
+        match self.sink.upgrade() {
+            Some(sink) => sink.log(record),
+            None => Ok(()),
+        }
+    }
+
+    fn flush(&self) -> Result<()> {
+        match self.sink.upgrade() {
+            Some(sink) => sink.flush(),
+            None => Ok(()),
+        }
+    }
+
+    fn shutdown(&self) -> Result<()> {
+        match self.sink.upgrade() {
+            Some(sink) => sink.shutdown(),
+            None => Ok(()),
+        }
+    }
+
+    fn level_filter(&self) -> LevelFilter {
+        self.level_filter.load(Ordering::Relaxed)
+    }
+
+    fn set_level_filter(&self, level_filter: LevelFilter) {
+        self.level_filter.store(level_filter, Ordering::Relaxed);
+    }
+
+    fn name(&self) -> Option<Arc<str>> {
+        self.name.read().clone()
+    }
+
+    fn set_name(&self, name: Option<String>) {
+        *self.name.write() = name.map(Arc::from);
+    }
+
+    fn swap_formatter(&self, formatter: Box<dyn Formatter>) -> Box<dyn Formatter> {
+        formatter
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{prelude::*, test_utils::*};
+
+    #[test]
+    fn dangling() {
+        let counter_sink = Arc::new(CounterSink::new());
+        let weak_counter_sink = Arc::downgrade(&counter_sink);
+
+        let sink: Arc<dyn Sink> = counter_sink.clone();
+        let weak_sink = Arc::new(WeakSink::new(Arc::downgrade(&sink)));
+        let logger = test_logger_builder().sink(weak_sink.clone()).build();
+        drop(sink);
+
+        info!(logger: logger, "hello");
+        logger.flush();
+        assert!(!weak_sink.is_dangling());
+        assert_eq!(counter_sink.payloads(), vec!["hello"]);
+        assert_eq!(counter_sink.flush_count(), 1);
+
+        drop(counter_sink);
+        assert!(weak_sink.is_dangling());
+        assert!(weak_sink.upgrade().is_none());
+        assert!(weak_counter_sink.upgrade().is_none());
+
+        info!(logger: logger, "world");
+        logger.flush();
+    }
+}
@@ -1,38 +1,81 @@
-use std::{ffi::OsStr, iter::once, mem, os::windows::ffi::OsStrExt, sync::atomic::Ordering};
+use std::{
+    ffi::OsStr,
+    iter::once,
+    mem,
+    os::windows::ffi::OsStrExt,
+    sync::{atomic::Ordering, Arc},
+    time::{Duration, Instant},
+};
 
 use atomic::Atomic;
-use winapi::um::debugapi::OutputDebugStringW;
+use winapi::um::debugapi::{IsDebuggerPresent, OutputDebugStringW};
 
 use crate::{
-    formatter::{Formatter, FullFormatter},
+    formatter::{Formatter, FormatterContext, FullFormatter},
+    profiling::{self, Stage},
     sink::Sink,
-    LevelFilter, Record, Result, StringBuf,
+    LevelFilter, Record, Result, StringBuf, EOL,
 };
 
 /// A sink with a win32 API `OutputDebugStringW` as the target.
+///
+/// Optionally, it can skip formatting and outputting entirely when no debugger
+/// is attached, see [`WinDebugSinkBuilder::only_when_debugger_present`].
 pub struct WinDebugSink {
     level_filter: Atomic<LevelFilter>,
+    name: spin::RwLock<Option<Arc<str>>>,
     formatter: spin::RwLock<Box<dyn Formatter>>,
+    debugger_check: Option<DebuggerCheck>,
+}
+
+struct DebuggerCheck {
+    interval: Duration,
+    // (the time of the last check, whether a debugger was present)
+    last: spin::Mutex<Option<(Instant, bool)>>,
 }
 
 impl WinDebugSink {
     /// Constructs a `WinDebugSink`.
     pub fn new() -> WinDebugSink {
-        WinDebugSink {
-            level_filter: Atomic::new(LevelFilter::All),
-            formatter: spin::RwLock::new(Box::new(FullFormatter::new())),
+        WinDebugSink::builder().build()
+    }
+
+    /// Constructs a [`WinDebugSinkBuilder`].
+    pub fn builder() -> WinDebugSinkBuilder {
+        WinDebugSinkBuilder::new()
+    }
+
+    fn debugger_present(&self) -> bool {
+        let check = match &self.debugger_check {
+            Some(check) => check,
+            None => return true,
+        };
+
+        let mut last = check.last.lock();
+        match *last {
+            Some((checked_at, present)) if checked_at.elapsed() < check.interval => present,
+            _ => {
+                let present = unsafe { IsDebuggerPresent() } != 0;
+                *last = Some((Instant::now(), present));
+                present
+            }
         }
     }
 }
 
 impl Sink for WinDebugSink {
     fn log(&self, record: &Record) -> Result<()> {
-        if !self.should_log(record.level()) {
+        if !self.should_log(record.level()) || !self.debugger_present() {
             return Ok(());
         }
 
+        let name = self.name.read();
+        let ctx = FormatterContext::new("WinDebugSink").with_sink_name(name.as_deref());
         let mut string_buf = StringBuf::new();
-        self.formatter.read().format(record, &mut string_buf)?;
+        profiling::measure(Stage::Format, || {
+            self.formatter.read().format(record, &mut string_buf, &ctx)
+        })?;
+        string_buf.push_str(EOL);
 
         let wide: Vec<u16> = OsStr::new(&string_buf)
             .encode_wide()
@@ -57,6 +100,14 @@ impl Sink for WinDebugSink {
         self.level_filter.store(level_filter, Ordering::Relaxed);
     }
 
+    fn name(&self) -> Option<Arc<str>> {
+        self.name.read().clone()
+    }
+
+    fn set_name(&self, name: Option<String>) {
+        *self.name.write() = name.map(Arc::from);
+    }
+
     fn swap_formatter(&self, mut formatter: Box<dyn Formatter>) -> Box<dyn Formatter> {
         mem::swap(&mut *self.formatter.write(), &mut formatter);
         formatter
@@ -68,3 +119,101 @@ impl Default for WinDebugSink {
         Self::new()
     }
 }
+
+/// The builder of [`WinDebugSink`].
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use spdlog::{prelude::*, sink::WinDebugSink};
+///
+/// let sink: WinDebugSink = WinDebugSink::builder()
+///     .level_filter(LevelFilter::MoreSevereEqual(Level::Debug))
+///     .only_when_debugger_present(true)
+///     .debugger_check_interval(Duration::from_secs(5))
+///     .build();
+/// ```
+pub struct WinDebugSinkBuilder {
+    level_filter: LevelFilter,
+    formatter: Option<Box<dyn Formatter>>,
+    only_when_debugger_present: bool,
+    debugger_check_interval: Duration,
+}
+
+impl WinDebugSinkBuilder {
+    /// Constructs a `WinDebugSinkBuilder`.
+    ///
+    /// By default, the level filter is [`LevelFilter::All`], the formatter is
+    /// [`FullFormatter`] and records are output regardless of whether a
+    /// debugger is attached.
+    pub fn new() -> Self {
+        Self {
+            level_filter: LevelFilter::All,
+            formatter: None,
+            only_when_debugger_present: false,
+            debugger_check_interval: Duration::from_secs(1),
+        }
+    }
+
+    /// Sets the level filter.
+    #[must_use]
+    pub fn level_filter(mut self, level_filter: LevelFilter) -> Self {
+        self.level_filter = level_filter;
+        self
+    }
+
+    /// Sets the formatter.
+    #[must_use]
+    pub fn formatter(mut self, formatter: Box<dyn Formatter>) -> Self {
+        self.formatter = Some(formatter);
+        self
+    }
+
+    /// Specifies whether to skip records when no debugger is attached.
+    ///
+    /// If `true`, records are neither formatted nor passed to
+    /// `OutputDebugStringW` when `IsDebuggerPresent` returns false, which
+    /// avoids the overhead in runs without a debugger. The result of
+    /// `IsDebuggerPresent` is cached, see
+    /// [`WinDebugSinkBuilder::debugger_check_interval`].
+    #[must_use]
+    pub fn only_when_debugger_present(mut self, only_when_debugger_present: bool) -> Self {
+        self.only_when_debugger_present = only_when_debugger_present;
+        self
+    }
+
+    /// Sets the interval for refreshing the cached result of
+    /// `IsDebuggerPresent`.
+    ///
+    /// A debugger attached later is noticed within this interval. The default
+    /// is 1 second.
+    #[must_use]
+    pub fn debugger_check_interval(mut self, interval: Duration) -> Self {
+        self.debugger_check_interval = interval;
+        self
+    }
+
+    /// Builds a [`WinDebugSink`].
+    pub fn build(self) -> WinDebugSink {
+        WinDebugSink {
+            level_filter: Atomic::new(self.level_filter),
+            name: spin::RwLock::new(None),
+            formatter: spin::RwLock::new(
+                self.formatter
+                    .unwrap_or_else(|| Box::new(FullFormatter::new())),
+            ),
+            debugger_check: self.only_when_debugger_present.then(|| DebuggerCheck {
+                interval: self.debugger_check_interval,
+                last: spin::Mutex::new(None),
+            }),
+        }
+    }
+}
+
+impl Default for WinDebugSinkBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -3,12 +3,32 @@
 use std::path;
 
 /// Represents a location in source code.
+///
+/// # Pruning
+///
+/// Source locations recorded by log macros contain the full path of source
+/// files, which increases the binary size and may leak the layout of the
+/// workspace. Additional crate features prune them at compile time while
+/// keeping `source-location` enabled:
+///
+///  - `source-location-file-name` records only the file name, e.g. `main.rs`
+///    instead of `src/app/main.rs`.
+///
+///  - `source-location-hashed` records only a hashed ID of the file path and
+///    the line number, see [`SourceLocation::id`]. The file path, line and
+///    column numbers are not present in the binary at all, formatters write the
+///    ID instead, e.g. `#5f0b1ad3c0e6f3a2`.
+///
+/// Both features imply `source-location`. If both are enabled,
+/// `source-location-hashed` takes precedence. The module path is kept in all
+/// modes, as filters may depend on it.
 #[derive(Clone, Hash, Debug)]
 pub struct SourceLocation {
     module_path: &'static str,
     file: &'static str,
     line: u32,
     column: u32,
+    hashed_id: Option<u64>,
 }
 
 impl SourceLocation {
@@ -22,6 +42,19 @@ impl SourceLocation {
             file,
             line,
             column,
+            hashed_id: None,
+        }
+    }
+
+    // Used by `source_location_current!` with `source-location-hashed`.
+    #[doc(hidden)]
+    pub fn __hashed(module_path: &'static str, id: u64) -> Self {
+        Self {
+            module_path,
+            file: "",
+            line: 0,
+            column: 0,
+            hashed_id: Some(id),
         }
     }
 
@@ -32,7 +65,8 @@ impl SourceLocation {
 
     /// Gets the source file.
     ///
-    /// It returns a string slice like this: `src/main.rs`
+    /// It returns a string slice like this: `src/main.rs`, or an empty string
+    /// if the location is hashed.
     pub fn file(&self) -> &'static str {
         self.file
     }
@@ -48,20 +82,98 @@ impl SourceLocation {
         }
     }
 
-    /// Gets the line number in the source file.
+    /// Gets the line number in the source file, or 0 if the location is
+    /// hashed.
     pub fn line(&self) -> u32 {
         self.line
     }
 
-    /// Gets the column number in the source file.
+    /// Gets the column number in the source file, or 0 if the location is
+    /// hashed.
     pub fn column(&self) -> u32 {
         self.column
     }
+
+    /// Gets the ID of the location, a 64-bit FNV-1a hash of the file path and
+    /// the line number.
+    ///
+    /// For hashed locations, it is computed at compile time from the full path
+    /// given by [`file!`]. Otherwise it is computed from
+    /// [`SourceLocation::file`] and [`SourceLocation::line`].
+    pub fn id(&self) -> u64 {
+        self.hashed_id
+            .unwrap_or_else(|| __source_location_id(self.file, self.line))
+    }
+
+    /// Returns `true` if the location only contains a hashed ID, see the
+    /// [pruning](#pruning) section.
+    pub fn is_hashed(&self) -> bool {
+        self.hashed_id.is_some()
+    }
+}
+
+// Used by `source_location_current!`, computes `SourceLocation::id`.
+#[doc(hidden)]
+pub const fn __source_location_id(file: &str, line: u32) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+    const PRIME: u64 = 0x100000001b3;
+
+    let mut hash = OFFSET_BASIS;
+    let bytes = file.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        hash = (hash ^ bytes[i] as u64).wrapping_mul(PRIME);
+        i += 1;
+    }
+    let line = line.to_le_bytes();
+    let mut i = 0;
+    while i < line.len() {
+        hash = (hash ^ line[i] as u64).wrapping_mul(PRIME);
+        i += 1;
+    }
+    hash
+}
+
+// Used by `source_location_current!` with `source-location-file-name`, gets the
+// length of the file name in a path.
+#[doc(hidden)]
+pub const fn __file_name_len(file: &str) -> usize {
+    file.len() - file_name_start(file)
+}
+
+// Used by `source_location_current!` with `source-location-file-name`, copies
+// the file name out of a path, so that the path itself is not present in the
+// binary.
+#[doc(hidden)]
+pub const fn __file_name_bytes<const N: usize>(file: &str) -> [u8; N] {
+    let bytes = file.as_bytes();
+    let start = file_name_start(file);
+    let mut name = [0; N];
+    let mut i = 0;
+    while i < N {
+        name[i] = bytes[start + i];
+        i += 1;
+    }
+    name
+}
+
+const fn file_name_start(file: &str) -> usize {
+    let bytes = file.as_bytes();
+    let mut i = bytes.len();
+    while i > 0 {
+        if bytes[i - 1] == b'/' || bytes[i - 1] == b'\\' {
+            return i;
+        }
+        i -= 1;
+    }
+    0
 }
 
 /// Constructs a [`SourceLocation`] with current source location.
 ///
-/// Returns `None` if the feature `source_location` is not enabled.
+/// Returns `None` if the feature `source-location` is not enabled. See the
+/// [pruning](SourceLocation#pruning) section of [`SourceLocation`] for
+/// features changing what it contains.
 ///
 /// # Examples
 ///
@@ -79,7 +191,11 @@ macro_rules! source_location_current {
 
 #[macro_export]
 #[doc(hidden)]
-#[cfg(feature = "source-location")]
+#[cfg(all(
+    feature = "source-location",
+    not(feature = "source-location-file-name"),
+    not(feature = "source-location-hashed")
+))]
 macro_rules! __private_source_location_current_inner {
     () => {
         Some($crate::SourceLocation::new(
@@ -91,6 +207,39 @@ macro_rules! __private_source_location_current_inner {
     };
 }
 
+#[macro_export]
+#[doc(hidden)]
+#[cfg(all(
+    feature = "source-location-file-name",
+    not(feature = "source-location-hashed")
+))]
+macro_rules! __private_source_location_current_inner {
+    () => {{
+        const LEN: usize = $crate::__file_name_len(file!());
+        const BYTES: [u8; LEN] = $crate::__file_name_bytes::<LEN>(file!());
+        const FILE: &str = match ::std::str::from_utf8(&BYTES) {
+            Ok(file) => file,
+            Err(_) => "",
+        };
+        Some($crate::SourceLocation::new(
+            module_path!(),
+            FILE,
+            line!(),
+            column!(),
+        ))
+    }};
+}
+
+#[macro_export]
+#[doc(hidden)]
+#[cfg(feature = "source-location-hashed")]
+macro_rules! __private_source_location_current_inner {
+    () => {{
+        const ID: u64 = $crate::__source_location_id(file!(), line!());
+        Some($crate::SourceLocation::__hashed(module_path!(), ID))
+    }};
+}
+
 #[macro_export]
 #[doc(hidden)]
 #[cfg(not(feature = "source-location"))]
@@ -99,3 +248,33 @@ macro_rules! __private_source_location_current_inner {
         None
     };
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn file_name() {
+        const LEN: usize = __file_name_len("src/app/main.rs");
+        const BYTES: [u8; LEN] = __file_name_bytes::<LEN>("src/app/main.rs");
+        assert_eq!(&BYTES, b"main.rs");
+        assert_eq!(__file_name_len("src\\app\\lib.rs"), 6);
+        assert_eq!(__file_name_len("lib.rs"), 6);
+        assert_eq!(__file_name_len("src/"), 0);
+    }
+
+    #[test]
+    fn id() {
+        let srcloc = SourceLocation::new("app", "src/main.rs", 42, 5);
+        assert!(!srcloc.is_hashed());
+        assert_eq!(srcloc.id(), __source_location_id("src/main.rs", 42));
+        assert_ne!(srcloc.id(), __source_location_id("src/main.rs", 43));
+        assert_eq!(__source_location_id("", 0), 0x4d25767f9dce13f5);
+
+        const ID: u64 = __source_location_id("src/main.rs", 42);
+        let srcloc = SourceLocation::__hashed("app", ID);
+        assert!(srcloc.is_hashed());
+        assert_eq!(srcloc.id(), ID);
+        assert_eq!((srcloc.file(), srcloc.line()), ("", 0));
+    }
+}
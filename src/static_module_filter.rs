@@ -0,0 +1,175 @@
+//! Provides the compile time level filters of modules.
+
+use crate::{Level, LevelFilter};
+
+// Used at log macros.
+//
+// Returns the level filter for the module in the value of the environment
+// variable `SPDLOG_RS_STATIC_LEVEL` at compile time, which is a comma-separated
+// list of `module_path=level`. The longest module path that is the module
+// itself or one of its ancestors wins, `All` is returned if there is no match.
+//
+// It is a `const fn` so that macros can use it to compile out records. Invalid
+// values result in compile errors.
+#[doc(hidden)]
+pub const fn __static_module_level_filter(spec: Option<&str>, module_path: &str) -> LevelFilter {
+    let spec = match spec {
+        Some(spec) => spec.as_bytes(),
+        None => return LevelFilter::All,
+    };
+    let module_path = module_path.as_bytes();
+
+    let mut res = LevelFilter::All;
+    let mut res_path_len = 0;
+    let mut has_res = false;
+
+    let mut begin = 0;
+    while begin < spec.len() {
+        let mut end = begin;
+        while end < spec.len() && spec[end] != b',' {
+            end += 1;
+        }
+
+        let (path_begin, path_end, level_begin, level_end) = split_directive(spec, begin, end);
+        if path_begin != path_end || level_begin != level_end {
+            let path_len = path_end - path_begin;
+            if is_same_or_ancestor(spec, path_begin, path_end, module_path)
+                && (!has_res || path_len >= res_path_len)
+            {
+                res = parse_level_filter(spec, level_begin, level_end);
+                res_path_len = path_len;
+                has_res = true;
+            }
+        }
+
+        begin = end + 1;
+    }
+
+    res
+}
+
+// Splits `module_path=level` in `spec[begin..end]`, returns the trimmed ranges
+// of the module path and the level.
+const fn split_directive(spec: &[u8], begin: usize, end: usize) -> (usize, usize, usize, usize) {
+    let mut eq = begin;
+    while eq < end && spec[eq] != b'=' {
+        eq += 1;
+    }
+    if eq == end {
+        let (begin, end) = trim(spec, begin, end);
+        if begin != end {
+            panic!("SPDLOG_RS_STATIC_LEVEL: expected `module_path=level`");
+        }
+        return (begin, end, begin, end);
+    }
+
+    let (path_begin, path_end) = trim(spec, begin, eq);
+    let (level_begin, level_end) = trim(spec, eq + 1, end);
+    if path_begin == path_end {
+        panic!("SPDLOG_RS_STATIC_LEVEL: the module path cannot be empty");
+    }
+    (path_begin, path_end, level_begin, level_end)
+}
+
+const fn trim(spec: &[u8], mut begin: usize, mut end: usize) -> (usize, usize) {
+    while begin < end && spec[begin].is_ascii_whitespace() {
+        begin += 1;
+    }
+    while begin < end && spec[end - 1].is_ascii_whitespace() {
+        end -= 1;
+    }
+    (begin, end)
+}
+
+const fn is_same_or_ancestor(spec: &[u8], begin: usize, end: usize, module_path: &[u8]) -> bool {
+    let len = end - begin;
+    if len > module_path.len() {
+        return false;
+    }
+
+    let mut i = 0;
+    while i < len {
+        if spec[begin + i] != module_path[i] {
+            return false;
+        }
+        i += 1;
+    }
+
+    len == module_path.len()
+        || (len + 1 < module_path.len() && module_path[len] == b':' && module_path[len + 1] == b':')
+}
+
+const fn parse_level_filter(spec: &[u8], begin: usize, end: usize) -> LevelFilter {
+    if eq_ignore_case(spec, begin, end, b"off") {
+        LevelFilter::Off
+    } else if eq_ignore_case(spec, begin, end, b"all") {
+        LevelFilter::All
+    } else if eq_ignore_case(spec, begin, end, b"critical") {
+        LevelFilter::MoreSevereEqual(Level::Critical)
+    } else if eq_ignore_case(spec, begin, end, b"error") {
+        LevelFilter::MoreSevereEqual(Level::Error)
+    } else if eq_ignore_case(spec, begin, end, b"warn") {
+        LevelFilter::MoreSevereEqual(Level::Warn)
+    } else if eq_ignore_case(spec, begin, end, b"info") {
+        LevelFilter::MoreSevereEqual(Level::Info)
+    } else if eq_ignore_case(spec, begin, end, b"debug") {
+        LevelFilter::MoreSevereEqual(Level::Debug)
+    } else if eq_ignore_case(spec, begin, end, b"trace") {
+        LevelFilter::MoreSevereEqual(Level::Trace)
+    } else {
+        panic!("SPDLOG_RS_STATIC_LEVEL: unknown level")
+    }
+}
+
+const fn eq_ignore_case(spec: &[u8], begin: usize, end: usize, expected: &[u8]) -> bool {
+    if end - begin != expected.len() {
+        return false;
+    }
+
+    let mut i = 0;
+    while i < expected.len() {
+        if spec[begin + i].to_ascii_lowercase() != expected[i] {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn module_level_filter() {
+        let filter = __static_module_level_filter;
+
+        assert_eq!(filter(None, "my_crate::hot"), LevelFilter::All);
+        assert_eq!(filter(Some(""), "my_crate::hot"), LevelFilter::All);
+
+        let spec = Some(" my_crate = DEBUG , my_crate::hot=warn,my_crate::hot::cold=all,");
+        assert_eq!(filter(spec, "other"), LevelFilter::All);
+        assert_eq!(
+            filter(spec, "my_crate"),
+            LevelFilter::MoreSevereEqual(Level::Debug)
+        );
+        assert_eq!(
+            filter(spec, "my_crate::hotter"),
+            LevelFilter::MoreSevereEqual(Level::Debug)
+        );
+        assert_eq!(
+            filter(spec, "my_crate::hot"),
+            LevelFilter::MoreSevereEqual(Level::Warn)
+        );
+        assert_eq!(
+            filter(spec, "my_crate::hot::inner"),
+            LevelFilter::MoreSevereEqual(Level::Warn)
+        );
+        assert_eq!(filter(spec, "my_crate::hot::cold"), LevelFilter::All);
+        assert_eq!(filter(Some("my_crate=off"), "my_crate"), LevelFilter::Off);
+
+        const FILTER: LevelFilter =
+            __static_module_level_filter(Some("spdlog=error"), module_path!());
+        assert_eq!(FILTER, LevelFilter::MoreSevereEqual(Level::Error));
+    }
+}
@@ -0,0 +1,253 @@
+//! Provides symbolization of hashed source locations.
+//!
+//! With the crate feature `source-location-hashed`, source locations in a
+//! binary are only IDs (see [`SourceLocation::id`]), so shipped binaries and
+//! their logs leak nothing about the source code. A [`SymbolMap`] maps the IDs
+//! back to file paths and line numbers. It is generated at build time and kept
+//! privately, e.g. with the debug symbols of a release, so that support can
+//! still resolve locations from logs.
+//!
+//! # Examples
+//!
+//! Generates the map in the build script of the crate logging with hashed
+//! locations:
+//!
+//! ```no_run
+//! // build.rs
+//! use std::{env, fs::File, path::Path};
+//!
+//! use spdlog::symbolization::SymbolMap;
+//!
+//! fn main() -> Result<(), Box<dyn std::error::Error>> {
+//!     let mut map = SymbolMap::new();
+//!     map.add_source_dir("src")?;
+//!
+//!     let out_dir = env::var("OUT_DIR")?;
+//!     map.write_to(File::create(Path::new(&out_dir).join("symbols.map"))?)?;
+//!     println!("cargo:rerun-if-changed=src");
+//!     Ok(())
+//! }
+//! ```
+//!
+//! Resolves locations of a log file afterwards:
+//!
+//! ```no_run
+//! use spdlog::symbolization::SymbolMap;
+//!
+//! # fn main() -> Result<(), spdlog::Error> {
+//! let map = SymbolMap::load("symbols.map")?;
+//! let log = std::fs::read_to_string("app.log").unwrap();
+//! // "[app, #5f0b1ad3c0e6f3a2]" becomes "[app, src/main.rs:42]"
+//! print!("{}", map.symbolize(&log));
+//! # Ok(()) }
+//! ```
+//!
+//! [`SourceLocation::id`]: crate::SourceLocation::id
+
+use std::{
+    collections::{hash_map::Entry, HashMap},
+    fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+use crate::{__source_location_id, Error, Result};
+
+/// A map from IDs of hashed source locations to file paths and line numbers.
+///
+/// See the [module level documentation](self) for details.
+#[derive(Clone, Default, Debug)]
+pub struct SymbolMap {
+    locations: HashMap<u64, (String, u32)>,
+}
+
+impl SymbolMap {
+    /// Constructs an empty `SymbolMap`.
+    pub fn new() -> SymbolMap {
+        SymbolMap::default()
+    }
+
+    /// Adds every line of the given file.
+    ///
+    /// `path` must be the path as given by [`file!`] in the file, which is
+    /// relative to the root of the package (or of the workspace, for members
+    /// of a workspace) when built by Cargo.
+    ///
+    /// # Errors
+    ///
+    /// If an error occurs reading the file, [`Error::ReadFile`] will be
+    /// returned.
+    pub fn add_source_file<P>(&mut self, path: P) -> Result<()>
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+        let source = fs::read_to_string(path).map_err(Error::ReadFile)?;
+        let file = path.to_string_lossy();
+        for line in 1..=source.lines().count() as u32 {
+            self.insert(__source_location_id(&file, line), &file, line);
+        }
+        Ok(())
+    }
+
+    /// Adds every line of the `.rs` files in the given directory, recursively.
+    ///
+    /// `dir` must be a prefix of paths as given by [`file!`], see
+    /// [`SymbolMap::add_source_file`].
+    ///
+    /// # Errors
+    ///
+    /// If an error occurs reading the directory or a file,
+    /// [`Error::ReadDirectory`] or [`Error::ReadFile`] will be returned.
+    pub fn add_source_dir<P>(&mut self, dir: P) -> Result<()>
+    where
+        P: AsRef<Path>,
+    {
+        let mut entries: Vec<PathBuf> = fs::read_dir(dir.as_ref())
+            .and_then(|entries| {
+                entries
+                    .map(|entry| entry.map(|entry| entry.path()))
+                    .collect()
+            })
+            .map_err(Error::ReadDirectory)?;
+        // Sorts for a deterministic result if IDs collide.
+        entries.sort();
+
+        for path in entries {
+            if path.is_dir() {
+                self.add_source_dir(&path)?;
+            } else if path.extension().is_some_and(|extension| extension == "rs") {
+                self.add_source_file(&path)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Resolves an ID into the file path and the line number.
+    pub fn resolve(&self, id: u64) -> Option<(&str, u32)> {
+        self.locations
+            .get(&id)
+            .map(|(file, line)| (file.as_str(), *line))
+    }
+
+    /// Replaces IDs written by formatters (`#` followed by 16 hexadecimal
+    /// digits) in the text with their `file:line`. Unknown IDs are kept.
+    pub fn symbolize(&self, text: &str) -> String {
+        let mut result = String::with_capacity(text.len());
+        let mut rest = text;
+        while let Some(index) = rest.find('#') {
+            result.push_str(&rest[..index]);
+            rest = &rest[index..];
+
+            let location = rest
+                .get(1..17)
+                .filter(|digits| digits.bytes().all(|byte| byte.is_ascii_hexdigit()))
+                .and_then(|digits| u64::from_str_radix(digits, 16).ok())
+                .and_then(|id| self.resolve(id));
+            match location {
+                Some((file, line)) => {
+                    result.push_str(file);
+                    result.push(':');
+                    result.push_str(&line.to_string());
+                    rest = &rest[17..];
+                }
+                None => {
+                    result.push('#');
+                    rest = &rest[1..];
+                }
+            }
+        }
+        result.push_str(rest);
+        result
+    }
+
+    /// Writes the map, one location per line as `<id in hex> <line> <file>`.
+    pub fn write_to<W>(&self, mut dest: W) -> io::Result<()>
+    where
+        W: Write,
+    {
+        let mut locations = self.locations.iter().collect::<Vec<_>>();
+        locations.sort_unstable_by_key(|(_, location)| *location);
+        for (id, (file, line)) in locations {
+            writeln!(dest, "{:016x} {} {}", id, line, file)?;
+        }
+        dest.flush()
+    }
+
+    /// Loads a map written by [`SymbolMap::write_to`].
+    ///
+    /// # Errors
+    ///
+    /// If an error occurs reading the file, or the file is malformed,
+    /// [`Error::ReadFile`] will be returned.
+    pub fn load<P>(path: P) -> Result<SymbolMap>
+    where
+        P: AsRef<Path>,
+    {
+        let content = fs::read_to_string(path).map_err(Error::ReadFile)?;
+
+        let mut map = SymbolMap::new();
+        for line in content.lines().filter(|line| !line.is_empty()) {
+            let mut parts = line.splitn(3, ' ');
+            let location = match (parts.next(), parts.next(), parts.next()) {
+                (Some(id), Some(number), Some(file)) => u64::from_str_radix(id, 16)
+                    .ok()
+                    .zip(number.parse::<u32>().ok())
+                    .map(|(id, number)| (id, number, file)),
+                _ => None,
+            };
+            let (id, number, file) = location.ok_or_else(|| {
+                Error::ReadFile(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("malformed symbol map line: {}", line),
+                ))
+            })?;
+            map.insert(id, file, number);
+        }
+        Ok(map)
+    }
+
+    // The first location wins if IDs collide.
+    fn insert(&mut self, id: u64, file: &str, line: u32) {
+        if let Entry::Vacant(entry) = self.locations.entry(id) {
+            entry.insert((file.to_owned(), line));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+
+    #[test]
+    fn symbolize() {
+        let dir = TEST_LOGS_PATH.join("symbolization");
+        fs::create_dir_all(dir.join("sub")).unwrap();
+        fs::write(dir.join("main.rs"), "fn main() {\n    info!(\"a\");\n}\n").unwrap();
+        fs::write(dir.join("sub").join("lib.rs"), "// lib\n").unwrap();
+        fs::write(dir.join("notes.txt"), "not source\n").unwrap();
+
+        let mut map = SymbolMap::new();
+        map.add_source_dir(&dir).unwrap();
+
+        let main_rs = dir.join("main.rs").to_string_lossy().into_owned();
+        let id = __source_location_id(&main_rs, 2);
+        assert_eq!(map.resolve(id), Some((main_rs.as_str(), 2)));
+        assert_eq!(map.locations.len(), 3 + 1);
+
+        let text = format!("[app, #{:016x}] a #{:016x} #1 #", id, id ^ 1);
+        assert_eq!(
+            map.symbolize(&text),
+            format!("[app, {}:2] a #{:016x} #1 #", main_rs, id ^ 1)
+        );
+
+        let path = dir.join("symbols.map");
+        map.write_to(fs::File::create(&path).unwrap()).unwrap();
+        let loaded = SymbolMap::load(&path).unwrap();
+        assert_eq!(loaded.locations, map.locations);
+
+        fs::write(&path, "xyz 1 src/main.rs\n").unwrap();
+        assert!(matches!(SymbolMap::load(&path), Err(Error::ReadFile(_))));
+    }
+}
@@ -4,7 +4,7 @@
 //!
 //! [ANSI escape code]: https://en.wikipedia.org/wiki/ANSI_escape_code#SGR_(Select_Graphic_Rendition)_parameters
 
-use crate::Level;
+use crate::{formatter::FmtExtraInfo, Level, Record, StringBuf};
 
 /// The terminal text color style.
 #[allow(missing_docs)]
@@ -271,3 +271,57 @@ impl Default for LevelStyleCodes {
         LevelStyles::default().into()
     }
 }
+
+// Returns whether to render styles for the stream in the style mode.
+pub(crate) fn should_render_style(style_mode: StyleMode, atty_stream: atty::Stream) -> bool {
+    match style_mode {
+        StyleMode::Always => true,
+        StyleMode::Auto => atty::is(atty_stream) && enable_ansi_escape_sequences(),
+        StyleMode::Never => false,
+    }
+}
+
+#[cfg(windows)]
+fn enable_ansi_escape_sequences() -> bool {
+    crossterm::ansi_support::supports_ansi()
+}
+
+#[cfg(not(windows))]
+fn enable_ansi_escape_sequences() -> bool {
+    true
+}
+
+// Writes the formatted record with style escape codes by pieces.
+//
+// The style of the record covers the whole record, otherwise the style of the
+// level covers the style range.
+pub(crate) fn write_styled<E>(
+    record: &Record,
+    string_buf: &StringBuf,
+    extra_info: &FmtExtraInfo,
+    level_style_codes: &LevelStyleCodes,
+    mut write: impl FnMut(&str) -> Result<(), E>,
+) -> Result<(), E> {
+    let text = &string_buf[..];
+
+    if let Some(style) = record.style() {
+        let style_code = style.code();
+
+        write(&style_code.start)?;
+        write(text)?;
+        return write(&style_code.end);
+    }
+
+    match extra_info.style_range() {
+        Some(style_range) => {
+            let style_code = level_style_codes.code(record.level());
+
+            write(&text[..style_range.start])?;
+            write(&style_code.start)?;
+            write(&text[style_range.start..style_range.end])?;
+            write(&style_code.end)?;
+            write(&text[style_range.end..])
+        }
+        None => write(text),
+    }
+}
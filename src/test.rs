@@ -0,0 +1,232 @@
+//! Provides utilities for testing code that logs.
+
+use std::{
+    cell::RefCell,
+    io, mem,
+    result::Result as StdResult,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+};
+
+use crate::Logger;
+
+// The number of isolated default loggers on all threads, so that getting the
+// default logger does not access the thread-local if none is active.
+static ISOLATIONS: AtomicUsize = AtomicUsize::new(0);
+
+thread_local! {
+    static ISOLATED_DEFAULT_LOGGER: RefCell<Option<Arc<Logger>>> = const { RefCell::new(None) };
+    static CAPTURED_OUTPUT: RefCell<Option<Vec<u8>>> = const { RefCell::new(None) };
+}
+
+/// Runs a closure with an isolated default logger on the current thread.
+///
+/// Within the closure, [`default_logger`], [`set_default_logger`] and
+/// [`swap_default_logger`] on the current thread operate on a fresh default
+/// logger instead of the global one, so tests running in parallel do not
+/// interfere with each other through the global default logger. The previous
+/// state is restored when the closure returns or panics.
+///
+/// The isolation is not inherited by threads spawned in the closure, they
+/// still use the global default logger.
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::Arc;
+///
+/// use spdlog::prelude::*;
+///
+/// # let test_logger = Arc::new(Logger::builder().build());
+/// spdlog::test::with_isolated_default_logger(|| {
+///     spdlog::set_default_logger(test_logger.clone());
+///     info!("this log will be handled by `test_logger`");
+/// });
+///
+/// info!("this log will be handled by the global default logger");
+/// ```
+///
+/// [`default_logger`]: crate::default_logger
+/// [`set_default_logger`]: crate::set_default_logger
+/// [`swap_default_logger`]: crate::swap_default_logger
+pub fn with_isolated_default_logger<F, R>(f: F) -> R
+where
+    F: FnOnce() -> R,
+{
+    struct Restore(Option<Arc<Logger>>);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            let previous = self.0.take();
+            ISOLATED_DEFAULT_LOGGER.with(|isolated| *isolated.borrow_mut() = previous);
+            ISOLATIONS.fetch_sub(1, Ordering::Relaxed);
+        }
+    }
+
+    let fresh = Arc::new(crate::new_default_logger());
+    // The thread-local is only read by the current thread, which observes its own
+    // increment.
+    ISOLATIONS.fetch_add(1, Ordering::Relaxed);
+    let _restore =
+        Restore(ISOLATED_DEFAULT_LOGGER.with(|isolated| isolated.borrow_mut().replace(fresh)));
+
+    f()
+}
+
+pub(crate) fn isolated_default_logger() -> Option<Arc<Logger>> {
+    if ISOLATIONS.load(Ordering::Relaxed) == 0 {
+        return None;
+    }
+    ISOLATED_DEFAULT_LOGGER.with(|isolated| isolated.borrow().clone())
+}
+
+// Returns the given logger back if there is no isolated default logger on the
+// current thread.
+pub(crate) fn swap_isolated_default_logger(
+    logger: Arc<Logger>,
+) -> StdResult<Arc<Logger>, Arc<Logger>> {
+    if ISOLATIONS.load(Ordering::Relaxed) == 0 {
+        return Err(logger);
+    }
+    ISOLATED_DEFAULT_LOGGER.with(|isolated| match isolated.borrow_mut().as_mut() {
+        Some(isolated) => Ok(mem::replace(isolated, logger)),
+        None => Err(logger),
+    })
+}
+
+/// Runs a closure and returns the text written by [`StdStreamSink`]s on the
+/// current thread during it.
+///
+/// While the closure is running, [`StdStreamSink`]s write to an in-memory
+/// buffer instead of `stdout` or `stderr` when logging on the current thread,
+/// which allows asserting on the output without capturing the stdio of the
+/// process. Output of both streams is captured into the same buffer in the
+/// order it was written.
+///
+/// If it is called within the closure of another `capture`, the output is
+/// captured only by the innermost one.
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::Arc;
+///
+/// use spdlog::{
+///     formatter::JsonFormatter,
+///     prelude::*,
+///     sink::{Sink, StdStream, StdStreamSink},
+///     terminal_style::StyleMode,
+/// };
+///
+/// let sink = StdStreamSink::new(StdStream::Stdout, StyleMode::Never);
+/// sink.set_formatter(Box::new(JsonFormatter::new()));
+/// let logger: Logger = Logger::builder().sink(Arc::new(sink)).build();
+///
+/// let output: String = spdlog::test::capture(|| {
+///     info!(logger: logger, "hello");
+/// });
+/// assert!(output.contains(r#""message":"hello""#));
+/// ```
+///
+/// [`StdStreamSink`]: crate::sink::StdStreamSink
+pub fn capture<F>(f: F) -> String
+where
+    F: FnOnce(),
+{
+    struct Restore(Option<Vec<u8>>);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            let previous = self.0.take();
+            CAPTURED_OUTPUT.with(|captured| *captured.borrow_mut() = previous);
+        }
+    }
+
+    let restore = Restore(CAPTURED_OUTPUT.with(|captured| captured.borrow_mut().replace(vec![])));
+
+    f();
+
+    let output = CAPTURED_OUTPUT.with(|captured| captured.borrow_mut().take().unwrap_or_default());
+    drop(restore);
+
+    String::from_utf8_lossy(&output).into_owned()
+}
+
+// Returns `None` if the output is not being captured on the current thread.
+pub(crate) fn write_captured_output(
+    write: impl FnOnce(&mut Vec<u8>) -> io::Result<()>,
+) -> Option<io::Result<()>> {
+    CAPTURED_OUTPUT.with(|captured| captured.borrow_mut().as_mut().map(write))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        prelude::*,
+        sink::{Sink, StdStream, StdStreamSink},
+        terminal_style::StyleMode,
+        test_utils::*,
+        EOL,
+    };
+
+    use std::panic;
+
+    #[test]
+    fn isolated_default_logger() {
+        let test_sink = Arc::new(CounterSink::new());
+        let test_logger = Arc::new(test_logger_builder().sink(test_sink.clone()).build());
+
+        with_isolated_default_logger(|| {
+            assert!(!Arc::ptr_eq(
+                &crate::default_logger(),
+                &crate::DEFAULT_LOGGER.load()
+            ));
+
+            crate::set_default_logger(test_logger.clone());
+            assert!(Arc::ptr_eq(&crate::default_logger(), &test_logger));
+            assert!(!Arc::ptr_eq(&crate::DEFAULT_LOGGER.load(), &test_logger));
+
+            // nested
+            with_isolated_default_logger(|| {
+                assert!(!Arc::ptr_eq(&crate::default_logger(), &test_logger));
+            });
+
+            info!("hello");
+        });
+
+        assert!(super::isolated_default_logger().is_none());
+        assert_eq!(test_sink.payloads(), vec!["hello"]);
+
+        let res = panic::catch_unwind(|| with_isolated_default_logger(|| panic!()));
+        assert!(res.is_err());
+        assert!(super::isolated_default_logger().is_none());
+    }
+
+    #[test]
+    fn capture_std_stream_sinks() {
+        let sinks: Vec<Arc<dyn Sink>> = [StdStream::Stdout, StdStream::Stderr]
+            .into_iter()
+            .map(|stream| {
+                let sink = StdStreamSink::new(stream, StyleMode::Never);
+                sink.set_formatter(Box::new(NoModFormatter::new()));
+                Arc::new(sink) as Arc<dyn Sink>
+            })
+            .collect();
+        let logger = test_logger_builder().sinks(sinks).build();
+
+        let output = capture(|| {
+            info!(logger: logger, "hello ");
+            let inner = capture(|| info!(logger: logger, "inner "));
+            assert_eq!(inner, "inner \ninner \n".replace('\n', EOL));
+            info!(logger: logger, "world ");
+        });
+        assert_eq!(
+            output,
+            "hello \nhello \nworld \nworld \n".replace('\n', EOL)
+        );
+        assert!(write_captured_output(|_| Ok(())).is_none());
+    }
+}
@@ -5,7 +5,7 @@ use std::{
     path::PathBuf,
     sync::{
         atomic::{AtomicUsize, Ordering},
-        Mutex,
+        Arc, Mutex,
     },
 };
 
@@ -13,9 +13,9 @@ use atomic::Atomic;
 use once_cell::sync::Lazy;
 
 use crate::{
-    formatter::{FmtExtraInfo, Formatter, FullFormatter},
+    formatter::{FmtExtraInfo, Formatter, FormatterContext, FullFormatter},
     sink::Sink,
-    Error, LevelFilter, LoggerBuilder, Record, Result, StringBuf,
+    Error, LevelFilter, LoggerBuilder, Record, RecordOwned, Result, StringBuf,
 };
 
 pub static TEST_LOGS_PATH: Lazy<PathBuf> = Lazy::new(|| {
@@ -30,10 +30,11 @@ pub static TEST_LOGS_PATH: Lazy<PathBuf> = Lazy::new(|| {
 
 pub struct CounterSink {
     level_filter: Atomic<LevelFilter>,
+    name: spin::RwLock<Option<Arc<str>>>,
     formatter: spin::RwLock<Box<dyn Formatter>>,
     log_counter: AtomicUsize,
     flush_counter: AtomicUsize,
-    payloads: Mutex<Vec<String>>,
+    records: Mutex<Vec<RecordOwned>>,
 }
 
 // no modifications formatter, it will write `record` to `dest` as is.
@@ -44,10 +45,11 @@ impl CounterSink {
     pub fn new() -> Self {
         Self {
             level_filter: Atomic::new(LevelFilter::All),
+            name: spin::RwLock::new(None),
             formatter: spin::RwLock::new(Box::new(FullFormatter::new())),
             log_counter: AtomicUsize::new(0),
             flush_counter: AtomicUsize::new(0),
-            payloads: Mutex::new(vec![]),
+            records: Mutex::new(vec![]),
         }
     }
 
@@ -60,13 +62,22 @@ impl CounterSink {
     }
 
     pub fn payloads(&self) -> Vec<String> {
-        self.payloads.lock().unwrap().clone()
+        self.records
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|record| record.payload().to_string())
+            .collect()
+    }
+
+    pub fn records(&self) -> Vec<RecordOwned> {
+        self.records.lock().unwrap().clone()
     }
 
     pub fn reset(&self) {
         self.log_counter.store(0, Ordering::Relaxed);
         self.flush_counter.store(0, Ordering::Relaxed);
-        self.payloads.lock().unwrap().clear();
+        self.records.lock().unwrap().clear();
     }
 }
 
@@ -74,10 +85,7 @@ impl Sink for CounterSink {
     fn log(&self, record: &Record) -> Result<()> {
         self.log_counter.fetch_add(1, Ordering::Relaxed);
 
-        self.payloads
-            .lock()
-            .unwrap()
-            .push(record.payload().to_string());
+        self.records.lock().unwrap().push(record.to_owned());
 
         Ok(())
     }
@@ -95,6 +103,14 @@ impl Sink for CounterSink {
         self.level_filter.store(level_filter, Ordering::Relaxed);
     }
 
+    fn name(&self) -> Option<Arc<str>> {
+        self.name.read().clone()
+    }
+
+    fn set_name(&self, name: Option<String>) {
+        *self.name.write() = name.map(Arc::from);
+    }
+
     fn swap_formatter(&self, mut formatter: Box<dyn Formatter>) -> Box<dyn Formatter> {
         mem::swap(&mut *self.formatter.write(), &mut formatter);
         formatter
@@ -114,7 +130,12 @@ impl NoModFormatter {
 }
 
 impl Formatter for NoModFormatter {
-    fn format(&self, record: &Record, dest: &mut StringBuf) -> Result<FmtExtraInfo> {
+    fn format(
+        &self,
+        record: &Record,
+        dest: &mut StringBuf,
+        _ctx: &FormatterContext,
+    ) -> Result<FmtExtraInfo> {
         dest.write_str(record.payload())
             .map_err(Error::FormatRecord)?;
 
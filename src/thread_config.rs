@@ -0,0 +1,447 @@
+//! Provides the configuration and the registry of background threads.
+
+use std::{
+    fmt,
+    sync::{Arc, Mutex},
+    thread,
+};
+
+use crate::Error;
+
+/// The configuration of background threads spawned by this crate.
+///
+/// Background threads are spawned by [`AsyncSink`] for writing records, and by
+/// periodic workers for flushing (see [`Logger::set_flush_period`]), closing
+/// idle files, sending digests and restoring boosted levels. Lowering their
+/// priority or pinning them to dedicated cores prevents them from competing
+/// with latency-critical threads of the application.
+///
+/// The priority and the core affinity are applied on a best-effort basis. They
+/// are supported on Linux and Windows, and ignored on other platforms. If
+/// applying them fails, the error is output to the terminal and the thread
+/// runs with the inherited settings.
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::Arc;
+///
+/// use spdlog::{
+///     sink::{AsyncSink, StdStream, StdStreamSink},
+///     terminal_style::StyleMode,
+///     ThreadConfig, ThreadPriority,
+/// };
+///
+/// # fn main() -> Result<(), spdlog::Error> {
+/// let config = ThreadConfig::new()
+///     .priority(ThreadPriority::Low)
+///     .affinity([0]);
+///
+/// // Applies to periodic workers spawned afterwards.
+/// spdlog::set_default_thread_config(config.clone());
+///
+/// // Applies to the thread of this sink only.
+/// let sink = AsyncSink::builder()
+///     .sink(Arc::new(StdStreamSink::new(StdStream::Stdout, StyleMode::Auto)))
+///     .thread_config(config.name("logging"))
+///     .build()?;
+/// # Ok(()) }
+/// ```
+///
+/// [`AsyncSink`]: crate::sink::AsyncSink
+/// [`Logger::set_flush_period`]: crate::Logger::set_flush_period
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub struct ThreadConfig {
+    name: Option<String>,
+    priority: ThreadPriority,
+    affinity: Option<Vec<usize>>,
+}
+
+impl ThreadConfig {
+    /// Constructs a `ThreadConfig`.
+    ///
+    /// By default, threads have their default names (e.g. `spdlog-rs
+    /// periodic`), have the normal priority and can run on any core.
+    pub const fn new() -> Self {
+        Self {
+            name: None,
+            priority: ThreadPriority::Normal,
+            affinity: None,
+        }
+    }
+
+    /// Sets the name of threads.
+    #[must_use]
+    pub fn name<S>(mut self, name: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Sets the priority of threads.
+    #[must_use]
+    pub fn priority(mut self, priority: ThreadPriority) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// Sets the indexes of the cores that threads are allowed to run on.
+    #[must_use]
+    pub fn affinity<I>(mut self, cores: I) -> Self
+    where
+        I: IntoIterator<Item = usize>,
+    {
+        self.affinity = Some(cores.into_iter().collect());
+        self
+    }
+
+    /// Gets the name of threads.
+    pub fn get_name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    /// Gets the priority of threads.
+    pub fn get_priority(&self) -> ThreadPriority {
+        self.priority
+    }
+
+    /// Gets the indexes of the cores that threads are allowed to run on.
+    pub fn get_affinity(&self) -> Option<&[usize]> {
+        self.affinity.as_deref()
+    }
+
+    // Spawns a thread with this configuration, and registers it until it
+    // exits. `stop` is called by `BackgroundThread::stop` to ask the thread to
+    // exit.
+    //
+    // Panics if the OS fails to create a thread, as `std::thread::spawn` does.
+    pub(crate) fn spawn<F, T, S>(
+        &self,
+        default_name: String,
+        stop: S,
+        f: F,
+    ) -> thread::JoinHandle<T>
+    where
+        F: FnOnce() -> T + Send + 'static,
+        T: Send + 'static,
+        S: Fn() + Send + Sync + 'static,
+    {
+        let name = self.name.clone().unwrap_or(default_name);
+        let priority = self.priority;
+        let affinity = self.affinity.clone();
+
+        // Holds the lock until the thread is registered, so that the thread
+        // cannot unregister itself before that.
+        let mut threads = THREADS.lock().unwrap();
+        let handle = thread::Builder::new()
+            .name(name.clone())
+            .spawn(move || {
+                let _guard = UnregisterGuard;
+                if let Err(err) = imp::apply(priority, affinity.as_deref()) {
+                    crate::default_error_handler("ThreadConfig", Error::ConfigureThread(err));
+                }
+                f()
+            })
+            .expect("failed to spawn thread");
+
+        threads.push(BackgroundThread {
+            name,
+            thread: handle.thread().clone(),
+            stop: Arc::new(stop),
+        });
+        handle
+    }
+}
+
+impl Default for ThreadConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The priority of background threads.
+///
+/// See [`ThreadConfig::priority`].
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum ThreadPriority {
+    /// The priority inherited from the spawning thread.
+    Normal,
+    /// A priority lower than normal, which is nice value 10 on Linux and
+    /// `THREAD_PRIORITY_BELOW_NORMAL` on Windows.
+    Low,
+}
+
+static DEFAULT_THREAD_CONFIG: spin::RwLock<ThreadConfig> = spin::RwLock::new(ThreadConfig::new());
+
+/// Sets the configuration of background threads that are not configured
+/// explicitly, i.e. threads of periodic workers and of [`AsyncSink`]s built
+/// without [`AsyncSinkBuilder::thread_config`].
+///
+/// Threads that have been spawned are not affected.
+///
+/// [`AsyncSink`]: crate::sink::AsyncSink
+/// [`AsyncSinkBuilder::thread_config`]: crate::sink::AsyncSinkBuilder::thread_config
+pub fn set_default_thread_config(config: ThreadConfig) {
+    *DEFAULT_THREAD_CONFIG.write() = config;
+}
+
+pub(crate) fn default_thread_config() -> ThreadConfig {
+    DEFAULT_THREAD_CONFIG.read().clone()
+}
+
+static THREADS: Mutex<Vec<BackgroundThread>> = Mutex::new(vec![]);
+
+struct UnregisterGuard;
+
+impl Drop for UnregisterGuard {
+    fn drop(&mut self) {
+        let id = thread::current().id();
+        let mut threads = THREADS.lock().unwrap_or_else(|err| err.into_inner());
+        threads.retain(|thread| thread.thread.id() != id);
+    }
+}
+
+/// A running background thread spawned by this crate.
+///
+/// Background threads are named after their owners by default, i.e.
+/// `spdlog-rs periodic` for periodic workers and `spdlog-rs async-sink N` for
+/// the `N`th [`AsyncSink`], so that they can be attributed in debuggers and
+/// thread dumps. The names can be changed by [`ThreadConfig::name`].
+///
+/// # Examples
+///
+/// ```
+/// for thread in spdlog::background_threads() {
+///     println!("{} ({:?})", thread.name(), thread.thread().id());
+/// }
+/// ```
+///
+/// [`AsyncSink`]: crate::sink::AsyncSink
+#[derive(Clone)]
+pub struct BackgroundThread {
+    name: String,
+    thread: thread::Thread,
+    stop: Arc<dyn Fn() + Send + Sync>,
+}
+
+impl BackgroundThread {
+    /// Gets the name.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Gets the handle of the thread.
+    pub fn thread(&self) -> &thread::Thread {
+        &self.thread
+    }
+
+    /// Asks the thread to exit, without waiting for it.
+    ///
+    /// The owner of the thread stops working in the background. A periodic
+    /// worker stops calling its task (e.g. a logger stops flushing
+    /// periodically), and an [`AsyncSink`] writes and flushes the buffered
+    /// records, and then discards records logged to it.
+    ///
+    /// It is useful for shutting down logging before the owners are dropped,
+    /// e.g. before `fork` or at the end of a process that leaks the default
+    /// logger.
+    ///
+    /// [`AsyncSink`]: crate::sink::AsyncSink
+    pub fn stop(&self) {
+        (self.stop)()
+    }
+}
+
+impl fmt::Debug for BackgroundThread {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("BackgroundThread")
+            .field("name", &self.name)
+            .field("thread", &self.thread)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Returns the running background threads spawned by this crate.
+///
+/// See [`BackgroundThread`].
+pub fn background_threads() -> Vec<BackgroundThread> {
+    THREADS.lock().unwrap().clone()
+}
+
+/// Asks all running background threads spawned by this crate to exit, without
+/// waiting for them.
+///
+/// See [`BackgroundThread::stop`].
+pub fn stop_background_threads() {
+    background_threads().iter().for_each(BackgroundThread::stop);
+}
+
+#[cfg(target_os = "linux")]
+mod imp {
+    use std::{io, mem};
+
+    use super::ThreadPriority;
+
+    pub(super) fn apply(priority: ThreadPriority, affinity: Option<&[usize]>) -> io::Result<()> {
+        if priority == ThreadPriority::Low {
+            // On Linux, the nice value is a per-thread attribute, so setting it for
+            // the thread ID only affects the current thread.
+            let tid = unsafe { libc::syscall(libc::SYS_gettid) } as libc::id_t;
+            if unsafe { libc::setpriority(libc::PRIO_PROCESS, tid, 10) } != 0 {
+                return Err(io::Error::last_os_error());
+            }
+        }
+
+        if let Some(cores) = affinity {
+            // SAFETY: `cpu_set_t` is a plain C struct, and all-zero is an empty set.
+            let mut set: libc::cpu_set_t = unsafe { mem::zeroed() };
+            for &core in cores {
+                if core >= libc::CPU_SETSIZE as usize {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        format!("core index {} out of range", core),
+                    ));
+                }
+                unsafe { libc::CPU_SET(core, &mut set) };
+            }
+            if unsafe { libc::sched_setaffinity(0, mem::size_of_val(&set), &set) } != 0 {
+                return Err(io::Error::last_os_error());
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(windows)]
+mod imp {
+    use std::io;
+
+    use winapi::um::{
+        processthreadsapi::{GetCurrentThread, SetThreadPriority},
+        winbase::{SetThreadAffinityMask, THREAD_PRIORITY_BELOW_NORMAL},
+    };
+
+    use super::ThreadPriority;
+
+    pub(super) fn apply(priority: ThreadPriority, affinity: Option<&[usize]>) -> io::Result<()> {
+        if priority == ThreadPriority::Low
+            && unsafe { SetThreadPriority(GetCurrentThread(), THREAD_PRIORITY_BELOW_NORMAL as _) }
+                == 0
+        {
+            return Err(io::Error::last_os_error());
+        }
+
+        if let Some(cores) = affinity {
+            let mut mask: usize = 0;
+            for &core in cores {
+                if core >= usize::BITS as usize {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        format!("core index {} out of range", core),
+                    ));
+                }
+                mask |= 1 << core;
+            }
+            if unsafe { SetThreadAffinityMask(GetCurrentThread(), mask as _) } == 0 {
+                return Err(io::Error::last_os_error());
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(not(any(target_os = "linux", windows)))]
+mod imp {
+    use std::io;
+
+    use super::ThreadPriority;
+
+    pub(super) fn apply(_priority: ThreadPriority, _affinity: Option<&[usize]>) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn spawn() {
+        let config = ThreadConfig::new()
+            .name("spdlog-rs test")
+            .priority(ThreadPriority::Low)
+            .affinity([0]);
+
+        let (name, _nice, _cores) = config
+            .spawn(
+                "default".to_string(),
+                || {},
+                || {
+                    let name = thread::current().name().map(str::to_owned);
+
+                    #[cfg(target_os = "linux")]
+                    {
+                        let tid = unsafe { libc::syscall(libc::SYS_gettid) } as libc::id_t;
+                        let nice = unsafe { libc::getpriority(libc::PRIO_PROCESS, tid) };
+
+                        let mut set: libc::cpu_set_t = unsafe { std::mem::zeroed() };
+                        unsafe {
+                            libc::sched_getaffinity(0, std::mem::size_of_val(&set), &mut set)
+                        };
+                        let cores = unsafe { libc::CPU_COUNT(&set) };
+
+                        (name, nice, cores)
+                    }
+                    #[cfg(not(target_os = "linux"))]
+                    (name, 0, 0)
+                },
+            )
+            .join()
+            .unwrap();
+
+        assert_eq!(name.as_deref(), Some("spdlog-rs test"));
+        #[cfg(target_os = "linux")]
+        {
+            assert!(_nice >= 10);
+            assert_eq!(_cores, 1);
+        }
+
+        let name = ThreadConfig::new()
+            .spawn(
+                "default".to_string(),
+                || {},
+                || thread::current().name().map(str::to_owned),
+            )
+            .join()
+            .unwrap();
+        assert_eq!(name.as_deref(), Some("default"));
+    }
+
+    #[test]
+    fn registry() {
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let sender = Mutex::new(sender);
+        let handle = ThreadConfig::new().spawn(
+            "spdlog-rs registry test".to_string(),
+            move || sender.lock().unwrap().send(()).unwrap(),
+            move || receiver.recv().unwrap(),
+        );
+        let id = handle.thread().id();
+
+        let thread = background_threads()
+            .into_iter()
+            .find(|thread| thread.thread().id() == id)
+            .unwrap();
+        assert_eq!(thread.name(), "spdlog-rs registry test");
+
+        thread.stop();
+        handle.join().unwrap();
+        assert!(background_threads()
+            .iter()
+            .all(|thread| thread.thread().id() != id));
+    }
+}
@@ -0,0 +1,341 @@
+//! Provides stuff related to time zones.
+
+use std::time::{Duration, SystemTime};
+
+/// An enum representing the time zone in which times are computed.
+///
+/// # Daylight saving time
+///
+/// When a local time is ambiguous (e.g. the clock is turned back at the end of
+/// daylight saving time), the earliest one is used. When a local time does not
+/// exist (e.g. the clock is turned forward at the start of daylight saving
+/// time), the first existing local time after it is used.
+///
+/// # Without `chrono`
+///
+/// If crate feature `chrono` is disabled, the local time zone is queried from
+/// the C library on Unix platforms and from the system on Windows. On other
+/// platforms, it is treated as UTC, which is reported once to the self-log
+/// channel (see [`SelfLogTarget`]).
+///
+/// [`SelfLogTarget`]: crate::SelfLogTarget
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum TimeZone {
+    /// The local time zone of the system.
+    Local,
+    /// Coordinated Universal Time.
+    Utc,
+}
+
+impl TimeZone {
+    // Converts a `SystemTime` to the wall-clock time in this time zone.
+    pub(crate) fn wall_time(self, system_time: SystemTime) -> WallTime {
+        let (secs, nanosecond) = unix_time(system_time);
+        match self {
+            Self::Local => WallTime::from_secs(secs + local_offset(secs), nanosecond),
+            Self::Utc => WallTime::from_secs(secs, nanosecond),
+        }
+    }
+
+    // Returns the offset in seconds of this time zone from UTC at the given
+    // time.
+    pub(crate) fn utc_offset(self, system_time: SystemTime) -> i64 {
+        match self {
+            Self::Local => local_offset(unix_time(system_time).0),
+            Self::Utc => 0,
+        }
+    }
+
+    // Converts a wall-clock time in this time zone to a `SystemTime`.
+    pub(crate) fn system_time(self, mut wall_time: WallTime) -> SystemTime {
+        let secs = match self {
+            Self::Local => loop {
+                let wall_secs = wall_time.to_secs();
+                // The offsets a day before and after cover the transition (if any) around it.
+                let found = [wall_secs - 86400, wall_secs + 86400]
+                    .into_iter()
+                    .map(|probe| wall_secs - local_offset(probe))
+                    .filter(|&secs| secs + local_offset(secs) == wall_secs)
+                    .min();
+                match found {
+                    Some(secs) => break secs,
+                    // skipped by a transition, try a later one
+                    None => wall_time = wall_time.add_secs(15 * 60),
+                }
+            },
+            Self::Utc => wall_time.to_secs(),
+        };
+
+        let time = if secs >= 0 {
+            SystemTime::UNIX_EPOCH + Duration::from_secs(secs as u64)
+        } else {
+            SystemTime::UNIX_EPOCH - Duration::from_secs(secs.unsigned_abs())
+        };
+        time + Duration::from_nanos(wall_time.nanosecond as u64)
+    }
+}
+
+// A wall-clock time without a time zone, in the proleptic Gregorian calendar.
+//
+// Fields are in the order of significance, so that the derived `Ord` compares
+// times chronologically.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Debug)]
+pub(crate) struct WallTime {
+    pub(crate) year: i32,
+    pub(crate) month: u32,
+    pub(crate) day: u32,
+    pub(crate) hour: u32,
+    pub(crate) minute: u32,
+    pub(crate) second: u32,
+    pub(crate) nanosecond: u32,
+}
+
+impl WallTime {
+    // Returns the time of the same day with the given hour, minute and second.
+    pub(crate) fn with_hms(self, hour: u32, minute: u32, second: u32) -> WallTime {
+        WallTime {
+            hour,
+            minute,
+            second,
+            nanosecond: 0,
+            ..self
+        }
+    }
+
+    pub(crate) fn add_secs(self, secs: i64) -> WallTime {
+        WallTime::from_secs(self.to_secs() + secs, self.nanosecond)
+    }
+
+    fn from_secs(secs: i64, nanosecond: u32) -> WallTime {
+        let (year, month, day) = civil_from_days(secs.div_euclid(86400));
+        let secs_of_day = secs.rem_euclid(86400) as u32;
+
+        WallTime {
+            year,
+            month,
+            day,
+            hour: secs_of_day / 3600,
+            minute: secs_of_day / 60 % 60,
+            second: secs_of_day % 60,
+            nanosecond,
+        }
+    }
+
+    fn to_secs(self) -> i64 {
+        days_from_civil(self.year, self.month, self.day) * 86400
+            + (self.hour * 3600 + self.minute * 60 + self.second) as i64
+    }
+}
+
+// Returns the seconds and the nanoseconds since the Unix epoch, the seconds
+// are floored for times before the epoch.
+pub(crate) fn unix_time(system_time: SystemTime) -> (i64, u32) {
+    match system_time.duration_since(SystemTime::UNIX_EPOCH) {
+        Ok(since) => (since.as_secs() as i64, since.subsec_nanos()),
+        Err(err) => {
+            let before = err.duration();
+            match before.subsec_nanos() {
+                0 => (-(before.as_secs() as i64), 0),
+                nanos => (-(before.as_secs() as i64) - 1, 1_000_000_000 - nanos),
+            }
+        }
+    }
+}
+
+// Algorithms from http://howardhinnant.github.io/date_algorithms.html
+fn civil_from_days(days: i64) -> (i32, u32, u32) {
+    let days = days + 719468;
+    let era = days.div_euclid(146097);
+    let day_of_era = days.rem_euclid(146097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = year_of_era + era * 400 + (month <= 2) as i64;
+    (year as i32, month, day)
+}
+
+fn days_from_civil(year: i32, month: u32, day: u32) -> i64 {
+    let year = year as i64 - (month <= 2) as i64;
+    let era = year.div_euclid(400);
+    let year_of_era = year.rem_euclid(400);
+    let mp = (month as i64 + 9) % 12;
+    let day_of_year = (153 * mp + 2) / 5 + day as i64 - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146097 + day_of_era - 719468
+}
+
+// Returns the offset in seconds of the local time zone from UTC at the given
+// Unix time.
+cfg_if::cfg_if! {
+    if #[cfg(feature = "chrono")] {
+        fn local_offset(secs: i64) -> i64 {
+            use chrono::{Local, Offset, TimeZone as _, Utc};
+
+            match Utc.timestamp_opt(secs, 0).single() {
+                Some(utc) => Local
+                    .offset_from_utc_datetime(&utc.naive_utc())
+                    .fix()
+                    .local_minus_utc() as i64,
+                None => 0,
+            }
+        }
+    } else if #[cfg(unix)] {
+        fn local_offset(secs: i64) -> i64 {
+            let time = secs as libc::time_t;
+            // SAFETY: `localtime_r` only writes to the given `tm`, which is a plain C struct.
+            let mut tm: libc::tm = unsafe { std::mem::zeroed() };
+            if unsafe { libc::localtime_r(&time, &mut tm) }.is_null() {
+                return 0;
+            }
+            tm.tm_gmtoff as i64
+        }
+    } else if #[cfg(windows)] {
+        fn local_offset(secs: i64) -> i64 {
+            use winapi::{
+                shared::minwindef::FILETIME,
+                um::{
+                    minwinbase::SYSTEMTIME,
+                    timezoneapi::{
+                        FileTimeToSystemTime, SystemTimeToFileTime, SystemTimeToTzSpecificLocalTime,
+                    },
+                },
+            };
+
+            // 100-nanosecond intervals between 1601-01-01 and 1970-01-01
+            const EPOCH_DIFF: i64 = 116_444_736_000_000_000;
+
+            let Some(utc_ticks) = secs
+                .checked_mul(10_000_000)
+                .and_then(|ticks| ticks.checked_add(EPOCH_DIFF))
+                .filter(|ticks| *ticks >= 0)
+            else {
+                return 0;
+            };
+            let utc_file_time = FILETIME {
+                dwLowDateTime: utc_ticks as u32,
+                dwHighDateTime: (utc_ticks >> 32) as u32,
+            };
+
+            // SAFETY: The functions only read and write the given plain C structs, and a null
+            // time zone means the current time zone of the system.
+            let local_ticks = unsafe {
+                let mut utc: SYSTEMTIME = std::mem::zeroed();
+                let mut local: SYSTEMTIME = std::mem::zeroed();
+                let mut local_file_time: FILETIME = std::mem::zeroed();
+                if FileTimeToSystemTime(&utc_file_time, &mut utc) == 0
+                    || SystemTimeToTzSpecificLocalTime(std::ptr::null(), &utc, &mut local) == 0
+                    || SystemTimeToFileTime(&local, &mut local_file_time) == 0
+                {
+                    return 0;
+                }
+                ((local_file_time.dwHighDateTime as i64) << 32)
+                    | local_file_time.dwLowDateTime as i64
+            };
+            (local_ticks - utc_ticks) / 10_000_000
+        }
+    } else {
+        fn local_offset(_secs: i64) -> i64 {
+            use std::sync::atomic::{AtomicBool, Ordering};
+
+            // Not `Once`, since the self-log may format times and call back here.
+            static REPORTED: AtomicBool = AtomicBool::new(false);
+
+            if !REPORTED.swap(true, Ordering::Relaxed) {
+                crate::self_log::event(
+                    crate::Level::Warn,
+                    "TimeZone",
+                    "the local time zone is unknown on this platform without crate feature \
+                     `chrono`, UTC is used instead",
+                    &[],
+                    crate::RecordFlags::empty(),
+                );
+            }
+            0
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use chrono::{DateTime, Datelike, Local, NaiveDate, TimeZone as _, Timelike, Utc};
+
+    #[test]
+    fn civil_conversion() {
+        for days in (-800_000..800_000).step_by(97) {
+            let date = NaiveDate::from_ymd_opt(1970, 1, 1).unwrap() + chrono::Duration::days(days);
+            assert_eq!(
+                civil_from_days(days),
+                (date.year(), date.month(), date.day())
+            );
+            assert_eq!(days_from_civil(date.year(), date.month(), date.day()), days);
+        }
+    }
+
+    #[test]
+    fn wall_time() {
+        let naive = NaiveDate::from_ymd_opt(2012, 3, 4)
+            .unwrap()
+            .and_hms_nano_opt(5, 6, 7, 890)
+            .unwrap();
+        let system_time: SystemTime = Utc.from_utc_datetime(&naive).into();
+
+        assert_eq!(
+            TimeZone::Utc.wall_time(system_time),
+            WallTime {
+                year: 2012,
+                month: 3,
+                day: 4,
+                hour: 5,
+                minute: 6,
+                second: 7,
+                nanosecond: 890
+            }
+        );
+
+        let local: DateTime<Local> = system_time.into();
+        let wall_time = TimeZone::Local.wall_time(system_time);
+        assert_eq!(
+            (wall_time.year, wall_time.month, wall_time.day),
+            (local.year(), local.month(), local.day())
+        );
+        assert_eq!(
+            (wall_time.hour, wall_time.minute, wall_time.second),
+            (local.hour(), local.minute(), local.second())
+        );
+
+        let before_epoch = SystemTime::UNIX_EPOCH - Duration::from_millis(1500);
+        assert_eq!(
+            TimeZone::Utc.wall_time(before_epoch),
+            WallTime {
+                year: 1969,
+                month: 12,
+                day: 31,
+                hour: 23,
+                minute: 59,
+                second: 58,
+                nanosecond: 500_000_000
+            }
+        );
+    }
+
+    #[test]
+    fn round_trip() {
+        let naive = NaiveDate::from_ymd_opt(2012, 3, 4)
+            .unwrap()
+            .and_hms_opt(5, 6, 7)
+            .unwrap();
+        let system_time: SystemTime = Utc.from_utc_datetime(&naive).into();
+
+        for time_zone in [TimeZone::Local, TimeZone::Utc] {
+            assert_eq!(
+                time_zone.system_time(time_zone.wall_time(system_time)),
+                system_time
+            );
+        }
+    }
+}
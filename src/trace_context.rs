@@ -0,0 +1,92 @@
+use crate::{Field, Record};
+
+// Returns the `trace_id` and `span_id` fields of the current span, or `None` if
+// there is no valid span or the record already carries a `trace_id` field.
+#[cfg(feature = "opentelemetry")]
+pub(crate) fn current_fields(record: &Record) -> Option<[Field<'static>; 2]> {
+    use opentelemetry::{trace::TraceContextExt, Context};
+
+    if record
+        .fields()
+        .iter()
+        .any(|field| field.key() == "trace_id")
+    {
+        return None;
+    }
+
+    let context = Context::current();
+    let span = context.span();
+    let span_context = span.span_context();
+    if !span_context.is_valid() {
+        return None;
+    }
+
+    Some([
+        Field::new("trace_id", span_context.trace_id().to_string()),
+        Field::new("span_id", span_context.span_id().to_string()),
+    ])
+}
+
+#[cfg(not(feature = "opentelemetry"))]
+pub(crate) fn current_fields(_record: &Record) -> Option<[Field<'static>; 2]> {
+    None
+}
+
+#[cfg(all(test, feature = "opentelemetry"))]
+mod tests {
+    use std::sync::Arc;
+
+    use opentelemetry::{
+        trace::{SpanContext, SpanId, TraceContextExt, TraceFlags, TraceId, TraceState},
+        Context,
+    };
+
+    use crate::{prelude::*, test_utils::*};
+
+    #[test]
+    fn inject() {
+        let test_sink = Arc::new(CounterSink::new());
+        let test_logger = test_logger_builder()
+            .sink(test_sink.clone())
+            .field("service", "billing")
+            .build();
+
+        let fields = || {
+            test_sink
+                .records()
+                .last()
+                .unwrap()
+                .fields()
+                .iter()
+                .map(|field| format!("{}={}", field.key(), field.value()))
+                .collect::<Vec<_>>()
+        };
+
+        info!(logger: test_logger, "outside");
+        assert_eq!(fields(), vec!["service=billing"]);
+
+        let span_context = SpanContext::new(
+            TraceId::from(0x4bf92f3577b34da6a3ce929d0e0e4736),
+            SpanId::from(0x00f067aa0ba902b7),
+            TraceFlags::SAMPLED,
+            true,
+            TraceState::NONE,
+        );
+        let _guard = Context::current()
+            .with_remote_span_context(span_context)
+            .attach();
+
+        info!(logger: test_logger, "inside");
+        assert_eq!(
+            fields(),
+            vec![
+                "service=billing",
+                "trace_id=4bf92f3577b34da6a3ce929d0e0e4736",
+                "span_id=00f067aa0ba902b7"
+            ]
+        );
+
+        info!(logger: test_logger, trace_id = "manual"; "propagated");
+        assert_eq!(fields(), vec!["service=billing", "trace_id=manual"]);
+    }
+}
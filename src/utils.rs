@@ -1,9 +1,54 @@
 use std::{
+    collections::HashSet,
+    env,
     fs::{self, File, OpenOptions},
-    path::Path,
+    path::{Path, PathBuf},
 };
 
-use crate::{Error, Result};
+use once_cell::sync::Lazy;
+
+use crate::{Error, InvalidArgumentError, Result};
+
+static CLAIMED_PATHS: Lazy<spin::Mutex<HashSet<PathBuf>>> = Lazy::new(Default::default);
+
+// A path written by a sink, released when the sink is dropped. Two sinks
+// writing to the same file would interleave or truncate each other's records.
+pub struct PathClaim(PathBuf);
+
+impl PathClaim {
+    pub fn new(path: impl AsRef<Path>) -> Result<PathClaim> {
+        let path = normalize_path(path.as_ref());
+        if !CLAIMED_PATHS.lock().insert(path.clone()) {
+            return Err(Error::InvalidArgument(InvalidArgumentError::Path(format!(
+                "{} is already written by another sink",
+                path.display()
+            ))));
+        }
+        Ok(PathClaim(path))
+    }
+}
+
+impl Drop for PathClaim {
+    fn drop(&mut self) {
+        CLAIMED_PATHS.lock().remove(&self.0);
+    }
+}
+
+// The file may not exist yet, so its parent directory is canonicalized if it
+// exists, otherwise the path is only made absolute.
+fn normalize_path(path: &Path) -> PathBuf {
+    let absolute = match env::current_dir() {
+        Ok(current_dir) => current_dir.join(path),
+        Err(_) => path.to_owned(),
+    };
+    match (absolute.parent(), absolute.file_name()) {
+        (Some(parent), Some(file_name)) => match fs::canonicalize(parent) {
+            Ok(parent) => parent.join(file_name),
+            Err(_) => absolute,
+        },
+        _ => absolute,
+    }
+}
 
 pub fn open_file(path: impl AsRef<Path>, truncate: bool) -> Result<File> {
     if let Some(parent) = path.as_ref().parent() {
@@ -25,3 +70,26 @@ pub fn open_file(path: impl AsRef<Path>, truncate: bool) -> Result<File> {
         .open(path)
         .map_err(Error::OpenFile)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+
+    #[test]
+    fn path_claim() {
+        let path = TEST_LOGS_PATH.join("utils_path_claim.log");
+        let claim = PathClaim::new(&path).unwrap();
+        let relative = path
+            .parent()
+            .unwrap()
+            .join(".")
+            .join("utils_path_claim.log");
+        assert!(matches!(
+            PathClaim::new(relative),
+            Err(Error::InvalidArgument(InvalidArgumentError::Path(_)))
+        ));
+        drop(claim);
+        PathClaim::new(&path).unwrap();
+    }
+}
@@ -0,0 +1,273 @@
+//! Provides request logging for web frameworks.
+//!
+//! It is framework-agnostic, middleware of a framework calls
+//! [`RequestLogger::start`] when a request arrives, and
+//! [`RequestScope::finish`] when the response is produced. Records are logged
+//! with fields of the same keys as [`CommonLogFormatter`] and
+//! [`CombinedLogFormatter`], so that sinks with these formatters write standard
+//! access logs, while other sinks write structured records as usual.
+//!
+//! # Examples
+//!
+//! Middleware of [axum] (`axum::middleware::from_fn`):
+//!
+//! ```ignore
+//! use std::sync::Arc;
+//!
+//! use axum::{extract::Request, middleware::Next, response::Response};
+//! use spdlog::web::RequestLogger;
+//!
+//! async fn log_requests(
+//!     request_logger: Arc<RequestLogger>,
+//!     request: Request,
+//!     next: Next,
+//! ) -> Response {
+//!     let mut scope = request_logger.start(request.method().as_str(), request.uri().path());
+//!     scope.field("protocol", format!("{:?}", request.version()));
+//!
+//!     let response = next.run(request).await;
+//!     scope.finish(response.status().as_u16(), None);
+//!     response
+//! }
+//! ```
+//!
+//! [`CommonLogFormatter`]: crate::formatter::CommonLogFormatter
+//! [`CombinedLogFormatter`]: crate::formatter::CombinedLogFormatter
+//! [axum]: https://docs.rs/axum
+
+use std::{
+    borrow::Cow,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use crate::{Field, Level, Logger, Record, Value};
+
+/// Logs HTTP requests to a logger.
+///
+/// See the [module level documentation](self) for details.
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::Arc;
+///
+/// use spdlog::{prelude::*, web::RequestLogger};
+///
+/// let request_logger =
+///     RequestLogger::new(spdlog::default_logger()).start_level(Some(Level::Debug));
+///
+/// let mut scope = request_logger.start("GET", "/index.html");
+/// scope.field("remote_addr", "127.0.0.1");
+/// scope.log(Level::Info, "cache miss");
+/// scope.finish(200, Some(2326));
+/// ```
+#[derive(Clone)]
+pub struct RequestLogger {
+    logger: Arc<Logger>,
+    level: Level,
+    start_level: Option<Level>,
+}
+
+impl RequestLogger {
+    /// Constructs a `RequestLogger` logging to the given logger.
+    ///
+    /// By default, finished requests are logged at [`Level::Info`], and the
+    /// start of requests is not logged.
+    pub fn new(logger: Arc<Logger>) -> RequestLogger {
+        RequestLogger {
+            logger,
+            level: Level::Info,
+            start_level: None,
+        }
+    }
+
+    /// Sets the level of records of finished requests.
+    ///
+    /// Requests with a status of 5xx are always logged at [`Level::Error`],
+    /// and 4xx at [`Level::Warn`].
+    #[must_use]
+    pub fn level(mut self, level: Level) -> Self {
+        self.level = level;
+        self
+    }
+
+    /// Sets the level of records of started requests, or `None` to not log
+    /// the start of requests.
+    #[must_use]
+    pub fn start_level(mut self, level: Option<Level>) -> Self {
+        self.start_level = level;
+        self
+    }
+
+    /// Gets the logger.
+    pub fn logger(&self) -> &Arc<Logger> {
+        &self.logger
+    }
+
+    /// Starts the scope of a request.
+    ///
+    /// The method and the path are attached to all records logged in the
+    /// scope as fields `method` and `path`.
+    pub fn start<M, P>(&self, method: M, path: P) -> RequestScope
+    where
+        M: Into<String>,
+        P: Into<String>,
+    {
+        let scope = RequestScope {
+            request_logger: self.clone(),
+            fields: vec![
+                Field::new("method", method.into()),
+                Field::new("path", path.into()),
+            ],
+            start: Instant::now(),
+            finished: false,
+        };
+        if let Some(level) = self.start_level {
+            scope.log(level, "request started");
+        }
+        scope
+    }
+}
+
+/// The scope of a request started by [`RequestLogger::start`].
+///
+/// Records logged in the scope carry the fields of the request. If the scope
+/// is dropped without [`RequestScope::finish`] (e.g. the handler panicked or
+/// the client disconnected), the request is logged as aborted at
+/// [`Level::Warn`].
+pub struct RequestScope {
+    request_logger: RequestLogger,
+    fields: Vec<Field<'static>>,
+    start: Instant,
+    finished: bool,
+}
+
+impl RequestScope {
+    /// Adds a field attached to all records logged in the scope.
+    ///
+    /// Fields with keys `remote_addr`, `user`, `protocol`, `referer` and
+    /// `user_agent` are used by access log formatters. Other keys such as a
+    /// request ID are useful for correlating records.
+    pub fn field<K, V>(&mut self, key: K, value: V) -> &mut Self
+    where
+        K: Into<Cow<'static, str>>,
+        V: Into<Value<'static>>,
+    {
+        self.fields.push(Field::new(key, value));
+        self
+    }
+
+    /// Gets the fields attached to all records logged in the scope.
+    pub fn fields(&self) -> &[Field<'static>] {
+        &self.fields
+    }
+
+    /// Gets the time elapsed since the request started.
+    pub fn elapsed(&self) -> Duration {
+        self.start.elapsed()
+    }
+
+    /// Logs a record with the fields of the request.
+    pub fn log<'a, S>(&self, level: Level, payload: S)
+    where
+        S: Into<Cow<'a, str>>,
+    {
+        self.log_with(level, &payload.into(), &[]);
+    }
+
+    /// Finishes the request, and logs it with fields `status`, `bytes` (if
+    /// given) and `latency`.
+    pub fn finish(mut self, status: u16, bytes: Option<u64>) {
+        self.finished = true;
+
+        let level = match status {
+            500.. => Level::Error,
+            400..=499 => Level::Warn,
+            _ => self.request_logger.level,
+        };
+        let fields = [
+            Some(Field::new("status", status)),
+            bytes.map(|bytes| Field::new("bytes", bytes)),
+            Some(Field::new("latency", self.elapsed())),
+        ];
+        let fields = fields.into_iter().flatten().collect::<Vec<_>>();
+        self.log_with(level, "request finished", &fields);
+    }
+
+    fn log_with(&self, level: Level, payload: &str, extra_fields: &[Field]) {
+        let logger = &self.request_logger.logger;
+        if !logger.should_log(level) {
+            return;
+        }
+
+        let mut builder = Record::builder(level, payload)
+            .fields(self.fields.iter().map(Field::as_borrowed))
+            .fields(extra_fields.iter().map(Field::as_borrowed));
+        if let Some(logger_name) = logger.name() {
+            builder = builder.logger_name(logger_name);
+        }
+        logger.log(&builder.build());
+    }
+}
+
+impl Drop for RequestScope {
+    fn drop(&mut self) {
+        if !self.finished {
+            self.log_with(
+                Level::Warn,
+                "request aborted",
+                &[Field::new("latency", self.elapsed())],
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{prelude::*, test_utils::*};
+
+    #[test]
+    fn scope() {
+        let test_sink = Arc::new(CounterSink::new());
+        let test_logger = Arc::new(
+            test_logger_builder()
+                .sink(test_sink.clone())
+                .level_filter(LevelFilter::All)
+                .build(),
+        );
+        let request_logger = RequestLogger::new(test_logger).start_level(Some(Level::Debug));
+
+        let mut scope = request_logger.start("GET", "/index.html");
+        scope.field("remote_addr", "127.0.0.1");
+        scope.log(Level::Info, "cache miss");
+        scope.finish(404, Some(0));
+
+        drop(request_logger.start("POST", "/upload"));
+
+        let records = test_sink.records();
+        let summary = records
+            .iter()
+            .map(|record| {
+                let keys = record
+                    .fields()
+                    .iter()
+                    .map(|field| field.key())
+                    .collect::<Vec<_>>()
+                    .join(",");
+                format!("{} {} [{}]", record.level(), record.payload(), keys)
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            summary,
+            [
+                "debug request started [method,path]",
+                "info cache miss [method,path,remote_addr]",
+                "warn request finished [method,path,remote_addr,status,bytes,latency]",
+                "debug request started [method,path]",
+                "warn request aborted [method,path,latency]",
+            ]
+        );
+    }
+}